@@ -3,7 +3,8 @@ import { createEffect, createSignal, onMount, type Component, For, type JSX } fr
 import logo from './logo.svg';
 import "./index.css"
 import init, * as wasm from '../pkg/compiler';
-import { CompileResult } from './types';
+import { CompileResult, Diagnostic, RunResult } from './types';
+import { runWasm } from './wasmHost';
 import { Terminal } from 'xterm';
 import { FitAddon } from '@xterm/addon-fit';
 import { WebLinksAddon } from '@xterm/addon-web-links';
@@ -273,25 +274,89 @@ function App() {
       }
     } catch (error: any) {
       console.error("Error during compilation:", error);
-      setErrors([error.message || error.toString()]);
-      
+
+      // compile throws an array of diagnostics (or a plain error)
+      const messages: string[] = Array.isArray(error)
+        ? (error as Diagnostic[]).map((d) => d.rendered)
+        : [error.message || error.toString()];
+      setErrors(messages);
+
       // Show error in terminal
       const term = terminal();
       if (term) {
         term.clear();
         term.writeln('\x1b[31m✗ Compilation failed:\x1b[0m');
-        
-        // The error message should already be formatted with ANSI codes
+
+        // The messages are already formatted with ANSI codes
         // Split by lines and write each line separately
-        const errorMessage = error.message || error.toString();
-        const lines = errorMessage.split('\n');
-        lines.forEach((line: string) => {
-          term.writeln(line);
+        messages.forEach((message) => {
+          message.split('\n').forEach((line: string) => {
+            term.writeln(line);
+          });
         });
       }
     }
   };
 
+  const showRunResult = (term: Terminal, result: RunResult) => {
+    term.write(result.output);
+    if (result.output && !result.output.endsWith('\n')) {
+      term.writeln('');
+    }
+
+    if (result.error) {
+      term.writeln(`\x1b[31m✗ Runtime error: ${result.error}\x1b[0m`);
+    } else {
+      term.writeln(`\x1b[32m✓ Exited with code ${result.exit_code}\x1b[0m`);
+    }
+  };
+
+  const showCompileErrors = (term: Terminal, error: any) => {
+    const messages: string[] = Array.isArray(error)
+      ? (error as Diagnostic[]).map((d) => d.rendered)
+      : [error.message || error.toString()];
+    setErrors(messages);
+
+    term.writeln('\x1b[31m✗ Compilation failed:\x1b[0m');
+    messages.forEach((message) => {
+      message.split('\n').forEach((line: string) => {
+        term.writeln(line);
+      });
+    });
+  };
+
+  const runCode = () => {
+    if (!wasmInitialized()) return;
+
+    const term = terminal();
+    if (!term) return;
+    term.clear();
+
+    try {
+      showRunResult(term, wasm.run(sourceCode()));
+    } catch (error: any) {
+      showCompileErrors(term, error);
+    }
+  };
+
+  const runCodeWasm = async () => {
+    if (!wasmInitialized()) return;
+
+    const term = terminal();
+    if (!term) return;
+    term.clear();
+
+    let bytes: Uint8Array;
+    try {
+      bytes = wasm.compile_wasm(sourceCode());
+    } catch (error: any) {
+      showCompileErrors(term, error);
+      return;
+    }
+
+    showRunResult(term, await runWasm(bytes));
+  };
+
   const handleVerticalResize = (delta: number) => {
     if (!containerRef) return;
     
@@ -450,6 +515,38 @@ function App() {
             >
               Compile
             </button>
+            <button
+              onClick={runCode}
+              disabled={!wasmInitialized()}
+              style={{
+                padding: '6px 12px',
+                'background-color': '#2f9e44',
+                color: 'white',
+                border: 'none',
+                'border-radius': '4px',
+                cursor: wasmInitialized() ? 'pointer' : 'not-allowed',
+                'font-size': '14px',
+                opacity: wasmInitialized() ? '1' : '0.5'
+              }}
+            >
+              Run
+            </button>
+            <button
+              onClick={runCodeWasm}
+              disabled={!wasmInitialized()}
+              style={{
+                padding: '6px 12px',
+                'background-color': '#6741d9',
+                color: 'white',
+                border: 'none',
+                'border-radius': '4px',
+                cursor: wasmInitialized() ? 'pointer' : 'not-allowed',
+                'font-size': '14px',
+                opacity: wasmInitialized() ? '1' : '0.5'
+              }}
+            >
+              Run (wasm)
+            </button>
           </div>
         </div>
         {!wasmInitialized() ? (
@@ -657,7 +754,7 @@ function App() {
         'flex-direction': 'column',
         overflow: 'hidden'
       }}>
-        <h3 style={{ color: 'white', margin: '0 0 10px 0' }}>Compiler output</h3>
+        <h3 style={{ color: 'white', margin: '0 0 10px 0' }}>Output</h3>
         <div 
           ref={terminalRef}
           style={{ 
@@ -4,6 +4,20 @@ export interface CompileResult {
     ir: string;
 } 
 
+export interface RunResult {
+    output: string;
+    exit_code: number | null;
+    error: string | null;
+}
+
+export interface Diagnostic {
+    id: number;
+    name: string;
+    message: string;
+    span: Span;
+    rendered: string;
+}
+
 export interface Position {
     abs: number;
     row: number;
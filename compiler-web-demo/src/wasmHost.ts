@@ -0,0 +1,192 @@
+import { RunResult } from './types';
+
+const PAGE_SIZE = 64 * 1024;
+
+/** thrown by `exit` to unwind out of the program */
+class ExitTrap {
+  constructor(public code: number) {}
+}
+
+/**
+ * Runs a module from `compile_wasm` in the browser.
+ *
+ * The module imports its `extern` functions from `env`, all integers and pointers are `i64`
+ * (`BigInt` in JavaScript). `malloc` is a bump allocator behind the exported `__heap_base`,
+ * each block is prefixed with its size so `realloc` knows how much to copy.
+ */
+export async function runWasm(bytes: Uint8Array): Promise<RunResult> {
+  let output = '';
+  let memory: WebAssembly.Memory;
+  let heap = 0;
+
+  const decoder = new TextDecoder();
+
+  const cString = (address: bigint): string => {
+    const data = new Uint8Array(memory.buffer);
+    const start = Number(address);
+    const end = data.indexOf(0, start);
+    return decoder.decode(data.subarray(start, end));
+  };
+
+  const malloc = (size: bigint): bigint => {
+    const address = heap + 8;
+    heap = address + Math.ceil(Number(size) / 8) * 8;
+
+    const missing = heap - memory.buffer.byteLength;
+    if (missing > 0) {
+      memory.grow(Math.ceil(missing / PAGE_SIZE));
+    }
+
+    new DataView(memory.buffer).setBigUint64(address - 8, size, true);
+    return BigInt(address);
+  };
+
+  const env: Record<string, (...args: bigint[]) => bigint | void> = {
+    // the arguments for `...` are stored in 8 byte slots starting at `args`
+    printf: (format, args) => {
+      const view = new DataView(memory.buffer);
+      let next = Number(args);
+      const nextArg = (): bigint => {
+        const value = view.getBigInt64(next, true);
+        next += 8;
+        return value;
+      };
+
+      const text = formatC(cString(format), nextArg, cString);
+      output += text;
+      return BigInt(text.length);
+    },
+    putchar: (c) => {
+      output += String.fromCharCode(Number(c & 0xffn));
+      return c;
+    },
+    // there is no stdin in the browser
+    getchar: () => -1n,
+    malloc,
+    calloc: (num, size) => {
+      const address = malloc(num * size);
+      new Uint8Array(memory.buffer, Number(address), Number(num * size)).fill(0);
+      return address;
+    },
+    realloc: (address, size) => {
+      const newAddress = malloc(size);
+      if (address !== 0n) {
+        const oldSize = new DataView(memory.buffer).getBigUint64(Number(address) - 8, true);
+        const len = Number(oldSize < size ? oldSize : size);
+        const data = new Uint8Array(memory.buffer);
+        data.copyWithin(Number(newAddress), Number(address), Number(address) + len);
+      }
+      return newAddress;
+    },
+    free: () => {},
+    exit: (code) => {
+      throw new ExitTrap(Number(code));
+    },
+    time: (address) => {
+      const now = BigInt(Math.floor(Date.now() / 1000));
+      if (address !== 0n) {
+        new DataView(memory.buffer).setBigInt64(Number(address), now, true);
+      }
+      return now;
+    },
+    // `Timespec` from `std/time.mx`, the wasm target uses the posix implementation
+    clock_gettime: (_clock, timespec) => {
+      const now = Date.now();
+      const view = new DataView(memory.buffer);
+      view.setBigInt64(Number(timespec), BigInt(Math.floor(now / 1000)), true);
+      view.setBigInt64(Number(timespec) + 8, BigInt((now % 1000) * 1000000), true);
+      return 0n;
+    },
+    // blocking is not possible in the browser
+    nanosleep: () => 0n,
+    // no program arguments in the browser, `args` is empty
+    mx_argc: () => 0n,
+    mx_argv: () => 0n,
+  };
+
+  try {
+    const { instance } = await WebAssembly.instantiate(bytes, { env });
+    memory = instance.exports.memory as WebAssembly.Memory;
+    heap = (instance.exports.__heap_base as WebAssembly.Global).value;
+
+    const main = instance.exports.main as () => number;
+    return { output, exit_code: main(), error: null };
+  } catch (error: any) {
+    if (error instanceof ExitTrap) {
+      return { output, exit_code: error.code, error: null };
+    }
+    return { output, exit_code: null, error: error.message || error.toString() };
+  }
+}
+
+/**
+ * `printf` like `format_c` in the interpreter: `d i u x X c s f p %` with `-`, `0`, width,
+ * precision and the lengths `l`, `ll`, `z`. Without a length integers are 32 bit.
+ */
+function formatC(
+  format: string,
+  nextArg: () => bigint,
+  cString: (address: bigint) => string,
+): string {
+  const pattern = /%([-0]*)(\d*)(?:\.(\d*))?([lzjh]*)([a-zA-Z%])/g;
+
+  return format.replace(pattern, (spec, flags: string, width, precision, length, conversion) => {
+    const long = /[lzj]/.test(length);
+    const signed = (value: bigint) => (long ? value : BigInt.asIntN(32, value));
+    const unsigned = (value: bigint) => (long ? BigInt.asUintN(64, value) : BigInt.asUintN(32, value));
+
+    let text: string;
+    let isNumber = true;
+    switch (conversion) {
+      case '%':
+        return '%';
+      case 'd':
+      case 'i':
+        text = signed(nextArg()).toString();
+        break;
+      case 'u':
+        text = unsigned(nextArg()).toString();
+        break;
+      case 'x':
+        text = unsigned(nextArg()).toString(16);
+        break;
+      case 'X':
+        text = unsigned(nextArg()).toString(16).toUpperCase();
+        break;
+      case 'f':
+      case 'F': {
+        const bits = new DataView(new ArrayBuffer(8));
+        bits.setBigInt64(0, nextArg(), true);
+        text = bits.getFloat64(0, true).toFixed(precision === undefined ? 6 : Number(precision));
+        break;
+      }
+      case 'p':
+        text = '0x' + BigInt.asUintN(64, nextArg()).toString(16);
+        isNumber = false;
+        break;
+      case 'c':
+        text = String.fromCharCode(Number(nextArg() & 0xffn));
+        isNumber = false;
+        break;
+      case 's':
+        text = cString(nextArg());
+        if (precision !== undefined) {
+          text = text.slice(0, Number(precision));
+        }
+        isNumber = false;
+        break;
+      default:
+        return spec;
+    }
+
+    const padding = Math.max(Number(width || 0) - text.length, 0);
+    if (flags.includes('-')) {
+      return text + ' '.repeat(padding);
+    }
+    if (flags.includes('0') && isNumber) {
+      const sign = text.startsWith('-') ? '-' : '';
+      return sign + '0'.repeat(padding) + text.slice(sign.length);
+    }
+    return ' '.repeat(padding) + text;
+  });
+}
@@ -1,12 +1,16 @@
-use std::{collections::HashMap, sync::atomic::AtomicU64};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::AtomicU64,
+};
 
 use itertools::Itertools;
+use ordermap::OrderMap;
 
 use super::llvm_instructions::{ToIR, IR};
 use crate::{
     codegen::llvm_instructions::{IRVariable, Instruction},
-    lexer::position::Spanned,
-    parser::ast::{DataType, Expr, Program, Statement},
+    lexer::position::{Span, Spanned},
+    parser::ast::{Capture, DataType, Expr, Program, Statement},
 };
 
 #[derive(Debug, Clone)]
@@ -21,11 +25,39 @@ impl ToIR for ComputedExpression {
     }
 }
 
+/// sprungziele für `break` und `continue`
+#[derive(Debug, Clone)]
+pub struct LoopLabels {
+    pub continue_label: String,
+    pub break_label: String,
+}
+
+/// ziel von `return` innerhalb eines block ausdrucks
+#[derive(Debug, Clone)]
+pub struct BlockResult {
+    pub result_var: IRVariable,
+    pub end_label: String,
+}
+
+/// umgebung einer closure, wird am anfang der closure funktion ausgepackt
+#[derive(Debug, Clone)]
+pub struct ClosureEnv {
+    pub _type: DataType,
+    pub captures: Vec<Capture>,
+}
+
 pub struct CodeGenerator {
     pub program: Program,
     pub variable_counter: AtomicU64,
     pub variable_map: HashMap<String, IRVariable>,
     pub source_code: Option<String>,
+    pub loop_labels: Vec<LoopLabels>, // innerster loop zuletzt
+    pub closure_envs: HashMap<String, ClosureEnv>, // name der closure funktion -> umgebung
+    pub block_results: Vec<BlockResult>, // innerster block zuletzt
+    pub c_abi_types: OrderMap<String, DataType>, // structs für rückgabewerte im c abi
+    pub boxed_variables: HashSet<Spanned<String>>, // von closures per referenz gefangen, liegen auf dem heap
+    pub local_closures: HashSet<Span>, // verlassen ihren block nicht, umgebung auf dem stack
+    pub library: bool, // `--emit obj`, `staticlib` und `sharedlib`, nur `#[export]` ist sichtbar
 }
 
 impl CodeGenerator {
@@ -35,6 +67,13 @@ impl CodeGenerator {
             variable_counter: AtomicU64::new(0),
             variable_map: HashMap::new(),
             source_code: None,
+            loop_labels: vec![],
+            closure_envs: HashMap::new(),
+            block_results: vec![],
+            c_abi_types: OrderMap::new(),
+            boxed_variables: HashSet::new(),
+            local_closures: HashSet::new(),
+            library: false,
         }
     }
 
@@ -43,25 +82,46 @@ impl CodeGenerator {
         self
     }
 
+    pub fn with_library(mut self, library: bool) -> Self {
+        self.library = library;
+        self
+    }
+
     pub fn parse(&mut self) -> IR {
-        let mut instructions = vec![];
+        let mut instructions = self.parse_globals();
+        let mut functions = vec![];
 
         // klassen und funktionen können während dem durchlauf hinzugefügt werden (anonyme funktionen)
         // TODO: vielleicht multithreading?
-        let mut i = 0;
-        while i < self.program.custom_types.len() {
-            let (_key, class) = self.program.custom_types.get_index(i).unwrap();
-            instructions.append(&mut self.parse_class_def(class.value.clone()));
-            i += 1;
-        }
+        let mut type_idx = 0;
+        let mut function_idx = 0;
+        // closure umgebungen kommen erst während den funktionen dazu
+        while type_idx < self.program.custom_types.len()
+            || function_idx < self.program.functions.len()
+        {
+            while type_idx < self.program.custom_types.len() {
+                let (_key, class) = self.program.custom_types.get_index(type_idx).unwrap();
+                instructions.append(&mut self.parse_class_def(class.value.clone()));
+                type_idx += 1;
+            }
 
-        let mut i = 0;
-        while i < self.program.functions.len() {
-            let (_key, function) = self.program.functions.get_index(i).unwrap();
-            instructions.append(&mut self.parse_func_def(function.value.clone()));
-            i += 1;
+            while function_idx < self.program.functions.len() {
+                let (_key, function) = self.program.functions.get_index(function_idx).unwrap();
+                functions.append(&mut self.parse_func_def(function.value.clone()));
+                function_idx += 1;
+            }
         }
 
+        // typen müssen vor ihrer ersten verwendung deklariert sein
+        instructions.extend(
+            self.c_abi_types
+                .values()
+                .cloned()
+                .map(Instruction::DeclareType),
+        );
+        instructions.append(&mut functions);
+        instructions.append(&mut self.main_wrapper());
+
         instructions.to_ir()
     }
 
@@ -107,11 +167,11 @@ impl CodeGenerator {
                 )
             }
             Statement::VariableDecl { name, value, .. } => {
-                self.parse_variable_decl(name.value.clone(), &value.value)
+                self.parse_variable_decl(name, &value.value)
             }
             Statement::Return { value } => self.parse_return_statement(&value.value),
-            Statement::Expr(expr) => {
-                if let Expr::Call { function, args } = &expr.value {
+            Statement::Expr(expr) => match &expr.value {
+                Expr::Call { function, args } => {
                     return self
                         .parse_func_call(
                             &function.value,
@@ -119,8 +179,23 @@ impl CodeGenerator {
                         )
                         .instructions;
                 }
-                unreachable!()
-            }
+                Expr::ClosureCall { closure, args } => {
+                    return self
+                        .parse_closure_call(
+                            &closure.value,
+                            &args.value.iter().map(|x| x.clone().value).collect_vec(),
+                        )
+                        .instructions;
+                }
+                Expr::Match { value, arms } => {
+                    comment = None;
+
+                    // der wert des match wird nicht verwendet
+                    self.parse_match(&value.value, arms, &DataType::None)
+                        .instructions
+                }
+                _ => unreachable!(),
+            },
             Statement::VariableMutation {
                 variable,
                 new_value,
@@ -128,6 +203,17 @@ impl CodeGenerator {
             Statement::WhileLoop { condition, body } => {
                 self.parse_while(&condition.value, &body.value)
             }
+            Statement::ForLoop {
+                variable,
+                iterable,
+                body,
+            } => {
+                comment = None;
+
+                self.parse_for(&variable.value, &iterable.value, &body.value)
+            }
+            Statement::Break => self.parse_break(),
+            Statement::Continue => self.parse_continue(),
         };
 
         if let Some(comment) = comment {
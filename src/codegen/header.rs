@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{
+    lexer::position::Spanned,
+    parser::ast::{CustomDataType, DataType, Program},
+};
+
+/// `.h` datei für c mit den `#[export]` funktionen und den klassen, die sie benutzen
+pub fn c_header(program: &Program, guard: &str) -> String {
+    let functions = program
+        .functions
+        .values()
+        .map(|f| &f.value)
+        .filter(|f| f.export)
+        .collect_vec();
+
+    let mut types = HeaderTypes::default();
+    for function in functions.iter() {
+        for param in function.params.value.iter() {
+            types.collect(program, &param.value._type.value);
+        }
+        types.collect(program, &function.return_type.value);
+    }
+
+    let guard = c_name(&guard.to_uppercase());
+    let mut out = format!(
+        "/* generated by the mx compiler */\n#ifndef {guard}\n#define {guard}\n\n#include <stdbool.h>\n#include <stdint.h>\n"
+    );
+
+    if types.function_values {
+        out.push_str("\n/* a function value: the function pointer and its environment */\n");
+        out.push_str("typedef struct {\n    void *function;\n    void *env;\n} mx_function;\n");
+    }
+
+    // klassen können sich über pointer gegenseitig benutzen
+    if !types.classes.is_empty() {
+        out.push('\n');
+    }
+    for class in types.classes.iter() {
+        let name = c_name(&class.name);
+        out.push_str(&format!("typedef struct {name} {name};\n"));
+    }
+
+    for class in types.classes.iter() {
+        let packed = match class.is_packed {
+            true => "__attribute__((packed)) ",
+            false => "",
+        };
+        out.push_str(&format!("\nstruct {packed}{} {{\n", c_name(&class.name)));
+        for field in class.fields.value.iter() {
+            out.push_str(&format!(
+                "    {};\n",
+                c_declaration(&field._type.value, &c_name(&field.name.value))
+            ));
+        }
+        out.push_str("};\n");
+    }
+
+    if !functions.is_empty() {
+        out.push('\n');
+    }
+    for function in functions {
+        let params = match function.params.value.is_empty() {
+            true => "void".to_string(),
+            false => function
+                .params
+                .value
+                .iter()
+                .map(|p| c_declaration(&p.value._type.value, &c_name(&p.value.name.value)))
+                .join(", "),
+        };
+
+        out.push_str(&format!(
+            "{} {}({params});\n",
+            c_type(&function.return_type.value),
+            function.display_name.value
+        ));
+    }
+
+    out.push_str(&format!("\n#endif /* {guard} */\n"));
+    out
+}
+
+#[derive(Default)]
+struct HeaderTypes {
+    seen: HashSet<String>,
+    /// felder stehen vor der klasse, in der sie liegen
+    classes: Vec<CustomDataType>,
+    function_values: bool,
+}
+
+impl HeaderTypes {
+    fn collect(&mut self, program: &Program, _type: &DataType) {
+        match _type {
+            DataType::Custom(class) => {
+                if !self.seen.insert(class.name.clone()) {
+                    return;
+                }
+                // das layout steht in `custom_types`
+                let class = match program.custom_types.get(&class.name) {
+                    Some(Spanned {
+                        value: DataType::Custom(declared),
+                        ..
+                    }) => declared,
+                    _ => class,
+                };
+                for field in class.fields.value.iter() {
+                    self.collect(program, &field._type.value);
+                }
+                self.classes.push((**class).clone());
+            }
+            DataType::Pointer(inner)
+            | DataType::Array {
+                value_type: inner, ..
+            } => self.collect(program, inner),
+            DataType::Function { .. } => self.function_values = true,
+            _ => {}
+        }
+    }
+}
+
+/// `foo.Bar` oder `List--int64` sind keine namen in c
+fn c_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+fn c_type(_type: &DataType) -> String {
+    match _type {
+        DataType::Integer8 => "int8_t".to_string(),
+        DataType::Integer16 => "int16_t".to_string(),
+        DataType::Integer32 => "int32_t".to_string(),
+        DataType::Integer64 => "int64_t".to_string(),
+        DataType::UnsignedInteger8 => "uint8_t".to_string(),
+        DataType::UnsignedInteger16 => "uint16_t".to_string(),
+        DataType::UnsignedInteger32 => "uint32_t".to_string(),
+        DataType::UnsignedInteger64 => "uint64_t".to_string(),
+        DataType::Float => "float".to_string(),
+        DataType::Float64 => "double".to_string(),
+        DataType::Boolean => "bool".to_string(),
+        // ein pointer auf ein array zeigt auf das erste element
+        DataType::Pointer(inner) => match inner.as_ref() {
+            DataType::Array { value_type, .. } => format!("{}*", c_type(value_type)),
+            inner => format!("{}*", c_type(inner)),
+        },
+        DataType::Custom(class) => c_name(&class.name),
+        DataType::Function { .. } => "mx_function".to_string(),
+        DataType::Array { value_type, .. } => c_type(value_type),
+        DataType::None | DataType::Generic(_) | DataType::DataType => "void".to_string(),
+    }
+}
+
+/// `int64_t name` oder `int8_t name[4]`
+fn c_declaration(_type: &DataType, name: &str) -> String {
+    match _type {
+        DataType::Array { value_type, len } => c_declaration(value_type, &format!("{name}[{len}]")),
+        _ => format!("{} {name}", c_type(_type)),
+    }
+}
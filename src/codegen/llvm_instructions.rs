@@ -7,7 +7,7 @@ use crate::{
     parser::ast::{CustomDataType, DataType, DataTypeGetter},
 };
 
-use super::structs::{IRFunction, IRFunctionParam};
+use super::structs::{IRFunction, IRFunctionParam, ParamAttribute};
 
 pub type IR = String;
 
@@ -20,6 +20,7 @@ pub enum Instruction {
     },
     MemoryOperation(MemoryOperation),
     Call(FunctionCall),
+    IndirectCall(IndirectCall),
     // Comparison(Comparison),
     VRegisterAssignment {
         variable: IRVariable,
@@ -31,6 +32,12 @@ pub enum Instruction {
     Jump(JumpKind),
     DeclareType(DataType),
     DeclareFunction(IRFunction),
+    DeclareGlobal {
+        name: String,
+        _type: DataType,
+        value: Literal,
+        is_const: bool,
+    },
     Cast(Cast),
 
     Comment(String),
@@ -47,6 +54,7 @@ impl ToIR for Instruction {
             Instruction::BinaryOperation(binary_operation) => binary_operation.to_ir(),
             Instruction::MemoryOperation(memory_operation) => memory_operation.to_ir(),
             Instruction::Call(function_call) => function_call.to_ir(),
+            Instruction::IndirectCall(indirect_call) => indirect_call.to_ir(),
             Instruction::VRegisterAssignment {
                 variable: name,
                 value,
@@ -60,13 +68,21 @@ impl ToIR for Instruction {
             Instruction::BlockDecl { label } => format!("{label}:\n"),
             Instruction::Jump(jump_kind) => jump_kind.to_ir(),
             Instruction::DeclareType(_type) => {
-                if let DataType::Custom(CustomDataType { fields, .. }) = _type.clone() {
+                if let DataType::Custom(custom) = _type {
+                    let CustomDataType {
+                        fields, is_packed, ..
+                    } = custom.as_ref();
+                    // `<{ ... }>` ist ein struct ohne padding
+                    let (open, close) = match is_packed {
+                        true => ("<{", "}>"),
+                        false => ("{", "}"),
+                    };
                     return format!(
-                        "{} = type {{\n{}}}",
+                        "{} = type {open}\n{}{close}",
                         _type.to_ir(),
                         fields
                             .value
-                            .into_iter()
+                            .iter()
                             .map(|f| format!("{} ;{}\n", f._type.value.to_ir(), f.name.value))
                             .join(",")
                     );
@@ -74,6 +90,26 @@ impl ToIR for Instruction {
                 unreachable!()
             }
             Instruction::DeclareFunction(inner) => inner.to_ir(),
+            Instruction::DeclareGlobal {
+                name,
+                _type,
+                value,
+                is_const,
+            } => {
+                let value = match (value, _type) {
+                    // float literale sind sonst immer f32
+                    (Literal::Float(float), DataType::Float64) => {
+                        format!("0x{}", to_hex_string(float.to_bits()))
+                    }
+                    (Literal::Integer(0), DataType::Pointer(_)) => "null".to_string(),
+                    _ => value.to_ir(),
+                };
+                format!(
+                    "@{name} = internal {} {} {value}",
+                    if *is_const { "constant" } else { "global" },
+                    _type.to_ir()
+                )
+            }
             Instruction::Comment(comment) => format!("; {}", comment.replace('\n', "\n; ")),
             Instruction::Cast(cast) => cast.to_ir(),
             Instruction::NOOP => "add i1 0, 0".to_string(),
@@ -95,14 +131,63 @@ pub struct FunctionCall {
     pub name: String,
     pub return_type: DataType,
     pub args: Vec<IRValue>,
+    /// die festen parameter einer variadischen funktion, llvm braucht dann den ganzen funktionstyp
+    pub variadic_params: Option<Vec<DataType>>,
+    /// `byval` und `sret` der argumente im c abi, sonst leer
+    pub arg_attributes: Vec<Option<ParamAttribute>>,
 }
 
 impl ToIR for FunctionCall {
     fn to_ir(&self) -> IR {
+        // `call i32 (i8*, ...) @printf(...)`
+        let function_type = match &self.variadic_params {
+            Some(params) => format!(
+                "{} ({})",
+                self.return_type.to_ir(),
+                params
+                    .iter()
+                    .map(|p| p.to_ir())
+                    .chain(std::iter::once("...".to_string()))
+                    .join(", ")
+            ),
+            None => self.return_type.to_ir(),
+        };
+
         format!(
             "call {} @{}({})",
-            self.return_type.to_ir(),
+            function_type,
             self.name,
+            self.args
+                .iter()
+                .enumerate()
+                .map(|(idx, a)| match self.arg_attributes.get(idx) {
+                    Some(Some(attribute)) => format!(
+                        "{} {} {}",
+                        a._type().to_ir(),
+                        attribute.to_ir(),
+                        a.clone().to_ir()
+                    ),
+                    _ => format!("{} {}", a._type().to_ir(), a.clone().to_ir()),
+                })
+                .join(","),
+        )
+    }
+}
+
+/// aufruf über einen funktionspointer (closures)
+#[derive(Debug, Clone)]
+pub struct IndirectCall {
+    pub pointer: IRVariable,
+    pub return_type: DataType,
+    pub args: Vec<IRValue>,
+}
+
+impl ToIR for IndirectCall {
+    fn to_ir(&self) -> IR {
+        format!(
+            "call {} {}({})",
+            self.return_type.to_ir(),
+            self.pointer.to_ir(),
             self.args
                 .iter()
                 .map(|a| format!("{} {}", a._type().to_ir(), a.clone().to_ir()))
@@ -111,6 +196,18 @@ impl ToIR for FunctionCall {
     }
 }
 
+/// `i64 (i8*, i64)*`, closures bekommen ihre umgebung als ersten parameter
+pub fn closure_function_pointer_type(params: &[DataType], return_type: &DataType) -> IR {
+    format!(
+        "{} ({})*",
+        return_type.to_ir(),
+        std::iter::once(DataType::Pointer(Box::new(DataType::Integer8)))
+            .chain(params.iter().cloned())
+            .map(|p| p.to_ir())
+            .join(", ")
+    )
+}
+
 #[derive(Debug, Clone)]
 pub enum IRValue {
     Literal(Literal),
@@ -184,7 +281,8 @@ impl From<IRFunctionParam> for IRVariable {
 impl ToIR for IRVariable {
     fn to_ir(&self) -> IR {
         let mut out = self.name.to_owned();
-        if !out.starts_with("%_") {
+        // globale variablen
+        if !out.starts_with("%_") && !out.starts_with('@') {
             out = format!("%_{}", out)
         }
         out
@@ -215,10 +313,17 @@ impl ToIR for BinaryOperation {
                 unreachable!()
             };
 
+            // fcmp hat eigene namen für die vergleiche
+            let operator = if comp == "fcmp" {
+                self.operator.to_float_ordering_ir()
+            } else {
+                self.operator.to_ir()
+            };
+
             return format!(
                 "{} {} {} {}, {}",
                 comp,
-                self.operator.to_ir(),
+                operator,
                 self.lhs._type().to_ir(),
                 self.lhs.to_ir(),
                 self.rhs.to_ir(),
@@ -243,6 +348,8 @@ pub enum IRBinaryOperator {
     Mul,
     Div,
     SRem, // modulo
+    UDiv,
+    URem,
 
     // float
     FAdd,
@@ -255,6 +362,11 @@ pub enum IRBinaryOperator {
     And,
     Or,
     Xor,
+    Shl,
+    /// `>>` mit vorzeichen
+    AShr,
+    /// `>>` ohne vorzeichen
+    LShr,
 
     // float & int
     Eq,
@@ -265,6 +377,12 @@ pub enum IRBinaryOperator {
 
     Slt,
     Sle,
+
+    // unsigned int
+    Ugt,
+    Uge,
+    Ult,
+    Ule,
 }
 
 impl IRBinaryOperator {
@@ -277,8 +395,26 @@ impl IRBinaryOperator {
                 | IRBinaryOperator::Sge
                 | IRBinaryOperator::Slt
                 | IRBinaryOperator::Sle
+                | IRBinaryOperator::Ugt
+                | IRBinaryOperator::Uge
+                | IRBinaryOperator::Ult
+                | IRBinaryOperator::Ule
         )
     }
+
+    /// vergleiche zwischen floats (ordered)
+    fn to_float_ordering_ir(&self) -> IR {
+        match self {
+            IRBinaryOperator::Eq => "oeq",
+            IRBinaryOperator::Ne => "one",
+            IRBinaryOperator::Sgt | IRBinaryOperator::Ugt => "ogt",
+            IRBinaryOperator::Sge | IRBinaryOperator::Uge => "oge",
+            IRBinaryOperator::Slt | IRBinaryOperator::Ult => "olt",
+            IRBinaryOperator::Sle | IRBinaryOperator::Ule => "ole",
+            _ => unreachable!(),
+        }
+        .to_string()
+    }
 }
 
 impl ToIR for IRBinaryOperator {
@@ -295,6 +431,9 @@ impl ToIR for IRBinaryOperator {
             IRBinaryOperator::And => "and",
             IRBinaryOperator::Or => "or",
             IRBinaryOperator::Xor => "xor",
+            IRBinaryOperator::Shl => "shl",
+            IRBinaryOperator::AShr => "ashr",
+            IRBinaryOperator::LShr => "lshr",
             IRBinaryOperator::Eq => "eq",
             IRBinaryOperator::Ne => "ne",
             IRBinaryOperator::Sgt => "sgt",
@@ -302,6 +441,12 @@ impl ToIR for IRBinaryOperator {
             IRBinaryOperator::Slt => "slt",
             IRBinaryOperator::Sle => "sle",
             IRBinaryOperator::SRem => "srem",
+            IRBinaryOperator::UDiv => "udiv",
+            IRBinaryOperator::URem => "urem",
+            IRBinaryOperator::Ugt => "ugt",
+            IRBinaryOperator::Uge => "uge",
+            IRBinaryOperator::Ult => "ult",
+            IRBinaryOperator::Ule => "ule",
             IRBinaryOperator::FRem => "frem",
         }
         .to_string()
@@ -376,14 +521,15 @@ impl ToIR for MemoryOperation {
 impl ToIR for DataType {
     fn to_ir(&self) -> IR {
         match self {
-            DataType::Integer8 => "i8".to_string(),
-            DataType::Integer16 => "i16".to_string(),
-            DataType::Integer32 => "i32".to_string(),
-            DataType::Integer64 => "i64".to_string(),
+            DataType::Integer8 | DataType::UnsignedInteger8 => "i8".to_string(),
+            DataType::Integer16 | DataType::UnsignedInteger16 => "i16".to_string(),
+            DataType::Integer32 | DataType::UnsignedInteger32 => "i32".to_string(),
+            DataType::Integer64 | DataType::UnsignedInteger64 => "i64".to_string(),
             DataType::Float => "float".to_string(),
+            DataType::Float64 => "double".to_string(),
             DataType::Boolean => "i1".to_string(),
             DataType::Pointer(data_type) => format!("{}*", (*data_type).to_ir()),
-            DataType::Custom(CustomDataType { name, .. }) => format!("%{name}"),
+            DataType::Custom(custom) => format!("%{}", custom.name),
             DataType::None => "void".to_string(),
             DataType::Array {
                 value_type: _type,
@@ -393,6 +539,8 @@ impl ToIR for DataType {
                 unreachable!("{inner}")
             }
             DataType::DataType => unreachable!(),
+            // funktionspointer und umgebung
+            DataType::Function { .. } => "{i8*, i8*}".to_string(),
         }
     }
 }
@@ -450,40 +598,77 @@ fn to_hex_string(mut l: u64) -> String {
 #[derive(Debug, Clone)]
 pub enum Cast {
     SignedIntUp(IRValue, DataType),
-    SignedIntDown(IRValue, DataType),
+    UnsignedIntUp(IRValue, DataType),
+    IntDown(IRValue, DataType),
     SignedIntToFloat(IRValue, DataType),
+    UnsignedIntToFloat(IRValue, DataType),
     FloatToSignedInt(IRValue, DataType),
+    FloatToUnsignedInt(IRValue, DataType),
+    FloatUp(IRValue, DataType),
+    FloatDown(IRValue, DataType),
+    /// zwischen zwei pointer typen
+    Bitcast(IRValue, DataType),
+    /// `@name` als `i8*`, der zweite wert ist der typ des funktionspointers
+    FunctionToPointer(String, IR),
+    /// `i8*` zurück zu einem funktionspointer
+    PointerToFunction(IRValue, IR),
+}
+
+/// typ im namen von intrinsics, z.b. `llvm.fptosi.sat.i64.f32`
+fn intrinsic_type_name(_type: &DataType) -> IR {
+    match _type {
+        DataType::Float => "f32".to_string(),
+        DataType::Float64 => "f64".to_string(),
+        _ => _type.to_ir(),
+    }
 }
 
 impl ToIR for Cast {
     fn to_ir(&self) -> IR {
-        match self {
-            Cast::SignedIntUp(value, to) => format!(
-                "sext {} {} to {}",
-                value._type().to_ir(),
-                value.to_ir(),
-                to.to_ir()
-            ),
-            Cast::SignedIntDown(value, to) => format!(
-                "trunc {} {} to {}",
-                value._type().to_ir(),
-                value.to_ir(),
-                to.to_ir()
-            ),
-            Cast::SignedIntToFloat(value, to) => format!(
-                "sitofp {} {} to {}",
-                value._type().to_ir(),
-                value.to_ir(),
-                to.to_ir()
-            ),
-            Cast::FloatToSignedInt(value, to) => format!(
-                "call {} @llvm.fptosi.sat.{}.{}({} {})",
-                to.to_ir(),
-                to.to_ir(),
-                value._type().to_ir(),
-                value._type().to_ir(),
-                value.to_ir()
-            ),
-        }
+        let (instruction, value, to) = match self {
+            Cast::SignedIntUp(value, to) => ("sext", value, to),
+            Cast::UnsignedIntUp(value, to) => ("zext", value, to),
+            Cast::IntDown(value, to) => ("trunc", value, to),
+            Cast::SignedIntToFloat(value, to) => ("sitofp", value, to),
+            Cast::UnsignedIntToFloat(value, to) => ("uitofp", value, to),
+            Cast::FloatUp(value, to) => ("fpext", value, to),
+            Cast::FloatDown(value, to) => ("fptrunc", value, to),
+            Cast::Bitcast(value, to) => ("bitcast", value, to),
+            Cast::FunctionToPointer(name, function_type) => {
+                return format!("bitcast {function_type} @{name} to i8*");
+            }
+            Cast::PointerToFunction(value, function_type) => {
+                return format!(
+                    "bitcast {} {} to {function_type}",
+                    value._type().to_ir(),
+                    value.to_ir()
+                );
+            }
+            Cast::FloatToSignedInt(value, to) | Cast::FloatToUnsignedInt(value, to) => {
+                let intrinsic = if matches!(self, Cast::FloatToSignedInt(..)) {
+                    "fptosi"
+                } else {
+                    "fptoui"
+                };
+
+                return format!(
+                    "call {} @llvm.{}.sat.{}.{}({} {})",
+                    to.to_ir(),
+                    intrinsic,
+                    intrinsic_type_name(to),
+                    intrinsic_type_name(&value._type()),
+                    value._type().to_ir(),
+                    value.to_ir()
+                );
+            }
+        };
+
+        format!(
+            "{} {} {} to {}",
+            instruction,
+            value._type().to_ir(),
+            value.to_ir(),
+            to.to_ir()
+        )
     }
 }
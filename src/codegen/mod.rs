@@ -1,4 +1,5 @@
 pub mod codegen_main;
+pub mod header;
 pub mod llvm_instructions;
 pub mod structs;
 pub mod structures;
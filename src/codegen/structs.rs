@@ -1,3 +1,5 @@
+use itertools::Itertools;
+
 use super::llvm_instructions::{Instruction, ToIR, IR};
 use crate::parser::ast::DataType;
 
@@ -26,19 +28,34 @@ pub struct IRFunction {
     pub body: IRFunctionBody,
     pub return_type: DataType,
     pub is_extern: bool,
+    /// nur bei `extern`, `declare i32 @printf(i8*, ...)`
+    pub is_variadic: bool,
+    /// `define internal`, das symbol ist außerhalb der objekt datei nicht sichtbar
+    pub is_internal: bool,
 }
 
 impl ToIR for IRFunction {
     fn to_ir(&self) -> IR {
         match self.is_extern {
             true => format!(
-                "declare {} @{}{}",
+                "declare {} @{}({})",
                 self.return_type.to_ir(),
                 self.name,
-                self.params.to_ir(),
+                self.params
+                    .iter()
+                    .map(|p| match &p.attribute {
+                        Some(attribute) => format!("{} {}", p._type.to_ir(), attribute.to_ir()),
+                        None => p._type.to_ir(),
+                    })
+                    .chain(self.is_variadic.then(|| "...".to_string()))
+                    .join(", "),
             ),
             false => format!(
-                "define {} @{}{} {}",
+                "define {}{} @{}{} {}",
+                match self.is_internal {
+                    true => "internal ",
+                    false => "",
+                },
                 self.return_type.to_ir(),
                 self.name,
                 self.params.to_ir(),
@@ -52,6 +69,35 @@ impl ToIR for IRFunction {
 pub struct IRFunctionParam {
     pub name: String,
     pub _type: DataType,
+    /// nur im c abi, `%Foo* byval(%Foo) align 8`
+    pub attribute: Option<ParamAttribute>,
+}
+
+/// wie ein pointer parameter im c abi übergeben wird
+#[derive(Debug, Clone)]
+pub enum ParamAttribute {
+    /// kopie der klasse auf dem stack
+    ByVal(DataType),
+    /// speicher für den rückgabewert, den der aufrufer anlegt
+    SRet(DataType),
+}
+
+impl ToIR for ParamAttribute {
+    fn to_ir(&self) -> IR {
+        match self {
+            // auf dem stack mindestens 8 bytes ausgerichtet
+            ParamAttribute::ByVal(_type) => {
+                format!(
+                    "byval({}) align {}",
+                    _type.to_ir(),
+                    _type.alignment().max(8)
+                )
+            }
+            ParamAttribute::SRet(_type) => {
+                format!("sret({}) align {}", _type.to_ir(), _type.alignment())
+            }
+        }
+    }
 }
 
 // impl ToIR for IRFunctionParam {
@@ -65,7 +111,11 @@ impl ToIR for Vec<IRFunctionParam> {
         format!(
             "({})",
             self.iter()
-                .map(|p| format!("{} %_{}", p._type.clone().to_ir(), p.name))
+                .map(|p| match &p.attribute {
+                    Some(attribute) =>
+                        format!("{} {} %_{}", p._type.to_ir(), attribute.to_ir(), p.name),
+                    None => format!("{} %_{}", p._type.clone().to_ir(), p.name),
+                })
                 .collect::<Vec<IR>>()
                 .join(",")
         )
@@ -58,7 +58,7 @@ impl CodeGenerator {
     ) -> ComputedExpression {
         let value_type = match array_like._type.clone() {
             DataType::Array { value_type, .. } => *value_type,
-            DataType::Custom { .. } => field_type.unwrap(),
+            DataType::Custom { .. } | DataType::Function { .. } => field_type.unwrap(),
 
             unknown => panic!("cannot index type: {:?}", unknown),
         };
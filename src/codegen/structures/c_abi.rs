@@ -0,0 +1,728 @@
+use itertools::Itertools;
+
+use crate::{
+    codegen::{
+        codegen_main::{CodeGenerator, ComputedExpression},
+        llvm_instructions::{
+            Cast, FunctionCall, IRValue, IRVariable, Instruction, MemoryOperation, ToIR,
+        },
+        structs::{IRFunction, IRFunctionBody, IRFunctionParam, ParamAttribute},
+    },
+    lexer::{position::Spanned, tokens::Literal},
+    parser::ast::{CustomDataType, DataType, Function, FunctionParam},
+};
+
+/// aufrufkonvention von c auf der plattform, für die clang kompiliert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Abi {
+    /// x86-64 auf linux und macos
+    SysV,
+    /// x86-64 auf windows
+    Win64,
+    AArch64,
+}
+
+/// wie ein parameter oder rückgabewert an c übergeben wird
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbiArg {
+    Direct,
+    /// in register aufgeteilt, jeder teil ist ein eigener parameter,
+    /// ein rückgabewert mit mehreren teilen wird ein struct aus den teilen
+    Coerce(Vec<DataType>),
+    /// parameter als `byval` kopie auf dem stack, rückgabewert über `sret`
+    Memory,
+    /// pointer auf eine kopie, die der aufrufer anlegt
+    Indirect,
+}
+
+/// parameter und rückgabewert einer funktion im c abi
+#[derive(Debug, Clone, PartialEq)]
+pub struct CSignature {
+    pub params: Vec<(DataType, AbiArg)>,
+    pub return_type: DataType,
+    pub return_value: AbiArg,
+}
+
+/// freie register für parameter bei sysv
+struct Registers {
+    integer: usize,
+    sse: usize,
+}
+
+impl Abi {
+    pub fn host() -> Self {
+        if cfg!(target_arch = "aarch64") {
+            Abi::AArch64
+        } else if cfg!(target_os = "windows") {
+            Abi::Win64
+        } else {
+            Abi::SysV
+        }
+    }
+
+    pub fn signature(&self, params: &[DataType], return_type: &DataType) -> CSignature {
+        let return_value = match is_aggregate(return_type) {
+            true => self.classify_return(return_type),
+            false => AbiArg::Direct,
+        };
+
+        // `sret` belegt den ersten integer register
+        let mut registers = Registers {
+            integer: 6 - usize::from(return_value == AbiArg::Memory),
+            sse: 8,
+        };
+
+        CSignature {
+            params: params
+                .iter()
+                .map(|p| (p.clone(), self.classify_param(p, &mut registers)))
+                .collect(),
+            return_type: return_type.clone(),
+            return_value,
+        }
+    }
+
+    fn classify_param(&self, _type: &DataType, registers: &mut Registers) -> AbiArg {
+        if !is_aggregate(_type) {
+            match _type.is_float() {
+                true => registers.sse = registers.sse.saturating_sub(1),
+                false => registers.integer = registers.integer.saturating_sub(1),
+            }
+            return AbiArg::Direct;
+        }
+
+        match self {
+            Abi::SysV => match sysv_parts(_type) {
+                Some(parts) => {
+                    let sse = parts.iter().filter(|p| p.is_float()).count();
+                    let integer = parts.len() - sse;
+
+                    // eine klasse liegt ganz in registern oder ganz auf dem stack
+                    if integer > registers.integer || sse > registers.sse {
+                        return AbiArg::Memory;
+                    }
+                    registers.integer -= integer;
+                    registers.sse -= sse;
+                    AbiArg::Coerce(parts)
+                }
+                None => AbiArg::Memory,
+            },
+            Abi::Win64 => win64_part(_type).map_or(AbiArg::Indirect, |p| AbiArg::Coerce(vec![p])),
+            Abi::AArch64 => {
+                aarch64_part(_type).map_or(AbiArg::Indirect, |p| AbiArg::Coerce(vec![p]))
+            }
+        }
+    }
+
+    fn classify_return(&self, _type: &DataType) -> AbiArg {
+        let parts = match self {
+            Abi::SysV => sysv_parts(_type),
+            Abi::Win64 => win64_part(_type).map(|p| vec![p]),
+            Abi::AArch64 => aarch64_part(_type).map(|p| vec![p]),
+        };
+        parts.map_or(AbiArg::Memory, AbiArg::Coerce)
+    }
+}
+
+impl CSignature {
+    /// die parameter wie sie in llvm stehen, `sret` ist der erste
+    pub fn lowered_params(&self) -> Vec<(DataType, Option<ParamAttribute>)> {
+        let mut params = vec![];
+
+        if self.return_value == AbiArg::Memory {
+            params.push((
+                DataType::Pointer(Box::new(self.return_type.clone())),
+                Some(ParamAttribute::SRet(self.return_type.clone())),
+            ));
+        }
+
+        for (_type, class) in self.params.iter() {
+            let pointer = DataType::Pointer(Box::new(_type.clone()));
+            match class {
+                AbiArg::Direct => params.push((_type.clone(), None)),
+                AbiArg::Coerce(parts) => params.extend(parts.iter().map(|p| (p.clone(), None))),
+                AbiArg::Memory => {
+                    params.push((pointer, Some(ParamAttribute::ByVal(_type.clone()))))
+                }
+                AbiArg::Indirect => params.push((pointer, None)),
+            }
+        }
+
+        params
+    }
+}
+
+/// klassen, arrays und funktionswerte, leere klassen bleiben wie sie sind
+fn is_aggregate(_type: &DataType) -> bool {
+    matches!(
+        _type,
+        DataType::Custom(_) | DataType::Array { .. } | DataType::Function { .. }
+    ) && _type.size() > 0
+}
+
+/// alle skalaren felder mit ihrem offset in bytes
+fn scalar_fields(_type: &DataType, offset: usize, out: &mut Vec<(usize, DataType)>) {
+    match _type {
+        DataType::Custom(class) => {
+            for (idx, field) in class.fields.value.iter().enumerate() {
+                scalar_fields(&field._type.value, offset + class.field_offset(idx), out);
+            }
+        }
+        DataType::Array { value_type, len } => {
+            for idx in 0..*len {
+                scalar_fields(value_type, offset + idx * value_type.size(), out);
+            }
+        }
+        // funktionspointer und umgebung
+        DataType::Function { .. } => {
+            let pointer = DataType::Pointer(Box::new(DataType::Integer8));
+            out.push((offset, pointer.clone()));
+            out.push((offset + pointer.size(), pointer));
+        }
+        scalar => out.push((offset, scalar.clone())),
+    }
+}
+
+/// kleinster integer mit mindestens `size` bytes
+fn integer_of_size(size: usize) -> DataType {
+    match size {
+        ..=1 => DataType::Integer8,
+        2 => DataType::Integer16,
+        3..=4 => DataType::Integer32,
+        _ => DataType::Integer64,
+    }
+}
+
+/// bis 16 bytes in 8 byte teilen, ein teil nur aus floats kommt in ein sse register,
+/// alle anderen in integer register
+fn sysv_parts(_type: &DataType) -> Option<Vec<DataType>> {
+    let size = _type.size();
+    let mut fields = vec![];
+    scalar_fields(_type, 0, &mut fields);
+
+    // nicht ausgerichtete felder (`#[packed]`) liegen immer im speicher
+    if size > 16
+        || fields
+            .iter()
+            .any(|(offset, field)| offset % field.alignment() != 0)
+    {
+        return None;
+    }
+
+    let parts = (0..size.div_ceil(8))
+        .map(|idx| {
+            let start = idx * 8;
+            let is_sse = fields
+                .iter()
+                .filter(|(offset, _)| (start..start + 8).contains(offset))
+                .all(|(_, field)| field.is_float());
+
+            match (is_sse, size - start) {
+                (true, ..=4) => DataType::Float,
+                (true, _) => DataType::Float64,
+                (false, rest) => integer_of_size(rest.min(8)),
+            }
+        })
+        .collect();
+
+    Some(parts)
+}
+
+/// windows übergibt nur klassen mit 1, 2, 4 oder 8 bytes in einem register
+fn win64_part(_type: &DataType) -> Option<DataType> {
+    match _type.size() {
+        size @ (1 | 2 | 4 | 8) => Some(integer_of_size(size)),
+        _ => None,
+    }
+}
+
+/// bis zu vier gleiche floats kommen in fp register, sonst bis 16 bytes in integer register
+fn aarch64_part(_type: &DataType) -> Option<DataType> {
+    let mut fields = vec![];
+    scalar_fields(_type, 0, &mut fields);
+
+    if let Some((_, first)) = fields.first() {
+        if first.is_float()
+            && fields.len() <= 4
+            && fields.iter().all(|(_, field)| field == first)
+            && fields.len() * first.size() == _type.size()
+        {
+            return Some(DataType::Array {
+                value_type: Box::new(first.clone()),
+                len: fields.len(),
+            });
+        }
+    }
+
+    match _type.size() {
+        ..=8 => Some(DataType::Integer64),
+        9..=16 => Some(DataType::Array {
+            value_type: Box::new(DataType::Integer64),
+            len: 2,
+        }),
+        _ => None,
+    }
+}
+
+impl CodeGenerator {
+    /// `self` ist wie bei mx funktionen ein pointer
+    pub(in crate::codegen) fn c_signature(&self, function: &Function) -> CSignature {
+        let params = function
+            .params
+            .value
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| {
+                match function.is_method() && !function.is_static_method() && idx == 0 {
+                    true => DataType::Pointer(Box::new(p.value._type.value.clone())),
+                    false => p.value._type.value.clone(),
+                }
+            })
+            .collect_vec();
+
+        Abi::host().signature(&params, &function.return_type.value)
+    }
+
+    /// symbol der funktion mit c abi, für `extern def` mit body und `#[export]`
+    pub(in crate::codegen) fn c_abi_symbol(&self, function: &Function) -> String {
+        match function.export {
+            true => function.display_name.value.clone(),
+            false => format!("{}.c", self.function_symbol(&function.name)),
+        }
+    }
+
+    /// `declare` einer `extern` funktion mit den parametern im c abi
+    pub(in crate::codegen) fn c_declaration(&mut self, function: &Function) -> Instruction {
+        let signature = self.c_signature(function);
+
+        Instruction::DeclareFunction(IRFunction {
+            name: self.function_symbol(&function.name),
+            params: signature
+                .lowered_params()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (_type, attribute))| IRFunctionParam {
+                    name: idx.to_string(),
+                    _type,
+                    attribute,
+                })
+                .collect(),
+            body: IRFunctionBody {
+                instructions: vec![],
+            },
+            return_type: self.lowered_return_type(&signature),
+            is_extern: true,
+            is_variadic: function.is_variadic,
+            is_internal: false,
+        })
+    }
+
+    /// aufruf einer `extern` funktion, klassen werden nach dem c abi übergeben
+    pub(in crate::codegen) fn parse_c_call(
+        &mut self,
+        function: &Function,
+        values: Vec<IRValue>,
+        mut instructions: Vec<Instruction>,
+    ) -> ComputedExpression {
+        let signature = self.c_signature(function);
+        let mut args = vec![];
+        let mut arg_attributes = vec![];
+
+        let sret = match signature.return_value {
+            AbiArg::Memory => {
+                let slot = self.c_abi_slot(&mut instructions, &signature.return_type);
+                args.push(as_pointer(&slot));
+                arg_attributes.push(Some(ParamAttribute::SRet(signature.return_type.clone())));
+                Some(slot)
+            }
+            _ => None,
+        };
+
+        for (idx, value) in values.into_iter().enumerate() {
+            // variadische argumente sind nie klassen
+            let Some((_type, class)) = signature.params.get(idx) else {
+                args.push(value);
+                arg_attributes.push(None);
+                continue;
+            };
+
+            match class {
+                AbiArg::Direct => {
+                    args.push(value);
+                    arg_attributes.push(None);
+                }
+                AbiArg::Coerce(parts) => {
+                    let coerce_type = self.coerce_type(parts);
+                    let slot = self.c_abi_slot(&mut instructions, &coerce_type);
+                    let view = self.reinterpret(&mut instructions, &slot, _type);
+                    instructions.push(store(value, view));
+
+                    for part in self.coerce_parts(&mut instructions, &slot, parts) {
+                        let part = self.load_slot(&mut instructions, part);
+                        args.push(IRValue::Variable(part));
+                        arg_attributes.push(None);
+                    }
+                }
+                AbiArg::Memory | AbiArg::Indirect => {
+                    let slot = self.c_abi_slot(&mut instructions, _type);
+                    instructions.push(store(value, slot.clone()));
+                    args.push(as_pointer(&slot));
+                    arg_attributes.push(
+                        (*class == AbiArg::Memory).then(|| ParamAttribute::ByVal(_type.clone())),
+                    );
+                }
+            }
+        }
+
+        let return_type = self.lowered_return_type(&signature);
+        let call = Instruction::Call(FunctionCall {
+            name: self.function_symbol(&function.name),
+            return_type: return_type.clone(),
+            args,
+            variadic_params: function.is_variadic.then(|| {
+                signature
+                    .lowered_params()
+                    .into_iter()
+                    .map(|(_type, _)| _type)
+                    .collect()
+            }),
+            arg_attributes,
+        });
+
+        let result_var = match (&signature.return_value, sret) {
+            (_, Some(slot)) => {
+                instructions.push(call);
+                self.load_slot(&mut instructions, slot)
+            }
+            (AbiArg::Coerce(_), None) => {
+                let returned = self.next_tmp_var(&return_type);
+                instructions.push(Instruction::VRegisterAssignment {
+                    variable: returned.clone(),
+                    value: Box::new(call),
+                });
+
+                let slot = self.c_abi_slot(&mut instructions, &return_type);
+                instructions.push(store(IRValue::Variable(returned), slot.clone()));
+                let view = self.reinterpret(&mut instructions, &slot, &signature.return_type);
+                self.load_slot(&mut instructions, view)
+            }
+            _ => {
+                let result_var = self.next_tmp_var(&return_type);
+                match return_type {
+                    DataType::None => instructions.push(call),
+                    _ => instructions.push(Instruction::VRegisterAssignment {
+                        variable: result_var.clone(),
+                        value: Box::new(call),
+                    }),
+                }
+                result_var
+            }
+        };
+
+        ComputedExpression {
+            instructions,
+            result_var,
+        }
+    }
+
+    /// `name.c` für `extern def` mit body, wandelt die parameter aus dem c abi um
+    /// und ruft die mx funktion auf
+    pub(in crate::codegen) fn c_abi_wrapper(&mut self, function: &Function) -> Instruction {
+        let signature = self.c_signature(function);
+        let return_type = self.lowered_return_type(&signature);
+        let mut params = vec![];
+        let mut instructions = vec![];
+        let mut args = vec![];
+
+        let sret = IRVariable {
+            name: ".sret".to_string(),
+            _type: signature.return_type.clone(),
+        };
+
+        if signature.return_value == AbiArg::Memory {
+            params.push(IRFunctionParam {
+                name: sret.name.clone(),
+                _type: DataType::Pointer(Box::new(sret._type.clone())),
+                attribute: Some(ParamAttribute::SRet(sret._type.clone())),
+            });
+        }
+
+        for (param, (_type, class)) in function.params.value.iter().zip(signature.params.iter()) {
+            let name = param.value.name.value.clone();
+
+            let value = match class {
+                AbiArg::Direct => {
+                    params.push(IRFunctionParam {
+                        name: name.clone(),
+                        _type: _type.clone(),
+                        attribute: None,
+                    });
+                    IRVariable {
+                        name,
+                        _type: _type.clone(),
+                    }
+                }
+                AbiArg::Coerce(parts) => {
+                    let coerce_type = self.coerce_type(parts);
+                    let slot = self.c_abi_slot(&mut instructions, &coerce_type);
+                    let pointers = self.coerce_parts(&mut instructions, &slot, parts);
+
+                    for (idx, (part, pointer)) in parts.iter().zip(pointers).enumerate() {
+                        let part_param = IRFunctionParam {
+                            name: format!("{name}.{idx}"),
+                            _type: part.clone(),
+                            attribute: None,
+                        };
+                        instructions
+                            .push(store(IRValue::Variable(part_param.clone().into()), pointer));
+                        params.push(part_param);
+                    }
+
+                    let view = self.reinterpret(&mut instructions, &slot, _type);
+                    self.load_slot(&mut instructions, view)
+                }
+                AbiArg::Memory | AbiArg::Indirect => {
+                    params.push(IRFunctionParam {
+                        name: name.clone(),
+                        _type: DataType::Pointer(Box::new(_type.clone())),
+                        attribute: (*class == AbiArg::Memory)
+                            .then(|| ParamAttribute::ByVal(_type.clone())),
+                    });
+                    self.load_slot(
+                        &mut instructions,
+                        IRVariable {
+                            name,
+                            _type: _type.clone(),
+                        },
+                    )
+                }
+            };
+
+            args.push(IRValue::Variable(value));
+        }
+
+        let call = Instruction::Call(FunctionCall {
+            name: self.function_symbol(&function.name),
+            return_type: signature.return_type.clone(),
+            args,
+            variadic_params: None,
+            arg_attributes: vec![],
+        });
+
+        let returned = self.next_tmp_var(&signature.return_type);
+        match signature.return_type {
+            DataType::None => instructions.push(call),
+            _ => instructions.push(Instruction::VRegisterAssignment {
+                variable: returned.clone(),
+                value: Box::new(call),
+            }),
+        }
+
+        let expr = match &signature.return_value {
+            AbiArg::Coerce(_) => {
+                let slot = self.c_abi_slot(&mut instructions, &return_type);
+                let view = self.reinterpret(&mut instructions, &slot, &signature.return_type);
+                instructions.push(store(IRValue::Variable(returned), view));
+                IRValue::Variable(self.load_slot(&mut instructions, slot))
+            }
+            AbiArg::Memory => {
+                instructions.push(store(IRValue::Variable(returned), sret));
+                IRValue::Literal(Literal::Void)
+            }
+            _ if signature.return_type == DataType::None => IRValue::Literal(Literal::Void),
+            _ => IRValue::Variable(returned),
+        };
+        instructions.push(Instruction::Return { expr });
+
+        Instruction::DeclareFunction(IRFunction {
+            name: self.c_abi_symbol(function),
+            params,
+            body: IRFunctionBody { instructions },
+            return_type,
+            is_extern: false,
+            is_variadic: false,
+            is_internal: self.library && !function.export,
+        })
+    }
+
+    /// `callback as *int8`, zeigt auf die funktion mit c abi
+    pub(in crate::codegen) fn c_function_pointer(
+        &mut self,
+        function: &Function,
+        to: &DataType,
+    ) -> ComputedExpression {
+        let signature = self.c_signature(function);
+        let function_type = format!(
+            "{} ({})*",
+            self.lowered_return_type(&signature).to_ir(),
+            signature
+                .lowered_params()
+                .iter()
+                .map(|(_type, _)| _type.to_ir())
+                .join(", ")
+        );
+
+        let raw_pointer = self.next_tmp_var(&DataType::Pointer(Box::new(DataType::Integer8)));
+        let mut instructions = vec![Instruction::VRegisterAssignment {
+            variable: raw_pointer.clone(),
+            value: Box::new(Instruction::Cast(Cast::FunctionToPointer(
+                self.c_abi_symbol(function),
+                function_type,
+            ))),
+        }];
+
+        if raw_pointer._type == *to {
+            return ComputedExpression {
+                instructions,
+                result_var: raw_pointer,
+            };
+        }
+
+        let result_var = self.next_tmp_var(to);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: result_var.clone(),
+            value: Box::new(Instruction::Cast(Cast::Bitcast(
+                IRValue::Variable(raw_pointer),
+                to.clone(),
+            ))),
+        });
+
+        ComputedExpression {
+            instructions,
+            result_var,
+        }
+    }
+
+    fn lowered_return_type(&mut self, signature: &CSignature) -> DataType {
+        match &signature.return_value {
+            AbiArg::Coerce(parts) => self.coerce_type(parts),
+            AbiArg::Memory => DataType::None,
+            _ => signature.return_type.clone(),
+        }
+    }
+
+    /// die teile hintereinander im speicher, mehrere teile werden ein struct
+    fn coerce_type(&mut self, parts: &[DataType]) -> DataType {
+        if let [part] = parts {
+            return part.clone();
+        }
+
+        let name = format!("c_abi.{}", parts.iter().map(|p| p.to_ir()).join("."));
+        self.c_abi_types
+            .entry(name.clone())
+            .or_insert_with(|| {
+                DataType::Custom(Box::new(CustomDataType {
+                    display_name: name.clone(),
+                    name,
+                    fields: Spanned {
+                        value: parts
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, part)| FunctionParam {
+                                name: Spanned {
+                                    value: idx.to_string(),
+                                    span: Default::default(),
+                                },
+                                _type: Spanned {
+                                    value: part.clone(),
+                                    span: Default::default(),
+                                },
+                            })
+                            .collect(),
+                        span: Default::default(),
+                    },
+                    ..Default::default()
+                }))
+            })
+            .clone()
+    }
+
+    /// pointer auf die einzelnen teile in `slot`
+    fn coerce_parts(
+        &mut self,
+        instructions: &mut Vec<Instruction>,
+        slot: &IRVariable,
+        parts: &[DataType],
+    ) -> Vec<IRVariable> {
+        if parts.len() == 1 {
+            return vec![slot.clone()];
+        }
+
+        let mut pointers = vec![];
+        for (idx, part) in parts.iter().enumerate() {
+            let mut field = self.get_index_pointer(
+                slot.clone(),
+                IRValue::Literal(Literal::Integer(idx as isize)),
+                Some(part.clone()),
+            );
+            instructions.append(&mut field.instructions);
+            pointers.push(field.result_var);
+        }
+        pointers
+    }
+
+    fn c_abi_slot(&mut self, instructions: &mut Vec<Instruction>, _type: &DataType) -> IRVariable {
+        let slot = self.next_tmp_var(_type);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: slot.clone(),
+            value: Box::new(Instruction::MemoryOperation(MemoryOperation::Alloca {
+                _type: _type.clone(),
+            })),
+        });
+        slot
+    }
+
+    /// der gleiche speicher als anderer typ
+    fn reinterpret(
+        &mut self,
+        instructions: &mut Vec<Instruction>,
+        slot: &IRVariable,
+        _type: &DataType,
+    ) -> IRVariable {
+        if slot._type == *_type {
+            return slot.clone();
+        }
+
+        let pointer_type = DataType::Pointer(Box::new(_type.clone()));
+        let view = self.next_tmp_var(&pointer_type);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: view.clone(),
+            value: Box::new(Instruction::Cast(Cast::Bitcast(
+                as_pointer(slot),
+                pointer_type,
+            ))),
+        });
+
+        IRVariable {
+            name: view.name,
+            _type: _type.clone(),
+        }
+    }
+
+    fn load_slot(
+        &mut self,
+        instructions: &mut Vec<Instruction>,
+        pointer: IRVariable,
+    ) -> IRVariable {
+        let value = self.next_tmp_var(&pointer._type);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: value.clone(),
+            value: Box::new(Instruction::MemoryOperation(MemoryOperation::Load {
+                pointer,
+            })),
+        });
+        value
+    }
+}
+
+/// `alloca` variablen als wert vom typ `T*`
+fn as_pointer(slot: &IRVariable) -> IRValue {
+    IRValue::Variable(IRVariable {
+        name: slot.name.clone(),
+        _type: DataType::Pointer(Box::new(slot._type.clone())),
+    })
+}
+
+fn store(value: IRValue, pointer: IRVariable) -> Instruction {
+    Instruction::MemoryOperation(MemoryOperation::Store { value, pointer })
+}
@@ -63,7 +63,7 @@ impl CodeGenerator {
         base: &TypedExpr,
         idx: usize,
     ) -> ComputedExpression {
-        if let DataType::Custom(CustomDataType { fields, .. }) = &base._type {
+        if let Some(CustomDataType { fields, .. }) = base._type.as_custom() {
             let mut instructions = vec![];
 
             let mut base = self.parse_expression(base, true);
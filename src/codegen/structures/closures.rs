@@ -0,0 +1,568 @@
+use itertools::Itertools;
+
+use crate::{
+    codegen::{
+        codegen_main::{ClosureEnv, CodeGenerator, ComputedExpression},
+        llvm_instructions::{
+            closure_function_pointer_type, Cast, FunctionCall, IRValue, IRVariable, IndirectCall,
+            Instruction, MemoryOperation,
+        },
+    },
+    lexer::{
+        position::{Span, Spanned},
+        tokens::Literal,
+    },
+    parser::ast::{
+        Block, CallArg, Closure, CustomDataType, DataType, Expr, Function, FunctionParam,
+        Statement, TypedExpr, Variable,
+    },
+};
+
+/// erster parameter jeder closure funktion
+const CLOSURE_ENV_ARG_NAME: &str = "__env";
+
+fn env_param() -> Spanned<FunctionParam> {
+    Spanned {
+        value: FunctionParam {
+            name: Spanned {
+                value: CLOSURE_ENV_ARG_NAME.to_string(),
+                span: Span::default(),
+            },
+            _type: Spanned {
+                value: DataType::Pointer(Box::new(DataType::Integer8)),
+                span: Span::default(),
+            },
+        },
+        span: Span::default(),
+    }
+}
+
+fn function_signature(_type: &DataType) -> (Vec<DataType>, DataType) {
+    match _type {
+        DataType::Function {
+            params,
+            return_type,
+        } => (params.clone(), *return_type.clone()),
+        _ => unreachable!(),
+    }
+}
+
+impl CodeGenerator {
+    /*
+        let offset = 1;
+        let f = |x: int64| -> int64 { x + offset };
+
+        wird zu:
+
+        %closure_env_3 = type { i64 }
+
+        define i64 @closure_3(i8* %___env, i64 %_x) {
+            ; offset aus der umgebung
+        }
+
+        f = { bitcast @closure_3 to i8*, malloc(closure_env_3) }
+
+        wird `f` nur aufgerufen, liegt die umgebung mit alloca auf dem stack
+    */
+    pub(in crate::codegen) fn parse_closure(
+        &mut self,
+        closure: &Closure,
+        _type: &DataType,
+    ) -> ComputedExpression {
+        let id = self.next_count();
+        let name = format!("closure_{id}");
+        let mut instructions = vec![];
+
+        let env = match closure.captures.is_empty() {
+            true => self.null_env(&mut instructions),
+            false => {
+                let env_name = format!("closure_env_{id}");
+                let env_type = DataType::Custom(Box::new(CustomDataType {
+                    display_name: env_name.clone(),
+                    name: env_name.clone(),
+                    fields: Spanned {
+                        value: closure
+                            .captures
+                            .iter()
+                            .map(|capture| FunctionParam {
+                                name: capture.variable.name.clone(),
+                                _type: Spanned {
+                                    value: match capture.by_reference {
+                                        true => DataType::Pointer(Box::new(
+                                            capture.variable._type.clone(),
+                                        )),
+                                        false => capture.variable._type.clone(),
+                                    },
+                                    span: Span::default(),
+                                },
+                            })
+                            .collect(),
+                        span: Span::default(),
+                    },
+                    ..Default::default()
+                }));
+
+                self.program.custom_types.insert(
+                    env_name,
+                    Spanned {
+                        value: env_type.clone(),
+                        span: Span::default(),
+                    },
+                );
+
+                let env = match self.local_closures.contains(&closure.body.span) {
+                    true => {
+                        let env = self.next_tmp_var(&env_type);
+                        instructions.push(Instruction::VRegisterAssignment {
+                            variable: env.clone(),
+                            value: Box::new(Instruction::MemoryOperation(
+                                MemoryOperation::Alloca {
+                                    _type: env_type.clone(),
+                                },
+                            )),
+                        });
+                        env
+                    }
+                    false => self.heap_allocate(&mut instructions, &env_type),
+                };
+
+                for (idx, capture) in closure.captures.iter().enumerate() {
+                    let variable = self
+                        .variable_map
+                        .get(&capture.variable.name.value)
+                        .unwrap()
+                        .clone();
+
+                    // mut variablen werden über ihren pointer geteilt
+                    let value = match capture.by_reference {
+                        true => IRVariable {
+                            name: variable.name,
+                            _type: DataType::Pointer(Box::new(variable._type)),
+                        },
+                        false => {
+                            let loaded = self.next_tmp_var(&variable._type);
+                            instructions.push(Instruction::VRegisterAssignment {
+                                variable: loaded.clone(),
+                                value: Box::new(Instruction::MemoryOperation(
+                                    MemoryOperation::Load { pointer: variable },
+                                )),
+                            });
+                            loaded
+                        }
+                    };
+
+                    let mut field = self.get_index_pointer(
+                        env.clone(),
+                        IRValue::Literal(Literal::Integer(idx.try_into().unwrap())),
+                        Some(value._type.clone()),
+                    );
+                    instructions.append(&mut field.instructions);
+                    instructions.push(Instruction::MemoryOperation(MemoryOperation::Store {
+                        value: IRValue::Variable(value),
+                        pointer: field.result_var,
+                    }));
+                }
+
+                self.closure_envs.insert(
+                    name.clone(),
+                    ClosureEnv {
+                        _type: env_type.clone(),
+                        captures: closure.captures.clone(),
+                    },
+                );
+
+                let raw_env = self.next_tmp_var(&DataType::Pointer(Box::new(DataType::Integer8)));
+                instructions.push(Instruction::VRegisterAssignment {
+                    variable: raw_env.clone(),
+                    value: Box::new(Instruction::Cast(Cast::Bitcast(
+                        IRValue::Variable(IRVariable {
+                            name: env.name,
+                            _type: DataType::Pointer(Box::new(env_type)),
+                        }),
+                        DataType::Pointer(Box::new(DataType::Integer8)),
+                    ))),
+                });
+                raw_env
+            }
+        };
+
+        let mut function = Function::from_block(
+            closure.body.value.clone(),
+            name.clone(),
+            std::iter::once(env_param())
+                .chain(closure.params.value.iter().cloned())
+                .collect(),
+        );
+        function.return_type.value = closure.return_type.clone();
+
+        self.program.functions.insert(
+            name.clone(),
+            Spanned {
+                value: function,
+                span: Span::default(),
+            },
+        );
+
+        self.closure_value(instructions, &name, _type, env)
+    }
+
+    /// funktionen als wert bekommen eine funktion mit (leerer) umgebung, die sie aufruft
+    pub(in crate::codegen) fn parse_function_ref(
+        &mut self,
+        function: &Function,
+        _type: &DataType,
+    ) -> ComputedExpression {
+        let name = format!("{}__ref", function.name);
+
+        if !self.program.functions.contains_key(&name) {
+            let args = function
+                .params
+                .value
+                .iter()
+                .map(|p| TypedExpr {
+                    expression: Expr::Variable(Variable {
+                        name: p.value.name.clone(),
+                        is_mutable: false,
+                        _type: p.value._type.value.clone(),
+                    }),
+                    _type: p.value._type.value.clone(),
+                    raw: None,
+                })
+                .collect_vec();
+
+            let call = function.to_call(&args);
+            let statement = match function.return_type.value {
+                DataType::None => Statement::Expr(Spanned {
+                    value: call.expression,
+                    span: Span::default(),
+                }),
+                _ => Statement::Return {
+                    value: Spanned {
+                        value: call,
+                        span: Span::default(),
+                    },
+                },
+            };
+
+            let wrapper = Function {
+                display_name: Spanned {
+                    value: name.clone(),
+                    span: Span::default(),
+                },
+                name: name.clone(),
+                params: Spanned {
+                    value: std::iter::once(env_param())
+                        .chain(function.params.value.iter().cloned())
+                        .collect(),
+                    span: Span::default(),
+                },
+                body: Spanned {
+                    value: Block {
+                        statements: vec![Spanned {
+                            value: statement,
+                            span: Span::default(),
+                        }],
+                        return_type: function.return_type.value.clone(),
+                        ..Default::default()
+                    },
+                    span: Span::default(),
+                },
+                return_type: function.return_type.clone(),
+                ..Default::default()
+            };
+
+            self.program.functions.insert(
+                name.clone(),
+                Spanned {
+                    value: wrapper,
+                    span: Span::default(),
+                },
+            );
+        }
+
+        let mut instructions = vec![];
+        let env = self.null_env(&mut instructions);
+        self.closure_value(instructions, &name, _type, env)
+    }
+
+    pub(in crate::codegen) fn parse_closure_call(
+        &mut self,
+        closure: &TypedExpr,
+        args: &[CallArg],
+    ) -> ComputedExpression {
+        let (params, return_type) = function_signature(&closure._type);
+        let raw_pointer_type = DataType::Pointer(Box::new(DataType::Integer8));
+
+        let result_var = self.next_tmp_var(&return_type);
+        let computed = self.parse_expression(closure, true);
+        let mut instructions = computed.instructions;
+
+        let mut fields = vec![];
+        for idx in 0..2 {
+            let mut field = self.get_index_pointer(
+                computed.result_var.clone(),
+                IRValue::Literal(Literal::Integer(idx)),
+                Some(raw_pointer_type.clone()),
+            );
+            instructions.append(&mut field.instructions);
+
+            let value = self.next_tmp_var(&raw_pointer_type);
+            instructions.push(Instruction::VRegisterAssignment {
+                variable: value.clone(),
+                value: Box::new(Instruction::MemoryOperation(MemoryOperation::Load {
+                    pointer: field.result_var,
+                })),
+            });
+            fields.push(value);
+        }
+
+        let env = fields.pop().unwrap();
+        let function_pointer = self.next_tmp_var(&raw_pointer_type);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: function_pointer.clone(),
+            value: Box::new(Instruction::Cast(Cast::PointerToFunction(
+                IRValue::Variable(fields.pop().unwrap()),
+                closure_function_pointer_type(&params, &return_type),
+            ))),
+        });
+
+        let mut argument_values = vec![IRValue::Variable(env)];
+        for arg in args.iter() {
+            let mut computed = self.parse_expression(&arg.0, false);
+            instructions.append(&mut computed.instructions);
+            argument_values.push(IRValue::Variable(computed.result_var));
+        }
+
+        let call = Instruction::IndirectCall(IndirectCall {
+            pointer: function_pointer,
+            return_type: return_type.clone(),
+            args: argument_values,
+        });
+
+        if return_type != DataType::None {
+            instructions.push(Instruction::VRegisterAssignment {
+                variable: result_var.clone(),
+                value: Box::new(call),
+            });
+        } else {
+            instructions.push(call);
+        }
+
+        ComputedExpression {
+            instructions,
+            result_var,
+        }
+    }
+
+    /// packt die umgebung am anfang der closure funktion aus
+    pub(in crate::codegen) fn unpack_closure_env(&mut self, env: &ClosureEnv) -> Vec<Instruction> {
+        let mut instructions = vec![];
+
+        let env_pointer = self.next_tmp_var(&DataType::Pointer(Box::new(env._type.clone())));
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: env_pointer.clone(),
+            value: Box::new(Instruction::Cast(Cast::Bitcast(
+                IRValue::Variable(IRVariable {
+                    name: CLOSURE_ENV_ARG_NAME.to_string(),
+                    _type: DataType::Pointer(Box::new(DataType::Integer8)),
+                }),
+                DataType::Pointer(Box::new(env._type.clone())),
+            ))),
+        });
+
+        let env_var = IRVariable {
+            name: env_pointer.name,
+            _type: env._type.clone(),
+        };
+
+        for (idx, capture) in env.captures.iter().enumerate() {
+            let variable_type = capture.variable._type.clone();
+            let field_type = match capture.by_reference {
+                true => DataType::Pointer(Box::new(variable_type.clone())),
+                false => variable_type.clone(),
+            };
+
+            let mut field = self.get_index_pointer(
+                env_var.clone(),
+                IRValue::Literal(Literal::Integer(idx.try_into().unwrap())),
+                Some(field_type.clone()),
+            );
+            instructions.append(&mut field.instructions);
+
+            let variable = match capture.by_reference {
+                true => {
+                    let pointer = self.next_tmp_var(&field_type);
+                    instructions.push(Instruction::VRegisterAssignment {
+                        variable: pointer.clone(),
+                        value: Box::new(Instruction::MemoryOperation(MemoryOperation::Load {
+                            pointer: field.result_var,
+                        })),
+                    });
+                    IRVariable {
+                        name: pointer.name,
+                        _type: variable_type,
+                    }
+                }
+                false => field.result_var,
+            };
+
+            self.variable_map
+                .insert(capture.variable.name.value.clone(), variable);
+        }
+
+        instructions
+    }
+
+    /// `{ funktionspointer, umgebung }`
+    fn closure_value(
+        &mut self,
+        mut instructions: Vec<Instruction>,
+        name: &str,
+        _type: &DataType,
+        env: IRVariable,
+    ) -> ComputedExpression {
+        let (params, return_type) = function_signature(_type);
+        let raw_pointer_type = DataType::Pointer(Box::new(DataType::Integer8));
+
+        let function_pointer = self.next_tmp_var(&raw_pointer_type);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: function_pointer.clone(),
+            value: Box::new(Instruction::Cast(Cast::FunctionToPointer(
+                name.to_string(),
+                closure_function_pointer_type(&params, &return_type),
+            ))),
+        });
+
+        let result_var = self.next_tmp_var(_type);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: result_var.clone(),
+            value: Box::new(Instruction::MemoryOperation(MemoryOperation::Alloca {
+                _type: _type.clone(),
+            })),
+        });
+
+        for (idx, value) in [function_pointer, env].into_iter().enumerate() {
+            let mut field = self.get_index_pointer(
+                result_var.clone(),
+                IRValue::Literal(Literal::Integer(idx.try_into().unwrap())),
+                Some(raw_pointer_type.clone()),
+            );
+            instructions.append(&mut field.instructions);
+            instructions.push(Instruction::MemoryOperation(MemoryOperation::Store {
+                value: IRValue::Variable(value),
+                pointer: field.result_var,
+            }));
+        }
+
+        ComputedExpression {
+            instructions,
+            result_var,
+        }
+    }
+
+    fn null_env(&mut self, instructions: &mut Vec<Instruction>) -> IRVariable {
+        let env = self.next_tmp_var(&DataType::Pointer(Box::new(DataType::Integer8)));
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: env.clone(),
+            value: Box::new(Instruction::MemoryOperation(MemoryOperation::IntToPtr {
+                value: IRValue::Literal(Literal::Integer(0)),
+                pointer: env._type.clone(),
+            })),
+        });
+        env
+    }
+
+    /// die umgebung (und per referenz gefangene variablen) liegt auf dem heap,
+    /// damit closures ihre funktion überleben. die umgebung gehört dem closure wert und wird mit
+    /// `free_closure` aus `std/mem.mx` freigegeben, gefangene variablen bleiben bis zum programmende
+    pub(in crate::codegen) fn heap_allocate(
+        &mut self,
+        instructions: &mut Vec<Instruction>,
+        _type: &DataType,
+    ) -> IRVariable {
+        let mut size = self.size_of(Expr::Literal(Literal::DataType {
+            value_type: Box::new(_type.clone()),
+        }));
+        instructions.append(&mut size.instructions);
+
+        let malloc = self.malloc_function();
+        let memory = self.next_tmp_var(&malloc.return_type.value);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: memory.clone(),
+            value: Box::new(Instruction::Call(FunctionCall {
+                name: malloc.name,
+                return_type: malloc.return_type.value.clone(),
+                args: vec![IRValue::Variable(size.result_var)],
+                variadic_params: None,
+                arg_attributes: vec![],
+            })),
+        });
+
+        let pointer_type = DataType::Pointer(Box::new(_type.clone()));
+        let pointer = self.next_tmp_var(&pointer_type);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: pointer.clone(),
+            value: Box::new(match malloc.return_type.value {
+                DataType::Pointer(_) => {
+                    Instruction::Cast(Cast::Bitcast(IRValue::Variable(memory), pointer_type))
+                }
+                _ => Instruction::MemoryOperation(MemoryOperation::IntToPtr {
+                    value: IRValue::Variable(memory),
+                    pointer: pointer_type,
+                }),
+            }),
+        });
+
+        IRVariable {
+            name: pointer.name,
+            _type: _type.clone(),
+        }
+    }
+
+    /// `malloc` aus `std/mem.mx`, sonst wird es hier deklariert
+    fn malloc_function(&mut self) -> Function {
+        if let Some(malloc) = self.program.functions.get("malloc") {
+            return malloc.value.clone();
+        }
+
+        let malloc = Function {
+            display_name: Spanned {
+                value: "malloc".to_string(),
+                span: Span::default(),
+            },
+            name: "malloc".to_string(),
+            params: Spanned {
+                value: vec![Spanned {
+                    value: FunctionParam {
+                        name: Spanned {
+                            value: "size".to_string(),
+                            span: Span::default(),
+                        },
+                        _type: Spanned {
+                            value: DataType::get_integer_type(),
+                            span: Span::default(),
+                        },
+                    },
+                    span: Span::default(),
+                }],
+                span: Span::default(),
+            },
+            return_type: Spanned {
+                value: DataType::get_integer_type(),
+                span: Span::default(),
+            },
+            is_extern: true,
+            ..Default::default()
+        };
+
+        self.program.functions.insert(
+            malloc.name.clone(),
+            Spanned {
+                value: malloc.clone(),
+                span: Span::default(),
+            },
+        );
+
+        malloc
+    }
+}
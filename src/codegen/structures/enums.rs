@@ -0,0 +1,67 @@
+use crate::{
+    codegen::{
+        codegen_main::{CodeGenerator, ComputedExpression},
+        llvm_instructions::{IRValue, Instruction, MemoryOperation},
+    },
+    lexer::{position::Spanned, tokens::Literal},
+    parser::ast::{CustomDataType, DataType, TypedExpr},
+};
+
+impl CodeGenerator {
+    pub(in crate::codegen) fn parse_enum_variant(
+        &mut self,
+        _type: &DataType,
+        variant_idx: usize,
+        args: &[Spanned<TypedExpr>],
+    ) -> ComputedExpression {
+        if let Some(CustomDataType {
+            variants: Some(variants),
+            ..
+        }) = _type.as_custom()
+        {
+            let result_var = self.next_tmp_var(_type);
+            let mut instructions = vec![Instruction::VRegisterAssignment {
+                variable: result_var.clone(),
+                value: Box::new(Instruction::MemoryOperation(MemoryOperation::Alloca {
+                    _type: _type.clone(),
+                })),
+            }];
+
+            let mut tag_ptr = self.get_index_pointer(
+                result_var.clone(),
+                IRValue::Literal(Literal::Integer(0)),
+                Some(DataType::get_integer_type()),
+            );
+            instructions.append(&mut tag_ptr.instructions);
+            instructions.push(Instruction::MemoryOperation(MemoryOperation::Store {
+                value: IRValue::Literal(Literal::Integer(variant_idx.try_into().unwrap())),
+                pointer: tag_ptr.result_var,
+            }));
+
+            // felder anderer varianten bleiben uninitialisiert
+            for (field_idx, arg) in variants[variant_idx].fields.iter().zip(args) {
+                let mut computed_value = self.parse_expression(&arg.value, false);
+                instructions.append(&mut computed_value.instructions);
+
+                let mut field_ptr = self.get_index_pointer(
+                    result_var.clone(),
+                    IRValue::Literal(Literal::Integer((*field_idx).try_into().unwrap())),
+                    Some(arg.value._type.clone()),
+                );
+                instructions.append(&mut field_ptr.instructions);
+
+                instructions.push(Instruction::MemoryOperation(MemoryOperation::Store {
+                    value: IRValue::Variable(computed_value.result_var),
+                    pointer: field_ptr.result_var,
+                }));
+            }
+
+            return ComputedExpression {
+                instructions,
+                result_var,
+            };
+        }
+
+        unreachable!()
+    }
+}
@@ -2,16 +2,13 @@ use itertools::Itertools;
 
 use crate::{
     codegen::{
-        codegen_main::{CodeGenerator, ComputedExpression},
+        codegen_main::{BlockResult, CodeGenerator, ComputedExpression},
         llvm_instructions::{
-            BinaryOperation, IRBinaryOperator, IRValue, Instruction, MemoryOperation,
+            BinaryOperation, IRBinaryOperator, IRValue, Instruction, JumpKind, MemoryOperation,
         },
     },
-    lexer::{position::Spanned, tokens::Literal},
-    parser::ast::{
-        BinaryOperator, CallArg, DataType, DataTypeGetter, Expr, Function, Trait, TypedExpr,
-        Variable,
-    },
+    lexer::tokens::Literal,
+    parser::ast::{BinaryOperator, Block, DataType, DataTypeGetter, Expr, Trait, TypedExpr},
 };
 
 impl CodeGenerator {
@@ -62,67 +59,41 @@ impl CodeGenerator {
             }
 
             Expr::Block { body } => {
-                is_ref = false;
-
-                /*
-                   def main() -> int {
-                       let bar = 10;
-                       let foo = {
-                           return bar + 1;
-                       }
-                       return foo;
-                   }
-
-                   wird zu:
-
-                   def main() -> int {
-                       let bar = 10;
-                       let foo = block_1(bar);
-                       return foo;
-                   }
-
-                   def block_1(bar: int) -> int {
-                       return bar + 1;
-                   }
-
-
-                */
-
-                let name = format!("block_{}", self.next_count());
-                let block_func = Function::from_block(body.value, name);
-
-                let virtual_args = block_func
-                    .params
-                    .value
-                    .iter()
-                    .map(|p| {
-                        CallArg(TypedExpr {
-                            expression: Expr::Variable(Variable {
-                                name: p.value.name.clone(),
-                                is_mutable: false,
-                                _type: p.value._type.value.clone(),
-                            }),
-                            _type: p.value._type.value.clone(),
-                            raw: None,
-                        })
-                    })
-                    .collect_vec();
-
-                self.program.functions.insert(
-                    block_func.name.clone(),
-                    Spanned {
-                        value: block_func.clone(),
-                        span: Default::default(),
-                    },
-                );
-
-                self.parse_func_call(&block_func, &virtual_args)
-            } // value
+                // ohne wert gibt es keinen pointer
+                is_ref = expr._type != DataType::None;
+                self.parse_block_expression(&body.value, &expr._type)
+            }
 
             Expr::Reference { value } => {
                 is_ref = true;
                 self.parse_reference(&value.value)
             }
+            Expr::EnumVariant {
+                variant_idx, args, ..
+            } => {
+                is_ref = true;
+                self.parse_enum_variant(&expr._type, variant_idx, &args.value)
+            }
+            Expr::Match { value, arms } => {
+                // ohne wert gibt es keinen pointer
+                is_ref = expr._type != DataType::None;
+                self.parse_match(&value.value, &arms, &expr._type)
+            }
+            Expr::Closure(closure) => {
+                is_ref = true;
+                self.parse_closure(&closure, &expr._type)
+            }
+            Expr::FunctionRef(function) => {
+                is_ref = true;
+                self.parse_function_ref(&function, &expr._type)
+            }
+            Expr::ClosureCall { closure, args } => {
+                is_ref = false;
+                self.parse_closure_call(
+                    &closure.value,
+                    &args.value.iter().map(|a| a.value.clone()).collect_vec(),
+                )
+            }
             _ => unreachable!(),
         };
 
@@ -159,6 +130,62 @@ impl CodeGenerator {
         }
     }
 
+    /*
+        let bar = 10;
+        let foo = {
+            if bar > 5 {
+                return bar;
+            }
+            return bar + 1;
+        };
+
+        wird zu:
+
+        %foo = alloca i64
+        ; return speichert den wert und springt ans ende
+        store i64 %bar, i64* %foo
+        br label %block_end_1
+        ...
+        block_end_1:
+    */
+    fn parse_block_expression(&mut self, body: &Block, _type: &DataType) -> ComputedExpression {
+        let count = self.next_count();
+        let end_label = format!("block_end_{}", count);
+        let result_var = self.next_tmp_var(_type);
+        let mut instructions = vec![];
+
+        if *_type != DataType::None {
+            instructions.push(Instruction::VRegisterAssignment {
+                variable: result_var.clone(),
+                value: Box::new(Instruction::MemoryOperation(MemoryOperation::Alloca {
+                    _type: _type.clone(),
+                })),
+            });
+        }
+
+        // variablen aus dem block sind danach nicht mehr sichtbar
+        let outer_variables = self.variable_map.clone();
+
+        self.block_results.push(BlockResult {
+            result_var: result_var.clone(),
+            end_label: end_label.clone(),
+        });
+        instructions.append(&mut self.parse_block(body));
+        self.block_results.pop();
+
+        self.variable_map = outer_variables;
+
+        instructions.push(Instruction::Jump(JumpKind::Jump {
+            label: end_label.clone(),
+        }));
+        instructions.push(Instruction::BlockDecl { label: end_label });
+
+        ComputedExpression {
+            instructions,
+            result_var,
+        }
+    }
+
     fn parse_binary_expr(
         &mut self,
         lhs: &TypedExpr,
@@ -203,6 +230,7 @@ impl CodeGenerator {
                 false
             }
         };
+        let is_unsigned = _type.is_unsigned_integer();
 
         let operator = match op {
             // TODO: binop enum
@@ -230,6 +258,8 @@ impl CodeGenerator {
             BinaryOperator::Divide => {
                 if numerical_is_float {
                     IRBinaryOperator::FDiv
+                } else if is_unsigned {
+                    IRBinaryOperator::UDiv
                 } else {
                     IRBinaryOperator::Div
                 }
@@ -237,6 +267,8 @@ impl CodeGenerator {
             BinaryOperator::Modulo => {
                 if numerical_is_float {
                     IRBinaryOperator::FRem
+                } else if is_unsigned {
+                    IRBinaryOperator::URem
                 } else {
                     IRBinaryOperator::SRem
                 }
@@ -244,12 +276,22 @@ impl CodeGenerator {
 
             BinaryOperator::Equal => IRBinaryOperator::Eq,
             BinaryOperator::NotEqual => IRBinaryOperator::Ne,
+            BinaryOperator::GreaterThan if is_unsigned => IRBinaryOperator::Ugt,
+            BinaryOperator::GreaterThanOrEqual if is_unsigned => IRBinaryOperator::Uge,
+            BinaryOperator::LessThan if is_unsigned => IRBinaryOperator::Ult,
+            BinaryOperator::LessThanOrEqual if is_unsigned => IRBinaryOperator::Ule,
             BinaryOperator::GreaterThan => IRBinaryOperator::Sgt,
             BinaryOperator::GreaterThanOrEqual => IRBinaryOperator::Sge,
             BinaryOperator::LessThan => IRBinaryOperator::Slt,
             BinaryOperator::LessThanOrEqual => IRBinaryOperator::Sle,
             BinaryOperator::And => IRBinaryOperator::And,
             BinaryOperator::Or => IRBinaryOperator::Or,
+            BinaryOperator::BitAnd => IRBinaryOperator::And,
+            BinaryOperator::BitOr => IRBinaryOperator::Or,
+            BinaryOperator::BitXor => IRBinaryOperator::Xor,
+            BinaryOperator::ShiftLeft => IRBinaryOperator::Shl,
+            BinaryOperator::ShiftRight if is_unsigned => IRBinaryOperator::LShr,
+            BinaryOperator::ShiftRight => IRBinaryOperator::AShr,
         };
 
         if operator.is_ordering() {
@@ -316,7 +358,10 @@ impl CodeGenerator {
 
     fn parse_deref(&mut self, base: &TypedExpr) -> ComputedExpression {
         let mut instructions = vec![];
-        let result_var = self.next_tmp_var(&base._type);
+        let DataType::Pointer(value_type) = &base._type else {
+            unreachable!("deref of {}", base._type)
+        };
+        let result_var = self.next_tmp_var(value_type);
         let mut base = self.parse_expression(base, true);
 
         instructions.append(&mut base.instructions);
@@ -0,0 +1,198 @@
+use crate::{
+    codegen::{
+        codegen_main::{CodeGenerator, LoopLabels},
+        llvm_instructions::{
+            BinaryOperation, IRBinaryOperator, IRValue, Instruction, JumpKind, MemoryOperation,
+        },
+        utils::InstructionVecExt,
+    },
+    lexer::{position::Spanned, tokens::Literal},
+    parser::ast::{Block, DataType, Expr, ForIterable, Trait, TypedExpr, Variable},
+};
+
+impl CodeGenerator {
+    /*
+        for x in list {
+            ...
+        }
+
+        wird zu:
+
+        let idx = 0;
+        let len = list.iter_len();
+        while idx < len {
+            let x = list.iter_get(idx);
+            ...
+            idx = idx + 1;
+        }
+    */
+    pub(in crate::codegen) fn parse_for(
+        &mut self,
+        variable: &Variable,
+        iterable: &ForIterable,
+        body: &Block,
+    ) -> Vec<Instruction> {
+        let mut instructions = vec![];
+        let count = self.next_count();
+
+        // die laufvariable kann eine äußere variable überdecken
+        let shadowed = self.variable_map.get(&variable.name.value).cloned();
+
+        let (start, end, element) = match iterable {
+            ForIterable::Range { start, end } => (start.value.clone(), end.value.clone(), None),
+            ForIterable::Iterable(iterable) => {
+                // das iterierte objekt wird nur einmal ausgewertet
+                let iterable_var = self.hidden_variable(
+                    &mut instructions,
+                    format!("for-iter-{}", count),
+                    &iterable.value,
+                );
+
+                let len = match &iterable.value._type {
+                    DataType::Array { len, .. } => TypedExpr {
+                        expression: Expr::Literal(Literal::Integer(*len as isize)),
+                        _type: DataType::Integer64,
+                        raw: None,
+                    },
+                    _ => self
+                        .iterable_trait_call(&Trait::IterLen, std::slice::from_ref(&iterable_var)),
+                };
+
+                let start = TypedExpr {
+                    expression: Expr::Literal(Literal::Integer(0)),
+                    _type: DataType::Integer64,
+                    raw: None,
+                };
+
+                (start, len, Some(iterable_var))
+            }
+        };
+
+        let counter_name = match element {
+            Some(_) => format!("for-idx-{}", count),
+            None => variable.name.value.clone(),
+        };
+
+        let counter = self.hidden_variable(&mut instructions, counter_name, &start);
+
+        let mut end = self.parse_expression(&end, false);
+        instructions.append(&mut end.instructions);
+
+        let head_label = format!("for_head_{}", count);
+        let body_label = format!("for_body_{}", count);
+        let step_label = format!("for_step_{}", count);
+        let end_label = format!("end_for_{}", count);
+
+        let head_jump = Instruction::Jump(JumpKind::Jump {
+            label: head_label.clone(),
+        });
+
+        instructions.push(head_jump.clone());
+        instructions.push(Instruction::BlockDecl { label: head_label });
+
+        let mut counter_value = self.parse_expression(&counter, false);
+        instructions.append(&mut counter_value.instructions);
+
+        let less_than = if counter_value.result_var._type.is_unsigned_integer() {
+            IRBinaryOperator::Ult
+        } else {
+            IRBinaryOperator::Slt
+        };
+
+        let cond = self.next_tmp_var(&DataType::Boolean);
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: cond.clone(),
+            value: Box::new(Instruction::BinaryOperation(BinaryOperation {
+                lhs: IRValue::Variable(counter_value.result_var),
+                operator: less_than,
+                rhs: IRValue::Variable(end.result_var),
+            })),
+        });
+
+        instructions.push(Instruction::Jump(JumpKind::ConditionJump {
+            condition: IRValue::Variable(cond),
+            true_label: body_label.clone(),
+            false_label: end_label.clone(),
+        }));
+
+        instructions.push(Instruction::BlockDecl { label: body_label });
+
+        if let Some(iterable_var) = element {
+            let value = match &iterable_var._type {
+                DataType::Array { value_type, .. } => TypedExpr {
+                    expression: Expr::Index {
+                        base: Box::new(Spanned {
+                            value: iterable_var.clone(),
+                            span: Default::default(),
+                        }),
+                        idx: Box::new(Spanned {
+                            value: counter.clone(),
+                            span: Default::default(),
+                        }),
+                    },
+                    _type: *value_type.clone(),
+                    raw: None,
+                },
+                _ => self.iterable_trait_call(&Trait::IterGet, &[iterable_var, counter.clone()]),
+            };
+
+            instructions.append(&mut self.parse_variable_decl(&variable.name, &value));
+        }
+
+        self.loop_labels.push(LoopLabels {
+            continue_label: step_label.clone(),
+            break_label: end_label.clone(),
+        });
+        instructions.append(&mut self.parse_block(body));
+        self.loop_labels.pop();
+
+        instructions.push(Instruction::Jump(JumpKind::Jump {
+            label: step_label.clone(),
+        }));
+        instructions.push(Instruction::BlockDecl { label: step_label });
+
+        let mut counter_ptr = self.parse_expression(&counter, true);
+        let mut counter_value = self.parse_expression(&counter, false);
+        instructions.append(&mut counter_ptr.instructions);
+        instructions.append(&mut counter_value.instructions);
+
+        let incremented = self.next_tmp_var(&counter._type);
+        instructions.append(&mut vec![
+            Instruction::VRegisterAssignment {
+                variable: incremented.clone(),
+                value: Box::new(Instruction::BinaryOperation(BinaryOperation {
+                    lhs: IRValue::Variable(counter_value.result_var),
+                    operator: IRBinaryOperator::Add,
+                    rhs: IRValue::Literal(Literal::Integer(1)),
+                })),
+            },
+            Instruction::MemoryOperation(MemoryOperation::Store {
+                value: IRValue::Variable(incremented),
+                pointer: counter_ptr.result_var,
+            }),
+        ]);
+
+        instructions.push(head_jump);
+        instructions.push(Instruction::BlockDecl { label: end_label });
+
+        if let Some(shadowed) = shadowed {
+            self.variable_map
+                .insert(variable.name.value.clone(), shadowed);
+        }
+
+        instructions.move_allocations_to_top(); // um stackoverflows zu vermeiden
+
+        instructions
+    }
+
+    fn iterable_trait_call(&mut self, trait_: &Trait, args: &[TypedExpr]) -> TypedExpr {
+        let params = args.iter().map(|a| a._type.clone()).collect::<Vec<_>>();
+        let type_info = self.program.get_type_info(&params[0]);
+
+        // wurde im parser geprüft
+        self.program
+            .get_trait_function(&type_info, trait_, &params)
+            .unwrap()
+            .to_call(args)
+    }
+}
@@ -5,17 +5,24 @@ use crate::{
         codegen_main::{CodeGenerator, ComputedExpression},
         llvm_instructions::{FunctionCall, IRValue, IRVariable, Instruction, MemoryOperation},
         structs::{IRFunction, IRFunctionBody, IRFunctionParam},
+        structures::main_function::RUNTIME_FUNCTIONS,
         utils::InstructionVecExt,
     },
     lexer::tokens::Literal,
-    parser::ast::{
-        Block, CallArg, CommonGeneric, DataType, Function, FunctionParam, CLASS_SELF_ARG_NAME,
+    parser::{
+        ast::{
+            Block, CallArg, CommonGeneric, DataType, Function, FunctionParam, CLASS_SELF_ARG_NAME,
+        },
+        structures::closures::{local_closures, reference_captures},
     },
 };
 
 impl CodeGenerator {
     pub fn parse_func_def(&mut self, function: Function) -> Vec<Instruction> {
-        if function.is_builtin {
+        // `mx_argc` und `mx_argv` definiert der wrapper von `main`
+        if function.is_builtin
+            || (function.is_extern && RUNTIME_FUNCTIONS.contains(&function.name.as_str()))
+        {
             return vec![];
         }
 
@@ -29,17 +36,23 @@ impl CodeGenerator {
             return instructions;
         }
 
-        let param_to_var_instructions = match function.is_extern {
-            true => vec![],
-            false => self.parse_func_params(
-                &function
-                    .params
-                    .value
-                    .iter()
-                    .map(|p| p.value.clone())
-                    .collect_vec(),
-            ),
-        };
+        self.declare_globals();
+
+        if function.is_extern {
+            return vec![self.c_declaration(&function)];
+        }
+
+        self.boxed_variables = reference_captures(&function.body.value);
+        self.local_closures = local_closures(&function.body.value);
+
+        let param_to_var_instructions = self.parse_func_params(
+            &function
+                .params
+                .value
+                .iter()
+                .map(|p| p.value.clone())
+                .collect_vec(),
+        );
 
         let params: Vec<IRFunctionParam> = function
             .params
@@ -53,13 +66,20 @@ impl CodeGenerator {
                 } else {
                     p.value._type.value.clone()
                 },
+                attribute: None,
             })
             .collect();
 
+        let env_instructions = match self.closure_envs.get(&function.name).cloned() {
+            Some(env) => self.unpack_closure_env(&env),
+            None => vec![],
+        };
+
         let mut body = IRFunctionBody {
             instructions: {
                 let mut instructions = Vec::new();
                 instructions.extend(param_to_var_instructions);
+                instructions.extend(env_instructions);
                 instructions.extend(self.parse_block(&function.body.value));
                 instructions.kill_last_unused();
                 instructions
@@ -72,13 +92,22 @@ impl CodeGenerator {
             });
         }
 
-        vec![Instruction::DeclareFunction(IRFunction {
-            name: function.name,
+        let mut instructions = vec![Instruction::DeclareFunction(IRFunction {
+            name: self.function_symbol(&function.name),
             params,
             body,
-            return_type: function.return_type.value,
-            is_extern: function.is_extern,
-        })]
+            return_type: function.return_type.value.clone(),
+            is_extern: false,
+            is_variadic: false,
+            // bibliotheken zeigen nur die `#[export]` symbole mit c abi
+            is_internal: self.library,
+        })];
+
+        if function.c_abi {
+            instructions.push(self.c_abi_wrapper(&function));
+        }
+
+        instructions
     }
 
     fn parse_func_params(&mut self, params: &[FunctionParam]) -> Vec<Instruction> {
@@ -153,10 +182,16 @@ impl CodeGenerator {
             instructions.append(&mut computed.instructions);
         }
 
+        if function.is_extern {
+            return self.parse_c_call(function, argument_values, instructions);
+        }
+
         let call = Instruction::Call(FunctionCall {
-            name: function.name.clone(),
+            name: self.function_symbol(&function.name),
             return_type: function.return_type.value.clone(),
             args: argument_values,
+            variadic_params: None,
+            arg_attributes: vec![],
         });
 
         if DataType::None != function.return_type.value {
@@ -0,0 +1,38 @@
+use crate::codegen::{
+    codegen_main::CodeGenerator,
+    llvm_instructions::{IRVariable, Instruction},
+};
+
+impl CodeGenerator {
+    /// `@NAME__global = internal global i64 0`
+    pub fn parse_globals(&self) -> Vec<Instruction> {
+        self.program
+            .globals
+            .iter()
+            .map(|(name, global)| Instruction::DeclareGlobal {
+                name: global_name(name),
+                _type: global.value._type.value.clone(),
+                value: global.value.value.clone(),
+                is_const: !global.value.is_mutable,
+            })
+            .collect()
+    }
+
+    /// globale variablen sind in jeder funktion sichtbar, lokale überdecken sie
+    pub fn declare_globals(&mut self) {
+        for (name, global) in self.program.globals.iter() {
+            self.variable_map.insert(
+                name.clone(),
+                IRVariable {
+                    name: format!("@{}", global_name(name)),
+                    _type: global.value._type.value.clone(),
+                },
+            );
+        }
+    }
+}
+
+/// eigener name, damit sich globale nicht mit funktionen überschneiden
+fn global_name(name: &str) -> String {
+    format!("{name}__global")
+}
@@ -0,0 +1,216 @@
+use crate::{
+    codegen::{
+        codegen_main::CodeGenerator,
+        llvm_instructions::{
+            Cast, FunctionCall, IRValue, IRVariable, Instruction, MemoryOperation,
+        },
+        structs::{IRFunction, IRFunctionBody, IRFunctionParam},
+    },
+    lexer::tokens::Literal,
+    parser::ast::{CommonGeneric, DataType, DataTypeGetter, MAIN_ARGS_FUNCTION_NAME},
+};
+
+/// name des `main` aus dem programm, `main` selbst ist der wrapper für c
+pub const MX_MAIN_NAME: &str = "__mx_main";
+
+/// werden hier erzeugt, in der sprache aber als `extern def` deklariert
+pub const RUNTIME_FUNCTIONS: [&str; 2] = ["mx_argc", "mx_argv"];
+
+impl CodeGenerator {
+    /// ob `main` einen wrapper bekommt, methoden oder externe funktionen zählen nicht
+    pub(in crate::codegen) fn wraps_main(&self) -> bool {
+        self.program.functions.get("main").is_some_and(|main| {
+            !main.value.is_extern && !main.value.is_method() && !main.value.is_generic()
+        })
+    }
+
+    /// der name der funktion im ir
+    pub(in crate::codegen) fn function_symbol(&self, name: &str) -> String {
+        match name == "main" && self.wraps_main() {
+            true => MX_MAIN_NAME.to_string(),
+            false => name.to_string(),
+        }
+    }
+
+    /// `main(i32, i8**)` für c, merkt sich `argc` und `argv` für `mx_argc` und `mx_argv`
+    /// und gibt den rückgabewert von `main` als exit code zurück. nur `main` ist ein globales
+    /// symbol, die beiden helfer kollidieren so nicht mit anderen objekt dateien
+    pub(in crate::codegen) fn main_wrapper(&self) -> Vec<Instruction> {
+        if !self.wraps_main() {
+            return vec![];
+        }
+        let main = &self.program.functions["main"].value;
+
+        let argc_global = IRVariable {
+            name: "@__mx_argc".to_string(),
+            _type: DataType::Integer32,
+        };
+        let argv_type = DataType::Pointer(Box::new(DataType::get_integer_type()));
+        let argv_global = IRVariable {
+            name: "@__mx_argv".to_string(),
+            _type: argv_type.clone(),
+        };
+
+        let argc = IRFunctionParam {
+            name: "argc".to_string(),
+            _type: DataType::Integer32,
+            attribute: None,
+        };
+        let argv = IRFunctionParam {
+            name: "argv".to_string(),
+            _type: DataType::Pointer(Box::new(DataType::Pointer(Box::new(DataType::Integer8)))),
+            attribute: None,
+        };
+
+        let argv_ptr = IRVariable {
+            name: self.next_var_name(),
+            _type: argv_type.clone(),
+        };
+        let mut body = vec![
+            Instruction::MemoryOperation(MemoryOperation::Store {
+                value: IRValue::Variable(argc.clone().into()),
+                pointer: argc_global.clone(),
+            }),
+            Instruction::VRegisterAssignment {
+                variable: argv_ptr.clone(),
+                value: Box::new(Instruction::Cast(Cast::Bitcast(
+                    IRValue::Variable(argv.clone().into()),
+                    argv_type.clone(),
+                ))),
+            },
+            Instruction::MemoryOperation(MemoryOperation::Store {
+                value: IRValue::Variable(argv_ptr),
+                pointer: argv_global.clone(),
+            }),
+        ];
+
+        // `def main(args: List<String>)`, die liste baut eine generierte funktion
+        let mut args = vec![];
+        if let Some(param) = main.params.value.first() {
+            let list = IRVariable {
+                name: self.next_var_name(),
+                _type: param.value._type.value.clone(),
+            };
+            body.push(Instruction::VRegisterAssignment {
+                variable: list.clone(),
+                value: Box::new(Instruction::Call(runtime_call(
+                    MAIN_ARGS_FUNCTION_NAME,
+                    list._type.clone(),
+                    vec![],
+                ))),
+            });
+            args.push(IRValue::Variable(list));
+        }
+
+        let return_type = main.return_type.value.clone();
+        let call = runtime_call(MX_MAIN_NAME, return_type.clone(), args);
+
+        let result = match return_type {
+            // ohne rückgabewert ist der exit code 0
+            DataType::None => {
+                body.push(Instruction::Call(call));
+                IRValue::Literal(Literal::Integer(0))
+            }
+            _ => {
+                let result = IRVariable {
+                    name: self.next_var_name(),
+                    _type: return_type,
+                };
+                body.push(Instruction::VRegisterAssignment {
+                    variable: result.clone(),
+                    value: Box::new(Instruction::Call(call)),
+                });
+                IRValue::Variable(result)
+            }
+        };
+
+        let exit_code = match result._type() {
+            DataType::Integer32 | DataType::UnsignedInteger32 => result,
+            _type => {
+                let exit_code = IRVariable {
+                    name: self.next_var_name(),
+                    _type: DataType::Integer32,
+                };
+                let cast = match _type {
+                    DataType::Integer64 | DataType::UnsignedInteger64 => Cast::IntDown,
+                    DataType::Boolean
+                    | DataType::UnsignedInteger8
+                    | DataType::UnsignedInteger16 => Cast::UnsignedIntUp,
+                    _ => Cast::SignedIntUp,
+                };
+                body.push(Instruction::VRegisterAssignment {
+                    variable: exit_code.clone(),
+                    value: Box::new(Instruction::Cast(cast(result, DataType::Integer32))),
+                });
+                IRValue::Variable(exit_code)
+            }
+        };
+        body.push(Instruction::Return { expr: exit_code });
+
+        vec![
+            Instruction::DeclareGlobal {
+                name: "__mx_argc".to_string(),
+                _type: DataType::Integer32,
+                value: Literal::Integer(0),
+                is_const: false,
+            },
+            Instruction::DeclareGlobal {
+                name: "__mx_argv".to_string(),
+                _type: argv_type,
+                value: Literal::Integer(0),
+                is_const: false,
+            },
+            self.runtime_getter("mx_argc", argc_global),
+            self.runtime_getter("mx_argv", argv_global),
+            Instruction::DeclareFunction(IRFunction {
+                name: "main".to_string(),
+                params: vec![argc, argv],
+                body: IRFunctionBody { instructions: body },
+                return_type: DataType::Integer32,
+                is_extern: false,
+                is_variadic: false,
+                is_internal: false,
+            }),
+        ]
+    }
+
+    /// `mx_argc()` und `mx_argv()` laden den wert, den `main` gespeichert hat
+    fn runtime_getter(&self, name: &str, global: IRVariable) -> Instruction {
+        let value = IRVariable {
+            name: self.next_var_name(),
+            _type: global._type.clone(),
+        };
+
+        Instruction::DeclareFunction(IRFunction {
+            name: name.to_string(),
+            params: vec![],
+            body: IRFunctionBody {
+                instructions: vec![
+                    Instruction::VRegisterAssignment {
+                        variable: value.clone(),
+                        value: Box::new(Instruction::MemoryOperation(MemoryOperation::Load {
+                            pointer: global.clone(),
+                        })),
+                    },
+                    Instruction::Return {
+                        expr: IRValue::Variable(value),
+                    },
+                ],
+            },
+            return_type: global._type,
+            is_extern: false,
+            is_variadic: false,
+            is_internal: true,
+        })
+    }
+}
+
+fn runtime_call(name: &str, return_type: DataType, args: Vec<IRValue>) -> FunctionCall {
+    FunctionCall {
+        name: name.to_string(),
+        return_type,
+        args,
+        variadic_params: None,
+        arg_attributes: vec![],
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    codegen::{
+        codegen_main::{CodeGenerator, ComputedExpression},
+        llvm_instructions::{
+            BinaryOperation, IRBinaryOperator, IRValue, Instruction, JumpKind, MemoryOperation,
+        },
+        utils::InstructionVecExt,
+    },
+    lexer::{position::Spanned, tokens::Literal},
+    parser::ast::{CustomDataType, DataType, Expr, MatchArm, MatchPattern, TypedExpr},
+};
+
+impl CodeGenerator {
+    /*
+        let area = match shape {
+            Shape::Circle(r) => r * r * 3.14,
+            Shape::Rect(w, h) => w * h,
+        };
+
+        wird zu:
+
+        let tag = shape.tag;
+        if tag == 0 {
+            let r = shape.Circle.0;
+            area = r * r * 3.14;
+        } else if tag == 1 {
+            let w = shape.Rect.0;
+            let h = shape.Rect.1;
+            area = w * h;
+        }
+    */
+    pub(in crate::codegen) fn parse_match(
+        &mut self,
+        value: &TypedExpr,
+        arms: &[Spanned<MatchArm>],
+        _type: &DataType,
+    ) -> ComputedExpression {
+        let enum_type = match value._type.as_custom() {
+            Some(
+                custom_type @ CustomDataType {
+                    variants: Some(_), ..
+                },
+            ) => custom_type.clone(),
+            _ => unreachable!(),
+        };
+
+        let mut instructions = vec![];
+        let count = self.next_count();
+
+        // der wert wird nur einmal ausgewertet
+        let value =
+            self.hidden_variable(&mut instructions, format!("match-value-{}", count), value);
+
+        let mut tag = self.parse_expression(
+            &TypedExpr {
+                expression: Expr::FieldAccess {
+                    base: Box::new(Spanned {
+                        value: value.clone(),
+                        span: Default::default(),
+                    }),
+                    field: Spanned {
+                        value: "tag".to_string(),
+                        span: Default::default(),
+                    },
+                    field_idx: 0,
+                },
+                _type: DataType::get_integer_type(),
+                raw: None,
+            },
+            false,
+        );
+        instructions.append(&mut tag.instructions);
+
+        let result_var = self.next_tmp_var(_type);
+        if *_type != DataType::None {
+            instructions.push(Instruction::VRegisterAssignment {
+                variable: result_var.clone(),
+                value: Box::new(Instruction::MemoryOperation(MemoryOperation::Alloca {
+                    _type: _type.clone(),
+                })),
+            });
+        }
+
+        let end_label = format!("end_match_{}", count);
+        let end_jump = Instruction::Jump(JumpKind::Jump {
+            label: end_label.clone(),
+        });
+
+        for (idx, arm) in arms.iter().enumerate() {
+            let arm_label = format!("match_arm_{}_{}", count, idx);
+            let next_label = format!("match_next_{}_{}", count, idx);
+
+            match &arm.value.pattern.value {
+                MatchPattern::Variant { variant_idx, .. } => {
+                    let cond = self.next_tmp_var(&DataType::Boolean);
+                    instructions.push(Instruction::VRegisterAssignment {
+                        variable: cond.clone(),
+                        value: Box::new(Instruction::BinaryOperation(BinaryOperation {
+                            lhs: IRValue::Variable(tag.result_var.clone()),
+                            operator: IRBinaryOperator::Eq,
+                            rhs: IRValue::Literal(Literal::Integer(
+                                (*variant_idx).try_into().unwrap(),
+                            )),
+                        })),
+                    });
+                    instructions.push(Instruction::Jump(JumpKind::ConditionJump {
+                        condition: IRValue::Variable(cond),
+                        true_label: arm_label.clone(),
+                        false_label: next_label.clone(),
+                    }));
+                }
+                MatchPattern::Wildcard => {
+                    instructions.push(Instruction::Jump(JumpKind::Jump {
+                        label: arm_label.clone(),
+                    }));
+                }
+            }
+
+            instructions.push(Instruction::BlockDecl { label: arm_label });
+
+            let mut shadowed = vec![];
+
+            if let MatchPattern::Variant {
+                variant_idx,
+                bindings,
+                ..
+            } = &arm.value.pattern.value
+            {
+                let variant = &enum_type.variants.as_ref().unwrap()[*variant_idx];
+
+                for (binding, field_idx) in bindings.iter().zip(variant.fields.iter()) {
+                    if binding.value == "_" {
+                        continue;
+                    }
+
+                    shadowed.push((
+                        binding.value.clone(),
+                        self.variable_map.get(&binding.value).cloned(),
+                    ));
+
+                    let field_value = TypedExpr {
+                        expression: Expr::FieldAccess {
+                            base: Box::new(Spanned {
+                                value: value.clone(),
+                                span: Default::default(),
+                            }),
+                            field: binding.clone(),
+                            field_idx: *field_idx,
+                        },
+                        _type: enum_type.fields.value[*field_idx]._type.value.clone(),
+                        raw: None,
+                    };
+
+                    instructions.append(&mut self.parse_variable_decl(binding, &field_value));
+                }
+            }
+
+            let mut body = self.parse_expression(&arm.value.body.value, false);
+            instructions.append(&mut body.instructions);
+
+            if *_type != DataType::None {
+                instructions.push(Instruction::MemoryOperation(MemoryOperation::Store {
+                    value: IRValue::Variable(body.result_var),
+                    pointer: result_var.clone(),
+                }));
+            }
+
+            for (name, variable) in shadowed {
+                if let Some(variable) = variable {
+                    self.variable_map.insert(name, variable);
+                }
+            }
+
+            instructions.push(end_jump.clone());
+            instructions.push(Instruction::BlockDecl { label: next_label });
+        }
+
+        // wird nie erreicht, alle varianten sind abgedeckt (siehe parser)
+        instructions.push(end_jump);
+        instructions.push(Instruction::BlockDecl { label: end_label });
+
+        instructions.move_allocations_to_top(); // um stackoverflows zu vermeiden
+
+        ComputedExpression {
+            instructions,
+            result_var,
+        }
+    }
+}
@@ -1,8 +1,15 @@
 pub mod arrays;
+pub mod c_abi;
 pub mod classes;
+pub mod closures;
+pub mod enums;
 pub mod expressions;
+pub mod r#for;
 pub mod functions;
+pub mod globals;
 pub mod r#if;
+pub mod main_function;
+pub mod r#match;
 pub mod references;
 pub mod r#return;
 pub mod type_cast;
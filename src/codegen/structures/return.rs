@@ -1,9 +1,9 @@
 use crate::{
     codegen::{
         codegen_main::CodeGenerator,
-        llvm_instructions::{IRValue, Instruction},
+        llvm_instructions::{IRValue, Instruction, JumpKind, MemoryOperation},
     },
-    parser::ast::TypedExpr,
+    parser::ast::{DataType, TypedExpr},
 };
 
 impl CodeGenerator {
@@ -15,6 +15,22 @@ impl CodeGenerator {
 
         let mut instructions = expr.instructions;
 
+        // in einem block ausdruck wird der wert zum wert des blocks
+        if let Some(block) = self.block_results.last().cloned() {
+            if block.result_var._type != DataType::None {
+                instructions.push(Instruction::MemoryOperation(MemoryOperation::Store {
+                    value: IRValue::Variable(expr.result_var),
+                    pointer: block.result_var,
+                }));
+            }
+
+            instructions.push(Instruction::Jump(JumpKind::Jump {
+                label: block.end_label,
+            }));
+
+            return instructions;
+        }
+
         instructions.push(Instruction::Return {
             expr: IRValue::Variable(expr.result_var),
         });
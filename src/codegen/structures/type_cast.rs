@@ -1,10 +1,10 @@
 use crate::{
     codegen::{
         codegen_main::{CodeGenerator, ComputedExpression},
-        llvm_instructions::{Cast, IRValue, Instruction, MemoryOperation},
+        llvm_instructions::{Cast, IRValue, IRVariable, Instruction, MemoryOperation},
     },
     lexer::position::Spanned,
-    parser::ast::{DataType, Expr, TypedExpr},
+    parser::ast::{DataType, DataTypeGetter, Expr, TypedExpr},
 };
 
 impl CodeGenerator {
@@ -13,6 +13,10 @@ impl CodeGenerator {
         value: &TypedExpr,
         to: &DataType,
     ) -> ComputedExpression {
+        if let (Expr::FunctionRef(function), DataType::Pointer(_)) = (&value.expression, to) {
+            return self.c_function_pointer(function, to);
+        }
+
         let mut instructions = vec![];
         let result_var = self.next_tmp_var(to);
         let mut computed_value = self.parse_expression(value, false);
@@ -71,66 +75,81 @@ impl CodeGenerator {
             };
         }
 
-        if (value._type.is_integer() || to.is_boolean()) && (to.is_integer() || to.is_boolean()) {
-            let is_upcast = value._type.size() < to.size();
+        if (value._type.is_integer() || value._type.is_boolean())
+            && (to.is_integer() || to.is_boolean())
+        {
             instructions.append(&mut computed_value.instructions);
 
-            if value._type == *to {
+            // uint8 -> int8 usw. ist in llvm der gleiche typ
+            if value._type == *to
+                || (value._type.size() == to.size() && value._type.is_integer() && to.is_integer())
+            {
                 return ComputedExpression {
                     instructions,
-                    result_var: computed_value.result_var,
+                    result_var: IRVariable {
+                        _type: to.clone(),
+                        ..computed_value.result_var
+                    },
                 };
             }
 
-            let cast = if is_upcast {
-                Cast::SignedIntUp(IRValue::Variable(computed_value.result_var), to.clone())
+            let value = IRValue::Variable(computed_value.result_var);
+            let cast = if value._type().size() > to.size() || to.is_boolean() {
+                Cast::IntDown(value, to.clone())
+            } else if value._type().is_signed_integer() {
+                Cast::SignedIntUp(value, to.clone())
             } else {
-                Cast::SignedIntDown(IRValue::Variable(computed_value.result_var), to.clone())
+                // unsigned und bool
+                Cast::UnsignedIntUp(value, to.clone())
             };
 
-            instructions.push(Instruction::VRegisterAssignment {
-                variable: result_var.clone(),
-                value: Box::new(Instruction::Cast(cast)),
-            });
-
-            return ComputedExpression {
-                instructions,
-                result_var,
-            };
+            return self.push_cast(instructions, cast, result_var);
         }
 
         if value._type.is_integer() && to.is_float() {
             instructions.append(&mut computed_value.instructions);
 
-            instructions.push(Instruction::VRegisterAssignment {
-                variable: result_var.clone(),
-                value: Box::new(Instruction::Cast(Cast::SignedIntToFloat(
-                    IRValue::Variable(computed_value.result_var),
-                    to.clone(),
-                ))),
-            });
-
-            return ComputedExpression {
-                instructions,
-                result_var,
+            let value = IRValue::Variable(computed_value.result_var);
+            let cast = if value._type().is_unsigned_integer() {
+                Cast::UnsignedIntToFloat(value, to.clone())
+            } else {
+                Cast::SignedIntToFloat(value, to.clone())
             };
+
+            return self.push_cast(instructions, cast, result_var);
         }
 
         if value._type.is_float() && to.is_integer() {
             instructions.append(&mut computed_value.instructions);
 
-            instructions.push(Instruction::VRegisterAssignment {
-                variable: result_var.clone(),
-                value: Box::new(Instruction::Cast(Cast::FloatToSignedInt(
-                    IRValue::Variable(computed_value.result_var),
-                    to.clone(),
-                ))),
-            });
+            let value = IRValue::Variable(computed_value.result_var);
+            let cast = if to.is_unsigned_integer() {
+                Cast::FloatToUnsignedInt(value, to.clone())
+            } else {
+                Cast::FloatToSignedInt(value, to.clone())
+            };
 
-            return ComputedExpression {
-                instructions,
-                result_var,
+            return self.push_cast(instructions, cast, result_var);
+        }
+
+        if value._type.is_float() && to.is_float() {
+            instructions.append(&mut computed_value.instructions);
+
+            if value._type == *to {
+                return ComputedExpression {
+                    instructions,
+                    result_var: computed_value.result_var,
+                };
+            }
+
+            let value = IRValue::Variable(computed_value.result_var);
+            let cast = if value._type().size() < to.size() {
+                Cast::FloatUp(value, to.clone())
+            } else {
+                Cast::FloatDown(value, to.clone())
             };
+
+            return self.push_cast(instructions, cast, result_var);
         }
 
         // if value._type.can_be_converted_to(&to) {
@@ -143,4 +162,21 @@ impl CodeGenerator {
         println!("from: {:?}, to: {:?}", value._type, to);
         unreachable!()
     }
+
+    fn push_cast(
+        &mut self,
+        mut instructions: Vec<Instruction>,
+        cast: Cast,
+        result_var: IRVariable,
+    ) -> ComputedExpression {
+        instructions.push(Instruction::VRegisterAssignment {
+            variable: result_var.clone(),
+            value: Box::new(Instruction::Cast(cast)),
+        });
+
+        ComputedExpression {
+            instructions,
+            result_var,
+        }
+    }
 }
@@ -3,36 +3,44 @@ use crate::{
         codegen_main::{CodeGenerator, ComputedExpression},
         llvm_instructions::{IRValue, IRVariable, Instruction, MemoryOperation},
     },
-    parser::ast::{TypedExpr, Variable},
+    lexer::position::Spanned,
+    parser::ast::{TypedExpr, Variable, ASSIGNMENT_PLACE_NAME},
 };
 
 impl CodeGenerator {
     pub(in crate::codegen) fn parse_variable_decl(
         &mut self,
-        name: String,
+        name: &Spanned<String>,
         value: &TypedExpr,
     ) -> Vec<Instruction> {
-        let variable = IRVariable {
-            name: format!("%_{}_{}", name, self.next_count()),
-            _type: value._type.clone(),
-        };
+        let value_type = value._type.clone();
         let value = self.parse_expression(value, false);
         let mut instructions = value.instructions;
 
-        instructions.append(&mut vec![
-            Instruction::VRegisterAssignment {
-                variable: variable.clone(),
-                value: Box::new(Instruction::MemoryOperation(MemoryOperation::Alloca {
-                    _type: variable._type.clone(),
-                })),
-            },
-            Instruction::MemoryOperation(MemoryOperation::Store {
-                value: IRValue::Variable(value.result_var),
-                pointer: variable.clone(),
-            }),
-        ]);
+        // eine closure kann die variable über die funktion hinaus behalten
+        let variable = match self.boxed_variables.contains(name) {
+            true => self.heap_allocate(&mut instructions, &value_type),
+            false => {
+                let variable = IRVariable {
+                    name: format!("%_{}_{}", name.value, self.next_count()),
+                    _type: value_type,
+                };
+                instructions.push(Instruction::VRegisterAssignment {
+                    variable: variable.clone(),
+                    value: Box::new(Instruction::MemoryOperation(MemoryOperation::Alloca {
+                        _type: variable._type.clone(),
+                    })),
+                });
+                variable
+            }
+        };
+
+        instructions.push(Instruction::MemoryOperation(MemoryOperation::Store {
+            value: IRValue::Variable(value.result_var),
+            pointer: variable.clone(),
+        }));
 
-        self.variable_map.insert(name, variable);
+        self.variable_map.insert(name.value.clone(), variable);
 
         instructions
     }
@@ -67,6 +75,9 @@ impl CodeGenerator {
 
         let mut var = self.parse_expression(variable, true); // zum überschreiben als referenz
         instructions.append(&mut var.instructions);
+        // `a[i] += 1` liest das ziel über den platzhalter, ohne `i` noch einmal zu berechnen
+        self.variable_map
+            .insert(ASSIGNMENT_PLACE_NAME.to_string(), var.result_var.clone());
 
         let mut new_value = self.parse_expression(new_value, false);
         instructions.append(&mut new_value.instructions);
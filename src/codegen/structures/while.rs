@@ -1,6 +1,6 @@
 use crate::{
     codegen::{
-        codegen_main::CodeGenerator,
+        codegen_main::{CodeGenerator, LoopLabels},
         llvm_instructions::{IRValue, Instruction, JumpKind},
         utils::InstructionVecExt,
     },
@@ -31,12 +31,14 @@ impl CodeGenerator {
             label: end_label.clone(),
         };
 
-        let start_jump = Instruction::Jump(JumpKind::Jump { label: start_label });
+        let start_jump = Instruction::Jump(JumpKind::Jump {
+            label: start_label.clone(),
+        });
 
         let cond_jump = Instruction::Jump(JumpKind::ConditionJump {
             condition: IRValue::Variable(cond.result_var),
             true_label: body_label,
-            false_label: end_label,
+            false_label: end_label.clone(),
         });
 
         instructions.push(start_jump.clone());
@@ -46,7 +48,12 @@ impl CodeGenerator {
         instructions.push(cond_jump);
         instructions.push(body_block);
 
+        self.loop_labels.push(LoopLabels {
+            continue_label: start_label,
+            break_label: end_label,
+        });
         instructions.append(&mut self.parse_block(body));
+        self.loop_labels.pop();
 
         instructions.push(start_jump);
         instructions.push(end_block);
@@ -55,4 +62,25 @@ impl CodeGenerator {
 
         instructions
     }
+
+    pub(in crate::codegen) fn parse_break(&mut self) -> Vec<Instruction> {
+        // wurde im parser geprüft
+        let label = self.loop_labels.last().unwrap().break_label.clone();
+        self.loop_jump(label)
+    }
+
+    pub(in crate::codegen) fn parse_continue(&mut self) -> Vec<Instruction> {
+        let label = self.loop_labels.last().unwrap().continue_label.clone();
+        self.loop_jump(label)
+    }
+
+    fn loop_jump(&mut self, label: String) -> Vec<Instruction> {
+        // code nach dem sprung braucht einen eigenen block
+        let after_label = format!("after_jump_{}", self.next_count());
+
+        vec![
+            Instruction::Jump(JumpKind::Jump { label }),
+            Instruction::BlockDecl { label: after_label },
+        ]
+    }
 }
@@ -1,8 +1,8 @@
 use std::cmp::Ordering;
 
 use crate::{
-    lexer::tokens::Literal,
-    parser::ast::{DataType, Expr},
+    lexer::{position::Spanned, tokens::Literal},
+    parser::ast::{DataType, Expr, TypedExpr, Variable},
 };
 
 use super::{
@@ -71,4 +71,30 @@ impl CodeGenerator {
 
         unreachable!()
     }
+
+    /// speichert `value` in einer variable, die im quellcode nicht erreichbar ist
+    pub(in crate::codegen) fn hidden_variable(
+        &mut self,
+        instructions: &mut Vec<Instruction>,
+        name: String,
+        value: &TypedExpr,
+    ) -> TypedExpr {
+        let name = Spanned {
+            value: name,
+            span: Default::default(),
+        };
+        instructions.append(&mut self.parse_variable_decl(&name, value));
+
+        let variable = Variable {
+            name,
+            is_mutable: true,
+            _type: value._type.clone(),
+        };
+
+        TypedExpr {
+            expression: Expr::Variable(variable),
+            _type: value._type.clone(),
+            raw: None,
+        }
+    }
 }
@@ -1,33 +1,99 @@
 use std::path::{Path, PathBuf};
 
-use itertools::Itertools;
-
 use crate::{
     codegen::{codegen_main::CodeGenerator, llvm_instructions::IR},
-    error::{emit_error, CompilerError},
+    error::{emit_error, emit_warnings, CompilerError, CompilerWarning, WarningConfig},
     lexer::{lexer_main::lex, position::Spanned},
-    parser::parser_main::Parser,
+    parser::{ast::Program, cfg::Target, parser_main::Parser, warnings::check_warnings},
+    wasm::WasmGenerator,
 };
 
 pub struct Compiler;
 
+/// das geparste programm und seine warnungen
+type CheckedProgram = (Program, Vec<Spanned<CompilerWarning>>);
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CompileOptions {
+    pub warnings: WarningConfig,
+    /// weitere ordner in denen imports gesucht werden
+    pub library_paths: Vec<PathBuf>,
+    /// für `#[cfg(target_os = "...")]`, das host system wenn nicht angegeben
+    pub target: Target,
+    /// bibliothek ohne `main`, für `--emit obj`, `staticlib` und `sharedlib`
+    pub library: bool,
+}
+
 impl Compiler {
     pub fn compile(
         input: &str,
         path: Option<PathBuf>,
-    ) -> Result<IR, Spanned<Box<dyn CompilerError>>> {
-        match Compiler::catch_errors(input, path.as_deref()) {
-            Ok(ir) => Ok(ir),
-            Err(e) => {
-                let lines = input.split('\n').collect_vec();
-                let _err_line = lines
-                    .get(e.span.start.row.saturating_sub(3)..e.span.start.row + 2)
-                    .unwrap_or_default()
-                    .join("\n");
+    ) -> Result<IR, Vec<Spanned<Box<dyn CompilerError>>>> {
+        Compiler::compile_with_options(input, path, &CompileOptions::default())
+    }
+
+    pub fn compile_with_options(
+        input: &str,
+        path: Option<PathBuf>,
+        options: &CompileOptions,
+    ) -> Result<IR, Vec<Spanned<Box<dyn CompilerError>>>> {
+        let program = Compiler::parse_with_options(input, path, options)?;
+
+        let mut codegen = CodeGenerator::new(program)
+            .with_source(input.to_string())
+            .with_library(options.library);
+        Ok(codegen.parse())
+    }
+
+    /// wie `compile_with_options`, aber mit dem wasm backend (text format)
+    pub fn compile_wasm_with_options(
+        input: &str,
+        path: Option<PathBuf>,
+        options: &CompileOptions,
+    ) -> Result<String, Vec<Spanned<Box<dyn CompilerError>>>> {
+        let program = Compiler::parse_with_options(input, path, options)?;
+        Ok(WasmGenerator::new(program).parse())
+    }
+
+    /// nur bis zum ast, z.b. für den interpreter. warnungen und fehler werden ausgegeben
+    pub fn parse_with_options(
+        input: &str,
+        path: Option<PathBuf>,
+        options: &CompileOptions,
+    ) -> Result<Program, Vec<Spanned<Box<dyn CompilerError>>>> {
+        let warning_config = &options.warnings;
+        let result = Compiler::catch_errors(input, path.as_deref(), options);
+        let file_name = path.unwrap_or_default();
+        let file_name = file_name.to_str().unwrap();
+
+        match result {
+            Ok((program, warnings)) => {
+                let (denied, warnings): (Vec<_>, Vec<_>) = warnings
+                    .into_iter()
+                    .filter(|w| !warning_config.is_allowed(&w.value))
+                    .partition(|w| warning_config.is_denied(&w.value));
+
+                emit_warnings(file_name, input, &warnings);
 
-                emit_error(path.unwrap_or_default().to_str().unwrap(), input, &e);
+                if !denied.is_empty() {
+                    let errs = denied
+                        .into_iter()
+                        .map(|w| Spanned {
+                            value: Box::new(w.value) as Box<dyn CompilerError>,
+                            span: w.span,
+                        })
+                        .collect::<Vec<_>>();
 
-                Err(e)
+                    emit_error(file_name, input, &errs);
+                    return Err(errs);
+                }
+
+                Ok(program)
+            }
+            Err(errs) => {
+                emit_error(file_name, input, &errs);
+
+                Err(errs)
             }
         }
     }
@@ -35,33 +101,38 @@ impl Compiler {
     fn catch_errors(
         input: &str,
         path: Option<&Path>,
-    ) -> Result<IR, Spanned<Box<dyn CompilerError>>> {
+        options: &CompileOptions,
+    ) -> Result<CheckedProgram, Vec<Spanned<Box<dyn CompilerError>>>> {
         let tokens = match lex(input) {
             Ok(tokens) => tokens,
             Err(e) => {
-                return Err(Spanned {
+                return Err(vec![Spanned {
                     value: Box::new(e.value),
                     span: e.span,
-                })
+                }])
             }
         };
 
         let program = match Parser::new(tokens, path)
             .with_source_code(input)
-            .with_require_main(true)
+            .with_require_main(!options.library)
+            .with_library_paths(options.library_paths.clone())
+            .with_target(options.target)
             .parse()
         {
             Ok(program) => program,
-            Err(e) => {
-                return Err(Spanned {
-                    value: Box::new(e.value),
-                    span: e.span,
-                })
+            Err(errs) => {
+                return Err(errs
+                    .into_iter()
+                    .map(|e| Spanned {
+                        value: Box::new(e.value) as Box<dyn CompilerError>,
+                        span: e.span,
+                    })
+                    .collect())
             }
         };
 
-        let mut codegen = CodeGenerator::new(program).with_source(input.to_string());
-        let code = codegen.parse();
-        Ok(code)
+        let warnings = check_warnings(&program);
+        Ok((program, warnings))
     }
 }
@@ -1,24 +1,17 @@
-use crate::lexer::position::Spanned;
+use crate::lexer::position::{Span, Spanned};
+use crate::parser::error::ImportError;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::files::SimpleFiles;
 use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 use core::fmt::Debug;
 use std::fmt::Display;
 use termcolor::Ansi;
+use thiserror::Error;
 
-pub fn emit_error(file_name: &str, code: &str, err: &Spanned<Box<dyn CompilerError>>) {
+pub fn emit_error(file_name: &str, code: &str, errs: &[Spanned<Box<dyn CompilerError>>]) {
     let mut files = SimpleFiles::new();
 
-    let file_id = files.add(file_name, code);
-
-    let diagnostic = Diagnostic::error()
-        .with_message(err.value.name())
-        .with_code(err.value.id().to_string())
-        .with_labels(vec![Label::primary(
-            file_id,
-            err.span.start.abs..err.span.end.abs,
-        )
-        .with_message(err.value.err_msg())]);
+    let file_id = files.add(file_name.to_string(), code.to_string());
 
     let writer = StandardStream::stderr(ColorChoice::Always);
     let config = codespan_reporting::term::Config {
@@ -27,7 +20,12 @@ pub fn emit_error(file_name: &str, code: &str, err: &Spanned<Box<dyn CompilerErr
         ..Default::default()
     };
 
-    codespan_reporting::term::emit(&mut writer.lock(), &config, &files, &diagnostic).unwrap();
+    let mut writer = writer.lock();
+    for err in errs {
+        for diagnostic in diagnostics(&mut files, file_id, err) {
+            codespan_reporting::term::emit(&mut writer, &config, &files, &diagnostic).unwrap();
+        }
+    }
 }
 
 pub fn error_as_string(
@@ -37,16 +35,7 @@ pub fn error_as_string(
 ) -> String {
     let mut files = SimpleFiles::new();
 
-    let file_id = files.add(file_name, code);
-
-    let diagnostic = Diagnostic::error()
-        .with_message(err.value.name())
-        .with_code(err.value.id().to_string())
-        .with_labels(vec![Label::primary(
-            file_id,
-            err.span.start.abs..err.span.end.abs,
-        )
-        .with_message(err.value.err_msg())]);
+    let file_id = files.add(file_name.to_string(), code.to_string());
 
     let mut writer = Ansi::new(Vec::new());
     let config = codespan_reporting::term::Config {
@@ -55,14 +44,190 @@ pub fn error_as_string(
         ..Default::default()
     };
 
-    codespan_reporting::term::emit(&mut writer, &config, &files, &diagnostic).unwrap();
+    for diagnostic in diagnostics(&mut files, file_id, err) {
+        codespan_reporting::term::emit(&mut writer, &config, &files, &diagnostic).unwrap();
+    }
 
     String::from_utf8(writer.into_inner())
         .unwrap_or_else(|_| "Error formatting error message".to_string())
 }
 
+pub fn emit_warnings(file_name: &str, code: &str, warnings: &[Spanned<CompilerWarning>]) {
+    let mut files = SimpleFiles::new();
+
+    let file_id = files.add(file_name.to_string(), code.to_string());
+
+    let writer = StandardStream::stderr(ColorChoice::Always);
+    let config = codespan_reporting::term::Config {
+        before_label_lines: 2,
+        after_label_lines: 2,
+        ..Default::default()
+    };
+
+    let mut writer = writer.lock();
+    for warning in warnings {
+        let diagnostic = Diagnostic::warning()
+            .with_message(warning.value.name())
+            .with_code(warning.value.code())
+            .with_labels(vec![Label::primary(
+                file_id,
+                warning.span.start.abs..warning.span.end.abs,
+            )
+            .with_message(warning.value.err_msg())]);
+
+        codespan_reporting::term::emit(&mut writer, &config, &files, &diagnostic).unwrap();
+    }
+}
+
+fn diagnostic(file_id: usize, err: &dyn CompilerError, span: Span) -> Diagnostic<usize> {
+    Diagnostic::error()
+        .with_message(err.name())
+        .with_code(err.code())
+        .with_labels(vec![
+            Label::primary(file_id, span.start.abs..span.end.abs).with_message(err.err_msg())
+        ])
+}
+
+/// fehler in importierten dateien werden in ihrer eigenen datei angezeigt,
+/// die import kette als secondary labels
+fn diagnostics(
+    files: &mut SimpleFiles<String, String>,
+    file_id: usize,
+    err: &Spanned<Box<dyn CompilerError>>,
+) -> Vec<Diagnostic<usize>> {
+    let import = match err.value.import_error() {
+        Some(import) => import,
+        None => return vec![diagnostic(file_id, err.value.as_ref(), err.span)],
+    };
+
+    let mut trace_labels = vec![import_label(file_id, err.span, import, 0)];
+    for (idx, trace) in import.trace.iter().enumerate() {
+        let trace_id = files.add(trace.file.display().to_string(), trace.source_code.clone());
+        trace_labels.push(import_label(trace_id, trace.span, import, idx + 1));
+    }
+
+    let failed_id = files.add(
+        import.file.display().to_string(),
+        import.source_code.clone(),
+    );
+
+    import
+        .errors
+        .iter()
+        .map(|inner| {
+            diagnostic(failed_id, &inner.value, inner.span).with_labels(trace_labels.clone())
+        })
+        .collect()
+}
+
+/// `import` an stelle `depth` der kette
+fn import_label(file_id: usize, span: Span, import: &ImportError, depth: usize) -> Label<usize> {
+    let imported = import
+        .trace
+        .get(depth)
+        .map(|trace| &trace.file)
+        .unwrap_or(&import.file);
+
+    Label::secondary(file_id, span.start.abs..span.end.abs)
+        .with_message(format!("\"{}\" imported here", imported.display()))
+}
+
 pub trait CompilerError: Debug + Display + std::error::Error + Send + Sync {
     fn id(&self) -> u32;
     fn name(&self) -> &str;
     fn err_msg(&self) -> String;
+
+    /// wird in der ausgabe angezeigt
+    fn code(&self) -> String {
+        self.id().to_string()
+    }
+
+    /// nur für fehler aus importierten dateien
+    fn import_error(&self) -> Option<&ImportError> {
+        None
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CompilerWarning {
+    #[error("variable \"{0}\" is never read")]
+    UnusedVariable(String),
+    #[error("variable \"{0}\" is declared as mutable but never mutated")]
+    UnusedMut(String),
+    #[error("this code is never executed")]
+    UnreachableCode,
+    #[error("function \"{0}\" is never called from \"main\"")]
+    UnusedFunction(String),
+    #[error("nothing from \"{0}\" is used")]
+    UnusedImport(String),
+    #[error("the result of \"{0}\" is never used")]
+    UnusedResult(String),
+}
+
+impl CompilerWarning {
+    /// die codes dürfen sich nicht ändern, sie werden mit `-A`/`-D` angegeben
+    pub fn code(&self) -> String {
+        format!("W{:03}", self.id() - WARNING_ID_OFFSET)
+    }
+}
+
+/// damit sich warnungen und fehler nicht überschneiden
+const WARNING_ID_OFFSET: u32 = 1000;
+
+impl CompilerError for CompilerWarning {
+    fn id(&self) -> u32 {
+        WARNING_ID_OFFSET
+            + match self {
+                CompilerWarning::UnusedVariable(..) => 1,
+                CompilerWarning::UnusedMut(..) => 2,
+                CompilerWarning::UnreachableCode => 3,
+                CompilerWarning::UnusedFunction(..) => 4,
+                CompilerWarning::UnusedImport(..) => 5,
+                CompilerWarning::UnusedResult(..) => 6,
+            }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            CompilerWarning::UnusedVariable(..) => "unused variable",
+            CompilerWarning::UnusedMut(..) => "unnecessary mut",
+            CompilerWarning::UnreachableCode => "unreachable code",
+            CompilerWarning::UnusedFunction(..) => "unused function",
+            CompilerWarning::UnusedImport(..) => "unused import",
+            CompilerWarning::UnusedResult(..) => "unused result",
+        }
+    }
+
+    fn err_msg(&self) -> String {
+        self.to_string()
+    }
+
+    fn code(&self) -> String {
+        CompilerWarning::code(self)
+    }
+}
+
+/// `warnings` steht für alle warnungen
+pub const ALL_WARNINGS: &str = "warnings";
+
+/// `-A <code>` schaltet warnungen ab, `-D <code>` macht fehler aus ihnen
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WarningConfig {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl WarningConfig {
+    fn matches(list: &[String], warning: &CompilerWarning) -> bool {
+        list.iter()
+            .any(|l| l == ALL_WARNINGS || l.eq_ignore_ascii_case(&warning.code()))
+    }
+
+    pub fn is_allowed(&self, warning: &CompilerWarning) -> bool {
+        Self::matches(&self.allow, warning) && !self.is_denied(warning)
+    }
+
+    pub fn is_denied(&self, warning: &CompilerWarning) -> bool {
+        Self::matches(&self.deny, warning)
+    }
 }
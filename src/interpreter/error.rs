@@ -0,0 +1,44 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+    #[error("invalid memory access at {0:#x}")]
+    InvalidAddress(u64),
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("stack overflow")]
+    StackOverflow,
+    #[error("out of memory")]
+    OutOfMemory,
+    #[error("extern function `{0}` is not available in the interpreter")]
+    UnknownExtern(String),
+    #[error("function `{0}` does not exist")]
+    UnknownFunction(String),
+    #[error("variable `{0}` does not exist")]
+    UnknownVariable(String),
+    #[error("called an invalid function value")]
+    InvalidFunction,
+    #[error("{0} is not supported by the interpreter")]
+    Unsupported(String),
+    #[error("io error: {0}")]
+    Io(String),
+}
+
+/// bricht die ausführung ab, `exit` ist kein fehler
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trap {
+    Exit(i32),
+    Error(RuntimeError),
+}
+
+impl From<RuntimeError> for Trap {
+    fn from(err: RuntimeError) -> Self {
+        Trap::Error(err)
+    }
+}
+
+impl From<std::io::Error> for Trap {
+    fn from(err: std::io::Error) -> Self {
+        Trap::Error(RuntimeError::Io(err.to_string()))
+    }
+}
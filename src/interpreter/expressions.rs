@@ -0,0 +1,327 @@
+use std::rc::Rc;
+
+use crate::{
+    lexer::{position::Spanned, tokens::Literal},
+    parser::ast::{
+        BinaryOperator, CallArg, CustomDataType, DataType, Expr, Function, MatchArm, MatchPattern,
+        Trait, TypedExpr,
+    },
+};
+
+use super::{
+    error::{RuntimeError, Trap},
+    interpreter_main::{literal_bytes, Interpreter},
+    statements::Flow,
+    values::{
+        binary_operation, cast, int_from_bytes, int_to_bytes, pointer_from_bytes, pointer_to_bytes,
+    },
+};
+
+impl Interpreter {
+    pub(in crate::interpreter) fn eval(&mut self, expr: &TypedExpr) -> Result<Vec<u8>, Trap> {
+        self.eval_expr(&expr.expression, &expr._type)
+    }
+
+    /// der wert von `expr` als bytes, `_type` ist der typ des ausdrucks
+    pub(in crate::interpreter) fn eval_expr(
+        &mut self,
+        expr: &Expr,
+        _type: &DataType,
+    ) -> Result<Vec<u8>, Trap> {
+        match expr {
+            Expr::Variable(_) | Expr::Index { .. } | Expr::FieldAccess { .. } | Expr::Deref(_) => {
+                let address = self.place(expr)?;
+                Ok(self.memory.read(address, _type.size())?.to_vec())
+            }
+            Expr::Literal(literal) => self.eval_literal(literal, _type),
+            Expr::Binary { lhs, op, rhs } => self.eval_binary(&lhs.value, &op.value, &rhs.value),
+            Expr::Cast { value, to_type } => {
+                let bytes = self.eval(&value.value)?;
+                Ok(cast(&bytes, &value.value._type, &to_type.value)?)
+            }
+            Expr::Reference { value } => Ok(pointer_to_bytes(self.eval_place(&value.value)?)),
+            Expr::Call { function, args } => {
+                let args = args
+                    .value
+                    .iter()
+                    .map(|arg| &arg.value.0)
+                    .collect::<Vec<_>>();
+
+                // `size_of(int64)`
+                if function.value.is_builtin {
+                    return match args.first().map(|arg| &arg.expression) {
+                        Some(Expr::Literal(Literal::DataType { value_type })) => Ok(int_to_bytes(
+                            value_type.size() as i128,
+                            DataType::get_integer_type().size(),
+                        )),
+                        _ => Err(RuntimeError::Unsupported(
+                            function.value.display_name.value.clone(),
+                        )
+                        .into()),
+                    };
+                }
+
+                let function = self.function(&function.value);
+                self.call_with(&function, &args)
+            }
+            Expr::Block { body } => match self.exec_block(&body.value)? {
+                Flow::Return(value) => Ok(value),
+                _ => Ok(vec![0; _type.size()]),
+            },
+            Expr::EnumVariant {
+                variant_idx, args, ..
+            } => {
+                let DataType::Custom(custom) = _type else {
+                    unreachable!()
+                };
+                self.eval_enum_variant(custom, *variant_idx, &args.value)
+            }
+            Expr::Match { value, arms } => self.eval_match(&value.value, arms, _type),
+            Expr::Closure(closure) => {
+                let id = self.closure_id(closure);
+
+                // wie `closure_env_*` in codegen, `mut` variablen als pointer
+                let mut env = vec![];
+                for capture in closure.captures.iter() {
+                    let address = self.lookup(&capture.variable.name.value)?;
+                    match capture.by_reference {
+                        true => env.extend(pointer_to_bytes(address)),
+                        false => env.extend_from_slice(
+                            self.memory.read(address, capture.variable._type.size())?,
+                        ),
+                    }
+                }
+
+                let env = match env.is_empty() {
+                    true => 0,
+                    false => self.allocate_env(closure, &env)?,
+                };
+
+                Ok([pointer_to_bytes(id), pointer_to_bytes(env)].concat())
+            }
+            Expr::ClosureCall { closure, args } => {
+                let value = self.eval(&closure.value)?;
+                let args = self.eval_args(&args.value)?;
+                self.call_value(&value, args)
+            }
+            Expr::FunctionRef(function) => {
+                let id = self.function_ref_id(function);
+                Ok([pointer_to_bytes(id), pointer_to_bytes(0)].concat())
+            }
+            Expr::Unary { op, .. } => {
+                Err(RuntimeError::Unsupported(format!("unary `{}`", op.value)).into())
+            }
+            Expr::ClassName(_) => unreachable!(),
+        }
+    }
+
+    /// die adresse eines ausdrucks, werte ohne adresse werden auf den stack gelegt
+    pub(in crate::interpreter) fn eval_place(&mut self, expr: &TypedExpr) -> Result<u64, Trap> {
+        match &expr.expression {
+            Expr::Variable(_) | Expr::Index { .. } | Expr::FieldAccess { .. } | Expr::Deref(_) => {
+                self.place(&expr.expression)
+            }
+            _ => {
+                let value = self.eval(expr)?;
+                Ok(self.allocate(&value)?)
+            }
+        }
+    }
+
+    fn place(&mut self, expr: &Expr) -> Result<u64, Trap> {
+        match expr {
+            Expr::Variable(variable) => Ok(self.lookup(&variable.name.value)?),
+            Expr::Index { base, idx } => {
+                let (base, idx) = (&base.value, &idx.value);
+
+                if let Some(index) =
+                    self.trait_function(&Trait::Index, &[base._type.clone(), idx._type.clone()])
+                {
+                    // `Index` gibt einen pointer auf das element zurück
+                    let pointer = self.call_with(&index, &[base, idx])?;
+                    return Ok(pointer_from_bytes(&pointer));
+                }
+
+                let DataType::Array { value_type, .. } = &base._type else {
+                    return Err(
+                        RuntimeError::Unsupported(format!("indexing `{}`", base._type)).into(),
+                    );
+                };
+
+                let address = self.eval_place(base)?;
+                let idx = int_from_bytes(&self.eval(idx)?, &idx._type);
+                Ok(address.wrapping_add((idx * value_type.size() as i128) as u64))
+            }
+            Expr::FieldAccess {
+                base, field_idx, ..
+            } => {
+                let base = &base.value;
+                let address = self.eval_place(base)?;
+
+                match &base._type {
+                    DataType::Custom(custom) => {
+                        Ok(address + custom.field_offset(*field_idx) as u64)
+                    }
+                    _type => Err(RuntimeError::Unsupported(format!("fields on `{_type}`")).into()),
+                }
+            }
+            Expr::Deref(base) => Ok(pointer_from_bytes(&self.eval(&base.value)?)),
+            _ => unreachable!(),
+        }
+    }
+
+    /// wie `parse_func_call`, `self` wird als pointer übergeben
+    pub(in crate::interpreter) fn call_with(
+        &mut self,
+        function: &Rc<Function>,
+        args: &[&TypedExpr],
+    ) -> Result<Vec<u8>, Trap> {
+        let has_self_arg = function.is_method() && !function.is_static_method();
+
+        let mut values = vec![];
+        for (idx, arg) in args.iter().enumerate() {
+            match idx == 0 && has_self_arg {
+                true => values.push(pointer_to_bytes(self.eval_place(arg)?)),
+                false => values.push(self.eval(arg)?),
+            }
+        }
+
+        self.call_function(function, values)
+    }
+
+    fn eval_args(&mut self, args: &[Spanned<CallArg>]) -> Result<Vec<Vec<u8>>, Trap> {
+        args.iter().map(|arg| self.eval(&arg.value.0)).collect()
+    }
+
+    fn eval_literal(&mut self, literal: &Literal, _type: &DataType) -> Result<Vec<u8>, Trap> {
+        match literal {
+            Literal::ArrayLiteral(array) => {
+                let mut bytes = vec![];
+                for value in array.values.value.iter() {
+                    bytes.extend(self.eval(&value.value)?);
+                }
+                Ok(bytes)
+            }
+            // die felder stehen schon in der richtigen reihenfolge
+            Literal::Custom(class) => {
+                let DataType::Custom(custom) = _type else {
+                    unreachable!()
+                };
+
+                let mut bytes = vec![0; _type.size()];
+                for (idx, (_, value)) in class.fields.value.iter().enumerate() {
+                    let offset = custom.field_offset(idx);
+                    let value = self.eval(&value.value)?;
+                    bytes[offset..offset + value.len()].copy_from_slice(&value);
+                }
+                Ok(bytes)
+            }
+            literal => Ok(literal_bytes(literal, _type)),
+        }
+    }
+
+    /// wie `parse_binary_expr`, überschriebene operatoren zuerst
+    fn eval_binary(
+        &mut self,
+        lhs: &TypedExpr,
+        op: &BinaryOperator,
+        rhs: &TypedExpr,
+    ) -> Result<Vec<u8>, Trap> {
+        let trait_ = Trait::from_binary_operator(op);
+        if let Some(function) =
+            self.trait_function(&trait_, &[lhs._type.clone(), rhs._type.clone()])
+        {
+            return self.call_with(&function, &[lhs, rhs]);
+        }
+
+        let left = self.eval(lhs)?;
+        let right = self.eval(rhs)?;
+        Ok(binary_operation(&left, op, &right, &lhs._type)?)
+    }
+
+    fn eval_enum_variant(
+        &mut self,
+        custom: &CustomDataType,
+        variant_idx: usize,
+        args: &[Spanned<TypedExpr>],
+    ) -> Result<Vec<u8>, Trap> {
+        let size = DataType::Custom(Box::new(custom.clone())).size();
+        let mut bytes = vec![0; size];
+
+        let tag_size = custom.fields.value[0]._type.value.size();
+        bytes[..tag_size].copy_from_slice(&int_to_bytes(variant_idx as i128, tag_size));
+
+        let variant = &custom.variants.as_ref().unwrap()[variant_idx];
+        for (field_idx, arg) in variant.fields.iter().zip(args) {
+            let offset = custom.field_offset(*field_idx);
+            let value = self.eval(&arg.value)?;
+            bytes[offset..offset + value.len()].copy_from_slice(&value);
+        }
+
+        Ok(bytes)
+    }
+
+    /// wie `parse_match`, der wert wird nur einmal ausgewertet
+    fn eval_match(
+        &mut self,
+        value: &TypedExpr,
+        arms: &[Spanned<MatchArm>],
+        _type: &DataType,
+    ) -> Result<Vec<u8>, Trap> {
+        let DataType::Custom(custom) = &value._type else {
+            unreachable!()
+        };
+
+        let bytes = self.eval(value)?;
+        let tag_size = custom.fields.value[0]._type.value.size();
+        let tag = int_from_bytes(&bytes[..tag_size], &DataType::get_integer_type());
+
+        for arm in arms {
+            let bindings: &[Spanned<String>] = match &arm.value.pattern.value {
+                MatchPattern::Variant {
+                    variant_idx,
+                    bindings,
+                    ..
+                } if *variant_idx as i128 == tag => bindings,
+                MatchPattern::Variant { .. } => continue,
+                MatchPattern::Wildcard => &[],
+            };
+
+            let fields = match bindings.is_empty() {
+                true => vec![],
+                false => custom.variants.as_ref().unwrap()[tag as usize]
+                    .fields
+                    .clone(),
+            };
+
+            self.push_scope();
+            let result = self.bind_variant_fields(custom, &fields, bindings, &bytes);
+            let result = result.and_then(|_| self.eval(&arm.value.body.value));
+            self.pop_scope();
+            return result;
+        }
+
+        Ok(vec![0; _type.size()])
+    }
+
+    fn bind_variant_fields(
+        &mut self,
+        custom: &CustomDataType,
+        fields: &[usize],
+        bindings: &[Spanned<String>],
+        bytes: &[u8],
+    ) -> Result<(), Trap> {
+        for (field_idx, binding) in fields.iter().zip(bindings) {
+            if binding.value == "_" {
+                continue;
+            }
+
+            let offset = custom.field_offset(*field_idx);
+            let size = custom.fields.value[*field_idx]._type.value.size();
+            let address = self.allocate(&bytes[offset..offset + size])?;
+            self.declare(&binding.value, address);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    cell::RefCell,
+    io::{Read, Write},
+    rc::Rc,
+    time::Duration,
+};
+
+use crate::parser::ast::{DataType, Function};
+
+use super::{
+    error::{RuntimeError, Trap},
+    interpreter_main::Interpreter,
+    values::{int_from_bytes, int_to_bytes, pointer_from_bytes, pointer_to_bytes, POINTER_SIZE},
+};
+
+/// fängt die ausgabe des programms ab, z.b. für die web demo
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl CapturedOutput {
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).to_string()
+    }
+}
+
+impl Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Interpreter {
+    /// die `extern` funktionen aus `std/mem.mx`, `std/io.mx`, `std/env.mx`,
+    /// `std/rand.mx` und `std/time.mx` in rust
+    pub(in crate::interpreter) fn call_extern(
+        &mut self,
+        function: &Function,
+        args: Vec<Vec<u8>>,
+    ) -> Result<Vec<u8>, Trap> {
+        let int = |idx: usize| -> i128 {
+            let _type = &function.params.value[idx].value._type.value;
+            int_from_bytes(&args[idx], _type)
+        };
+        let pointer = |idx: usize| pointer_from_bytes(&args[idx]);
+        // negative größen passen in keinen speicher
+        let size = |value: i128| u64::try_from(value).map_err(|_| RuntimeError::OutOfMemory);
+
+        let result: i128 = match function.name.as_str() {
+            "malloc" => self.memory.malloc(size(int(0))?)? as i128,
+            "calloc" => {
+                let total = int(0).checked_mul(int(1)).unwrap_or(-1);
+                self.memory.calloc(size(total)?)? as i128
+            }
+            "realloc" => self.memory.realloc(int(0) as u64, size(int(1))?)? as i128,
+            "free" => {
+                self.memory.free(int(0) as u64)?;
+                0
+            }
+            // die argumente für `...` sind schon erweitert, `int32` hat 4 bytes, der rest 8
+            "printf" => {
+                let format = self.memory.c_string(pointer(0))?;
+                let mut variadic = args[function.params.value.len()..]
+                    .iter()
+                    .map(|arg| int_from_bytes(arg, &DataType::Integer64) as i64);
+
+                // fehlende argumente sind in c undefiniert, hier 0
+                let text = format_c(
+                    &format,
+                    || variadic.next().unwrap_or(0),
+                    |address| self.memory.c_string(address),
+                )?;
+                self.stdout.write_all(&text)?;
+                text.len() as i128
+            }
+            "putchar" => {
+                self.stdout.write_all(&[int(0) as u8])?;
+                int(0)
+            }
+            "getchar" => {
+                self.stdout.flush()?;
+                let mut buf = [0];
+                match self.stdin.read(&mut buf)? {
+                    0 => -1,
+                    _ => buf[0] as i128,
+                }
+            }
+            "exit" => {
+                self.stdout.flush()?;
+                return Err(Trap::Exit(int(0) as i32));
+            }
+            // wie `rand` aus der c standard bibliothek (RAND_MAX = 32767)
+            "rand" => {
+                self.random_state = self
+                    .random_state
+                    .wrapping_mul(1103515245)
+                    .wrapping_add(12345);
+                ((self.random_state / 65536) % 32768) as i128
+            }
+            "srand" => {
+                self.random_state = int(0) as u64;
+                0
+            }
+            "time" => {
+                let now = current_time().as_secs() as i128;
+                if pointer(0) != 0 {
+                    self.memory.write(pointer(0), &int_to_bytes(now, 8))?;
+                }
+                now
+            }
+            "Sleep" => {
+                #[cfg(not(target_arch = "wasm32"))]
+                std::thread::sleep(Duration::from_millis(int(0) as u64));
+                0
+            }
+            // `Timespec` aus `std/time.mx`, sekunden und nanosekunden als `int64`
+            "nanosleep" => {
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let request = self.memory.read(pointer(0), 16)?;
+                    let secs = int_from_bytes(&request[..8], &DataType::Integer64);
+                    let nanos = int_from_bytes(&request[8..], &DataType::Integer64);
+                    std::thread::sleep(Duration::new(secs as u64, nanos as u32));
+                }
+                0
+            }
+            // 100ns schritte seit 1601 in zwei `int32`
+            "GetSystemTimeAsFileTime" => {
+                let file_time = current_time().as_nanos() / 100 + WINDOWS_EPOCH_OFFSET;
+                self.memory
+                    .write(pointer(0), &int_to_bytes(file_time as i128, 8))?;
+                0
+            }
+            "clock_gettime" => {
+                let now = current_time();
+                let mut timespec = int_to_bytes(now.as_secs() as i128, 8);
+                timespec.extend(int_to_bytes(now.subsec_nanos() as i128, 8));
+                self.memory.write(pointer(1), &timespec)?;
+                0
+            }
+            "getenv" => {
+                let name = self.memory.c_string(pointer(0))?;
+                let name = String::from_utf8_lossy(&name).to_string();
+                let value = self
+                    .environment
+                    .get(&name)
+                    .cloned()
+                    .or_else(|| std::env::var(&name).ok());
+
+                match value {
+                    Some(value) => self.memory.allocate_c_string(value.as_bytes())? as i128,
+                    None => 0,
+                }
+            }
+            // nur für diesen prozess, wie in c
+            "putenv" => {
+                let entry = self.memory.c_string(pointer(0))?;
+                let entry = String::from_utf8_lossy(&entry).to_string();
+                match entry.split_once('=') {
+                    Some((name, value)) => {
+                        self.environment.insert(name.to_string(), value.to_string());
+                        0
+                    }
+                    None => -1,
+                }
+            }
+            "mx_argc" => self.args.len() as i128,
+            "mx_argv" => self.argv()? as i128,
+            name => return Err(RuntimeError::UnknownExtern(name.to_string()).into()),
+        };
+
+        Ok(match &function.return_type.value {
+            DataType::None => vec![],
+            DataType::Pointer(_) => pointer_to_bytes(result as u64),
+            _type => int_to_bytes(result, _type.size()),
+        })
+    }
+
+    /// `argv` wird beim ersten aufruf auf den heap gelegt
+    fn argv(&mut self) -> Result<u64, RuntimeError> {
+        if let Some(argv) = self.argv {
+            return Ok(argv);
+        }
+
+        let argv = self
+            .memory
+            .malloc((self.args.len() * POINTER_SIZE) as u64)?;
+
+        for (idx, arg) in self.args.clone().iter().enumerate() {
+            let address = self.memory.allocate_c_string(arg.as_bytes())?;
+            self.memory.write(
+                argv + (idx * POINTER_SIZE) as u64,
+                &pointer_to_bytes(address),
+            )?;
+        }
+
+        self.argv = Some(argv);
+        Ok(argv)
+    }
+}
+
+/// `printf` für den interpreter und die wasm hosts. `next_arg` gibt die argumente wie bei
+/// `...` erweitert zurück: integer und pointer als `i64`, `float64` als bits.
+/// unterstützt `d i u x X c s f p %` mit `-`, `0`, breite, genauigkeit und `l`/`ll`/`z`
+pub fn format_c<E>(
+    format: &[u8],
+    mut next_arg: impl FnMut() -> i64,
+    mut c_string: impl FnMut(u64) -> Result<Vec<u8>, E>,
+) -> Result<Vec<u8>, E> {
+    let mut out = vec![];
+    let mut idx = 0;
+
+    while idx < format.len() {
+        if format[idx] != b'%' {
+            out.push(format[idx]);
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        idx += 1;
+
+        let (mut left_align, mut zero_pad) = (false, false);
+        while let Some(&flag) = format.get(idx) {
+            match flag {
+                b'-' => left_align = true,
+                b'0' => zero_pad = true,
+                _ => break,
+            }
+            idx += 1;
+        }
+
+        let width = parse_number(format, &mut idx).unwrap_or(0);
+        let precision = match format.get(idx) {
+            Some(b'.') => {
+                idx += 1;
+                Some(parse_number(format, &mut idx).unwrap_or(0))
+            }
+            _ => None,
+        };
+
+        // ohne länge ist es ein `int` mit 32 bit
+        let mut long = false;
+        while let Some(&length) = format.get(idx) {
+            match length {
+                b'l' | b'z' | b'j' => long = true,
+                b'h' => {}
+                _ => break,
+            }
+            idx += 1;
+        }
+
+        let Some(&conversion) = format.get(idx) else {
+            out.extend_from_slice(&format[start..]);
+            break;
+        };
+        idx += 1;
+
+        let signed = |value: i64| if long { value } else { value as i32 as i64 };
+        let unsigned = |value: i64| {
+            if long {
+                value as u64
+            } else {
+                value as u32 as u64
+            }
+        };
+
+        let (text, is_number) = match conversion {
+            b'%' => {
+                out.push(b'%');
+                continue;
+            }
+            b'd' | b'i' => (signed(next_arg()).to_string().into_bytes(), true),
+            b'u' => (unsigned(next_arg()).to_string().into_bytes(), true),
+            b'x' => (format!("{:x}", unsigned(next_arg())).into_bytes(), true),
+            b'X' => (format!("{:X}", unsigned(next_arg())).into_bytes(), true),
+            b'f' | b'F' => {
+                let value = f64::from_bits(next_arg() as u64);
+                let text = format!("{:.*}", precision.unwrap_or(6), value);
+                (text.into_bytes(), true)
+            }
+            b'p' => (format!("{:#x}", next_arg() as u64).into_bytes(), false),
+            b'c' => (vec![next_arg() as u8], false),
+            b's' => {
+                let mut text = c_string(next_arg() as u64)?;
+                if let Some(precision) = precision {
+                    text.truncate(precision);
+                }
+                (text, false)
+            }
+            // unbekannt, bleibt wie es ist
+            _ => {
+                out.extend_from_slice(&format[start..idx]);
+                continue;
+            }
+        };
+
+        let padding = width.saturating_sub(text.len());
+        match (left_align, zero_pad && is_number) {
+            (true, _) => {
+                out.extend(text);
+                out.resize(out.len() + padding, b' ');
+            }
+            // die nullen kommen nach dem vorzeichen
+            (false, true) => {
+                let sign = text.starts_with(b"-") as usize;
+                out.extend_from_slice(&text[..sign]);
+                out.resize(out.len() + padding, b'0');
+                out.extend_from_slice(&text[sign..]);
+            }
+            (false, false) => {
+                out.resize(out.len() + padding, b' ');
+                out.extend(text);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+fn parse_number(format: &[u8], idx: &mut usize) -> Option<usize> {
+    let start = *idx;
+    while format.get(*idx).is_some_and(u8::is_ascii_digit) {
+        *idx += 1;
+    }
+
+    std::str::from_utf8(&format[start..*idx]).ok()?.parse().ok()
+}
+
+/// 1601 bis 1970 in 100ns schritten, für `GetSystemTimeAsFileTime`
+const WINDOWS_EPOCH_OFFSET: u128 = 116_444_736_000_000_000;
+
+/// zeit seit 1970, im browser gibt es keine systemzeit
+fn current_time() -> Duration {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    Duration::ZERO
+}
@@ -0,0 +1,510 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Write},
+    rc::Rc,
+};
+
+use crate::{
+    lexer::{
+        position::{Span, Spanned},
+        tokens::Literal,
+    },
+    parser::{
+        ast::{
+            Block, Closure, CommonGeneric, DataType, Function, FunctionParam, Program, Trait,
+            CLASS_SELF_ARG_NAME, MAIN_ARGS_FUNCTION_NAME,
+        },
+        structures::closures::{local_closures, reference_captures},
+    },
+};
+
+use super::{
+    error::{RuntimeError, Trap},
+    memory::Memory,
+    statements::Flow,
+    values::{
+        bool_to_bytes, float_to_bytes, int_from_bytes, int_to_bytes, pointer_from_bytes,
+        pointer_to_bytes, POINTER_SIZE,
+    },
+};
+
+/// tiefer geht es auch nativ meistens nicht, der rust stack reicht dafür mit 256mb
+const MAX_CALL_DEPTH: usize = 4096;
+/// `argv[0]` wenn keine argumente angegeben sind
+const DEFAULT_PROGRAM_NAME: &str = "main";
+
+/// funktionen als wert, `{ index + 1, umgebung }` im speicher
+#[derive(Debug, Clone)]
+pub(in crate::interpreter) enum Callable {
+    /// `list.map(double)`, die umgebung wird ignoriert
+    Function(Rc<Function>),
+    Closure(Rc<Closure>),
+}
+
+/// ein block, beim verlassen wird der stack zurückgesetzt
+#[derive(Debug)]
+struct Scope {
+    variables: HashMap<String, u64>,
+    stack_pointer: u64,
+}
+
+/// wo `let` und closures eines bodys ihren speicher bekommen, wie in codegen
+#[derive(Debug, Default)]
+struct ClosureStorage {
+    /// von closures per referenz gefangen, liegen auf dem heap
+    boxed_variables: HashSet<Spanned<String>>,
+    /// verlassen ihren block nicht, die umgebung liegt auf dem stack
+    local_closures: HashSet<Span>,
+}
+
+/// variablen eines funktionsaufrufs, innerster block zuletzt
+#[derive(Debug)]
+struct Frame {
+    scopes: Vec<Scope>,
+    closure_storage: Rc<ClosureStorage>,
+}
+
+/// führt ein `Program` direkt aus, ohne llvm und clang
+///
+/// alle werte liegen als bytes im (simulierten) speicher, das layout ist das von `DataType::size`,
+/// damit funktionieren pointer, `&x` und `size_of` wie im kompilierten programm
+pub struct Interpreter {
+    pub program: Program,
+    pub(in crate::interpreter) memory: Memory,
+    /// alle funktionen inklusive generischer subtypen, nach internem namen
+    functions: HashMap<String, Rc<Function>>,
+    pub(in crate::interpreter) callables: Vec<Callable>,
+    /// closures aus dem ast, damit eine schleife nicht jedes mal eine neue anlegt
+    closure_ids: HashMap<*const Closure, u64>,
+    /// pro body, die funktionen und closures dazu werden nie freigegeben
+    closure_storage: HashMap<*const Block, Rc<ClosureStorage>>,
+    function_ref_ids: HashMap<String, u64>,
+    trait_functions: HashMap<(Trait, Vec<DataType>), Option<Rc<Function>>>,
+    globals: HashMap<String, u64>,
+    frames: Vec<Frame>,
+    pub(in crate::interpreter) args: Vec<String>,
+    pub(in crate::interpreter) argv: Option<u64>,
+    /// von `putenv`, wird vor den variablen des prozesses gesucht
+    pub(in crate::interpreter) environment: HashMap<String, String>,
+    pub(in crate::interpreter) random_state: u64,
+    pub(in crate::interpreter) stdout: Box<dyn Write>,
+    pub(in crate::interpreter) stdin: Box<dyn Read>,
+}
+
+impl Interpreter {
+    pub fn new(program: Program) -> Self {
+        let mut interpreter = Interpreter {
+            program: Program::default(),
+            memory: Memory::default(),
+            functions: HashMap::new(),
+            callables: vec![],
+            closure_ids: HashMap::new(),
+            closure_storage: HashMap::new(),
+            function_ref_ids: HashMap::new(),
+            trait_functions: HashMap::new(),
+            globals: HashMap::new(),
+            frames: vec![],
+            args: vec![DEFAULT_PROGRAM_NAME.to_string()],
+            argv: None,
+            environment: HashMap::new(),
+            random_state: 1,
+            stdout: Box::new(std::io::sink()),
+            stdin: Box::new(std::io::empty()),
+        };
+
+        // äußerster block, hier liegen die globalen und die variablen der repl
+        interpreter.frames.push(Frame {
+            scopes: vec![Scope {
+                variables: HashMap::new(),
+                stack_pointer: interpreter.memory.stack_pointer(),
+            }],
+            closure_storage: Rc::default(),
+        });
+
+        interpreter.set_program(program);
+        interpreter
+    }
+
+    /// `argv`, mit dem namen des programms als erstem argument
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    pub fn with_output(mut self, stdout: Box<dyn Write>) -> Self {
+        self.stdout = stdout;
+        self
+    }
+
+    pub fn with_input(mut self, stdin: Box<dyn Read>) -> Self {
+        self.stdin = stdin;
+        self
+    }
+
+    /// ersetzt das programm, z.b. wenn in der repl neue funktionen dazu kommen
+    pub fn set_program(&mut self, program: Program) {
+        self.program = program;
+        self.trait_functions.clear();
+        self.index_functions();
+
+        let new_globals = self
+            .program
+            .globals
+            .iter()
+            .filter(|(name, _)| !self.globals.contains_key(*name))
+            .map(|(name, global)| (name.clone(), global.value.clone()))
+            .collect::<Vec<_>>();
+
+        for (name, global) in new_globals {
+            let bytes = literal_bytes(&global.value, &global._type.value);
+            // die globalen liegen im äußersten block und bleiben damit für immer
+            let address = self
+                .allocate(&bytes)
+                .expect("globals should fit on the stack");
+            self.globals.insert(name, address);
+        }
+    }
+
+    /// ruft `main` auf und gibt den exit code zurück
+    pub fn run_main(&mut self) -> Result<i32, RuntimeError> {
+        let result = self.call_main();
+        self.stdout.flush().ok();
+
+        match result {
+            Ok(code) | Err(Trap::Exit(code)) => Ok(code),
+            Err(Trap::Error(err)) => Err(err),
+        }
+    }
+
+    fn call_main(&mut self) -> Result<i32, Trap> {
+        let main = self
+            .functions
+            .get("main")
+            .cloned()
+            .ok_or_else(|| RuntimeError::UnknownFunction("main".to_string()))?;
+
+        // `def main(args: List<String>)`, wie beim wrapper in codegen
+        let args = match main.params.value.is_empty() {
+            true => vec![],
+            false => {
+                let build_args = self.function_by_name(MAIN_ARGS_FUNCTION_NAME)?;
+                vec![self.call_function(&build_args, vec![])?]
+            }
+        };
+
+        let result = self.call_function(&main, args)?;
+        let return_type = &main.return_type.value;
+
+        Ok(match return_type {
+            DataType::None => 0,
+            _ => int_from_bytes(&result, return_type) as i32,
+        })
+    }
+
+    /// funktionen aus dem programm und alle generischen subtypen davon,
+    /// schon bekannte bleiben erhalten (closures im ast werden über ihre adresse gefunden)
+    fn index_functions(&mut self) {
+        fn add(functions: &mut HashMap<String, Rc<Function>>, function: &Function) {
+            for subtype in function.generic_subtypes.values() {
+                add(functions, subtype);
+            }
+
+            if !function.is_generic() {
+                functions
+                    .entry(function.name.clone())
+                    .or_insert_with(|| Rc::new(function.clone()));
+            }
+        }
+
+        for function in self.program.functions.values() {
+            add(&mut self.functions, &function.value);
+        }
+    }
+
+    /// die vollständige funktion, die im aufruf ist bei rekursion noch ohne body
+    pub(in crate::interpreter) fn function(&mut self, function: &Function) -> Rc<Function> {
+        if let Some(known) = self.functions.get(&function.name) {
+            return known.clone();
+        }
+
+        let function = Rc::new(function.clone());
+        self.functions
+            .insert(function.name.clone(), function.clone());
+        function
+    }
+
+    fn function_by_name(&self, name: &str) -> Result<Rc<Function>, RuntimeError> {
+        self.functions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| RuntimeError::UnknownFunction(name.to_string()))
+    }
+
+    /// überschriebener operator (oder `Index`, `IterLen`, ...) wie `get_trait_function`
+    pub(in crate::interpreter) fn trait_function(
+        &mut self,
+        trait_: &Trait,
+        params: &[DataType],
+    ) -> Option<Rc<Function>> {
+        let key = (trait_.clone(), params.to_vec());
+        if let Some(function) = self.trait_functions.get(&key) {
+            return function.clone();
+        }
+
+        let type_info = self.program.get_type_info(&params[0]);
+        let function = self
+            .program
+            .get_trait_function(&type_info, trait_, params)
+            .map(|function| self.function(&function));
+
+        self.trait_functions.insert(key, function.clone());
+        function
+    }
+
+    pub(in crate::interpreter) fn closure_id(&mut self, closure: &Closure) -> u64 {
+        let key = closure as *const Closure;
+        if let Some(id) = self.closure_ids.get(&key) {
+            return *id;
+        }
+
+        self.callables
+            .push(Callable::Closure(Rc::new(closure.clone())));
+        let id = self.callables.len() as u64;
+        self.closure_ids.insert(key, id);
+        id
+    }
+
+    pub(in crate::interpreter) fn function_ref_id(&mut self, function: &Function) -> u64 {
+        if let Some(id) = self.function_ref_ids.get(&function.name) {
+            return *id;
+        }
+
+        let function = self.function(function);
+        self.callables.push(Callable::Function(function.clone()));
+        let id = self.callables.len() as u64;
+        self.function_ref_ids.insert(function.name.clone(), id);
+        id
+    }
+
+    /// nach einer eingabe der repl gibt es deren ast nicht mehr
+    pub(in crate::interpreter) fn forget_closure_ids(&mut self) {
+        self.closure_ids.clear();
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().unwrap()
+    }
+
+    pub(in crate::interpreter) fn push_scope(&mut self) {
+        let stack_pointer = self.memory.stack_pointer();
+        self.frame().scopes.push(Scope {
+            variables: HashMap::new(),
+            stack_pointer,
+        });
+    }
+
+    pub(in crate::interpreter) fn pop_scope(&mut self) {
+        let scope = self.frame().scopes.pop().unwrap();
+        self.memory.reset_stack(scope.stack_pointer);
+    }
+
+    pub(in crate::interpreter) fn declare(&mut self, name: &str, address: u64) {
+        let scope = self.frame().scopes.last_mut().unwrap();
+        scope.variables.insert(name.to_string(), address);
+    }
+
+    /// lokale variablen überdecken globale
+    pub(in crate::interpreter) fn lookup(&self, name: &str) -> Result<u64, RuntimeError> {
+        let frame = self.frames.last().unwrap();
+        frame
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.variables.get(name))
+            .or_else(|| self.globals.get(name))
+            .copied()
+            .ok_or_else(|| RuntimeError::UnknownVariable(name.to_string()))
+    }
+
+    /// legt `bytes` auf den stack
+    pub(in crate::interpreter) fn allocate(&mut self, bytes: &[u8]) -> Result<u64, RuntimeError> {
+        let address = self.memory.alloca(bytes.len())?;
+        self.memory.write(address, bytes)?;
+        Ok(address)
+    }
+
+    /// `let`, eine closure kann die variable über den aufruf hinaus behalten
+    pub(in crate::interpreter) fn allocate_variable(
+        &mut self,
+        name: &Spanned<String>,
+        bytes: &[u8],
+    ) -> Result<u64, RuntimeError> {
+        if !self.frame().closure_storage.boxed_variables.contains(name) {
+            return self.allocate(bytes);
+        }
+
+        let address = self.memory.malloc(bytes.len() as u64)?;
+        self.memory.write(address, bytes)?;
+        Ok(address)
+    }
+
+    /// die umgebung einer closure, auf dem heap wenn die closure ihren block verlassen kann
+    pub(in crate::interpreter) fn allocate_env(
+        &mut self,
+        closure: &Closure,
+        bytes: &[u8],
+    ) -> Result<u64, RuntimeError> {
+        let span = &closure.body.span;
+        if self.frame().closure_storage.local_closures.contains(span) {
+            return self.allocate(bytes);
+        }
+
+        let address = self.memory.malloc(bytes.len() as u64)?;
+        self.memory.write(address, bytes)?;
+        Ok(address)
+    }
+
+    /// `self` wird als pointer übergeben, alle anderen argumente als wert
+    pub(in crate::interpreter) fn call_function(
+        &mut self,
+        function: &Rc<Function>,
+        args: Vec<Vec<u8>>,
+    ) -> Result<Vec<u8>, Trap> {
+        if function.is_extern {
+            return self.call_extern(function, args);
+        }
+
+        self.with_frame(&function.body.value, |interpreter| {
+            interpreter.bind_params(&function.params.value, args)?;
+            interpreter.run_body(&function.body.value)
+        })
+    }
+
+    pub(in crate::interpreter) fn call_closure(
+        &mut self,
+        closure: &Rc<Closure>,
+        env: u64,
+        args: Vec<Vec<u8>>,
+    ) -> Result<Vec<u8>, Trap> {
+        self.with_frame(&closure.body.value, |interpreter| {
+            // wie `unpack_closure_env`, `mut` variablen liegen als pointer in der umgebung
+            let mut offset = 0;
+            for capture in closure.captures.iter() {
+                let field = env + offset;
+                let address = match capture.by_reference {
+                    true => {
+                        offset += POINTER_SIZE as u64;
+                        pointer_from_bytes(interpreter.memory.read(field, POINTER_SIZE)?)
+                    }
+                    false => {
+                        offset += capture.variable._type.size() as u64;
+                        field
+                    }
+                };
+                interpreter.declare(&capture.variable.name.value, address);
+            }
+
+            interpreter.bind_params(&closure.params.value, args)?;
+            interpreter.run_body(&closure.body.value)
+        })
+    }
+
+    /// ruft einen funktionswert (`{ index + 1, umgebung }`) auf
+    pub(in crate::interpreter) fn call_value(
+        &mut self,
+        value: &[u8],
+        mut args: Vec<Vec<u8>>,
+    ) -> Result<Vec<u8>, Trap> {
+        let id = pointer_from_bytes(value);
+        let env = pointer_from_bytes(&value[POINTER_SIZE..]);
+
+        let callable = id
+            .checked_sub(1)
+            .and_then(|idx| self.callables.get(idx as usize))
+            .cloned()
+            .ok_or(RuntimeError::InvalidFunction)?;
+
+        match callable {
+            Callable::Function(function) => {
+                if function.is_method() && !function.is_static_method() && !args.is_empty() {
+                    let address = self.allocate(&args[0])?;
+                    args[0] = pointer_to_bytes(address);
+                }
+                self.call_function(&function, args)
+            }
+            Callable::Closure(closure) => self.call_closure(&closure, env, args),
+        }
+    }
+
+    fn with_frame<F>(&mut self, body: &Block, f: F) -> Result<Vec<u8>, Trap>
+    where
+        F: FnOnce(&mut Interpreter) -> Result<Vec<u8>, Trap>,
+    {
+        if self.frames.len() > MAX_CALL_DEPTH {
+            return Err(RuntimeError::StackOverflow.into());
+        }
+
+        let closure_storage = self
+            .closure_storage
+            .entry(body as *const Block)
+            .or_insert_with(|| {
+                Rc::new(ClosureStorage {
+                    boxed_variables: reference_captures(body),
+                    local_closures: local_closures(body),
+                })
+            })
+            .clone();
+
+        let stack_pointer = self.memory.stack_pointer();
+        self.frames.push(Frame {
+            scopes: vec![Scope {
+                variables: HashMap::new(),
+                stack_pointer,
+            }],
+            closure_storage,
+        });
+
+        let result = f(self);
+
+        self.frames.pop();
+        self.memory.reset_stack(stack_pointer);
+        result
+    }
+
+    fn bind_params(
+        &mut self,
+        params: &[Spanned<FunctionParam>],
+        args: Vec<Vec<u8>>,
+    ) -> Result<(), Trap> {
+        for (idx, (param, arg)) in params.iter().zip(args).enumerate() {
+            let name = &param.value.name.value;
+
+            // `self` zeigt auf das objekt des aufrufers
+            let address = match idx == 0 && name == CLASS_SELF_ARG_NAME {
+                true => pointer_from_bytes(&arg),
+                false => self.allocate(&arg)?,
+            };
+            self.declare(name, address);
+        }
+
+        Ok(())
+    }
+
+    fn run_body(&mut self, body: &crate::parser::ast::Block) -> Result<Vec<u8>, Trap> {
+        match self.exec_block(body)? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+/// die bytes eines literals mit dem typ des ausdrucks (`1` kann auch ein `int8` sein)
+pub(crate) fn literal_bytes(literal: &Literal, _type: &DataType) -> Vec<u8> {
+    match literal {
+        Literal::Integer(value) if _type.is_float() => float_to_bytes(*value as f64, _type),
+        Literal::Integer(value) => int_to_bytes(*value as i128, _type.size()),
+        Literal::Float(value) => float_to_bytes(*value, _type),
+        Literal::Boolean(value) => bool_to_bytes(*value),
+        _ => vec![],
+    }
+}
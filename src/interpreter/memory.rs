@@ -0,0 +1,174 @@
+use super::error::RuntimeError;
+
+/// adresse 0 ist null, davor liegt nichts
+const STACK_BASE: u64 = 0x1000;
+pub const STACK_SIZE: u64 = 8 * 1024 * 1024;
+/// passt auch mit 32 bit pointern (wasm)
+const HEAP_BASE: u64 = 0x1000_0000;
+const HEAP_SIZE: u64 = 1024 * 1024 * 1024;
+/// vor jedem block auf dem heap steht seine größe
+const HEADER_SIZE: u64 = 8;
+const ALIGN: u64 = 8;
+
+/// der speicher des programms, der stack wird wie bei `alloca` nur weiter geschoben
+/// und beim verlassen eines blocks zurückgesetzt, aber nicht überschrieben
+#[derive(Debug, Default)]
+pub struct Memory {
+    stack: Vec<u8>,
+    stack_pointer: u64,
+    heap: Vec<u8>,
+    /// freigegebene blöcke (adresse, größe)
+    free_blocks: Vec<(u64, u64)>,
+}
+
+impl Memory {
+    pub fn stack_pointer(&self) -> u64 {
+        STACK_BASE + self.stack_pointer
+    }
+
+    /// alles über `stack_pointer` darf wieder überschrieben werden
+    pub fn reset_stack(&mut self, stack_pointer: u64) {
+        self.stack_pointer = stack_pointer - STACK_BASE;
+    }
+
+    pub fn alloca(&mut self, size: usize) -> Result<u64, RuntimeError> {
+        let address = self.stack_pointer();
+        let end = self.stack_pointer + size as u64;
+
+        if end > STACK_SIZE {
+            return Err(RuntimeError::StackOverflow);
+        }
+
+        if end as usize > self.stack.len() {
+            self.stack.resize(end as usize, 0);
+        }
+
+        self.stack_pointer = end;
+        Ok(address)
+    }
+
+    pub fn malloc(&mut self, size: u64) -> Result<u64, RuntimeError> {
+        // `malloc(-1)` ist hier ein riesiger block, c gibt dafür null zurück
+        let size = size
+            .max(1)
+            .checked_next_multiple_of(ALIGN)
+            .ok_or(RuntimeError::OutOfMemory)?;
+
+        if let Some(idx) = self.free_blocks.iter().position(|(_, free)| *free >= size) {
+            return Ok(self.free_blocks.swap_remove(idx).0);
+        }
+
+        let header = self.heap.len() as u64;
+        let end = match (header + HEADER_SIZE).checked_add(size) {
+            Some(end) if end <= HEAP_SIZE => end,
+            _ => return Err(RuntimeError::OutOfMemory),
+        };
+
+        self.heap.resize(end as usize, 0);
+        let address = HEAP_BASE + header + HEADER_SIZE;
+        self.write(HEAP_BASE + header, &size.to_le_bytes())?;
+        Ok(address)
+    }
+
+    pub fn calloc(&mut self, size: u64) -> Result<u64, RuntimeError> {
+        let address = self.malloc(size)?;
+        self.write(address, &vec![0; size as usize])?;
+        Ok(address)
+    }
+
+    pub fn realloc(&mut self, address: u64, size: u64) -> Result<u64, RuntimeError> {
+        if address == 0 {
+            return self.malloc(size);
+        }
+
+        let old_size = self.block_size(address)?;
+        if size <= old_size {
+            return Ok(address);
+        }
+
+        let new_address = self.malloc(size)?;
+        let data = self.read(address, old_size as usize)?.to_vec();
+        self.write(new_address, &data)?;
+        self.free(address)?;
+        Ok(new_address)
+    }
+
+    pub fn free(&mut self, address: u64) -> Result<(), RuntimeError> {
+        if address == 0 {
+            return Ok(());
+        }
+
+        let size = self.block_size(address)?;
+        self.free_blocks.push((address, size));
+        Ok(())
+    }
+
+    fn block_size(&self, address: u64) -> Result<u64, RuntimeError> {
+        if address < HEAP_BASE + HEADER_SIZE {
+            return Err(RuntimeError::InvalidAddress(address));
+        }
+
+        let header = self.read(address - HEADER_SIZE, HEADER_SIZE as usize)?;
+        Ok(u64::from_le_bytes(header.try_into().unwrap()))
+    }
+
+    pub fn read(&self, address: u64, size: usize) -> Result<&[u8], RuntimeError> {
+        let (segment, start) = self.segment(address, size)?;
+        let segment = match segment {
+            Segment::Stack => &self.stack,
+            Segment::Heap => &self.heap,
+        };
+        Ok(&segment[start..start + size])
+    }
+
+    pub fn write(&mut self, address: u64, bytes: &[u8]) -> Result<(), RuntimeError> {
+        let (segment, start) = self.segment(address, bytes.len())?;
+        let segment = match segment {
+            Segment::Stack => &mut self.stack,
+            Segment::Heap => &mut self.heap,
+        };
+        segment[start..start + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// bis zur ersten 0, ohne die 0
+    pub fn c_string(&self, address: u64) -> Result<Vec<u8>, RuntimeError> {
+        let mut out = vec![];
+        loop {
+            match self.read(address + out.len() as u64, 1)?[0] {
+                0 => return Ok(out),
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// legt `bytes` mit einer 0 am ende auf den heap
+    pub fn allocate_c_string(&mut self, bytes: &[u8]) -> Result<u64, RuntimeError> {
+        let address = self.malloc(bytes.len() as u64 + 1)?;
+        self.write(address, bytes)?;
+        self.write(address + bytes.len() as u64, &[0])?;
+        Ok(address)
+    }
+
+    fn segment(&self, address: u64, size: usize) -> Result<(Segment, usize), RuntimeError> {
+        let invalid = RuntimeError::InvalidAddress(address);
+
+        let (segment, start, len) = if address >= HEAP_BASE {
+            (Segment::Heap, address - HEAP_BASE, self.heap.len())
+        } else if address >= STACK_BASE {
+            (Segment::Stack, address - STACK_BASE, self.stack.len())
+        } else {
+            return Err(invalid);
+        };
+
+        match start.checked_add(size as u64) {
+            Some(end) if end <= len as u64 => Ok((segment, start as usize)),
+            _ => Err(invalid),
+        }
+    }
+}
+
+enum Segment {
+    Stack,
+    Heap,
+}
@@ -0,0 +1,14 @@
+pub mod error;
+mod expressions;
+pub mod host;
+pub mod interpreter_main;
+pub mod memory;
+pub mod repl;
+mod statements;
+pub mod values;
+
+pub use error::RuntimeError;
+pub use host::CapturedOutput;
+pub use interpreter_main::Interpreter;
+pub use repl::{ReplError, Session};
+pub use values::Value;
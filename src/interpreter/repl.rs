@@ -0,0 +1,239 @@
+use std::{io::Write, path::PathBuf};
+
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::{
+    error::CompilerError,
+    lexer::{
+        lexer_main::lex,
+        position::{Span, Spanned},
+        tokens::{Keyword, Punctuation, Token},
+    },
+    parser::{
+        ast::{Block, DataType},
+        parser_main::Parser,
+    },
+};
+
+use super::{
+    error::{RuntimeError, Trap},
+    interpreter_main::Interpreter,
+    values::Value,
+};
+
+#[derive(Error, Debug)]
+pub enum ReplError {
+    #[error("{}", .0.iter().map(|e| e.value.err_msg()).join("\n"))]
+    Compile(Vec<Spanned<Box<dyn CompilerError>>>),
+    #[error("{0}")]
+    Runtime(RuntimeError),
+    /// `exit` wurde aufgerufen
+    #[error("exited with code {0}")]
+    Exit(i32),
+}
+
+impl From<Trap> for ReplError {
+    fn from(trap: Trap) -> Self {
+        match trap {
+            Trap::Exit(code) => ReplError::Exit(code),
+            Trap::Error(err) => ReplError::Runtime(err),
+        }
+    }
+}
+
+/// eine repl sitzung, definitionen und variablen bleiben zwischen den eingaben erhalten
+///
+/// items (`def`, `class`, `import`, ...) kommen ins `Program`, statements werden direkt
+/// im äußersten block ausgeführt und ein einzelner ausdruck gibt seinen wert zurück
+pub struct Session {
+    parser: Parser,
+    /// die variablen der sitzung für den parser
+    scope: Block,
+    interpreter: Interpreter,
+}
+
+impl Session {
+    pub fn new(library_paths: Vec<PathBuf>) -> Self {
+        let current_dir = std::env::current_dir().ok();
+        let parser = Parser::new(vec![], current_dir.as_deref()).with_library_paths(library_paths);
+        let interpreter = Interpreter::new(parser.program.clone())
+            .with_output(Box::new(std::io::stdout()))
+            .with_input(Box::new(std::io::stdin()));
+
+        Session {
+            parser,
+            scope: Block::default(),
+            interpreter,
+        }
+    }
+
+    pub fn with_output(mut self, stdout: Box<dyn Write>) -> Self {
+        self.interpreter.stdout = stdout;
+        self
+    }
+
+    pub fn eval(&mut self, input: &str) -> Result<Option<Value>, ReplError> {
+        let result = self.eval_input(input);
+        self.interpreter.forget_closure_ids();
+        self.interpreter.stdout.flush().ok();
+        result
+    }
+
+    fn eval_input(&mut self, input: &str) -> Result<Option<Value>, ReplError> {
+        let mut tokens = lex(input).map_err(|err| {
+            ReplError::Compile(vec![Spanned {
+                value: Box::new(err.value) as Box<dyn CompilerError>,
+                span: err.span,
+            }])
+        })?;
+
+        let Some(first) = tokens.first() else {
+            return Ok(None);
+        };
+
+        if is_item(&first.value) {
+            let mut parser = self.parser.clone();
+            parser.tokens = itertools::peek_nth(tokens);
+            parser.parse().map_err(compile_errors)?;
+            self.commit(parser);
+            return Ok(None);
+        }
+
+        let ends_with = |tokens: &[Spanned<Token>], punctuation| {
+            tokens.last().map(|t| &t.value) == Some(&Token::Punctuation(punctuation))
+        };
+
+        // `x + 1` ohne `;` ist ein ausdruck, dessen wert angezeigt wird
+        if !ends_with(&tokens, Punctuation::SemiColon) {
+            if let Some(value) = self.try_eval_expression(&tokens)? {
+                return Ok(value);
+            }
+        }
+
+        // auch nach `for ... { }`, sonst endet `parse_statement` beim suchen nach dem `;` in einem UnexpectedEOF
+        if !ends_with(&tokens, Punctuation::SemiColon) {
+            tokens.push(semicolon());
+        }
+
+        let mut parser = self.parser.clone();
+        let mut scope = self.scope.clone();
+        parser.tokens = itertools::peek_nth(tokens);
+
+        let mut statements = vec![];
+        while parser.tokens.peek().is_some() {
+            let statement = parser
+                .parse_statement(&mut scope)
+                .map_err(|err| compile_errors(vec![err]))?;
+            if !parser.errors.is_empty() {
+                return Err(compile_errors(std::mem::take(&mut parser.errors)));
+            }
+            statements.push(statement);
+        }
+
+        self.scope = scope;
+        self.commit(parser);
+        self.interpreter.exec_statements(&statements)?;
+        Ok(None)
+    }
+
+    /// `None` wenn die eingabe kein einzelner ausdruck ist
+    fn try_eval_expression(
+        &mut self,
+        tokens: &[Spanned<Token>],
+    ) -> Result<Option<Option<Value>>, ReplError> {
+        let mut parser = self.parser.clone();
+        let mut scope = self.scope.clone();
+        let mut tokens = tokens.to_vec();
+        tokens.push(semicolon());
+        parser.tokens = itertools::peek_nth(tokens);
+
+        let expr = match parser.parse_expression(&mut scope) {
+            Ok(expr) if parser.errors.is_empty() => expr,
+            _ => return Ok(None),
+        };
+
+        let is_end = parser.tokens.next().map(|t| t.value) == Some(semicolon().value)
+            && parser.tokens.peek().is_none();
+        if !is_end {
+            return Ok(None);
+        }
+
+        self.commit(parser);
+
+        // werte von ausdrücken werden danach nicht mehr gebraucht
+        let stack_pointer = self.interpreter.memory.stack_pointer();
+        let value = self.interpreter.eval(&expr.value);
+        self.interpreter.memory.reset_stack(stack_pointer);
+
+        Ok(Some(match expr.value._type {
+            DataType::None => {
+                value?;
+                None
+            }
+            ref _type => Some(Value::decode(&value?, _type)),
+        }))
+    }
+
+    /// neue funktionen und generische subtypen aus dem parser übernehmen
+    fn commit(&mut self, parser: Parser) {
+        self.parser = parser;
+        self.interpreter.set_program(self.parser.program.clone());
+    }
+}
+
+/// klammern sind noch offen, die eingabe geht in der nächsten zeile weiter
+pub fn is_complete(input: &str) -> bool {
+    let Ok(tokens) = lex(input) else {
+        return true;
+    };
+
+    let depth = tokens.iter().fold(0, |depth, token| match token.value {
+        Token::Punctuation(
+            Punctuation::OpenBrace | Punctuation::OpenParen | Punctuation::OpenBracket,
+        ) => depth + 1,
+        Token::Punctuation(
+            Punctuation::CloseBrace | Punctuation::CloseParen | Punctuation::CloseBracket,
+        ) => depth - 1,
+        _ => depth,
+    });
+
+    depth <= 0
+}
+
+fn is_item(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Keyword(
+            Keyword::Def
+                | Keyword::Extern
+                | Keyword::Class
+                | Keyword::Enum
+                | Keyword::Trait
+                | Keyword::Impl
+                | Keyword::Import
+                | Keyword::From
+                | Keyword::Pub
+                | Keyword::Const
+                | Keyword::Static
+        )
+    )
+}
+
+fn semicolon() -> Spanned<Token> {
+    Spanned {
+        value: Token::Punctuation(Punctuation::SemiColon),
+        span: Span::default(),
+    }
+}
+
+fn compile_errors<E: CompilerError + 'static>(errs: Vec<Spanned<E>>) -> ReplError {
+    ReplError::Compile(
+        errs.into_iter()
+            .map(|err| Spanned {
+                value: Box::new(err.value) as Box<dyn CompilerError>,
+                span: err.span,
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,232 @@
+use crate::{
+    lexer::position::Spanned,
+    parser::ast::{
+        Block, DataType, ForIterable, Statement, Trait, TypedExpr, Variable, ASSIGNMENT_PLACE_NAME,
+    },
+};
+
+use super::{
+    error::{RuntimeError, Trap},
+    interpreter_main::Interpreter,
+    values::{int_from_bytes, int_to_bytes, pointer_to_bytes},
+};
+
+/// wie es nach einem statement weiter geht
+#[derive(Debug, Clone, PartialEq)]
+pub(in crate::interpreter) enum Flow {
+    Next,
+    Break,
+    Continue,
+    /// aus der funktion oder dem block ausdruck
+    Return(Vec<u8>),
+}
+
+impl Interpreter {
+    pub(in crate::interpreter) fn exec_block(&mut self, block: &Block) -> Result<Flow, Trap> {
+        self.push_scope();
+        let flow = self.exec_statements(&block.statements);
+        self.pop_scope();
+        flow
+    }
+
+    /// ohne eigenen block, die variablen bleiben danach sichtbar (repl)
+    pub(in crate::interpreter) fn exec_statements(
+        &mut self,
+        statements: &[Spanned<Statement>],
+    ) -> Result<Flow, Trap> {
+        for statement in statements {
+            match self.exec_statement(&statement.value)? {
+                Flow::Next => {}
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn exec_statement(&mut self, statement: &Statement) -> Result<Flow, Trap> {
+        match statement {
+            Statement::If {
+                condition,
+                true_branch,
+                else_if_branches,
+                false_branch,
+            } => {
+                if self.condition(&condition.value)? {
+                    return self.exec_block(&true_branch.value);
+                }
+
+                for branch in else_if_branches {
+                    if self.condition(&branch.value.condition.value)? {
+                        return self.exec_block(&branch.value.body.value);
+                    }
+                }
+
+                match false_branch {
+                    Some(false_branch) => self.exec_block(&false_branch.value),
+                    None => Ok(Flow::Next),
+                }
+            }
+            Statement::WhileLoop { condition, body } => {
+                loop {
+                    // werte aus der bedingung werden nach der prüfung nicht mehr gebraucht
+                    let stack_pointer = self.memory.stack_pointer();
+                    let keep_going = self.condition(&condition.value)?;
+                    self.memory.reset_stack(stack_pointer);
+
+                    if !keep_going {
+                        break;
+                    }
+
+                    match self.exec_block(&body.value)? {
+                        Flow::Break => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                        Flow::Next | Flow::Continue => {}
+                    }
+                }
+
+                Ok(Flow::Next)
+            }
+            Statement::ForLoop {
+                variable,
+                iterable,
+                body,
+            } => {
+                self.push_scope();
+                let flow = self.exec_for(&variable.value, &iterable.value, &body.value);
+                self.pop_scope();
+                flow
+            }
+            Statement::VariableDecl { name, value, .. } => {
+                let value = self.eval(&value.value)?;
+                let address = self.allocate_variable(name, &value)?;
+                self.declare(&name.value, address);
+                Ok(Flow::Next)
+            }
+            Statement::Return { value } => Ok(Flow::Return(self.eval(&value.value)?)),
+            // der typ des ausdrucks ist hier nicht bekannt, der wert wird aber auch nicht gebraucht
+            Statement::Expr(expr) => {
+                self.eval_expr(&expr.value, &DataType::None)?;
+                Ok(Flow::Next)
+            }
+            Statement::VariableMutation {
+                variable,
+                new_value,
+            } => {
+                let address = self.eval_place(&variable.value)?;
+                // `a[i] += 1` liest das ziel über den platzhalter, ohne `i` noch einmal zu berechnen
+                self.declare(ASSIGNMENT_PLACE_NAME, address);
+                let value = self.eval(&new_value.value)?;
+                self.memory.write(address, &value)?;
+                Ok(Flow::Next)
+            }
+            Statement::Break => Ok(Flow::Break),
+            Statement::Continue => Ok(Flow::Continue),
+        }
+    }
+
+    pub(in crate::interpreter) fn condition(
+        &mut self,
+        condition: &TypedExpr,
+    ) -> Result<bool, Trap> {
+        Ok(self.eval(condition)?[0] != 0)
+    }
+
+    /// wie `parse_for`, der zähler läuft von `start` bis `end`,
+    /// bei `Iterable` wird das element über den index geholt
+    fn exec_for(
+        &mut self,
+        variable: &Variable,
+        iterable: &ForIterable,
+        body: &Block,
+    ) -> Result<Flow, Trap> {
+        match iterable {
+            ForIterable::Range { start, end } => {
+                let counter_type = &start.value._type;
+                let start = self.eval(&start.value)?;
+                let counter = self.allocate(&start)?;
+                self.declare(&variable.name.value, counter);
+
+                let end = int_from_bytes(&self.eval(&end.value)?, &end.value._type);
+
+                loop {
+                    let value = self.memory.read(counter, counter_type.size())?;
+                    let value = int_from_bytes(value, counter_type);
+                    if value >= end {
+                        break;
+                    }
+
+                    match self.exec_block(body)? {
+                        Flow::Break => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                        Flow::Next | Flow::Continue => {}
+                    }
+
+                    let value = self.memory.read(counter, counter_type.size())?;
+                    let value = int_from_bytes(value, counter_type) + 1;
+                    self.memory
+                        .write(counter, &int_to_bytes(value, counter_type.size()))?;
+                }
+            }
+            ForIterable::Iterable(iterable) => {
+                let iterable_type = &iterable.value._type;
+                let value = self.eval(&iterable.value)?;
+                let iterable = self.allocate(&value)?;
+
+                let (len, element_type) = match iterable_type {
+                    DataType::Array { value_type, len } => (*len as i128, Some(value_type)),
+                    _ => {
+                        let iter_len = self
+                            .trait_function(&Trait::IterLen, std::slice::from_ref(iterable_type))
+                            .ok_or_else(|| {
+                                RuntimeError::Unsupported("iterating this type".to_string())
+                            })?;
+                        let len =
+                            self.call_function(&iter_len, vec![pointer_to_bytes(iterable)])?;
+                        (int_from_bytes(&len, &iter_len.return_type.value), None)
+                    }
+                };
+
+                for idx in 0..len {
+                    let element = match element_type {
+                        Some(element_type) => {
+                            let size = element_type.size();
+                            let address = iterable + (idx as usize * size) as u64;
+                            self.memory.read(address, size)?.to_vec()
+                        }
+                        None => {
+                            let index_type = DataType::get_integer_type();
+                            let iter_get = self
+                                .trait_function(
+                                    &Trait::IterGet,
+                                    &[iterable_type.clone(), index_type.clone()],
+                                )
+                                .ok_or_else(|| {
+                                    RuntimeError::Unsupported("iterating this type".to_string())
+                                })?;
+                            let args = vec![
+                                pointer_to_bytes(iterable),
+                                int_to_bytes(idx, index_type.size()),
+                            ];
+                            self.call_function(&iter_get, args)?
+                        }
+                    };
+
+                    self.push_scope();
+                    let address = self.allocate(&element)?;
+                    self.declare(&variable.name.value, address);
+                    let flow = self.exec_block(body);
+                    self.pop_scope();
+
+                    match flow? {
+                        Flow::Break => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                        Flow::Next | Flow::Continue => {}
+                    }
+                }
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use super::error::RuntimeError;
+use crate::parser::ast::{BinaryOperator, CustomDataType, DataType};
+
+/// wie `DataType::Pointer(_).size()`
+pub const POINTER_SIZE: usize = std::mem::size_of::<usize>();
+
+/// ein wert aus dem speicher, zum anzeigen (z.b. in der repl)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i128),
+    Float(f64),
+    Boolean(bool),
+    Pointer(u64),
+    Array(Vec<Value>),
+    Class {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+    Enum {
+        name: String,
+        variant: String,
+        fields: Vec<Value>,
+    },
+    Function,
+    Void,
+}
+
+impl Value {
+    pub fn decode(bytes: &[u8], _type: &DataType) -> Value {
+        match _type {
+            t if t.is_integer() => Value::Integer(int_from_bytes(bytes, t)),
+            // über den string, damit `16.4 as float` nicht als 16.399999618530273 angezeigt wird
+            DataType::Float => Value::Float(
+                (float_from_bytes(bytes, _type) as f32)
+                    .to_string()
+                    .parse()
+                    .unwrap(),
+            ),
+            DataType::Float64 => Value::Float(float_from_bytes(bytes, _type)),
+            DataType::Boolean => Value::Boolean(bytes[0] != 0),
+            DataType::Pointer(_) => Value::Pointer(int_from_bytes(bytes, _type) as u64),
+            DataType::Array { value_type, len } => Value::Array(
+                (0..*len)
+                    .map(|idx| {
+                        let size = value_type.size();
+                        Value::decode(&bytes[idx * size..(idx + 1) * size], value_type)
+                    })
+                    .collect(),
+            ),
+            DataType::Custom(custom) => decode_custom(bytes, custom),
+            DataType::Function { .. } => Value::Function,
+            _ => Value::Void,
+        }
+    }
+}
+
+fn decode_custom(bytes: &[u8], custom: &CustomDataType) -> Value {
+    let field = |idx: usize| {
+        let offset = custom.field_offset(idx);
+        let _type = &custom.fields.value[idx]._type.value;
+        Value::decode(&bytes[offset..offset + _type.size()], _type)
+    };
+
+    let Some(variants) = &custom.variants else {
+        return Value::Class {
+            name: custom.to_string(),
+            fields: custom
+                .fields
+                .value
+                .iter()
+                .enumerate()
+                .map(|(idx, f)| (f.name.value.clone(), field(idx)))
+                .collect(),
+        };
+    };
+
+    let tag = int_from_bytes(
+        &bytes[..custom.fields.value[0]._type.value.size()],
+        &DataType::get_integer_type(),
+    );
+    let name = custom.base_name().to_string();
+
+    match variants.get(tag as usize) {
+        Some(variant) => Value::Enum {
+            name,
+            variant: variant.name.value.clone(),
+            fields: variant.fields.iter().map(|idx| field(*idx)).collect(),
+        },
+        // nicht initialisierter speicher
+        None => Value::Enum {
+            name,
+            variant: format!("<{tag}>"),
+            fields: vec![],
+        },
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value:?}"),
+            Value::Boolean(value) => write!(f, "{value}"),
+            Value::Pointer(address) => write!(f, "{address:#x}"),
+            Value::Array(values) => write!(f, "[{}]", values.iter().join(", ")),
+            Value::Class { name, fields } if fields.is_empty() => write!(f, "{name} {{}}"),
+            Value::Class { name, fields } => write!(
+                f,
+                "{name} {{ {} }}",
+                fields
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .join(", ")
+            ),
+            Value::Enum {
+                name,
+                variant,
+                fields,
+            } if fields.is_empty() => write!(f, "{name}::{variant}"),
+            Value::Enum {
+                name,
+                variant,
+                fields,
+            } => write!(f, "{name}::{variant}({})", fields.iter().join(", ")),
+            Value::Function => write!(f, "<function>"),
+            Value::Void => write!(f, "void"),
+        }
+    }
+}
+
+/// signed typen werden vorzeichenerweitert, alles andere (auch pointer) nicht
+pub fn int_from_bytes(bytes: &[u8], _type: &DataType) -> i128 {
+    let mut buffer = [0; 16];
+    buffer[..bytes.len()].copy_from_slice(bytes);
+    let value = i128::from_le_bytes(buffer);
+
+    match _type.is_signed_integer() && !bytes.is_empty() {
+        true => {
+            let shift = 128 - bytes.len() * 8;
+            (value << shift) >> shift
+        }
+        false => value,
+    }
+}
+
+/// schneidet wie llvm auf `size` bytes ab
+pub fn int_to_bytes(value: i128, size: usize) -> Vec<u8> {
+    value.to_le_bytes()[..size].to_vec()
+}
+
+pub fn float_from_bytes(bytes: &[u8], _type: &DataType) -> f64 {
+    match _type {
+        DataType::Float => f32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+        _ => f64::from_le_bytes(bytes.try_into().unwrap()),
+    }
+}
+
+pub fn float_to_bytes(value: f64, _type: &DataType) -> Vec<u8> {
+    match _type {
+        DataType::Float => (value as f32).to_le_bytes().to_vec(),
+        _ => value.to_le_bytes().to_vec(),
+    }
+}
+
+pub fn pointer_to_bytes(address: u64) -> Vec<u8> {
+    int_to_bytes(address as i128, POINTER_SIZE)
+}
+
+pub fn pointer_from_bytes(bytes: &[u8]) -> u64 {
+    int_from_bytes(&bytes[..POINTER_SIZE], &DataType::UnsignedInteger64) as u64
+}
+
+pub fn bool_to_bytes(value: bool) -> Vec<u8> {
+    vec![value as u8]
+}
+
+/// operatoren ohne trait, wie in `parse_binary_expr` hat das ergebnis den typ der linken seite
+pub fn binary_operation(
+    left: &[u8],
+    op: &BinaryOperator,
+    right: &[u8],
+    _type: &DataType,
+) -> Result<Vec<u8>, RuntimeError> {
+    if _type.is_float() {
+        let (left, right) = (
+            float_from_bytes(left, _type),
+            float_from_bytes(right, _type),
+        );
+
+        let value = match op {
+            BinaryOperator::Add => left + right,
+            BinaryOperator::Subtract => left - right,
+            BinaryOperator::Multiply => left * right,
+            BinaryOperator::Divide => left / right,
+            BinaryOperator::Modulo => left % right,
+            BinaryOperator::Equal => return Ok(bool_to_bytes(left == right)),
+            BinaryOperator::NotEqual => return Ok(bool_to_bytes(left != right)),
+            BinaryOperator::LessThan => return Ok(bool_to_bytes(left < right)),
+            BinaryOperator::LessThanOrEqual => return Ok(bool_to_bytes(left <= right)),
+            BinaryOperator::GreaterThan => return Ok(bool_to_bytes(left > right)),
+            BinaryOperator::GreaterThanOrEqual => return Ok(bool_to_bytes(left >= right)),
+            op => return Err(RuntimeError::Unsupported(format!("`{op}` on floats"))),
+        };
+
+        return Ok(float_to_bytes(value, _type));
+    }
+
+    // unsigned werte sind hier nie negativ, `/`, `%`, `>>` und vergleiche passen also
+    let size = _type.size();
+    let bits = size as i128 * 8;
+    let (left, right) = (int_from_bytes(left, _type), int_from_bytes(right, _type));
+
+    let value = match op {
+        BinaryOperator::Add => left.wrapping_add(right),
+        BinaryOperator::Subtract => left.wrapping_sub(right),
+        BinaryOperator::Multiply => left.wrapping_mul(right),
+        BinaryOperator::Divide | BinaryOperator::Modulo if right == 0 => {
+            return Err(RuntimeError::DivisionByZero)
+        }
+        BinaryOperator::Divide => left / right,
+        BinaryOperator::Modulo => left % right,
+        BinaryOperator::And | BinaryOperator::BitAnd => left & right,
+        BinaryOperator::Or | BinaryOperator::BitOr => left | right,
+        BinaryOperator::BitXor => left ^ right,
+        // in llvm ist das poison, hier einfach 0
+        BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight if !(0..bits).contains(&right) => 0,
+        BinaryOperator::ShiftLeft => left.wrapping_shl(right as u32),
+        BinaryOperator::ShiftRight => left >> right,
+        BinaryOperator::Equal => return Ok(bool_to_bytes(left == right)),
+        BinaryOperator::NotEqual => return Ok(bool_to_bytes(left != right)),
+        BinaryOperator::LessThan => return Ok(bool_to_bytes(left < right)),
+        BinaryOperator::LessThanOrEqual => return Ok(bool_to_bytes(left <= right)),
+        BinaryOperator::GreaterThan => return Ok(bool_to_bytes(left > right)),
+        BinaryOperator::GreaterThanOrEqual => return Ok(bool_to_bytes(left >= right)),
+    };
+
+    Ok(int_to_bytes(value, size))
+}
+
+/// wie `parse_type_cast`
+pub fn cast(bytes: &[u8], from: &DataType, to: &DataType) -> Result<Vec<u8>, RuntimeError> {
+    let is_int_like =
+        |t: &DataType| t.is_integer() || t.is_boolean() || matches!(t, DataType::Pointer(_));
+
+    if is_int_like(from) && is_int_like(to) {
+        let value = int_from_bytes(bytes, from);
+        // `trunc` zu i1 behält nur das letzte bit
+        return match to.is_boolean() {
+            true => Ok(bool_to_bytes(value & 1 == 1)),
+            false => Ok(int_to_bytes(value, to.size())),
+        };
+    }
+
+    if from.is_integer() && to.is_float() {
+        return Ok(float_to_bytes(int_from_bytes(bytes, from) as f64, to));
+    }
+
+    if from.is_float() && to.is_integer() {
+        return Ok(int_to_bytes(
+            float_from_bytes(bytes, from) as i128,
+            to.size(),
+        ));
+    }
+
+    if from.is_float() && to.is_float() {
+        return Ok(float_to_bytes(float_from_bytes(bytes, from), to));
+    }
+
+    Err(RuntimeError::Unsupported(format!(
+        "casting from `{from}` to `{to}`"
+    )))
+}
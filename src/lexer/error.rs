@@ -7,7 +7,7 @@ pub enum TokenError {
     UnknownToken(String),
 }
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum LexerError {
     #[error("{0}")]
     InvalidSyntax(String),
@@ -107,8 +107,8 @@ impl Lexer {
                 '\n' => {
                     self.advance(1);
                 }
-                // Kommentare
-                '#' => {
+                // Kommentare, `#[` beginnt ein attribut
+                '#' if self.chars.get(self.position.abs + 1) != Some(&'[') => {
                     while let Some(c) = self.chars.get(self.position.abs).cloned() {
                         self.advance(1);
                         if c == '\n' {
@@ -150,6 +150,13 @@ impl Lexer {
         }
 
         if let Some((pattern, token)) = last_match {
+            // keywords nur als ganzes wort, sonst wird "int64" zu "in" + "t64"
+            let is_word = pattern.chars().all(|c| c.is_alphanumeric() || c == '_');
+            let next_char = self.chars.get(self.position.abs + pattern.chars().count());
+            if is_word && next_char.is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                return Ok(None);
+            }
+
             self.advance(pattern.len());
             return Ok(Some(Spanned {
                 value: token,
@@ -82,6 +82,16 @@ pub enum Punctuation {
     #[strum(serialize = ".")]
     Period,
 
+    /// `..`
+    #[display(fmt = "..")]
+    #[strum(serialize = "..")]
+    Range,
+
+    /// `...`
+    #[display(fmt = "...")]
+    #[strum(serialize = "...")]
+    Ellipsis,
+
     /// `:`
     #[display(fmt = ":")]
     #[strum(serialize = ":")]
@@ -137,6 +147,11 @@ pub enum Punctuation {
     #[strum(serialize = "->")]
     ThinArrow,
 
+    /// `=>`
+    #[display(fmt = "=>")]
+    #[strum(serialize = "=>")]
+    FatArrow,
+
     /// `~`
     #[display(fmt = "~")]
     #[strum(serialize = "~")]
@@ -156,6 +171,11 @@ pub enum Punctuation {
     #[display(fmt = "'")]
     #[strum(serialize = "'")]
     SingleQuote,
+
+    /// `#[`, sonst ist `#` ein kommentar
+    #[display(fmt = "#[")]
+    #[strum(serialize = "#[")]
+    AttributeStart,
 }
 
 #[derive(
@@ -237,6 +257,17 @@ pub enum Operator {
     #[display(fmt = "!")]
     #[strum(serialize = "!")]
     Not,
+
+    /// `^`
+    #[display(fmt = "^")]
+    #[strum(serialize = "^")]
+    BitXor,
+
+    /// `<<`
+    /// `>>` wird nicht als ein token gelext, sonst würde `List<List<T>>` nicht mehr gehen
+    #[display(fmt = "<<")]
+    #[strum(serialize = "<<")]
+    ShiftLeft,
 }
 
 impl Operator {
@@ -255,6 +286,8 @@ impl Operator {
             Operator::And => Some(BinaryOperator::And),
             Operator::Or => Some(BinaryOperator::Or),
             Operator::Modulo => Some(BinaryOperator::Modulo),
+            Operator::BitXor => Some(BinaryOperator::BitXor),
+            Operator::ShiftLeft => Some(BinaryOperator::ShiftLeft),
 
             Operator::Not | Operator::LossyEqual => None,
         }
@@ -277,6 +310,8 @@ impl Operator {
             | Operator::And
             | Operator::Or
             | Operator::Modulo
+            | Operator::BitXor
+            | Operator::ShiftLeft
             | Operator::LossyEqual => None,
         }
     }
@@ -346,6 +381,61 @@ pub enum Keyword {
     #[display(fmt = "import")]
     #[strum(serialize = "import")]
     Import,
+
+    /// `in`
+    #[display(fmt = "in")]
+    #[strum(serialize = "in")]
+    In,
+
+    /// `break`
+    #[display(fmt = "break")]
+    #[strum(serialize = "break")]
+    Break,
+
+    /// `continue`
+    #[display(fmt = "continue")]
+    #[strum(serialize = "continue")]
+    Continue,
+
+    /// `enum`
+    #[display(fmt = "enum")]
+    #[strum(serialize = "enum")]
+    Enum,
+
+    /// `match`
+    #[display(fmt = "match")]
+    #[strum(serialize = "match")]
+    Match,
+
+    /// `trait`
+    #[display(fmt = "trait")]
+    #[strum(serialize = "trait")]
+    Trait,
+
+    /// `impl`
+    #[display(fmt = "impl")]
+    #[strum(serialize = "impl")]
+    Impl,
+
+    /// `const`
+    #[display(fmt = "const")]
+    #[strum(serialize = "const")]
+    Const,
+
+    /// `static`
+    #[display(fmt = "static")]
+    #[strum(serialize = "static")]
+    Static,
+
+    /// `pub`
+    #[display(fmt = "pub")]
+    #[strum(serialize = "pub")]
+    Pub,
+
+    /// `from`
+    #[display(fmt = "from")]
+    #[strum(serialize = "from")]
+    From,
 }
 
 #[derive(Debug, Display, Default, PartialEq, Clone, Serialize, Deserialize)]
@@ -439,15 +529,68 @@ pub enum ReassignmentOperator {
     #[display(fmt = "-=")]
     #[strum(serialize = "-=")]
     MinusEqual,
+
+    /// `*=`
+    #[display(fmt = "*=")]
+    #[strum(serialize = "*=")]
+    MultiplyEqual,
+
+    /// `/=`
+    #[display(fmt = "/=")]
+    #[strum(serialize = "/=")]
+    DivideEqual,
+
+    /// `%=`
+    #[display(fmt = "%=")]
+    #[strum(serialize = "%=")]
+    ModuloEqual,
+
+    /// `&=`
+    #[display(fmt = "&=")]
+    #[strum(serialize = "&=")]
+    BitAndEqual,
+
+    /// `|=`
+    #[display(fmt = "|=")]
+    #[strum(serialize = "|=")]
+    BitOrEqual,
+
+    /// `^=`
+    #[display(fmt = "^=")]
+    #[strum(serialize = "^=")]
+    BitXorEqual,
+
+    /// `<<=`
+    #[display(fmt = "<<=")]
+    #[strum(serialize = "<<=")]
+    ShiftLeftEqual,
+
+    /// `>>=`
+    #[display(fmt = ">>=")]
+    #[strum(serialize = ">>=")]
+    ShiftRightEqual,
+}
+
+impl ReassignmentOperator {
+    /// `a += b` wird zu `a = a + b`
+    pub fn to_binary_op(&self) -> BinaryOperator {
+        match self {
+            ReassignmentOperator::PlusEqual => BinaryOperator::Add,
+            ReassignmentOperator::MinusEqual => BinaryOperator::Subtract,
+            ReassignmentOperator::MultiplyEqual => BinaryOperator::Multiply,
+            ReassignmentOperator::DivideEqual => BinaryOperator::Divide,
+            ReassignmentOperator::ModuloEqual => BinaryOperator::Modulo,
+            ReassignmentOperator::BitAndEqual => BinaryOperator::BitAnd,
+            ReassignmentOperator::BitOrEqual => BinaryOperator::BitOr,
+            ReassignmentOperator::BitXorEqual => BinaryOperator::BitXor,
+            ReassignmentOperator::ShiftLeftEqual => BinaryOperator::ShiftLeft,
+            ReassignmentOperator::ShiftRightEqual => BinaryOperator::ShiftRight,
+        }
+    }
 }
 
 impl Token {
     pub fn is_reassignment_operator(&self) -> bool {
-        matches!(
-            self,
-            Token::Assignment
-                | Token::ReassignmentOperator(ReassignmentOperator::PlusEqual)
-                | Token::ReassignmentOperator(ReassignmentOperator::MinusEqual)
-        )
+        matches!(self, Token::Assignment | Token::ReassignmentOperator(_))
     }
 }
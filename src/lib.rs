@@ -1,15 +1,21 @@
 pub mod codegen;
 pub mod compiler;
 pub mod error;
+pub mod interpreter;
 pub mod lexer;
 pub mod parser;
+pub mod wasm;
 
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 use crate::{
     error::error_as_string,
-    lexer::{position::Spanned, tokens::Token},
+    interpreter::{CapturedOutput, Interpreter},
+    lexer::{
+        position::{Span, Spanned},
+        tokens::Token,
+    },
     parser::ast::{DataType, DataTypeInfo, Program},
 };
 
@@ -20,6 +26,44 @@ struct CompileResult {
     ir: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RunResult {
+    output: String,
+    exit_code: Option<i32>,
+    /// laufzeitfehler, dann gibt es keinen exit code
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DiagnosticSer {
+    id: u32,
+    name: String,
+    message: String,
+    span: Span,
+    /// mit ANSI farben formatiert
+    rendered: String,
+}
+
+impl DiagnosticSer {
+    pub fn from_error(input: &str, err: &Spanned<Box<dyn error::CompilerError>>) -> Self {
+        DiagnosticSer {
+            id: err.value.id(),
+            name: err.value.name().to_string(),
+            message: err.value.err_msg(),
+            span: err.span,
+            rendered: error_as_string("input-file", input, err),
+        }
+    }
+}
+
+fn diagnostics_to_js(input: &str, errs: &[Spanned<Box<dyn error::CompilerError>>]) -> JsValue {
+    let diagnostics: Vec<DiagnosticSer> = errs
+        .iter()
+        .map(|err| DiagnosticSer::from_error(input, err))
+        .collect();
+    serde_wasm_bindgen::to_value(&diagnostics).unwrap()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ProgramSer {
     data_types: Vec<(String, DataTypeInfo)>,
@@ -47,27 +91,37 @@ impl ProgramSer {
     }
 }
 
-#[wasm_bindgen]
-pub fn compile(input: &str) -> Result<JsValue, JsValue> {
+/// die tokens und der ast, fehler als diagnostics für die web demo
+fn parse(input: &str) -> Result<(Vec<Spanned<Token>>, Program), JsValue> {
     let tokens = lexer::lexer_main::lex(input).map_err(|e| {
         let boxed: Spanned<Box<dyn error::CompilerError>> = Spanned {
             span: e.span,
             value: Box::new(e.value),
         };
-        JsValue::from_str(&error_as_string("input-file", input, &boxed))
+        diagnostics_to_js(input, &[boxed])
     })?;
 
     let ast = parser::parser_main::Parser::new(tokens.clone(), None)
         .with_require_main(true)
         .parse()
-        .map_err(|e| {
-            let boxed: Spanned<Box<dyn error::CompilerError>> = Spanned {
-                span: e.span,
-                value: Box::new(e.value),
-            };
-            JsValue::from_str(&error_as_string("input-file", input, &boxed))
+        .map_err(|errs| {
+            let boxed: Vec<Spanned<Box<dyn error::CompilerError>>> = errs
+                .into_iter()
+                .map(|e| Spanned {
+                    span: e.span,
+                    value: Box::new(e.value) as Box<dyn error::CompilerError>,
+                })
+                .collect();
+            diagnostics_to_js(input, &boxed)
         })?;
 
+    Ok((tokens, ast))
+}
+
+#[wasm_bindgen]
+pub fn compile(input: &str) -> Result<JsValue, JsValue> {
+    let (tokens, ast) = parse(input)?;
+
     let ir = codegen::codegen_main::CodeGenerator::new(ast.clone()).parse();
 
     Ok(serde_wasm_bindgen::to_value(&CompileResult {
@@ -77,3 +131,35 @@ pub fn compile(input: &str) -> Result<JsValue, JsValue> {
     })
     .unwrap())
 }
+
+/// das programm als wasm modul, der browser stellt die importe (`printf`, `malloc`, ...) bereit
+#[wasm_bindgen]
+pub fn compile_wasm(input: &str) -> Result<Vec<u8>, JsValue> {
+    let (_, ast) = parse(input)?;
+
+    let wat = wasm::WasmGenerator::new(ast).parse();
+    wat::parse_str(&wat).map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// führt das programm mit dem interpreter aus, ohne clang
+#[wasm_bindgen]
+pub fn run(input: &str) -> Result<JsValue, JsValue> {
+    let (_, ast) = parse(input)?;
+
+    let output = CapturedOutput::default();
+    let result = Interpreter::new(ast)
+        .with_output(Box::new(output.clone()))
+        .run_main();
+
+    let (exit_code, error) = match result {
+        Ok(code) => (Some(code), None),
+        Err(err) => (None, Some(err.to_string())),
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&RunResult {
+        output: output.contents(),
+        exit_code,
+        error,
+    })
+    .unwrap())
+}
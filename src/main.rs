@@ -1,35 +1,133 @@
-use clap::Parser;
-use compiler::Compiler;
+use clap::{Parser, Subcommand};
+use codegen::{codegen_main::CodeGenerator, header::c_header, llvm_instructions::IR};
+use compiler::{CompileOptions, Compiler};
+use error::{emit_error, WarningConfig};
+use interpreter::{repl::is_complete, Interpreter, ReplError, RuntimeError, Session};
+use manifest::{Manifest, MANIFEST_FILE_NAME};
+use parser::{ast::Program, cfg::Target};
 use semver::VersionReq;
 use std::{
     io::{Read, Write},
     path::PathBuf,
 };
 use thiserror::Error;
-use utils::{get_clang_version, ClangError};
+use utils::{build, exit_code, get_clang_version, link, run_ir, ClangError, Emit};
 
 pub mod codegen;
 pub mod compiler;
 pub mod error;
+pub mod interpreter;
 pub mod lexer;
+pub mod manifest;
 pub mod parser;
 pub mod tests;
 pub mod utils;
+pub mod wasm;
 
 const LLVM_FILE_DEFAULT_NAME: &str = "out.ll";
-const EXECUTABLE_FILE_DEFAULT_NAME: &str = "out.exe";
+const WASM_FILE_DEFAULT_NAME: &str = "out.wasm";
 const CLANG_VERSION_REQ: &str = ">=16.0.0";
+/// ordner für imports, getrennt wie bei `PATH`
+const LIBRARY_PATH_ENV: &str = "MX_PATH";
+/// der interpreter ist rekursiv, tiefe rekursion im programm braucht viel stack
+const INTERPRETER_STACK_SIZE: usize = 256 * 1024 * 1024;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     file_name: Option<PathBuf>,
     #[arg(short, long, default_value = "false")]
     emit_llvm: bool,
     #[arg(short, long, default_value = "false")]
     dont_write_output: bool,
+    /// Emit a WebAssembly module instead of an executable, as text if the output path ends in `.wat`
+    #[arg(long, default_value = "false")]
+    emit_wasm: bool,
     #[arg(short, long)]
     output_path: Option<PathBuf>,
+    /// What clang builds from the IR (exe, obj, staticlib or sharedlib), libraries need no `main`
+    #[arg(long, value_name = "KIND", default_value = "exe")]
+    emit: Emit,
+    /// Write a C header with the `#[export]` functions and the classes they use
+    #[arg(long, value_name = "FILE")]
+    emit_header: Option<PathBuf>,
+    #[command(flatten)]
+    warnings: WarningArgs,
+    /// Additional directory to search for imports, can be repeated
+    #[arg(short = 'I', long = "lib-path", value_name = "DIR")]
+    lib_path: Vec<PathBuf>,
+    /// Operating system for `#[cfg(...)]` items (linux, macos, windows or wasm), defaults to the host or `wasm` with `--emit-wasm`
+    #[arg(long, value_name = "OS")]
+    target: Option<Target>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Build the project described by `mx.toml`
+    Build(ProjectArgs),
+    /// Build a file (or the project described by `mx.toml`) into a temporary directory and run it
+    Run(RunArgs),
+    /// Check the project described by `mx.toml` for errors without writing any output
+    Check(ProjectArgs),
+    /// Start an interactive session that evaluates code with the interpreter
+    Repl(ReplArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct ProjectArgs {
+    /// Path to the manifest, `mx.toml` in the current directory by default
+    #[arg(long, value_name = "FILE")]
+    manifest_path: Option<PathBuf>,
+    #[command(flatten)]
+    warnings: WarningArgs,
+    /// Operating system for `#[cfg(...)]` items (linux, macos, windows or wasm), defaults to the host
+    #[arg(long, value_name = "OS")]
+    target: Option<Target>,
+    /// Additional directory to search for imports, can be repeated
+    #[arg(short = 'I', long = "lib-path", value_name = "DIR")]
+    lib_path: Vec<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// File to run, the project in `mx.toml` is run if no file is given
+    file_name: Option<PathBuf>,
+    #[command(flatten)]
+    project: ProjectArgs,
+    /// Run with the interpreter instead of compiling with clang
+    #[arg(long, default_value = "false")]
+    interpret: bool,
+    /// Arguments passed to the program, after `--`
+    #[arg(last = true)]
+    args: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ReplArgs {
+    /// Additional directory to search for imports, can be repeated
+    #[arg(short = 'I', long = "lib-path", value_name = "DIR")]
+    lib_path: Vec<PathBuf>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct WarningArgs {
+    /// Treat a warning code as an error, `-D warnings` denies all warnings
+    #[arg(short = 'D', long = "deny", value_name = "WARNING")]
+    deny: Vec<String>,
+    /// Silence a warning code, e.g. `-A W001`
+    #[arg(short = 'A', long = "allow", value_name = "WARNING")]
+    allow: Vec<String>,
+}
+
+impl From<WarningArgs> for WarningConfig {
+    fn from(args: WarningArgs) -> Self {
+        WarningConfig {
+            allow: args.allow,
+            deny: args.deny,
+        }
+    }
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -38,6 +136,8 @@ pub enum ProgramError {
     ClangError(ClangError),
     #[error("compiler error: {0}")]
     CompilerError(u32),
+    #[error("runtime error: {0}")]
+    RuntimeError(RuntimeError),
 }
 
 fn main() -> color_eyre::Result<()> {
@@ -45,6 +145,10 @@ fn main() -> color_eyre::Result<()> {
 
     let args = Args::parse();
 
+    if let Some(command) = args.command {
+        return run_command(command);
+    }
+
     let source_code = match args.file_name {
         Some(ref file_name) => std::fs::read_to_string(file_name)?,
         None => {
@@ -56,17 +160,53 @@ fn main() -> color_eyre::Result<()> {
 
     let start_time = std::time::Instant::now();
 
-    let ir = match Compiler::compile(
-        &source_code,
-        args.file_name
-            .as_deref()
-            .map(|p| p.parent().unwrap().into()),
-    ) {
-        Ok(ir) => ir,
-        Err(e) => {
-            return Err(ProgramError::CompilerError(e.value.id()).into());
-        }
+    let default_target = match args.emit_wasm {
+        true => Target::Wasm,
+        false => Target::host(),
     };
+    let options = CompileOptions {
+        warnings: args.warnings.into(),
+        library_paths: library_paths(args.lib_path),
+        target: args.target.unwrap_or(default_target),
+        library: args.emit != Emit::Executable,
+    };
+
+    let path = args
+        .file_name
+        .as_deref()
+        .map(|p| p.parent().unwrap().into());
+
+    if args.emit_wasm {
+        let wat = compile_wasm(&source_code, path, &options)?;
+        if args.dont_write_output {
+            return Ok(());
+        }
+
+        let output_path = args
+            .output_path
+            .unwrap_or_else(|| PathBuf::from(WASM_FILE_DEFAULT_NAME));
+
+        match output_path.extension().is_some_and(|ext| ext == "wat") {
+            true => std::fs::write(output_path, wat)?,
+            false => std::fs::write(output_path, wat::parse_str(&wat)?)?,
+        }
+        return Ok(());
+    }
+
+    let program = parse(&source_code, path, &options)?;
+
+    if let Some(header_path) = args.emit_header.filter(|_| !args.dont_write_output) {
+        let guard = header_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        std::fs::write(&header_path, c_header(&program, &guard))?;
+    }
+
+    let ir = CodeGenerator::new(program)
+        .with_source(source_code)
+        .with_library(options.library)
+        .parse();
 
     if args.dont_write_output {
         return Ok(());
@@ -78,7 +218,7 @@ fn main() -> color_eyre::Result<()> {
             if args.emit_llvm {
                 PathBuf::from(LLVM_FILE_DEFAULT_NAME)
             } else {
-                PathBuf::from(EXECUTABLE_FILE_DEFAULT_NAME)
+                PathBuf::from(args.emit.default_file_name())
             }
         }
     };
@@ -88,36 +228,215 @@ fn main() -> color_eyre::Result<()> {
         return Ok(());
     }
 
-    let clang_version = match get_clang_version() {
-        Some(clang_version) => clang_version,
-        None => return Err(ProgramError::ClangError(ClangError::NotInstalled).into()),
-    };
+    check_clang_version();
+    build(&ir, &output_path, args.emit, &[]).map_err(ProgramError::ClangError)?;
 
-    let clang_version_req = VersionReq::parse(CLANG_VERSION_REQ).unwrap();
+    // nicht auf stdout, das gehört dem programm
+    let elapsed_time = start_time.elapsed();
+    eprintln!("took: {:?}", elapsed_time);
+    Ok(())
+}
 
-    if !clang_version_req.matches(&clang_version) {
-        println!("WARN: The clang version might not be supported!")
-        // return Err(ProgramError::ClangError(ClangError::UnsupportedVersion(clang_version)).into());
+/// `build`, `run` und `check`
+fn run_command(command: Command) -> color_eyre::Result<()> {
+    match command {
+        Command::Build(args) => {
+            let (manifest, ir) = compile_project(&args)?;
+            check_clang_version();
+            link(&ir, &manifest.output(), &manifest.link_args())
+                .map_err(ProgramError::ClangError)?;
+            Ok(())
+        }
+        Command::Check(args) => compile_project(&args).map(|_| ()),
+        Command::Run(args) => {
+            let (source_code, path, options, link_args, program_name) = match args.file_name {
+                Some(file_name) => {
+                    let source_code = std::fs::read_to_string(&file_name)?;
+                    let options = CompileOptions {
+                        warnings: args.project.warnings.into(),
+                        library_paths: library_paths(args.project.lib_path),
+                        target: args.project.target.unwrap_or_default(),
+                        library: false,
+                    };
+                    let path = file_name.parent().map(|p| p.to_path_buf());
+                    (source_code, path, options, vec![], file_name)
+                }
+                None => {
+                    let (manifest, source_code, options) = load_project(&args.project)?;
+                    let entry = manifest.entry();
+                    let path = entry.parent().map(|p| p.to_path_buf());
+                    (source_code, path, options, manifest.link_args(), entry)
+                }
+            };
+
+            if args.interpret {
+                let program = parse(&source_code, path, &options)?;
+                let argv = std::iter::once(program_name.display().to_string())
+                    .chain(args.args)
+                    .collect();
+                std::process::exit(interpret(program, argv)?);
+            }
+
+            let ir = compile(&source_code, path, &options)?;
+            check_clang_version();
+            let status = run_ir(&ir, &link_args, &args.args)?;
+            std::process::exit(exit_code(status));
+        }
+        Command::Repl(args) => repl(args),
     }
+}
+
+/// liest die `mx.toml` und kompiliert die hauptdatei des projekts
+fn compile_project(args: &ProjectArgs) -> color_eyre::Result<(Manifest, IR)> {
+    let (manifest, source_code, options) = load_project(args)?;
+    let entry = manifest.entry();
+
+    let ir = compile(
+        &source_code,
+        entry.parent().map(|p| p.to_path_buf()),
+        &options,
+    )?;
+
+    Ok((manifest, ir))
+}
+
+/// die `mx.toml`, der quellcode der hauptdatei und die optionen dafür
+fn load_project(args: &ProjectArgs) -> color_eyre::Result<(Manifest, String, CompileOptions)> {
+    let manifest_path = args
+        .manifest_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(MANIFEST_FILE_NAME));
+    let manifest = Manifest::load(&manifest_path)?;
+
+    let source_code = std::fs::read_to_string(manifest.entry())?;
+
+    // `-I` vor den ordnern aus der `mx.toml`, beide vor `MX_PATH`
+    let paths = args
+        .lib_path
+        .iter()
+        .cloned()
+        .chain(manifest.library_paths())
+        .collect();
+    let options = CompileOptions {
+        warnings: args.warnings.clone().into(),
+        library_paths: library_paths(paths),
+        target: args.target.unwrap_or_default(),
+        library: false,
+    };
+
+    Ok((manifest, source_code, options))
+}
+
+/// führt `main` in einem eigenen thread aus, damit genug stack da ist
+fn interpret(program: Program, argv: Vec<String>) -> Result<i32, ProgramError> {
+    let handle = std::thread::Builder::new()
+        .stack_size(INTERPRETER_STACK_SIZE)
+        .spawn(move || {
+            Interpreter::new(program)
+                .with_args(argv)
+                .with_output(Box::new(std::io::stdout()))
+                .with_input(Box::new(std::io::stdin()))
+                .run_main()
+        })
+        .expect("failed to spawn the interpreter thread");
+
+    handle
+        .join()
+        .expect("the interpreter panicked")
+        .map_err(ProgramError::RuntimeError)
+}
+
+/// liest so lange zeilen, bis alle klammern geschlossen sind
+fn repl(args: ReplArgs) -> color_eyre::Result<()> {
+    let mut session = Session::new(library_paths(args.lib_path));
+    let stdin = std::io::stdin();
+    let mut input = String::new();
+
+    loop {
+        let prompt = match input.is_empty() {
+            true => "mx> ",
+            false => "... ",
+        };
+        print!("{prompt}");
+        std::io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            println!();
+            return Ok(());
+        }
 
-    let mut clang = std::process::Command::new("clang")
-        .args(["-x", "ir", "-", "-o", output_path.to_str().unwrap()])
-        .stdin(std::process::Stdio::piped())
-        .spawn()?;
+        input.push_str(&line);
+        if !is_complete(&input) {
+            continue;
+        }
 
-    let mut stdin = clang.stdin.as_ref().unwrap();
-    stdin.write_all(ir.as_bytes())?;
+        let code = std::mem::take(&mut input);
+        if code.trim().is_empty() {
+            continue;
+        }
 
-    let status = clang.wait()?;
+        match session.eval(&code) {
+            Ok(Some(value)) => println!("{value}"),
+            Ok(None) => {}
+            Err(ReplError::Compile(errs)) => emit_error("repl", &code, &errs),
+            Err(ReplError::Runtime(err)) => eprintln!("runtime error: {err}"),
+            Err(ReplError::Exit(code)) => std::process::exit(code),
+        }
+    }
+}
 
-    if !status.success() {
-        return Err(ProgramError::ClangError(ClangError::ClangErrorCode(
-            status.code().unwrap_or(-1),
-        ))
-        .into());
+/// `paths` werden vor `MX_PATH` durchsucht
+fn library_paths(mut paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    if let Some(env_paths) = std::env::var_os(LIBRARY_PATH_ENV) {
+        paths.extend(std::env::split_paths(&env_paths).filter(|p| !p.as_os_str().is_empty()));
     }
+    paths
+}
 
-    let elapsed_time = start_time.elapsed();
-    println!("took: {:?}", elapsed_time);
-    Ok(())
+fn compile(
+    source_code: &str,
+    path: Option<PathBuf>,
+    options: &CompileOptions,
+) -> Result<String, ProgramError> {
+    Compiler::compile_with_options(source_code, path, options).map_err(|errs| {
+        let id = errs.first().map(|e| e.value.id()).unwrap_or_default();
+        ProgramError::CompilerError(id)
+    })
+}
+
+fn compile_wasm(
+    source_code: &str,
+    path: Option<PathBuf>,
+    options: &CompileOptions,
+) -> Result<String, ProgramError> {
+    Compiler::compile_wasm_with_options(source_code, path, options).map_err(|errs| {
+        let id = errs.first().map(|e| e.value.id()).unwrap_or_default();
+        ProgramError::CompilerError(id)
+    })
+}
+
+fn parse(
+    source_code: &str,
+    path: Option<PathBuf>,
+    options: &CompileOptions,
+) -> Result<Program, ProgramError> {
+    Compiler::parse_with_options(source_code, path, options).map_err(|errs| {
+        let id = errs.first().map(|e| e.value.id()).unwrap_or_default();
+        ProgramError::CompilerError(id)
+    })
+}
+
+fn check_clang_version() {
+    let Some(clang_version) = get_clang_version() else {
+        // `link` meldet dann, dass clang fehlt
+        return;
+    };
+
+    let clang_version_req = VersionReq::parse(CLANG_VERSION_REQ).unwrap();
+
+    if !clang_version_req.matches(&clang_version) {
+        eprintln!("WARN: The clang version might not be supported!")
+        // return Err(ProgramError::ClangError(ClangError::UnsupportedVersion(clang_version)).into());
+    }
 }
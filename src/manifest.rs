@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const MANIFEST_FILE_NAME: &str = "mx.toml";
+const DEFAULT_ENTRY: &str = "main.mx";
+
+/// `mx.toml`, beschreibt ein projekt aus mehreren dateien
+///
+/// ```toml
+/// [project]
+/// name = "server"
+/// entry = "src/main.mx"
+/// lib-paths = ["vendor"]
+///
+/// [link]
+/// libs = ["ws2_32"]
+/// search-paths = ["C:/libs"]
+/// objects = ["c/helper.o"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub project: Project,
+    #[serde(default)]
+    pub link: Link,
+    /// ordner in dem die `mx.toml` liegt, alle pfade sind relativ dazu
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Project {
+    pub name: String,
+    #[serde(default = "default_entry")]
+    pub entry: PathBuf,
+    /// standardmäßig der name des projekts
+    pub output: Option<PathBuf>,
+    /// ordner in denen imports gesucht werden
+    #[serde(default)]
+    pub lib_paths: Vec<PathBuf>,
+}
+
+/// wird so an clang weitergegeben
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Link {
+    /// `-l`
+    #[serde(default)]
+    pub libs: Vec<String>,
+    /// `-L`
+    #[serde(default)]
+    pub search_paths: Vec<PathBuf>,
+    /// c objekt dateien (`.o`, `.obj`), die mit gelinkt werden
+    #[serde(default)]
+    pub objects: Vec<PathBuf>,
+}
+
+fn default_entry() -> PathBuf {
+    PathBuf::from(DEFAULT_ENTRY)
+}
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("could not read {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("invalid manifest {0}: {1}")]
+    Invalid(PathBuf, toml::de::Error),
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self, ManifestError> {
+        let code =
+            std::fs::read_to_string(path).map_err(|e| ManifestError::Io(path.to_path_buf(), e))?;
+        let root = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+        Manifest::parse(&code, root).map_err(|e| ManifestError::Invalid(path.to_path_buf(), e))
+    }
+
+    pub fn parse(code: &str, root: PathBuf) -> Result<Self, toml::de::Error> {
+        let mut manifest: Manifest = toml::from_str(code)?;
+        manifest.root = root;
+        Ok(manifest)
+    }
+
+    pub fn entry(&self) -> PathBuf {
+        self.root.join(&self.project.entry)
+    }
+
+    pub fn output(&self) -> PathBuf {
+        match &self.project.output {
+            Some(output) => self.root.join(output),
+            None => self.root.join(format!(
+                "{}{}",
+                self.project.name,
+                std::env::consts::EXE_SUFFIX
+            )),
+        }
+    }
+
+    pub fn library_paths(&self) -> Vec<PathBuf> {
+        self.project
+            .lib_paths
+            .iter()
+            .map(|path| self.root.join(path))
+            .collect()
+    }
+
+    /// zusätzliche argumente für clang
+    pub fn link_args(&self) -> Vec<String> {
+        let objects = self
+            .link
+            .objects
+            .iter()
+            .map(|object| self.root.join(object).display().to_string());
+        let search_paths = self
+            .link
+            .search_paths
+            .iter()
+            .map(|path| format!("-L{}", self.root.join(path).display()));
+        let libs = self.link.libs.iter().map(|lib| format!("-l{lib}"));
+
+        objects.chain(search_paths).chain(libs).collect()
+    }
+}
@@ -23,8 +23,7 @@ use strum_macros::EnumString;
 use super::{
     builtins::get_builtin_functions,
     error::ParserError,
-    parser_main::Parser,
-    utils::{same_variant, specify_generics},
+    utils::{handle_generics, same_variant, specify_generics},
 };
 
 /// Trait, Parameter der Methode, Name der Methode (falls überschrieben), Rückgabetyp
@@ -41,6 +40,10 @@ pub struct Program {
     pub data_types: OrderMap<String, DataTypeInfo>,
     pub custom_types: OrderMap<String, Spanned<DataType>>,
     pub functions: OrderMap<String, Spanned<Function>>,
+    pub traits: OrderMap<String, Spanned<TraitDecl>>,
+    pub globals: OrderMap<String, Spanned<Global>>,
+    /// alles was mit `pub` markiert ist
+    pub exports: Exports,
     pub require_main: bool,
     #[serde(skip)]
     pub dependency_cache: Arc<RwLock<HashMap<PathBuf, Program>>>,
@@ -48,6 +51,39 @@ pub struct Program {
     pub import_queue: Vec<PathBuf>,
     #[serde(skip)]
     pub source_code: Vec<char>,
+    /// nur die imports dieser datei
+    #[serde(skip)]
+    pub imports: Vec<Import>,
+}
+
+/// `import "foo.mx"` mit allem, was dadurch verfügbar wird
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub name: Spanned<String>,
+    pub functions: Vec<String>,
+    pub types: Vec<String>,
+    pub globals: Vec<String>,
+}
+
+/// was eine datei mit `pub` anderen dateien zur verfügung stellt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Exports {
+    /// name im code -> interner name
+    pub functions: OrderMap<String, String>,
+    /// name im code -> interner name
+    pub globals: OrderMap<String, String>,
+    pub types: OrderSet<String>,
+}
+
+/// `const NAME: int64 = 1;` oder `static mut NAME: int64 = 0;`
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Global {
+    pub name: Spanned<String>,
+    pub _type: Spanned<DataType>,
+    /// wird beim parsen ausgewertet
+    pub value: Literal,
+    pub is_mutable: bool,
+    pub is_const: bool,
 }
 
 impl Default for Program {
@@ -56,10 +92,14 @@ impl Default for Program {
             data_types: OrderMap::new(),
             custom_types: OrderMap::new(),
             functions: get_builtin_functions(),
+            traits: OrderMap::new(),
+            globals: OrderMap::new(),
+            exports: Exports::default(),
             require_main: false,
             dependency_cache: Arc::new(RwLock::new(HashMap::new())),
             import_queue: vec![],
             source_code: vec![],
+            imports: vec![],
         }
     }
 }
@@ -124,6 +164,154 @@ impl Program {
             .map(|function_name| self.functions.get(&function_name).cloned().unwrap().value)
     }
 
+    /// operator methoden wie `add` implementieren ihr trait auch ohne `impl` block
+    pub fn builtin_trait_of_method(&self, method: &str) -> Option<TraitDecl> {
+        let trait_ = Trait::from_method_name(method)?;
+        self.operator_trait(&trait_).cloned()
+    }
+
+    /// das trait aus dem prelude, über das der operator aufgelöst wird
+    pub fn operator_trait(&self, trait_: &Trait) -> Option<&TraitDecl> {
+        self.traits
+            .values()
+            .map(|t| &t.value)
+            .find(|t| t.operator.as_ref() == Some(trait_))
+    }
+
+    /// die eingebauten implementierungen für zahlen, bool und arrays,
+    /// ohne deklaration im prelude gibt es den operator nicht
+    fn default_implementation(&self, _type: &DataType) -> Vec<TraitInfo> {
+        let mut traits = intrinsic_traits(_type)
+            .into_iter()
+            .filter_map(|trait_| {
+                let method = self.operator_trait(&trait_)?.methods.first()?;
+                let (params, mut return_type) = method.value.signature(_type);
+
+                // `Out` aus `idx<Out>`, arrays geben das element direkt zurück
+                if let DataType::Array { value_type, .. } = _type {
+                    return_type = *value_type.clone();
+                }
+
+                Some((trait_, params, None, return_type))
+            })
+            .collect_vec();
+
+        traits.extend(cast_implementation(_type));
+        traits
+    }
+
+    pub fn implements_trait(&self, _type: &DataType, trait_: &Trait, params: &[DataType]) -> bool {
+        self.get_trait_return_type(_type, trait_, params).is_some()
+    }
+
+    /// eigene implementierungen vor den eingebauten
+    pub fn get_trait_return_type(
+        &self,
+        _type: &DataType,
+        trait_: &Trait,
+        params: &[DataType],
+    ) -> Option<DataType> {
+        let type_info = self.get_type_info(_type);
+        let defaults = self.default_implementation(&type_info.parent_type);
+
+        type_info
+            .traits
+            .iter()
+            .chain(defaults.iter())
+            .find(|(t, p, _, _)| t == trait_ && p == params)
+            .map(|(_, _, _, return_type)| return_type.clone())
+    }
+
+    pub fn implements(&self, _type: &DataType, trait_name: &str) -> bool {
+        let trait_decl = match self.traits.get(trait_name) {
+            Some(decl) => &decl.value,
+            None => return false,
+        };
+        let trait_ = trait_decl.as_trait();
+
+        // `T: Add` verlangt die signatur aus dem prelude, `Foo + int64` reicht dafür nicht
+        let operator_method = trait_decl
+            .builtin()
+            .and(trait_decl.methods.first())
+            .filter(|method| method.value.generics.is_empty());
+
+        let has_trait = |info: DataTypeInfo| {
+            let signature = operator_method.map(|method| method.value.signature(&info.parent_type));
+
+            self.default_implementation(&info.parent_type)
+                .into_iter()
+                .chain(info.traits)
+                .any(|(t, params, _, return_type)| {
+                    t == trait_
+                        && match &signature {
+                            Some(signature) => *signature == (params, return_type),
+                            None => true,
+                        }
+                })
+        };
+
+        if has_trait(self.get_type_info(_type)) {
+            return true;
+        }
+
+        // impl für den generischen typ, impl Show for Foo<T>
+        match _type.as_custom() {
+            Some(CustomDataType {
+                subtype_of: Some(base),
+                ..
+            }) => self
+                .custom_types
+                .get(base)
+                .is_some_and(|base| has_trait(self.get_type_info(&base.value))),
+            _ => false,
+        }
+    }
+
+    /// `x.show()` mit `x: T`, sobald T ein konkreter typ ist
+    pub fn resolve_trait_method(&mut self, _type: &DataType, method: &str) -> Option<Function> {
+        let type_name = match _type {
+            DataType::Custom(inner) => inner.name.clone(),
+            other => other.internal_name(),
+        };
+
+        if let Some(function) = self.functions.get(&format!("{}_{}", type_name, method)) {
+            if !function.value.is_generic() {
+                return Some(function.value.clone());
+            }
+        }
+
+        // generische implementierung, show(self) for Foo<T>
+        let base = match _type.as_custom() {
+            Some(CustomDataType {
+                subtype_of: Some(base),
+                ..
+            }) => base.clone(),
+            _ => return None,
+        };
+
+        let function_name = format!("{}_{}", base, method);
+        let function = self.functions.get(&function_name)?.value.clone();
+        let self_type = function.params.value.first()?.value._type.value.clone();
+
+        let (specified_generics, map) =
+            handle_generics(std::slice::from_ref(_type), &[self_type], None);
+
+        if let Some(subtype) = function.generic_subtypes.get(&specified_generics) {
+            return Some(subtype.clone());
+        }
+
+        let subtype = function.subtype(&map, Some(_type), self, true);
+
+        self.functions
+            .get_mut(&function_name)
+            .unwrap()
+            .value
+            .generic_subtypes
+            .insert(specified_generics, subtype.clone());
+
+        Some(subtype)
+    }
+
     // pub fn get_trait_return_type(&self, type_info: &DataTypeInfo, trait_: &Trait, params: &[DataType]) -> DataType {
     //     type_info.get_default_trait_return_type(trait_, params)
     //         .unwrap_or_else(|| self.get_trait_function(type_info, trait_, params).unwrap().return_type.value)
@@ -139,6 +327,12 @@ pub struct Function {
     pub body: Spanned<Block>,
     pub return_type: Spanned<DataType>,
     pub is_extern: bool,
+    /// `extern def printf(fmt: *int8, ...)`, weitere argumente nach den festen parametern
+    pub is_variadic: bool,
+    /// `extern def` mit body, kann zusätzlich über das c abi aufgerufen werden
+    pub c_abi: bool,
+    /// `#[export]`, das symbol mit c abi ist der name ohne präfix
+    pub export: bool,
     pub method_of: Option<DataType>,
     pub trait_of: Option<DataType>,
     pub generic_subtypes: HashMap<Vec<DataType>, Function>,
@@ -180,19 +374,34 @@ impl Function {
             && self.return_type == other.return_type
             // && self.body == other.body
             && self.is_extern == other.is_extern
+            && self.is_variadic == other.is_variadic
+            && self.c_abi == other.c_abi
+            && self.export == other.export
             && self.method_of == other.method_of
             && self.trait_of == other.trait_of
         // && self.generic_subtypes == other.generic_subtypes
     }
 
-    pub fn from_block(block: Block, name: String) -> Function {
+    /// gleiche parameter und rückgabetyp, die namen sind egal
+    pub fn same_signature(&self, other: &Function) -> bool {
+        self.return_type.value == other.return_type.value
+            && self.params.value.len() == other.params.value.len()
+            && self
+                .params
+                .value
+                .iter()
+                .zip(other.params.value.iter())
+                .all(|(a, b)| a.value._type.value == b.value._type.value)
+    }
+
+    pub fn from_block(block: Block, name: String, params: Vec<Spanned<FunctionParam>>) -> Function {
         Function {
             display_name: Spanned {
                 ..Default::default()
             },
             name,
             params: Spanned {
-                value: block.closure_params.clone(),
+                value: params,
                 span: Span::default(),
             },
             body: Spanned {
@@ -204,6 +413,9 @@ impl Function {
                 span: Span::default(),
             },
             is_extern: false,
+            is_variadic: false,
+            c_abi: false,
+            export: false,
             method_of: None,
             generic_subtypes: HashMap::new(),
             is_builtin: false,
@@ -341,6 +553,9 @@ impl Function {
                 span: Span::default(),
             },
             is_extern: self.is_extern,
+            is_variadic: self.is_variadic,
+            c_abi: self.c_abi,
+            export: self.export,
             method_of,
             generic_subtypes: HashMap::new(),
             is_builtin: self.is_builtin,
@@ -511,6 +726,9 @@ impl Default for Function {
                 span: Span::default(),
             },
             is_extern: false,
+            is_variadic: false,
+            c_abi: false,
+            export: false,
             method_of: None,
             generic_subtypes: HashMap::new(),
             is_builtin: false,
@@ -556,6 +774,11 @@ pub enum Statement {
         condition: Spanned<TypedExpr>,
         body: Box<Spanned<Block>>,
     },
+    ForLoop {
+        variable: Spanned<Variable>,
+        iterable: Spanned<ForIterable>,
+        body: Box<Spanned<Block>>,
+    },
     VariableDecl {
         is_mutable: bool,
         name: Spanned<String>,
@@ -570,6 +793,10 @@ pub enum Statement {
         variable: Spanned<TypedExpr>,
         new_value: Spanned<TypedExpr>,
     },
+    /// springt ans ende des innersten loops
+    Break,
+    /// springt zur nächsten iteration des innersten loops
+    Continue,
 }
 
 impl Statement {
@@ -590,7 +817,7 @@ impl Statement {
                     false_branch.value.walk(f);
                 }
             }
-            Statement::WhileLoop { body, .. } => {
+            Statement::WhileLoop { body, .. } | Statement::ForLoop { body, .. } => {
                 body.value.walk(f);
             }
 
@@ -625,6 +852,16 @@ impl DataTypeSetter for Statement {
                 out.append(&mut body.value._type_mut());
                 out
             }
+            Statement::ForLoop {
+                variable,
+                iterable,
+                body,
+            } => {
+                let mut out = variable.value._type._type_mut();
+                out.append(&mut iterable.value._type_mut());
+                out.append(&mut body.value._type_mut());
+                out
+            }
             Statement::VariableDecl { _type, value, .. } => {
                 let mut out = vec![];
                 if let Some(_type) = _type {
@@ -643,18 +880,48 @@ impl DataTypeSetter for Statement {
                 out.append(&mut new_value.value._type_mut());
                 out
             }
+            Statement::Break | Statement::Continue => vec![],
+        }
+    }
+}
+
+/// `for i in 0..n` bzw. `for x in list`
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum ForIterable {
+    Range {
+        start: Box<Spanned<TypedExpr>>,
+        end: Box<Spanned<TypedExpr>>,
+    },
+    /// Arrays oder Typen mit `iter_len` und `iter_get`
+    Iterable(Box<Spanned<TypedExpr>>),
+}
+
+impl DataTypeSetter for ForIterable {
+    fn _type_mut(&mut self) -> Vec<DataTypeSettable<'_>> {
+        match self {
+            ForIterable::Range { start, end } => {
+                let mut out = start.value._type_mut();
+                out.append(&mut end.value._type_mut());
+                out
+            }
+            ForIterable::Iterable(iterable) => iterable.value._type_mut(),
         }
     }
 }
 
+/// `<T: Show + Equal>`, generic -> trait namen
+pub type GenericBounds = BTreeMap<String, Vec<String>>;
+
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Block {
     pub statements: Vec<Spanned<Statement>>,
     pub variables: BTreeMap<String, Spanned<Variable>>,
-    pub closure_params: Vec<Spanned<FunctionParam>>,
     pub generics: Vec<Spanned<DataType>>,
+    pub generic_bounds: GenericBounds,
     pub return_type: DataType, // für anonyme funktion
     pub function_depth: usize, // tiefe innerhalb der funktion
+    pub in_loop: bool,         // für break und continue
+    pub is_value: bool,        // closure oder block ausdruck, der letzte ausdruck ist der wert
 }
 
 impl Block {
@@ -807,6 +1074,25 @@ pub enum Expr {
     Deref(Box<Spanned<TypedExpr>>), // ~var
 
     ClassName(String), // statische methode: Foo::bar()
+    /// `Shape::Circle(1.0)`
+    EnumVariant {
+        variant: Spanned<String>,
+        variant_idx: usize,
+        args: Spanned<Vec<Spanned<TypedExpr>>>,
+    },
+    Match {
+        value: Box<Spanned<TypedExpr>>,
+        arms: Vec<Spanned<MatchArm>>,
+    },
+    /// `|x: int64| -> int64 { x + 1 }`
+    Closure(Closure),
+    /// `f(1)` mit `f: |int64| -> int64`
+    ClosureCall {
+        closure: Box<Spanned<TypedExpr>>,
+        args: Spanned<Vec<Spanned<CallArg>>>,
+    },
+    /// funktion als wert, `list.map(double)`
+    FunctionRef(Box<Function>),
 }
 
 impl DataTypeSetter for Expr {
@@ -852,11 +1138,90 @@ impl DataTypeSetter for Expr {
                 out
             }
             Expr::Reference { value } => value.value._type_mut(),
+            Expr::EnumVariant { args, .. } => args
+                .value
+                .iter_mut()
+                .flat_map(|a| a.value._type_mut())
+                .collect(),
+            Expr::Match { value, arms } => {
+                let mut out = value.value._type_mut();
+                for arm in arms.iter_mut() {
+                    out.append(&mut arm.value.body.value._type_mut());
+                }
+                out
+            }
+            Expr::Closure(closure) => closure._type_mut(),
+            Expr::ClosureCall { closure, args } => {
+                let mut out = closure.value._type_mut();
+                for arg in args.value.iter_mut() {
+                    out.append(&mut arg.value.0._type_mut());
+                }
+                out
+            }
+            Expr::FunctionRef(_) => vec![],
+        }
+    }
+}
+
+/// ```txt
+/// let offset = 1;
+/// let f = |x: int64| -> int64 { x + offset };
+/// ```
+/// wird zu einer funktion mit der umgebung (`offset`) als erstem parameter
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Closure {
+    pub params: Spanned<Vec<Spanned<FunctionParam>>>,
+    pub body: Spanned<Block>,
+    pub return_type: DataType,
+    pub captures: Vec<Capture>,
+}
+
+impl DataTypeSetter for Closure {
+    fn _type_mut(&mut self) -> Vec<DataTypeSettable<'_>> {
+        let mut out = self.return_type._type_mut();
+        for param in self.params.value.iter_mut() {
+            out.append(&mut param.value._type.value._type_mut());
+        }
+        for capture in self.captures.iter_mut() {
+            out.append(&mut capture.variable._type._type_mut());
         }
+        out.append(&mut self.body.value._type_mut());
+        out
     }
 }
 
+/// `mut` variablen werden per referenz gefangen, alle anderen werden kopiert
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Capture {
+    pub variable: Variable,
+    pub by_reference: bool,
+}
+
+/// `Shape::Rect(w, h) => w * h`
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct MatchArm {
+    pub pattern: Spanned<MatchPattern>,
+    pub body: Spanned<TypedExpr>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum MatchPattern {
+    Variant {
+        variant: Spanned<String>,
+        variant_idx: usize,
+        /// `_` wird nicht gebunden
+        bindings: Vec<Spanned<String>>,
+    },
+    /// `_`
+    Wildcard,
+}
+
 pub const CLASS_SELF_ARG_NAME: &str = "self";
+/// baut die `args` für `def main(args: List<String>)`
+pub const MAIN_ARGS_FUNCTION_NAME: &str = "__mx_args";
+/// `a[f()] += 1` wird zu `a[f()] = __mx_place + 1`, die backends binden den namen an die
+/// schon berechnete adresse des ziels, damit `f()` nur einmal aufgerufen wird
+pub const ASSIGNMENT_PLACE_NAME: &str = "__mx_place";
 
 #[derive(Debug, Display, PartialEq, Eq, Clone, Serialize, Deserialize, Hash)]
 pub enum Trait {
@@ -908,218 +1273,118 @@ pub enum Trait {
     /// `||`
     #[display(fmt = "Or")]
     Or,
+    /// `&`
+    #[display(fmt = "BitAnd")]
+    BitAnd,
+    /// `|`
+    #[display(fmt = "BitOr")]
+    BitOr,
+    /// `^`
+    #[display(fmt = "BitXor")]
+    BitXor,
+    /// `<<`
+    #[display(fmt = "ShiftLeft")]
+    Shl,
+    /// `>>`
+    #[display(fmt = "ShiftRight")]
+    Shr,
 
     /// `as`
     #[display(fmt = "Cast")]
     Cast,
+
+    /// `for x in ...` (anzahl der elemente)
+    #[display(fmt = "IterLen")]
+    IterLen,
+    /// `for x in ...` (element an index)
+    #[display(fmt = "IterGet")]
+    IterGet,
+
+    /// `trait Show { ... }`
+    #[display(fmt = "{}", _0)]
+    Custom(String),
 }
 
-pub const TRAIT_NAMES_MAP: phf::Map<&str, Trait> = phf::phf_map!(
-    "add" => Trait::Add,
-    "sub" => Trait::Sub,
-    "mul" => Trait::Mul,
-    "div" => Trait::Div,
-    "idx" => Trait::Index,
-    "neg" => Trait::Negate,
-    "not" => Trait::BooleanNot,
-
-    "eq" => Trait::Equal,
-    "ne" => Trait::NotEqual,
-    "lt" => Trait::LessThan,
-    "lte" => Trait::LessThanOrEqual,
-    "gt" => Trait::GreaterThan,
-    "gte" => Trait::GreaterThanOrEqual,
-    "and" => Trait::And,
-    "or" => Trait::Or,
-    "mod" => Trait::Modulo,
-
-    "as" => Trait::Cast,
-);
-
-fn get_default_implementation(type_: &DataType) -> Option<HashSet<TraitInfo>> {
+/// alle zahlentypen, zwischen denen mit `as` gecastet werden kann
+const NUMERIC_TYPES: [DataType; 10] = [
+    DataType::Integer8,
+    DataType::Integer16,
+    DataType::Integer32,
+    DataType::Integer64,
+    DataType::UnsignedInteger8,
+    DataType::UnsignedInteger16,
+    DataType::UnsignedInteger32,
+    DataType::UnsignedInteger64,
+    DataType::Float,
+    DataType::Float64,
+];
+
+/// operator traits, die der compiler für zahlen, bool und arrays selbst umsetzt,
+/// parameter und rückgabetyp kommen aus der deklaration im prelude
+fn intrinsic_traits(type_: &DataType) -> Vec<Trait> {
     match type_ {
-        DataType::Integer8 | DataType::Integer32 | DataType::Integer64 | DataType::Float => {
-            Some(HashSet::from([
-                (
-                    Trait::Add,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    type_.to_owned(),
-                ),
-                (
-                    Trait::Sub,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    type_.to_owned(),
-                ),
-                (
-                    Trait::Mul,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    type_.to_owned(),
-                ),
-                (
-                    Trait::Div,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    type_.to_owned(),
-                ),
-                (
-                    Trait::Modulo,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    type_.to_owned(),
-                ),
-                (
-                    Trait::Equal,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    DataType::Boolean,
-                ),
-                (
-                    Trait::NotEqual,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    DataType::Boolean,
-                ),
-                (
-                    Trait::LessThan,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    DataType::Boolean,
-                ),
-                (
-                    Trait::LessThanOrEqual,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    DataType::Boolean,
-                ),
-                (
-                    Trait::GreaterThan,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    DataType::Boolean,
-                ),
-                (
-                    Trait::GreaterThanOrEqual,
-                    vec![type_.to_owned(), type_.to_owned()],
-                    None,
-                    DataType::Boolean,
-                ),
-                (
-                    Trait::Negate,
-                    vec![type_.to_owned()],
-                    None,
-                    type_.to_owned(),
-                ),
-                (
-                    Trait::Cast,
-                    vec![type_.to_owned(), DataType::Integer8],
-                    None,
-                    DataType::Integer8,
-                ),
-                (
-                    Trait::Cast,
-                    vec![type_.to_owned(), DataType::Integer32],
-                    None,
-                    DataType::Integer32,
-                ),
-                (
-                    Trait::Cast,
-                    vec![type_.to_owned(), DataType::Integer64],
-                    None,
-                    DataType::Integer64,
-                ),
-            ]))
-        }
-        DataType::Boolean => Some(HashSet::from([
-            (
+        _ if type_.is_integer() || type_.is_float() => {
+            let mut traits = vec![
+                Trait::Add,
+                Trait::Sub,
+                Trait::Mul,
+                Trait::Div,
+                Trait::Modulo,
                 Trait::Equal,
-                vec![type_.to_owned(), type_.to_owned()],
-                None,
-                DataType::Boolean,
-            ),
-            (
                 Trait::NotEqual,
-                vec![type_.to_owned(), type_.to_owned()],
-                None,
-                DataType::Boolean,
-            ),
-            (
-                Trait::And,
-                vec![type_.to_owned(), type_.to_owned()],
-                None,
-                DataType::Boolean,
-            ),
-            (
-                Trait::Or,
-                vec![type_.to_owned(), type_.to_owned()],
-                None,
-                DataType::Boolean,
-            ),
-            (
-                Trait::BooleanNot,
-                vec![type_.to_owned()],
-                None,
-                DataType::Boolean,
-            ),
-        ])),
-        DataType::Array { value_type, .. } => Some(HashSet::from([(
-            Trait::Index,
-            vec![type_.to_owned(), DataType::Integer64],
-            None,
-            *value_type.clone(),
-        )])),
-        _ => None,
-    }
-}
-
-impl Parser {
-    pub(in crate::parser) fn check_trait_reqs(
-        &mut self,
-        _object: &DataType,
-        trait_: &Trait,
-        function: &Function,
-    ) -> Option<ParserError> {
-        let err = match trait_ {
-            Trait::Index => {
-                if !matches!(function.return_type.value, DataType::Pointer(_)) {
-                    Some("Index function must return a pointer".to_string())
-                } else {
-                    None
-                }
+                Trait::LessThan,
+                Trait::LessThanOrEqual,
+                Trait::GreaterThan,
+                Trait::GreaterThanOrEqual,
+                Trait::Negate,
+            ];
+
+            // `&`, `|`, `^`, `<<` und `>>` gibt es nur für integer
+            if type_.is_integer() {
+                traits.extend([
+                    Trait::BitAnd,
+                    Trait::BitOr,
+                    Trait::BitXor,
+                    Trait::Shl,
+                    Trait::Shr,
+                ]);
             }
-            Trait::Add
-            | Trait::Sub
-            | Trait::Mul
-            | Trait::Div
-            | Trait::Modulo
-            | Trait::Negate
-            | Trait::BooleanNot
-            | Trait::Equal
-            | Trait::NotEqual
-            | Trait::LessThan
-            | Trait::LessThanOrEqual
-            | Trait::GreaterThan
-            | Trait::GreaterThanOrEqual
-            | Trait::And
-            | Trait::Or
-            | Trait::Cast => None,
-        };
 
-        if let Some(err) = err {
-            return Some(ParserError::TraitRequirementsNotFulfilled(err));
+            traits
         }
-
-        None
+        DataType::Boolean => vec![
+            Trait::Equal,
+            Trait::NotEqual,
+            Trait::And,
+            Trait::Or,
+            Trait::BooleanNot,
+            Trait::BitAnd,
+            Trait::BitOr,
+            Trait::BitXor,
+        ],
+        DataType::Array { .. } => vec![Trait::Index],
+        _ => vec![],
     }
 }
 
-// pub fn trait_requirements_fullfill) -> bool {
-//     match trait_ {
-//         Trait
-//     }
-// }
+/// `as` ist ein keyword und hat kein trait im prelude
+fn cast_implementation(type_: &DataType) -> Vec<TraitInfo> {
+    if !type_.is_integer() && !type_.is_float() {
+        return vec![];
+    }
+
+    NUMERIC_TYPES
+        .iter()
+        .map(|to| {
+            (
+                Trait::Cast,
+                vec![type_.to_owned(), to.to_owned()],
+                None,
+                to.to_owned(),
+            )
+        })
+        .collect()
+}
 
 impl Trait {
     pub fn from_binary_operator(op: &BinaryOperator) -> Trait {
@@ -1137,6 +1402,11 @@ impl Trait {
             BinaryOperator::GreaterThanOrEqual => Trait::GreaterThanOrEqual,
             BinaryOperator::And => Trait::And,
             BinaryOperator::Or => Trait::Or,
+            BinaryOperator::BitAnd => Trait::BitAnd,
+            BinaryOperator::BitOr => Trait::BitOr,
+            BinaryOperator::BitXor => Trait::BitXor,
+            BinaryOperator::ShiftLeft => Trait::Shl,
+            BinaryOperator::ShiftRight => Trait::Shr,
         }
     }
 
@@ -1147,38 +1417,222 @@ impl Trait {
         }
     }
 
-    pub fn method_name(&self) -> String {
-        for (name, trait_) in TRAIT_NAMES_MAP.entries() {
-            if trait_ == self {
-                return name.to_string();
+    /// die methode, die ein operator aufruft, `a + b` ist `a.add(b)`
+    pub fn from_method_name(name: &str) -> Option<Trait> {
+        match name {
+            "add" => Some(Trait::Add),
+            "sub" => Some(Trait::Sub),
+            "mul" => Some(Trait::Mul),
+            "div" => Some(Trait::Div),
+            "mod" => Some(Trait::Modulo),
+            "idx" => Some(Trait::Index),
+            "neg" => Some(Trait::Negate),
+            "not" => Some(Trait::BooleanNot),
+            "eq" => Some(Trait::Equal),
+            "ne" => Some(Trait::NotEqual),
+            "lt" => Some(Trait::LessThan),
+            "lte" => Some(Trait::LessThanOrEqual),
+            "gt" => Some(Trait::GreaterThan),
+            "gte" => Some(Trait::GreaterThanOrEqual),
+            "and" => Some(Trait::And),
+            "or" => Some(Trait::Or),
+            "bitand" => Some(Trait::BitAnd),
+            "bitor" => Some(Trait::BitOr),
+            "bitxor" => Some(Trait::BitXor),
+            "shl" => Some(Trait::Shl),
+            "shr" => Some(Trait::Shr),
+            "iter_len" => Some(Trait::IterLen),
+            "iter_get" => Some(Trait::IterGet),
+            _ => None,
+        }
+    }
+
+    pub fn to_binary_operator(&self) -> Option<BinaryOperator> {
+        match self {
+            Trait::Add => Some(BinaryOperator::Add),
+            Trait::Sub => Some(BinaryOperator::Subtract),
+            Trait::Mul => Some(BinaryOperator::Multiply),
+            Trait::Div => Some(BinaryOperator::Divide),
+            Trait::Modulo => Some(BinaryOperator::Modulo),
+            Trait::Equal => Some(BinaryOperator::Equal),
+            Trait::NotEqual => Some(BinaryOperator::NotEqual),
+            Trait::LessThan => Some(BinaryOperator::LessThan),
+            Trait::LessThanOrEqual => Some(BinaryOperator::LessThanOrEqual),
+            Trait::GreaterThan => Some(BinaryOperator::GreaterThan),
+            Trait::GreaterThanOrEqual => Some(BinaryOperator::GreaterThanOrEqual),
+            Trait::And => Some(BinaryOperator::And),
+            Trait::Or => Some(BinaryOperator::Or),
+            Trait::BitAnd => Some(BinaryOperator::BitAnd),
+            Trait::BitOr => Some(BinaryOperator::BitOr),
+            Trait::BitXor => Some(BinaryOperator::BitXor),
+            Trait::Shl => Some(BinaryOperator::ShiftLeft),
+            Trait::Shr => Some(BinaryOperator::ShiftRight),
+            _ => None,
+        }
+    }
+}
+
+/// der implementierende typ in trait signaturen
+pub const TRAIT_SELF_TYPE_NAME: &str = "Self";
+
+/// `trait Show { def show(self) -> String; }`
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TraitDecl {
+    pub name: Spanned<String>,
+    pub methods: Vec<Spanned<TraitMethod>>,
+    /// der operator, der über dieses trait aufgelöst wird, siehe `parse_trait_def`
+    pub operator: Option<Trait>,
+}
+
+impl TraitDecl {
+    /// operator traits aus dem prelude
+    pub fn builtin(&self) -> Option<Trait> {
+        self.operator.clone()
+    }
+
+    pub fn as_trait(&self) -> Trait {
+        self.builtin()
+            .unwrap_or_else(|| Trait::Custom(self.name.value.clone()))
+    }
+
+    pub fn get_method(&self, name: &str) -> Option<&TraitMethod> {
+        self.methods
+            .iter()
+            .map(|m| &m.value)
+            .find(|m| m.name.value == name)
+    }
+}
+
+/// signatur ohne body, `self` ist immer der erste parameter
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TraitMethod {
+    pub name: Spanned<String>,
+    /// generics der methode passen auf jeden typ
+    pub generics: Vec<Spanned<DataType>>,
+    /// ohne self
+    pub params: Vec<Spanned<DataType>>,
+    pub return_type: Spanned<DataType>,
+}
+
+impl TraitMethod {
+    /// prüft ob `function` diese signatur für `implementor` erfüllt
+    pub fn check_impl(
+        &self,
+        trait_: &Trait,
+        implementor: &DataType,
+        function: &Function,
+    ) -> Result<(), ParserError> {
+        self.check_signature(trait_, function, |expected, actual| {
+            self.fits(expected, actual, implementor)
+        })
+    }
+
+    /// operator methoden außerhalb von `impl` dürfen andere typen haben (`Foo + int64`),
+    /// nur pointer müssen pointer bleiben (`idx` gibt `*Out` zurück)
+    pub fn check_overload(&self, trait_: &Trait, function: &Function) -> Result<(), ParserError> {
+        self.check_signature(trait_, function, fits_pointers)
+    }
+
+    fn check_signature(
+        &self,
+        trait_: &Trait,
+        function: &Function,
+        fits: impl Fn(&DataType, &DataType) -> bool,
+    ) -> Result<(), ParserError> {
+        if function.is_static_method() {
+            return Err(ParserError::TraitIsStaticMethod);
+        }
+
+        // -1 weil jedes trait self als parameter hat
+        let param_count = function.params.value.len() - 1;
+        if param_count != self.params.len() {
+            return Err(ParserError::TraitParamCountMismatch(
+                trait_.clone(),
+                self.params.len(),
+                param_count,
+            ));
+        }
+
+        for (expected, param) in self.params.iter().zip(function.params.value.iter().skip(1)) {
+            if !fits(&expected.value, &param.value._type.value) {
+                return Err(ParserError::TraitRequirementsNotFulfilled(format!(
+                    "parameter \"{}\" of {} must be {}",
+                    param.value.name.value, self.name.value, expected.value
+                )));
             }
         }
 
-        unreachable!()
+        if !fits(&self.return_type.value, &function.return_type.value) {
+            return Err(ParserError::TraitRequirementsNotFulfilled(format!(
+                "{} must return {}",
+                self.name.value, self.return_type.value
+            )));
+        }
+
+        Ok(())
     }
-    /// anzahl der parameter inklusive self
-    pub fn param_len(&self) -> usize {
-        match self {
-            Trait::Add
-            | Trait::Sub
-            | Trait::Mul
-            | Trait::Div
-            | Trait::Modulo
-            | Trait::Equal
-            | Trait::NotEqual
-            | Trait::LessThan
-            | Trait::LessThanOrEqual
-            | Trait::GreaterThan
-            | Trait::GreaterThanOrEqual
-            | Trait::And
-            | Trait::Or
-            | Trait::Index
-            | Trait::Cast => 1,
-            Trait::Negate | Trait::BooleanNot => 0,
+
+    /// parameter (mit `self`) und rückgabetyp, wenn `implementor` für `Self` eingesetzt wird
+    pub fn signature(&self, implementor: &DataType) -> (Vec<DataType>, DataType) {
+        let params = std::iter::once(implementor.clone())
+            .chain(
+                self.params
+                    .iter()
+                    .map(|p| replace_self_type(&p.value, implementor)),
+            )
+            .collect();
+
+        (
+            params,
+            replace_self_type(&self.return_type.value, implementor),
+        )
+    }
+
+    fn fits(&self, expected: &DataType, actual: &DataType, implementor: &DataType) -> bool {
+        match expected {
+            DataType::Generic(name) if name == TRAIT_SELF_TYPE_NAME => actual == implementor,
+            DataType::Generic(_) if self.generics.iter().any(|g| g.value == *expected) => true,
+            DataType::Pointer(expected) => {
+                matches!(actual, DataType::Pointer(actual) if self.fits(expected, actual, implementor))
+            }
+            _ => expected == actual,
         }
     }
 }
 
+fn fits_pointers(expected: &DataType, actual: &DataType) -> bool {
+    match expected {
+        DataType::Pointer(expected) => {
+            matches!(actual, DataType::Pointer(actual) if fits_pointers(expected, actual))
+        }
+        _ => true,
+    }
+}
+
+fn replace_self_type(_type: &DataType, implementor: &DataType) -> DataType {
+    match _type {
+        DataType::Generic(name) if name == TRAIT_SELF_TYPE_NAME => implementor.clone(),
+        DataType::Pointer(inner) => {
+            DataType::Pointer(Box::new(replace_self_type(inner, implementor)))
+        }
+        DataType::Array { value_type, len } => DataType::Array {
+            value_type: Box::new(replace_self_type(value_type, implementor)),
+            len: *len,
+        },
+        DataType::Function {
+            params,
+            return_type,
+        } => DataType::Function {
+            params: params
+                .iter()
+                .map(|p| replace_self_type(p, implementor))
+                .collect(),
+            return_type: Box::new(replace_self_type(return_type, implementor)),
+        },
+        _type => _type.clone(),
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct CallArg(pub TypedExpr);
 
@@ -1202,10 +1656,30 @@ pub enum DataType {
     #[strum(serialize = "int64")]
     Integer64,
 
+    #[display(fmt = "uint8")]
+    #[strum(serialize = "uint8")]
+    UnsignedInteger8,
+
+    #[display(fmt = "uint16")]
+    #[strum(serialize = "uint16")]
+    UnsignedInteger16,
+
+    #[display(fmt = "uint32")]
+    #[strum(serialize = "uint32")]
+    UnsignedInteger32,
+
+    #[display(fmt = "uint64")]
+    #[strum(serialize = "uint64")]
+    UnsignedInteger64,
+
     #[display(fmt = "float")]
     #[strum(serialize = "float")]
     Float,
 
+    #[display(fmt = "float64")]
+    #[strum(serialize = "float64")]
+    Float64,
+
     #[display(fmt = "bool")]
     #[strum(serialize = "bool")]
     Boolean,
@@ -1214,7 +1688,7 @@ pub enum DataType {
     Pointer(Box<DataType>),
 
     #[display(fmt = "{}", _0)]
-    Custom(CustomDataType),
+    Custom(Box<CustomDataType>),
 
     #[display(fmt = "[{}; {}]", value_type, len)]
     Array {
@@ -1224,6 +1698,18 @@ pub enum DataType {
     #[display(fmt = "{}", _0)]
     Generic(String),
 
+    /// `|int64, int64| -> bool`, funktionen und closures
+    #[display(
+        fmt = "|{}| -> {}",
+        "params.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(\", \")",
+        return_type
+    )]
+    #[strum(disabled)]
+    Function {
+        params: Vec<DataType>,
+        return_type: Box<DataType>,
+    },
+
     #[display(fmt = "void")]
     #[strum(serialize = "void")]
     #[default]
@@ -1238,25 +1724,48 @@ pub enum DataType {
 impl DataType {
     pub fn size(&self) -> usize {
         match self {
-            DataType::Integer8 => 1,
-            DataType::Integer16 => 2,
-            DataType::Integer32 => 4,
-            DataType::Integer64 => 8,
+            DataType::Integer8 | DataType::UnsignedInteger8 => 1,
+            DataType::Integer16 | DataType::UnsignedInteger16 => 2,
+            DataType::Integer32 | DataType::UnsignedInteger32 => 4,
+            DataType::Integer64 | DataType::UnsignedInteger64 => 8,
             DataType::Float => 4,
+            DataType::Float64 => 8,
             DataType::Boolean => 1,
             DataType::Pointer(_) => size_of::<usize>(),
-            DataType::Custom(inner) => inner
-                .fields
-                .value
-                .iter()
-                .map(|f| f._type.value.size())
-                .sum(),
+            // wie in c, am ende bis zur ausrichtung aufgefüllt
+            DataType::Custom(inner) => match inner.fields.value.last() {
+                Some(last) => {
+                    let end =
+                        inner.field_offset(inner.fields.value.len() - 1) + last._type.value.size();
+                    end.next_multiple_of(self.alignment())
+                }
+                None => 0,
+            },
             DataType::Array { value_type, len } => value_type.size() * len,
             DataType::Generic(_) => 8,
+            // funktion und umgebung
+            DataType::Function { .. } => 2 * size_of::<usize>(),
             DataType::None => 0,
             DataType::DataType => 8,
         }
     }
+
+    pub fn alignment(&self) -> usize {
+        match self {
+            DataType::Custom(inner) if inner.is_packed => 1,
+            DataType::Custom(inner) => inner
+                .fields
+                .value
+                .iter()
+                .map(|f| f._type.value.alignment())
+                .max()
+                .unwrap_or(1),
+            DataType::Array { value_type, .. } => value_type.alignment(),
+            DataType::Function { .. } => size_of::<usize>(),
+            DataType::None => 1,
+            _type => _type.size(),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -1269,22 +1778,6 @@ pub struct DataTypeInfo {
 }
 
 impl DataTypeInfo {
-    pub fn implements_trait(&self, trait_: &Trait, params: &[DataType]) -> bool {
-        if let Some(traits) = get_default_implementation(&self.parent_type) {
-            // standard implementierungen, float, int, bool
-            if let Some((_, _, _, _)) = traits
-                .iter()
-                .find(|(t, p, _, _)| t == trait_ && p == params)
-            {
-                return true;
-            }
-        }
-
-        self.traits
-            .iter()
-            .any(|(t, p, _, _)| t == trait_ && p == params)
-    }
-
     pub fn get_trait_override_function_name(
         &self,
         trait_: &Trait,
@@ -1298,25 +1791,6 @@ impl DataTypeInfo {
             }
         })
     }
-
-    pub fn get_trait_return_type(&self, trait_: &Trait, params: &[DataType]) -> Option<DataType> {
-        for (t, p, _, return_type) in self.traits.iter() {
-            if t == trait_ && p == params {
-                return Some(return_type.clone());
-            }
-        }
-
-        if let Some(traits) = get_default_implementation(&self.parent_type) {
-            if let Some((_, _, _, return_type)) = traits
-                .iter()
-                .find(|(t, p, _, _)| t == trait_ && p == params)
-            {
-                return Some(return_type.clone());
-            }
-        }
-        // TODO: Option<DataType> ?
-        None
-    }
 }
 
 #[derive(Debug, Display, Clone, PartialEq, Eq, Serialize, Deserialize, EnumString, Hash)]
@@ -1334,6 +1808,16 @@ pub enum BinaryOperator {
     GreaterThanOrEqual,
     And,
     Or,
+    /// `&`
+    BitAnd,
+    /// `|`
+    BitOr,
+    /// `^`
+    BitXor,
+    /// `<<`
+    ShiftLeft,
+    /// `>>`
+    ShiftRight,
 }
 
 impl BinaryOperator {
@@ -1351,8 +1835,13 @@ impl BinaryOperator {
 
     pub fn precedence(&self) -> u8 {
         match self {
-            BinaryOperator::Add | BinaryOperator::Subtract => 3,
-            BinaryOperator::Multiply | BinaryOperator::Divide => 4,
+            BinaryOperator::Add | BinaryOperator::Subtract => 7,
+            BinaryOperator::Multiply | BinaryOperator::Divide => 8,
+            // wie in rust: `a & b == c` ist `(a & b) == c`
+            BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => 6,
+            BinaryOperator::BitAnd => 5,
+            BinaryOperator::BitXor => 4,
+            BinaryOperator::BitOr => 3,
             // BinaryOperator::Modulo => 4,
             BinaryOperator::LessThan
             | BinaryOperator::LessThanOrEqual
@@ -1410,7 +1899,12 @@ impl InternalNameGetter for DataType {
             | DataType::Integer16
             | DataType::Integer32
             | DataType::Integer64
+            | DataType::UnsignedInteger8
+            | DataType::UnsignedInteger16
+            | DataType::UnsignedInteger32
+            | DataType::UnsignedInteger64
             | DataType::Float
+            | DataType::Float64
             | DataType::Boolean
             | DataType::Array { .. }
             | DataType::Generic(..)
@@ -1418,49 +1912,53 @@ impl InternalNameGetter for DataType {
             | DataType::None => self.to_string(),
             DataType::Pointer(inner) => format!("ptr-{}", inner.internal_name()),
             DataType::Custom(custom_type) => custom_type.name.clone(),
+            DataType::Function {
+                params,
+                return_type,
+            } => format!(
+                "fn-{}-ret-{}",
+                params.iter().map(|p| p.internal_name()).join("-"),
+                return_type.internal_name()
+            ),
         }
     }
 }
 
 impl DataType {
+    /// typ eines arguments für `...` nach den c regeln, `float` wird zu `float64` und
+    /// kleinere integer zu `int32`, `None` wenn der typ nicht übergeben werden kann
+    pub fn variadic_promotion(&self) -> Option<DataType> {
+        match self {
+            DataType::Float => Some(DataType::Float64),
+            DataType::Integer8
+            | DataType::Integer16
+            | DataType::UnsignedInteger8
+            | DataType::UnsignedInteger16
+            | DataType::Boolean => Some(DataType::Integer32),
+            DataType::Integer32
+            | DataType::Integer64
+            | DataType::UnsignedInteger32
+            | DataType::UnsignedInteger64
+            | DataType::Float64
+            | DataType::Pointer(_) => Some(self.clone()),
+            _ => None,
+        }
+    }
+
     pub fn can_be_converted_to(&self, to: &DataType) -> bool {
-        matches!(
-            (self, to),
-            (
-                DataType::Integer8
-                    | DataType::Integer16
-                    | DataType::Integer32
-                    | DataType::Integer64,
-                DataType::Pointer(_)
-            ) | (DataType::Pointer(_), DataType::Pointer(_))
-                | (DataType::Pointer(_), DataType::Integer64,)
-                | (
-                    DataType::Integer8
-                        | DataType::Integer16
-                        | DataType::Integer32
-                        | DataType::Integer64
-                        | DataType::Boolean,
-                    DataType::Integer8
-                        | DataType::Integer16
-                        | DataType::Integer32
-                        | DataType::Integer64
-                        | DataType::Boolean,
-                )
-                | (
-                    DataType::Integer8
-                        | DataType::Integer16
-                        | DataType::Integer32
-                        | DataType::Integer64,
-                    DataType::Float,
-                )
-                | (
-                    DataType::Float,
-                    DataType::Integer8
-                        | DataType::Integer16
-                        | DataType::Integer32
-                        | DataType::Integer64,
-                )
-        )
+        match (self, to) {
+            (from, DataType::Pointer(_)) if from.is_integer() => true,
+            (DataType::Pointer(_), DataType::Pointer(_) | DataType::Integer64) => true,
+            (from, to)
+                if (from.is_integer() || from.is_boolean())
+                    && (to.is_integer() || to.is_boolean()) =>
+            {
+                true
+            }
+            (from, to) => {
+                (from.is_integer() || from.is_float()) && (to.is_integer() || to.is_float())
+            }
+        }
 
         // (
         //     DataType::Integer8 | DataType::Integer64,
@@ -1503,18 +2001,39 @@ impl DataType {
     }
 
     pub fn is_integer(&self) -> bool {
+        self.is_signed_integer() || self.is_unsigned_integer()
+    }
+
+    pub fn is_signed_integer(&self) -> bool {
         matches!(
             self,
             DataType::Integer8 | DataType::Integer16 | DataType::Integer32 | DataType::Integer64
         )
     }
 
+    pub fn is_unsigned_integer(&self) -> bool {
+        matches!(
+            self,
+            DataType::UnsignedInteger8
+                | DataType::UnsignedInteger16
+                | DataType::UnsignedInteger32
+                | DataType::UnsignedInteger64
+        )
+    }
+
     pub fn is_boolean(&self) -> bool {
         matches!(self, DataType::Boolean)
     }
 
     pub fn is_float(&self) -> bool {
-        matches!(self, DataType::Float)
+        matches!(self, DataType::Float | DataType::Float64)
+    }
+
+    pub fn as_custom(&self) -> Option<&CustomDataType> {
+        match self {
+            DataType::Custom(inner) => Some(inner),
+            _ => None,
+        }
     }
 }
 
@@ -1523,6 +2042,8 @@ impl CommonGeneric for DataType {
         matches!(self, DataType::Generic(_))
             || matches!(self, DataType::Custom(inner) if inner.is_generic())
             || matches!(self, DataType::Pointer(inner) if inner.is_generic())
+            || matches!(self, DataType::Function { params, return_type }
+                if params.iter().any(|p| p.is_generic()) || return_type.is_generic())
     }
 
     fn generics(&self) -> OrderSet<String> {
@@ -1534,6 +2055,14 @@ impl CommonGeneric for DataType {
             }
             DataType::Custom(inner) => inner.generics.iter().map(|g| g.value.to_string()).collect(),
             DataType::Pointer(inner) => inner.generics(),
+            DataType::Function {
+                params,
+                return_type,
+            } => params
+                .iter()
+                .chain(std::iter::once(&**return_type))
+                .flat_map(|t| t.generics())
+                .collect(),
             _ => OrderSet::new(),
         }
     }
@@ -1548,11 +2077,15 @@ pub struct CustomDataType {
     pub subtypes: HashMap<Vec<DataType>, CustomDataType>,
     pub generics: Vec<Spanned<DataType>>,
     pub subtype_of: Option<String>,
+    /// nur bei enums, die payloads liegen in `fields` (feld 0 ist der tag)
+    pub variants: Option<Vec<EnumVariant>>,
     // ```
     // struct Foo<T> {
     //     bar: int,
     // }
     pub is_generic: bool, // falls es einen generischen Paremeter gibt, der aber nicht verwendet wird
+    /// `#[packed]`, felder ohne lücken wie `__attribute__((packed))` in c
+    pub is_packed: bool,
 }
 
 impl Hash for CustomDataType {
@@ -1655,7 +2188,7 @@ impl CustomDataType {
         {
             // panic!("class already exists: {:?}", inner.methods.keys());
             // panic!("fef");
-            return inner.clone();
+            return (**inner).clone();
         }
 
         // let mut generic_subtypes = AHashMap::new();
@@ -1677,18 +2210,20 @@ impl CustomDataType {
             subtypes: HashMap::new(),
             generics: vec![],
             subtype_of: Some(self.name.clone()),
+            variants: self.variants.clone(),
             is_generic: false,
+            is_packed: self.is_packed,
         };
 
         if out.is_generic() {
             return self.clone();
         }
 
-        let data_type_info = program.get_type_info(&DataType::Custom(self.clone()));
+        let data_type_info = program.get_type_info(&DataType::Custom(Box::new(self.clone())));
 
         let mut new_data_type_info = DataTypeInfo {
             methods: data_type_info.methods.clone(),
-            parent_type: DataType::Custom(out.clone()),
+            parent_type: DataType::Custom(Box::new(out.clone())),
             traits: HashSet::new(),
         };
 
@@ -1697,7 +2232,7 @@ impl CustomDataType {
                 let mut trait_: TraitInfo = trait_;
                 let mut types: Vec<DataTypeSettable> = vec![];
                 // trait_.1[0] ist der subtyp, muss hier gesetzt werden
-                trait_.1[0] = DataType::Custom(out.clone());
+                trait_.1[0] = DataType::Custom(Box::new(out.clone()));
 
                 types.append(
                     &mut trait_
@@ -1710,25 +2245,24 @@ impl CustomDataType {
 
                 specify_generics(&mut types, &generic_map, program, false);
 
-                let trait_name = trait_.clone().2.unwrap();
+                // user traits haben keine eigene funktion, die methoden sind normale methoden
+                if let Some(trait_name) = trait_.2.clone() {
+                    let trait_function = program.functions.get(&trait_name).unwrap().clone();
+                    let subtype = trait_function
+                        .value
+                        .subtype(&generic_map, None, program, false);
 
-                let trait_function = program.functions.get(&trait_name).unwrap().clone();
-                let subtype = trait_function
-                    .value
-                    .subtype(&generic_map, None, program, false);
+                    trait_.2 = Some(subtype.name.clone());
 
-                if let Some(ref mut name) = trait_.2 {
-                    *name = subtype.name.clone();
+                    program.functions.insert(
+                        subtype.name.clone(),
+                        Spanned {
+                            value: subtype,
+                            span: Span::default(),
+                        },
+                    );
                 }
 
-                program.functions.insert(
-                    subtype.name.clone(),
-                    Spanned {
-                        value: subtype,
-                        span: Span::default(),
-                    },
-                );
-
                 new_data_type_info.traits.insert(trait_);
             }
 
@@ -1746,7 +2280,7 @@ impl CustomDataType {
         program.custom_types.insert(
             out.name.clone(),
             Spanned {
-                value: DataType::Custom(out.clone()),
+                value: DataType::Custom(Box::new(out.clone())),
                 span: Span::default(),
             },
         );
@@ -1764,6 +2298,58 @@ impl CustomDataType {
     // }
 }
 
+/// ```txt
+/// enum Shape {
+///     Circle(float),      // tag 0, feld 1
+///     Rect(float, float), // tag 1, felder 2 und 3
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EnumVariant {
+    pub name: Spanned<String>,
+    /// indizes in `CustomDataType::fields`
+    pub fields: Vec<usize>,
+}
+
+impl CustomDataType {
+    pub fn is_enum(&self) -> bool {
+        self.variants.is_some()
+    }
+
+    /// der name ohne generische parameter, `Option--int64` => `Option`
+    pub fn base_name(&self) -> &str {
+        self.subtype_of.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn get_variant(&self, name: &str) -> Option<(usize, &EnumVariant)> {
+        self.variants
+            .as_ref()?
+            .iter()
+            .find_position(|v| v.name.value == name)
+    }
+
+    /// offset von feld `idx` wie in c, jedes feld liegt auf seiner ausrichtung
+    pub fn field_offset(&self, idx: usize) -> usize {
+        let align = |offset: usize, _type: &DataType| match self.is_packed {
+            true => offset,
+            false => offset.next_multiple_of(_type.alignment()),
+        };
+
+        let end = self.fields.value[..idx].iter().fold(0, |offset, field| {
+            align(offset, &field._type.value) + field._type.value.size()
+        });
+        align(end, &self.fields.value[idx]._type.value)
+    }
+
+    pub fn variant_types(&self, variant: &EnumVariant) -> Vec<DataType> {
+        variant
+            .fields
+            .iter()
+            .map(|idx| self.fields.value[*idx]._type.value.clone())
+            .collect()
+    }
+}
+
 impl CommonGeneric for CustomDataType {
     fn is_generic(&self) -> bool {
         self.is_generic || self.fields.value.iter().any(|f| f._type.value.is_generic())
@@ -1817,7 +2403,12 @@ impl DataTypeSetter for DataType {
             | DataType::Integer16
             | DataType::Integer32
             | DataType::Integer64
+            | DataType::UnsignedInteger8
+            | DataType::UnsignedInteger16
+            | DataType::UnsignedInteger32
+            | DataType::UnsignedInteger64
             | DataType::Float
+            | DataType::Float64
             | DataType::Boolean
             | DataType::None
             | DataType::DataType => vec![],
@@ -1825,6 +2416,14 @@ impl DataTypeSetter for DataType {
             DataType::Custom(_inner) => vec![DataTypeSettable::DataType(self)],
             DataType::Array { value_type, .. } => value_type._type_mut(),
             DataType::Generic(_inner) => vec![DataTypeSettable::DataType(self)],
+            DataType::Function {
+                params,
+                return_type,
+            } => {
+                let mut out = params.iter_mut().flat_map(|p| p._type_mut()).collect_vec();
+                out.append(&mut return_type._type_mut());
+                out
+            }
         }
     }
 }
@@ -5,6 +5,9 @@ use ordermap::OrderMap;
 
 use super::ast::{DataType, Function, FunctionParam};
 
+/// trait deklarationen, die in jedem programm vorhanden sind
+pub const PRELUDE: &str = include_str!("prelude.mx");
+
 pub fn get_builtin_functions() -> OrderMap<String, Spanned<Function>> {
     let size_of = Function {
         display_name: Spanned {
@@ -38,6 +41,9 @@ pub fn get_builtin_functions() -> OrderMap<String, Spanned<Function>> {
             span: Span::default(),
         },
         is_extern: true,
+        is_variadic: false,
+        c_abi: false,
+        export: false,
         method_of: None,
         is_builtin: true,
         // generics: vec![],
@@ -0,0 +1,251 @@
+use derive_more::Display;
+use strum_macros::EnumString;
+
+use crate::lexer::{
+    position::Spanned,
+    tokens::{Keyword, Punctuation, Token},
+};
+
+use super::{error::ParserError, parser_main::Parser};
+
+/// das betriebssystem für das kompiliert wird, `--target` in der cli
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum Target {
+    #[display(fmt = "linux")]
+    #[strum(serialize = "linux")]
+    Linux,
+    #[display(fmt = "macos")]
+    #[strum(serialize = "macos")]
+    MacOs,
+    #[display(fmt = "windows")]
+    #[strum(serialize = "windows")]
+    Windows,
+    #[display(fmt = "wasm")]
+    #[strum(serialize = "wasm")]
+    Wasm,
+}
+
+impl Target {
+    /// das system auf dem der compiler läuft
+    pub fn host() -> Self {
+        if cfg!(target_os = "windows") {
+            Target::Windows
+        } else if cfg!(target_os = "macos") {
+            Target::MacOs
+        } else if cfg!(target_arch = "wasm32") {
+            Target::Wasm
+        } else {
+            Target::Linux
+        }
+    }
+
+    /// `target_family` wie in rust
+    pub fn family(&self) -> &'static str {
+        match self {
+            Target::Linux | Target::MacOs => "unix",
+            Target::Windows => "windows",
+            Target::Wasm => "wasm",
+        }
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Target::host()
+    }
+}
+
+/// attribute für das nächste item, werden von dessen `parse_*` übernommen
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemAttributes {
+    /// `#[packed]`, nur für klassen
+    pub packed: bool,
+    /// `#[export]`, nur für funktionen mit body
+    pub export: bool,
+}
+
+impl Parser {
+    /// `#[cfg(...)]`, `#[packed]` oder `#[export]` vor einem item, trifft die bedingung nicht zu
+    /// wird das item übersprungen
+    pub(in crate::parser) fn parse_attributes(&mut self) -> Result<(), Spanned<ParserError>> {
+        let mut enabled = true;
+        let mut attributes = ItemAttributes::default();
+        let mut packed_span = None;
+        let mut export_span = None;
+
+        while self.peek()?.value == Token::Punctuation(Punctuation::AttributeStart) {
+            self.next_token()?;
+
+            let name = self.parse_cfg_identifier()?;
+            match name.value.as_str() {
+                "cfg" => {
+                    self.expect_next(&[Token::Punctuation(Punctuation::OpenParen)])?;
+                    // alle bedingungen auswerten, damit auch fehler in späteren gefunden werden
+                    enabled &= self.parse_cfg_predicate()?;
+                    self.expect_next(&[Token::Punctuation(Punctuation::CloseParen)])?;
+                }
+                "packed" => {
+                    attributes.packed = true;
+                    packed_span = Some(name.span);
+                }
+                "export" => {
+                    attributes.export = true;
+                    export_span = Some(name.span);
+                }
+                _ => {
+                    return Err(Spanned {
+                        value: ParserError::UnknownAttribute(name.value),
+                        span: name.span,
+                    })
+                }
+            }
+
+            self.expect_next(&[Token::Punctuation(Punctuation::CloseBracket)])?;
+        }
+
+        if !enabled {
+            return self.skip_item();
+        }
+
+        let mut item = self.peek()?.value;
+        if item == Token::Keyword(Keyword::Pub) {
+            item = self.peek_nth(1)?.value;
+        }
+
+        if let Some(span) = packed_span {
+            if item != Token::Keyword(Keyword::Class) {
+                return Err(Spanned {
+                    value: ParserError::AttributeNotAllowed("packed".to_string()),
+                    span,
+                });
+            }
+        }
+
+        if let Some(span) = export_span {
+            if item != Token::Keyword(Keyword::Def) {
+                return Err(Spanned {
+                    value: ParserError::AttributeNotAllowed("export".to_string()),
+                    span,
+                });
+            }
+        }
+
+        self.item_attributes = attributes;
+        Ok(())
+    }
+
+    /// `target_os = "linux"`, `target_family = "unix"`, `not(...)`, `any(...)` oder `all(...)`
+    fn parse_cfg_predicate(&mut self) -> Result<bool, Spanned<ParserError>> {
+        let name = self.parse_cfg_identifier()?;
+
+        match name.value.as_str() {
+            "not" => {
+                self.expect_next(&[Token::Punctuation(Punctuation::OpenParen)])?;
+                let value = self.parse_cfg_predicate()?;
+                self.expect_next(&[Token::Punctuation(Punctuation::CloseParen)])?;
+                Ok(!value)
+            }
+            "any" | "all" => {
+                self.expect_next(&[Token::Punctuation(Punctuation::OpenParen)])?;
+
+                let mut values = vec![];
+                self.walk_separated_values(
+                    Token::Punctuation(Punctuation::Comma),
+                    Token::Punctuation(Punctuation::CloseParen),
+                    |parser| {
+                        values.push(parser.parse_cfg_predicate()?);
+                        Ok(())
+                    },
+                )?;
+
+                match name.value.as_str() {
+                    "any" => Ok(values.into_iter().any(|v| v)),
+                    _ => Ok(values.into_iter().all(|v| v)),
+                }
+            }
+            key => {
+                self.expect_next(&[Token::Assignment])?;
+                let value = match self.expect_next(&[Token::String("value".to_string())])? {
+                    Spanned {
+                        value: Token::String(value),
+                        span,
+                    } => Spanned { value, span },
+                    _ => unreachable!(),
+                };
+
+                let unknown_option = || Spanned {
+                    value: ParserError::UnknownCfgOption(key.to_string(), value.value.clone()),
+                    span: name.span.extend(&value.span),
+                };
+
+                match key {
+                    "target_os" => match value.value.parse::<Target>() {
+                        Ok(target) => Ok(target == self.target),
+                        Err(_) => Err(unknown_option()),
+                    },
+                    "target_family"
+                        if matches!(value.value.as_str(), "unix" | "windows" | "wasm") =>
+                    {
+                        Ok(value.value == self.target.family())
+                    }
+                    _ => Err(unknown_option()),
+                }
+            }
+        }
+    }
+
+    fn parse_cfg_identifier(&mut self) -> Result<Spanned<String>, Spanned<ParserError>> {
+        match self.expect_next(&[Token::Identifier("identifier".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Ok(Spanned { value: name, span }),
+            _ => unreachable!(),
+        }
+    }
+
+    /// überspringt das nächste item bis zum beginn des folgenden
+    fn skip_item(&mut self) -> Result<(), Spanned<ParserError>> {
+        // `pub` und `extern` gehören zum folgenden `def`
+        let mut first = self.next_token()?;
+        while matches!(
+            first.value,
+            Token::Keyword(Keyword::Pub) | Token::Keyword(Keyword::Extern)
+        ) {
+            first = self.next_token()?;
+        }
+
+        // `from "foo.mx" import bar` endet nicht beim `import`
+        if first.value == Token::Keyword(Keyword::From) {
+            while self.next_token()?.value != Token::Keyword(Keyword::Import) {}
+        }
+
+        while let Ok(token) = self.peek() {
+            if self.brace_depth == 0 && is_item_start(&token.value) {
+                break;
+            }
+            self.next_token()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// tokens mit denen ein item (`def`, `class`, ...) beginnt
+pub(in crate::parser) fn is_item_start(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Keyword(Keyword::Def)
+            | Token::Keyword(Keyword::Extern)
+            | Token::Keyword(Keyword::Class)
+            | Token::Keyword(Keyword::Enum)
+            | Token::Keyword(Keyword::Trait)
+            | Token::Keyword(Keyword::Impl)
+            | Token::Keyword(Keyword::Import)
+            | Token::Keyword(Keyword::From)
+            | Token::Keyword(Keyword::Pub)
+            | Token::Keyword(Keyword::Const)
+            | Token::Keyword(Keyword::Static)
+            | Token::Punctuation(Punctuation::AttributeStart)
+    )
+}
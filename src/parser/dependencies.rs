@@ -1,39 +1,445 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
 
 use crate::{
-    error::{emit_error, CompilerError},
     lexer::{
-        position::Spanned,
-        tokens::{Keyword, Token},
+        position::{Span, Spanned},
+        tokens::{Keyword, Operator, Punctuation, Token},
     },
-    parser::ast::Program,
+    parser::ast::{Block, DataType, Expr, Import, Program, TypedExpr},
+};
+
+use super::{
+    error::{ImportError, ImportTrace, ImportedError, ParserError},
+    parser_main::Parser,
+    std_lib::{self, EMBEDDED_ROOT},
 };
 
-use super::{error::ParserError, parser_main::Parser};
+/// was durch einen import sichtbar wird
+enum ImportKind {
+    /// `import "foo.mx"`, alles mit `pub`
+    All,
+    /// `import "foo.mx" as foo`
+    Namespace(Spanned<String>),
+    /// `from "foo.mx" import bar, baz`
+    Selected(Vec<Spanned<String>>),
+}
 
 impl Parser {
+    /// `import "foo.mx"`, `import "foo.mx" as foo` oder `from "foo.mx" import bar, baz`
     pub(in crate::parser) fn parse_import(&mut self) -> Result<(), Spanned<ParserError>> {
-        let _span = self.expect_next(&[Token::Keyword(Keyword::Import)])?.span;
-        let name = self.expect_next(&[Token::String("file name".to_string())])?;
+        let start = self.expect_next(&[
+            Token::Keyword(Keyword::Import),
+            Token::Keyword(Keyword::From),
+        ])?;
+
+        let name = match self.expect_next(&[Token::String("file name".to_string())])? {
+            Spanned {
+                value: Token::String(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        let kind = if start.value == Token::Keyword(Keyword::From) {
+            self.expect_next(&[Token::Keyword(Keyword::Import)])?;
+            let mut names = vec![self.parse_import_name()?];
+
+            while let Some(Spanned {
+                value: Token::Punctuation(Punctuation::Comma),
+                ..
+            }) = self.tokens.peek()
+            {
+                self.next_token()?;
+                names.push(self.parse_import_name()?);
+            }
+
+            ImportKind::Selected(names)
+        } else if let Some(Spanned {
+            value: Token::Keyword(Keyword::As),
+            ..
+        }) = self.tokens.peek()
+        {
+            self.next_token()?;
+            ImportKind::Namespace(self.parse_import_name()?)
+        } else {
+            ImportKind::All
+        };
+
+        // `;` nach einem import ist optional
+        if let Some(Spanned {
+            value: Token::Punctuation(Punctuation::SemiColon),
+            ..
+        }) = self.tokens.peek()
+        {
+            self.next_token()?;
+        }
+
+        let path = self.resolve_import(&name)?;
+        let program = self.parse_dependency(&path, name.clone())?;
+
+        self.program.imports.push(Import {
+            name: name.clone(),
+            functions: program
+                .functions
+                .iter()
+                .filter(|(_, f)| !f.value.is_builtin)
+                .map(|(name, _)| name.clone())
+                .collect(),
+            types: program.custom_types.keys().cloned().collect(),
+            globals: program.globals.keys().cloned().collect(),
+        });
+
+        self.expose_dependency(&program, kind, &name)?;
+        self.add_dependency(program)?;
+
+        Ok(())
+    }
 
-        if let Spanned {
-            value: Token::String(name),
+    /// sucht zuerst relativ zur aktuellen datei, dann in den bibliotheks ordnern
+    /// und zuletzt in der eingebetteten std
+    fn resolve_import(&self, name: &Spanned<String>) -> Result<PathBuf, Spanned<ParserError>> {
+        // im browser gibt es kein arbeitsverzeichnis
+        let base = self
+            .relative_path
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+
+        // imports innerhalb der eingebetteten std bleiben dort
+        if std_lib::is_embedded(&base) {
+            return Ok(std_lib::normalize(&base.join(&name.value)));
+        }
+
+        let relative = base.join(&name.value);
+        if relative.exists() {
+            return Ok(relative);
+        }
+
+        if let Some(path) = self
+            .library_paths
+            .iter()
+            .map(|dir| dir.join(&name.value))
+            .find(|path| path.exists())
+        {
+            return Ok(path);
+        }
+
+        let embedded = std_lib::normalize(&Path::new(EMBEDDED_ROOT).join(&name.value));
+        if std_lib::embedded_file(&embedded).is_some() {
+            return Ok(embedded);
+        }
+
+        Err(Spanned {
+            value: ParserError::FileNotFound(name.value.clone()),
+            span: name.span,
+        })
+    }
+
+    fn parse_import_name(&mut self) -> Result<Spanned<String>, Spanned<ParserError>> {
+        match self.expect_next(&[Token::Identifier("name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Ok(Spanned { value: name, span }),
+            _ => unreachable!(),
+        }
+    }
+
+    /// `pub def`, `pub class`, `pub enum`, `pub const` oder `pub static`
+    pub(in crate::parser) fn parse_pub_item(&mut self) -> Result<(), Spanned<ParserError>> {
+        self.expect_next(&[Token::Keyword(Keyword::Pub)])?;
+        let next = self.peek()?;
+
+        match next.value {
+            Token::Keyword(Keyword::Def) | Token::Keyword(Keyword::Extern) => {
+                let function = self.parse_func_def()?.value;
+
+                // methoden sind über ihren typ sowieso erreichbar
+                if function.method_of.is_none() {
+                    self.program
+                        .exports
+                        .functions
+                        .insert(function.display_name.value, function.name);
+                }
+            }
+            Token::Keyword(Keyword::Class) | Token::Keyword(Keyword::Enum) => {
+                let data_type = match next.value {
+                    Token::Keyword(Keyword::Class) => self.parse_class_def()?,
+                    _ => self.parse_enum_def()?,
+                };
+
+                if let DataType::Custom(custom_type) = data_type.value {
+                    self.program.exports.types.insert(custom_type.name);
+                }
+            }
+            Token::Keyword(Keyword::Const) | Token::Keyword(Keyword::Static) => {
+                let name = self.parse_global()?.value.name.value;
+                let internal_name = self.visible_globals[&name].clone();
+                self.program.exports.globals.insert(name, internal_name);
+            }
+            token => {
+                return Err(Spanned {
+                    value: ParserError::UnexpectedToken(token),
+                    span: next.span,
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `io::println("hi")`, `io::BUFFER_SIZE` oder `io::println` als wert
+    pub(in crate::parser) fn parse_namespace_access(
+        &mut self,
+        scope: &mut Block,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let namespace = self.parse_import_name()?;
+        self.expect_next(&[Token::Punctuation(Punctuation::Colon)])?;
+        self.expect_next(&[Token::Punctuation(Punctuation::Colon)])?;
+        let member = self.parse_import_name()?;
+
+        let span = namespace.span.extend(&member.span);
+        let exports = self.namespaces[&namespace.value].clone();
+
+        if let Some(function_name) = exports.functions.get(&member.value).cloned() {
+            let name = Spanned {
+                value: member.value,
+                span,
+            };
+
+            return match self.peek()?.value {
+                Token::Punctuation(Punctuation::OpenParen)
+                | Token::Operator(Operator::LessThan) => {
+                    self.parse_func_call_named(scope, None, name, function_name)
+                }
+                _ => self.parse_function_ref_named(name, function_name),
+            };
+        }
+
+        if let Some(internal_name) = exports.globals.get(&member.value) {
+            let mut variable = self.global_variable(internal_name);
+            variable.name.span = span;
+
+            return Ok(Spanned {
+                value: TypedExpr {
+                    _type: variable._type.clone(),
+                    expression: Expr::Variable(variable),
+                    raw: None,
+                },
+                span,
+            });
+        }
+
+        Err(Spanned {
+            value: ParserError::NotExported(member.value, namespace.value),
             span,
-        } = name
+        })
+    }
+
+    /// interner name einer funktion, die ohne namespace benutzt wird
+    pub(in crate::parser) fn resolve_function(
+        &self,
+        name: &Spanned<String>,
+    ) -> Result<String, Spanned<ParserError>> {
+        if let Some(internal_name) = self.visible_functions.get(&name.value) {
+            return Ok(internal_name.clone());
+        }
+
+        if self.is_visible_function(&name.value) {
+            return Ok(name.value.clone());
+        }
+
+        // aus einer importierten datei, aber ohne `pub` (mit `as` importiert zählt nicht)
+        let is_private = self
+            .program
+            .functions
+            .iter()
+            .filter(|(_, f)| {
+                f.value.method_of.is_none() && f.value.display_name.value == name.value
+            })
+            .any(|(key, _)| {
+                !self
+                    .namespaces
+                    .values()
+                    .any(|exports| exports.functions.values().any(|k| k == key))
+            });
+
+        Err(Spanned {
+            value: match is_private {
+                true => ParserError::ItemIsPrivate(name.value.clone()),
+                false => ParserError::FunctionDoesNotExist(name.value.clone()),
+            },
+            span: name.span,
+        })
+    }
+
+    pub(in crate::parser) fn is_visible_function(&self, name: &str) -> bool {
+        self.visible_functions.contains_key(name)
+            || self
+                .program
+                .functions
+                .get(name)
+                .is_some_and(|f| f.value.is_builtin)
+    }
+
+    /// macht die `pub` items einer importierten datei sichtbar,
+    /// muss vor `add_dependency` passieren
+    fn expose_dependency(
+        &mut self,
+        dependency: &Program,
+        kind: ImportKind,
+        file: &Spanned<String>,
+    ) -> Result<(), Spanned<ParserError>> {
+        let exports = &dependency.exports;
+        let mut types = vec![];
+
+        match kind {
+            ImportKind::All => {
+                for (name, internal_name) in exports.functions.iter() {
+                    self.expose_function(name, internal_name, file.span)?;
+                }
+                for (name, internal_name) in exports.globals.iter() {
+                    self.expose_global(name, internal_name, file.span)?;
+                }
+                types.extend(exports.types.iter().cloned());
+            }
+            ImportKind::Namespace(namespace) => {
+                if self.namespaces.contains_key(&namespace.value) {
+                    return Err(Spanned {
+                        value: ParserError::NamespaceAlreadyExists(namespace.value),
+                        span: namespace.span,
+                    });
+                }
+
+                self.namespaces.insert(namespace.value, exports.clone());
+                // klassen haben keinen namespace
+                types.extend(exports.types.iter().cloned());
+            }
+            ImportKind::Selected(names) => {
+                for name in names {
+                    if let Some(internal_name) = exports.functions.get(&name.value) {
+                        self.expose_function(&name.value, internal_name, name.span)?;
+                    } else if let Some(internal_name) = exports.globals.get(&name.value) {
+                        self.expose_global(&name.value, internal_name, name.span)?;
+                    } else if exports.types.contains(&name.value) {
+                        types.push(name.value);
+                    } else {
+                        return Err(Spanned {
+                            value: ParserError::NotExported(name.value, file.value.clone()),
+                            span: name.span,
+                        });
+                    }
+                }
+            }
+        }
+
+        for type_name in dependency.custom_types.keys() {
+            if types.contains(type_name) {
+                self.hidden_types.remove(type_name);
+            } else if !self.program.custom_types.contains_key(type_name) {
+                self.hidden_types.insert(type_name.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expose_function(
+        &mut self,
+        name: &str,
+        internal_name: &str,
+        span: Span,
+    ) -> Result<(), Spanned<ParserError>> {
+        if self
+            .visible_functions
+            .get(name)
+            .is_some_and(|visible| visible != internal_name)
+        {
+            return Err(Spanned {
+                value: ParserError::FunctionAlreadyExists(name.to_string()),
+                span,
+            });
+        }
+
+        self.visible_functions
+            .insert(name.to_string(), internal_name.to_string());
+        Ok(())
+    }
+
+    fn expose_global(
+        &mut self,
+        name: &str,
+        internal_name: &str,
+        span: Span,
+    ) -> Result<(), Spanned<ParserError>> {
+        if self
+            .visible_globals
+            .get(name)
+            .is_some_and(|visible| visible != internal_name)
         {
-            let path = self
-                .relative_path
-                .as_ref()
-                .unwrap_or(&std::env::current_dir().unwrap())
-                .join(&name);
+            return Err(Spanned {
+                value: ParserError::GlobalAlreadyExists(name.to_string()),
+                span,
+            });
+        }
+
+        self.visible_globals
+            .insert(name.to_string(), internal_name.to_string());
+        Ok(())
+    }
 
-            let program = self.parse_dependency(&path, Spanned { value: name, span })?;
-            self.add_dependency(program)?;
+    /// eindeutiger präfix für die internen namen einer datei, `std/io.mx` -> `io.3f2c81aa`.
+    /// gehasht wird der pfad relativ zur hauptdatei oder zum bibliotheks ordner, damit ir und
+    /// symbole nicht davon abhängen, wo das projekt liegt
+    fn module_prefix(&self, path: &Path) -> String {
+        let root = self
+            .root
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .and_then(|root| std::fs::canonicalize(root).ok());
+        let libraries = self
+            .library_paths
+            .iter()
+            .filter_map(|dir| std::fs::canonicalize(dir).ok());
 
-            return Ok(());
+        // `src:` und `lib0:` trennen gleiche relative pfade in verschiedenen ordnern
+        let key = root
+            .into_iter()
+            .map(|dir| ("src".to_string(), dir))
+            .chain(
+                libraries
+                    .enumerate()
+                    .map(|(idx, dir)| (format!("lib{idx}"), dir)),
+            )
+            .find_map(|(tag, dir)| {
+                let relative = path.strip_prefix(dir).ok()?;
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .join("/");
+                Some(format!("{tag}:{relative}"))
+            })
+            .unwrap_or_else(|| path.to_string_lossy().replace('\\', "/"));
+
+        let mut stem: String = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c,
+                false => '_',
+            })
+            .collect();
+
+        // llvm namen dürfen nicht mit einer zahl anfangen
+        if !stem.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            stem.insert(0, '_');
         }
 
-        unreachable!()
+        format!("{stem}.{:08x}", stable_hash(key.as_bytes()))
     }
 
     fn parse_dependency(
@@ -41,7 +447,13 @@ impl Parser {
         path: &Path,
         name: Spanned<String>,
     ) -> Result<Program, Spanned<ParserError>> {
-        let path = std::fs::canonicalize(path).unwrap();
+        let path = match std_lib::is_embedded(path) {
+            true => path.to_path_buf(),
+            false => std::fs::canonicalize(path).map_err(|_| Spanned {
+                value: ParserError::FileNotFound(name.value.clone()),
+                span: name.span,
+            })?,
+        };
         if self.program.import_queue.contains(&path.to_path_buf()) {
             return Err(Spanned {
                 value: ParserError::CircularDependency(name.value),
@@ -55,37 +467,101 @@ impl Parser {
         // println!("path: {path:?}");
         if let Some(cached) = self.program.get_cached_dependency(&path) {
             // println!("HIT: {path:?}");
+            self.pop_dependency_queue();
             return Ok(cached.clone());
         }
 
-        let code = std::fs::read_to_string(&path).map_err(|_| Spanned {
-            value: ParserError::FileNotFound(name.value),
-            span: name.span,
-        })?;
+        let code = match std_lib::embedded_file(&path) {
+            Some(code) => code.to_string(),
+            None => std::fs::read_to_string(&path).map_err(|_| Spanned {
+                value: ParserError::FileNotFound(name.value),
+                span: name.span,
+            })?,
+        };
 
-        let tokens = crate::lexer::lexer_main::lex(&code).map_err(|err| Spanned {
-            value: ParserError::UnexpectedEOF,
-            span: err.span,
-        })?;
+        let tokens = match crate::lexer::lexer_main::lex(&code) {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                self.pop_dependency_queue();
+                return Err(Spanned {
+                    value: ParserError::ImportFailed(Box::new(ImportError {
+                        file: path,
+                        source_code: code,
+                        errors: vec![Spanned {
+                            value: ImportedError::Lexer(err.value),
+                            span: err.span,
+                        }],
+                        trace: vec![],
+                    })),
+                    span: name.span,
+                });
+            }
+        };
 
         let mut parser = Parser::new(tokens, Some(&path))
+            .with_source_code(&code)
             .with_dependency_cache(self.program.dependency_cache.clone())
             .with_import_queue(self.program.import_queue.clone())
-            .with_relative_path(Some(path.parent().unwrap().to_path_buf()));
+            .with_relative_path(Some(path.parent().unwrap().to_path_buf()))
+            .with_root(self.root.clone())
+            .with_library_paths(self.library_paths.clone())
+            .with_target(self.target)
+            .with_module_prefix(self.module_prefix(&path));
 
         let program = match parser.parse() {
             Ok(program) => program,
-            Err(err) => {
-                let exit_code = err.value.id().try_into().unwrap_or(-1);
-                emit_error(
-                    path.to_str().unwrap(),
-                    &code,
-                    &Spanned {
-                        value: Box::new(err.value),
-                        span: err.span,
-                    },
-                );
-                std::process::exit(exit_code);
+            Err(errs) => {
+                self.pop_dependency_queue();
+
+                let mut errors = vec![];
+                let mut failed = vec![];
+
+                for err in errs {
+                    match err.value {
+                        // fehler aus tieferen imports, die kette wird um diesen import länger
+                        ParserError::ImportFailed(mut import) => {
+                            import.trace.insert(
+                                0,
+                                ImportTrace {
+                                    file: path.clone(),
+                                    source_code: code.clone(),
+                                    span: err.span,
+                                },
+                            );
+                            failed.push(import);
+                        }
+                        other => errors.push(Spanned {
+                            value: ImportedError::Parser(other),
+                            span: err.span,
+                        }),
+                    }
+                }
+
+                if !errors.is_empty() {
+                    failed.insert(
+                        0,
+                        Box::new(ImportError {
+                            file: path,
+                            source_code: code,
+                            errors,
+                            trace: vec![],
+                        }),
+                    );
+                }
+
+                // der letzte fehler wird zurückgegeben, die anderen direkt gesammelt
+                let last = failed.pop().expect("parser returned no errors");
+                for import in failed {
+                    self.report_error(Spanned {
+                        value: ParserError::ImportFailed(import),
+                        span: name.span,
+                    });
+                }
+
+                return Err(Spanned {
+                    value: ParserError::ImportFailed(last),
+                    span: name.span,
+                });
             }
         };
 
@@ -101,7 +577,12 @@ impl Parser {
             if let Some(function_here) = self.program.functions.get_mut(&name) {
                 // dependency reexportiert bereits vorhandene Funktion
                 // könnte man vielleicht besser lösen
-                if function_here.value.import_compare(&function.value) || function.value.is_builtin
+                // die gleiche externe funktion kann in mehreren dateien deklariert sein
+                if function_here.value.import_compare(&function.value)
+                    || function.value.is_builtin
+                    || (function_here.value.is_extern
+                        && function.value.is_extern
+                        && function_here.value.same_signature(&function.value))
                 {
                     for (specifics, subtype) in function.value.generic_subtypes {
                         if let Some(subtype_here) =
@@ -133,6 +614,20 @@ impl Parser {
                 });
             }
 
+            // zwei `#[export]` funktionen mit dem gleichen symbol
+            let symbol = &function.value.display_name.value;
+            if function.value.export
+                && self.program.functions.values().any(|f| {
+                    f.value.name == *symbol
+                        || (f.value.export && f.value.display_name.value == *symbol)
+                })
+            {
+                return Err(Spanned {
+                    value: ParserError::FunctionAlreadyExists(symbol.clone()),
+                    span: function.span,
+                });
+            }
+
             self.program.functions.insert(name, function);
         }
 
@@ -151,6 +646,37 @@ impl Parser {
             self.program.custom_types.insert(name, class);
         }
 
+        for (name, trait_decl) in dependency.traits {
+            if let Some(trait_here) = self.program.traits.get(&name) {
+                // prelude oder bereits importiert
+                if trait_here.value == trait_decl.value {
+                    continue;
+                }
+
+                return Err(Spanned {
+                    value: ParserError::TraitAlreadyExists(name),
+                    span: trait_decl.span,
+                });
+            }
+
+            self.program.traits.insert(name, trait_decl);
+        }
+
+        for (name, global) in dependency.globals {
+            if let Some(global_here) = self.program.globals.get(&name) {
+                if global_here.value == global.value {
+                    continue;
+                }
+
+                return Err(Spanned {
+                    value: ParserError::GlobalAlreadyExists(name),
+                    span: global.span,
+                });
+            }
+
+            self.program.globals.insert(name, global);
+        }
+
         for (data_type, data_type_info) in dependency.data_types {
             self.program.data_types.insert(data_type, data_type_info);
         }
@@ -158,3 +684,10 @@ impl Parser {
         Ok(())
     }
 }
+
+/// fnv-1a, anders als `DefaultHasher` auf jeder rust version gleich
+fn stable_hash(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x01000193)
+    })
+}
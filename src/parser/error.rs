@@ -1,6 +1,12 @@
+use std::path::PathBuf;
+
 use crate::{
     error::CompilerError,
-    lexer::tokens::{Operator, Token},
+    lexer::{
+        error::LexerError,
+        position::{Span, Spanned},
+        tokens::{Keyword, Operator, Token},
+    },
 };
 use thiserror::Error;
 
@@ -99,6 +105,115 @@ pub enum ParserError {
     WrongGenericParamCount(usize, usize),
     #[error("variable can not have type \"void\"")]
     VoidVariable,
+    #[error("type \"{0}\" can not be iterated over")]
+    NotIterable(DataType),
+    /// ``keyword``
+    #[error("\"{0}\" is only allowed inside a loop")]
+    OutsideOfLoop(Keyword),
+    /// ``variant name`` ``enum name``
+    #[error("variant \"{0}\" does not exist for enum \"{1}\"")]
+    VariantDoesNotExist(String, String),
+    #[error("variant with name \"{0}\" already exists")]
+    VariantAlreadyExists(String),
+    #[error("type \"{0}\" is not an enum")]
+    NotAnEnum(DataType),
+    /// ``missing variants``
+    #[error("match is not exhaustive, missing: {0:?}")]
+    NonExhaustiveMatch(Vec<String>),
+    /// ``pattern``, schon von einem arm davor oder `_` abgedeckt
+    #[error("match arm \"{0}\" is unreachable")]
+    UnreachableMatchArm(String),
+    #[error("trait \"{0}\" does not exist")]
+    TraitDoesNotExist(String),
+    #[error("trait with name \"{0}\" already exists")]
+    TraitAlreadyExists(String),
+    /// ``method name`` ``trait name``
+    #[error("method \"{0}\" is not a member of trait \"{1}\"")]
+    MethodNotInTrait(String, String),
+    /// ``trait name`` ``missing methods``
+    #[error("not all methods of trait \"{0}\" are implemented, missing: {1:?}")]
+    MissingTraitMethods(String, Vec<String>),
+    /// ``trait name`` ``type``
+    #[error("type \"{1}\" does not implement trait \"{0}\"")]
+    TraitNotImplemented(String, DataType),
+    #[error("could not compile imported file \"{}\"", .0.file.display())]
+    ImportFailed(Box<ImportError>),
+    /// ``function name``
+    #[error("generic function \"{0}\" can not be used as a value, wrap it in a closure")]
+    GenericFunctionAsValue(String),
+    #[error("expression can not be evaluated at compile time")]
+    NotConstant,
+    #[error("global with name \"{0}\" already exists")]
+    GlobalAlreadyExists(String),
+    #[error("\"{0}\" is private to its module")]
+    ItemIsPrivate(String),
+    #[error("module \"{1}\" has no public item \"{0}\"")]
+    NotExported(String, String),
+    #[error("namespace \"{0}\" already exists")]
+    NamespaceAlreadyExists(String),
+    #[error("main must be `def main() -> int64` or `def main(args: List<String>) -> int64`")]
+    InvalidMainSignature,
+    #[error("unknown attribute \"{0}\"")]
+    UnknownAttribute(String),
+    /// ``key`` ``value``
+    #[error("unknown cfg option {0} = \"{1}\"")]
+    UnknownCfgOption(String, String),
+    #[error("attribute \"{0}\" is not allowed on this item")]
+    AttributeNotAllowed(String),
+    #[error("only extern functions can take variadic arguments")]
+    VariadicNotExtern,
+    #[error("\"...\" has to be the last parameter")]
+    VariadicNotLast,
+    #[error("type {0} can not be passed as a variadic argument")]
+    InvalidVariadicArg(DataType),
+}
+
+/// fehler in einer importierten datei
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    /// die datei, in der die fehler aufgetreten sind
+    pub file: PathBuf,
+    pub source_code: String,
+    pub errors: Vec<Spanned<ImportedError>>,
+    /// die `import` statements zwischen der datei mit dem fehler und der
+    /// ursprünglichen datei (ohne diese), äußerstes zuerst
+    pub trace: Vec<ImportTrace>,
+}
+
+/// ein `import "..."` in `file`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportTrace {
+    pub file: PathBuf,
+    pub source_code: String,
+    pub span: Span,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ImportedError {
+    #[error("{0}")]
+    Lexer(LexerError),
+    #[error("{0}")]
+    Parser(ParserError),
+}
+
+impl CompilerError for ImportedError {
+    fn id(&self) -> u32 {
+        match self {
+            ImportedError::Lexer(err) => err.id(),
+            ImportedError::Parser(err) => err.id(),
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            ImportedError::Lexer(err) => err.name(),
+            ImportedError::Parser(err) => err.name(),
+        }
+    }
+
+    fn err_msg(&self) -> String {
+        self.to_string()
+    }
 }
 
 impl CompilerError for ParserError {
@@ -143,6 +258,32 @@ impl CompilerError for ParserError {
             ParserError::CircularDependency(_) => 38,
             ParserError::WrongGenericParamCount(_, _) => 39,
             ParserError::VoidVariable => 40,
+            ParserError::NotIterable(_) => 41,
+            ParserError::OutsideOfLoop(_) => 42,
+            ParserError::VariantDoesNotExist(_, _) => 43,
+            ParserError::VariantAlreadyExists(_) => 44,
+            ParserError::NotAnEnum(_) => 45,
+            ParserError::NonExhaustiveMatch(_) => 46,
+            ParserError::TraitDoesNotExist(_) => 47,
+            ParserError::TraitAlreadyExists(_) => 48,
+            ParserError::MethodNotInTrait(_, _) => 49,
+            ParserError::MissingTraitMethods(_, _) => 50,
+            ParserError::TraitNotImplemented(_, _) => 51,
+            ParserError::ImportFailed(_) => 52,
+            ParserError::GenericFunctionAsValue(_) => 53,
+            ParserError::NotConstant => 54,
+            ParserError::GlobalAlreadyExists(_) => 55,
+            ParserError::ItemIsPrivate(_) => 56,
+            ParserError::NotExported(..) => 57,
+            ParserError::NamespaceAlreadyExists(_) => 58,
+            ParserError::InvalidMainSignature => 59,
+            ParserError::UnknownAttribute(_) => 60,
+            ParserError::UnknownCfgOption(..) => 61,
+            ParserError::AttributeNotAllowed(_) => 62,
+            ParserError::VariadicNotExtern => 63,
+            ParserError::VariadicNotLast => 64,
+            ParserError::InvalidVariadicArg(_) => 65,
+            ParserError::UnreachableMatchArm(_) => 66,
         }
     }
 
@@ -189,10 +330,43 @@ impl CompilerError for ParserError {
             ParserError::CircularDependency(_) => "circular dependency",
             ParserError::WrongGenericParamCount(_, _) => "wrong generic parameter count",
             ParserError::VoidVariable => "variable can not have type void",
+            ParserError::NotIterable(_) => "type is not iterable",
+            ParserError::OutsideOfLoop(_) => "outside of loop",
+            ParserError::VariantDoesNotExist(_, _) => "unknown enum variant",
+            ParserError::VariantAlreadyExists(_) => "variant name already used",
+            ParserError::NotAnEnum(_) => "not an enum",
+            ParserError::NonExhaustiveMatch(_) => "non exhaustive match",
+            ParserError::TraitDoesNotExist(_) => "unknown trait",
+            ParserError::TraitAlreadyExists(_) => "trait name already used",
+            ParserError::MethodNotInTrait(_, _) => "method is not part of the trait",
+            ParserError::MissingTraitMethods(_, _) => "missing trait methods",
+            ParserError::TraitNotImplemented(_, _) => "trait not implemented",
+            ParserError::ImportFailed(_) => "error in imported file",
+            ParserError::GenericFunctionAsValue(_) => "generic function used as value",
+            ParserError::NotConstant => "not a constant expression",
+            ParserError::GlobalAlreadyExists(_) => "global name already used",
+            ParserError::ItemIsPrivate(_) => "private item",
+            ParserError::NotExported(..) => "item not exported",
+            ParserError::NamespaceAlreadyExists(_) => "namespace already used",
+            ParserError::InvalidMainSignature => "invalid main function",
+            ParserError::UnknownAttribute(_) => "unknown attribute",
+            ParserError::UnknownCfgOption(..) => "unknown cfg option",
+            ParserError::AttributeNotAllowed(_) => "attribute not allowed here",
+            ParserError::VariadicNotExtern => "variadic function is not extern",
+            ParserError::VariadicNotLast => "variadic parameter is not last",
+            ParserError::InvalidVariadicArg(_) => "invalid variadic argument",
+            ParserError::UnreachableMatchArm(_) => "unreachable match arm",
         }
     }
 
     fn err_msg(&self) -> String {
         self.to_string()
     }
+
+    fn import_error(&self) -> Option<&ImportError> {
+        match self {
+            ParserError::ImportFailed(import) => Some(import),
+            _ => None,
+        }
+    }
 }
@@ -1,7 +1,10 @@
 pub mod ast;
 pub mod builtins;
+pub mod cfg;
 pub mod dependencies;
 pub mod error;
 pub mod parser_main;
+pub mod std_lib;
 pub mod structures;
 pub mod utils;
+pub mod warnings;
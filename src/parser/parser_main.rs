@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -7,20 +8,27 @@ use std::sync::Arc;
 use std::sync::RwLock;
 
 use itertools::peek_nth;
-use itertools::Itertools;
 use itertools::PeekNth;
 use std::vec::IntoIter;
+use strum::IntoEnumIterator;
 
+use crate::lexer::lexer_main::lex;
 use crate::lexer::position::Span;
 use crate::lexer::tokens::Literal;
 use crate::lexer::tokens::Operator;
+use crate::lexer::tokens::ReassignmentOperator;
 use crate::lexer::tokens::{Keyword, Punctuation};
 use crate::lexer::{position::Spanned, tokens::Token};
 use crate::parser::ast::DataType;
 
 use super::ast::CommonGeneric;
+use super::builtins::PRELUDE;
+use super::cfg::is_item_start;
+use super::cfg::ItemAttributes;
+use super::cfg::Target;
 
 use super::ast::DataTypeInfo;
+use super::ast::Exports;
 
 use super::ast::Trait;
 use super::ast::{Block, Function, Program};
@@ -35,16 +43,59 @@ pub struct Parser {
     pub program: Program,
     pub count: u32,
     pub relative_path: Option<PathBuf>,
+    /// ordner der hauptdatei, auch in importierten dateien
+    pub root: Option<PathBuf>,
+    /// weitere ordner in denen imports gesucht werden (`-I` und `MX_PATH`)
+    pub library_paths: Vec<PathBuf>,
+    /// alle fehler, nach denen der parser weiter gemacht hat
+    pub errors: Vec<Spanned<ParserError>>,
+    /// anzahl der offenen `{`, wird zum wiederaufsetzen nach fehlern gebraucht
+    pub brace_depth: usize,
+    /// ob das letzte token ein `;` war
+    pub after_semicolon: bool,
+    /// präfix für die internen namen dieser datei, `None` bei der hauptdatei
+    pub module_prefix: Option<String>,
+    /// ohne namespace sichtbare funktionen, name im code -> interner name
+    pub visible_functions: HashMap<String, String>,
+    /// ohne namespace sichtbare globale, name im code -> interner name
+    pub visible_globals: HashMap<String, String>,
+    /// `import "foo.mx" as foo`
+    pub namespaces: HashMap<String, Exports>,
+    /// für `#[cfg(target_os = "...")]`
+    pub target: Target,
+    /// die attribute vor dem item, das gerade geparst wird
+    pub item_attributes: ItemAttributes,
+    /// importierte klassen ohne `pub`
+    pub hidden_types: HashSet<String>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Spanned<Token>>, path: Option<&Path>) -> Self {
-        Parser {
-            tokens: peek_nth(tokens),
+        let prelude = lex(PRELUDE).expect("prelude should always lex");
+
+        let mut parser = Parser {
+            tokens: peek_nth(prelude),
             program: Program::default(),
             count: 0,
             relative_path: path.map(|p| p.to_path_buf()),
-        }
+            root: path.map(|p| p.to_path_buf()),
+            library_paths: vec![],
+            errors: vec![],
+            brace_depth: 0,
+            after_semicolon: false,
+            module_prefix: None,
+            visible_functions: HashMap::new(),
+            visible_globals: HashMap::new(),
+            namespaces: HashMap::new(),
+            hidden_types: HashSet::new(),
+            target: Target::host(),
+            item_attributes: ItemAttributes::default(),
+        };
+
+        parser.parse().expect("prelude should always parse");
+        parser.tokens = peek_nth(tokens);
+        parser.brace_depth = 0;
+        parser
     }
 
     pub fn with_source_code(mut self, source_code: &str) -> Self {
@@ -75,6 +126,26 @@ impl Parser {
         self
     }
 
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = root;
+        self
+    }
+
+    pub fn with_library_paths(mut self, library_paths: Vec<PathBuf>) -> Self {
+        self.library_paths = library_paths;
+        self
+    }
+
+    pub fn with_target(mut self, target: Target) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn with_module_prefix(mut self, prefix: String) -> Self {
+        self.module_prefix = Some(prefix);
+        self
+    }
+
     pub fn add_dependency_to_queue(&mut self, path: PathBuf) {
         self.program.add_dependency_to_queue(path)
     }
@@ -95,38 +166,112 @@ impl Parser {
 
     // }
 
-    pub fn parse(&mut self) -> Result<Program, Spanned<ParserError>> {
+    pub fn parse(&mut self) -> Result<Program, Vec<Spanned<ParserError>>> {
         while let Ok(Spanned { value, span }) = self.peek() {
-            match value {
+            let result = match value {
                 Token::Keyword(Keyword::Def) | Token::Keyword(Keyword::Extern) => {
-                    self.parse_func_def()?;
+                    self.parse_func_def().map(|_| ())
                 }
-                Token::Keyword(Keyword::Class) => {
-                    self.parse_class_def()?;
+                Token::Keyword(Keyword::Class) => self.parse_class_def().map(|_| ()),
+                Token::Keyword(Keyword::Enum) => self.parse_enum_def().map(|_| ()),
+                Token::Keyword(Keyword::Trait) => self.parse_trait_def().map(|_| ()),
+                Token::Keyword(Keyword::Impl) => self.parse_impl(),
+                Token::Keyword(Keyword::Import) | Token::Keyword(Keyword::From) => {
+                    self.parse_import().map(|_| ())
                 }
-                Token::Keyword(Keyword::Import) => {
-                    self.parse_import()?;
+                Token::Keyword(Keyword::Pub) => self.parse_pub_item(),
+                Token::Keyword(Keyword::Const) | Token::Keyword(Keyword::Static) => {
+                    self.parse_global().map(|_| ())
                 }
+                Token::Punctuation(Punctuation::AttributeStart) => self.parse_attributes(),
 
-                _ => {
-                    return Err(Spanned {
-                        value: ParserError::UnexpectedToken(value),
-                        span,
-                    })
-                }
+                _ => Err(Spanned {
+                    value: ParserError::UnexpectedToken(value),
+                    span,
+                }),
             };
+
+            if let Err(err) = result {
+                self.report_error(err);
+                self.recover_item();
+            }
         }
 
-        if !self.program.functions.contains_key("main") && self.program.require_main {
-            return Err(Spanned {
+        if self.program.require_main && self.errors.is_empty() {
+            if let Some(main) = self.program.functions.get("main").cloned() {
+                if let Err(err) = self.check_main(main) {
+                    self.report_error(err);
+                }
+            }
+        }
+
+        if !self.program.functions.contains_key("main")
+            && self.program.require_main
+            && self.errors.is_empty()
+        {
+            self.errors.push(Spanned {
                 value: ParserError::NoMainFunction,
                 span: Span::default(),
             });
         }
 
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
         Ok(self.program.clone())
     }
 
+    /// fehler merken, ein UnexpectedEOF nach anderen fehlern ist meistens nur eine folge davon
+    pub(in crate::parser) fn report_error(&mut self, err: Spanned<ParserError>) {
+        if matches!(err.value, ParserError::UnexpectedEOF) && !self.errors.is_empty() {
+            return;
+        }
+        self.errors.push(err);
+    }
+
+    /// überspringt tokens bis zum nächsten item (`def`, `class`, ...) außerhalb von `{}`
+    fn recover_item(&mut self) {
+        while let Ok(token) = self.peek() {
+            if is_item_start(&token.value) && self.brace_depth == 0 {
+                break;
+            }
+
+            let _ = self.next_token();
+        }
+    }
+
+    /// überspringt tokens bis nach dem nächsten `;` oder bis vor das `}` des blocks,
+    /// `block_depth` ist die tiefe innerhalb des blocks
+    fn recover_statement(&mut self, block_depth: usize) {
+        // der fehler kam erst nach dem `;`
+        if self.after_semicolon && self.brace_depth == block_depth {
+            return;
+        }
+
+        while let Ok(token) = self.peek() {
+            if self.brace_depth <= block_depth {
+                match token.value {
+                    Token::Punctuation(Punctuation::SemiColon) => {
+                        let _ = self.next_token();
+                        break;
+                    }
+                    Token::Punctuation(Punctuation::CloseBrace) => break,
+                    _ => {}
+                }
+            }
+
+            let _ = self.next_token();
+
+            // z.B. `if ... { }` ist ohne `;` zu ende
+            if token.value == Token::Punctuation(Punctuation::CloseBrace)
+                && self.brace_depth == block_depth
+            {
+                break;
+            }
+        }
+    }
+
     pub fn get_count(&mut self) -> u32 {
         self.count += 1;
         self.count
@@ -162,6 +307,22 @@ impl Parser {
     pub fn parse_block(
         &mut self,
         scope: &mut Block,
+    ) -> Result<Spanned<Block>, Spanned<ParserError>> {
+        self.parse_block_with(scope, false)
+    }
+
+    /// body einer closure oder `{ ... }` als ausdruck, `{ x + 1 }` ohne `;` gibt `x + 1` zurück
+    pub fn parse_value_block(
+        &mut self,
+        scope: &mut Block,
+    ) -> Result<Spanned<Block>, Spanned<ParserError>> {
+        self.parse_block_with(scope, true)
+    }
+
+    fn parse_block_with(
+        &mut self,
+        scope: &mut Block,
+        is_value: bool,
     ) -> Result<Spanned<Block>, Spanned<ParserError>> {
         let start = self
             .expect_next(&[Token::Punctuation(Punctuation::OpenBrace)])?
@@ -172,30 +333,41 @@ impl Parser {
                 statements: vec![],
                 return_type: DataType::None,
                 variables: scope.variables.clone(),
-                closure_params: scope
-                    .variables
-                    .values()
-                    .map(|v| Spanned {
-                        value: v.value.clone().into(),
-                        span: v.span,
-                    })
-                    .collect_vec(),
                 generics: scope.generics.clone(),
+                generic_bounds: scope.generic_bounds.clone(),
                 function_depth: scope.function_depth,
+                in_loop: scope.in_loop,
+                is_value,
             },
             span: start,
         };
 
+        let block_depth = self.brace_depth;
+        let errors_before = self.errors.len();
+
         let end =
             self.walk_to_terminator(Token::Punctuation(Punctuation::CloseBrace), |parser| {
-                let statement = parser.parse_statement(&mut block.value)?;
-                block.value.statements.push(statement);
+                // das `;` des letzten statements gehört nicht zu diesem
+                parser.after_semicolon = false;
+                match parser.parse_statement(&mut block.value) {
+                    Ok(statement) => block.value.statements.push(statement),
+                    Err(err) if !matches!(err.value, ParserError::UnexpectedEOF) => {
+                        parser.report_error(err);
+                        parser.recover_statement(block_depth);
+                    }
+                    Err(err) => return Err(err),
+                }
                 Ok(())
             })?;
 
         block.span = block.span.extend(&end);
 
-        block.value.return_type = validate_block_return(&block)?.unwrap_or_default();
+        // nach fehlern im block ist der return type nicht mehr aussagekräftig
+        block.value.return_type = match validate_block_return(&block) {
+            Ok(return_type) => return_type.unwrap_or_default(),
+            Err(_) if self.errors.len() > errors_before => DataType::None,
+            Err(err) => return Err(err),
+        };
 
         Ok(block)
     }
@@ -212,27 +384,52 @@ impl Parser {
             Token::Keyword(Keyword::Return) => self.parse_return(scope)?,
             Token::Keyword(Keyword::If) => self.parse_if(scope)?,
             Token::Keyword(Keyword::While) => self.parse_while(scope)?,
+            Token::Keyword(Keyword::For) => self.parse_for(scope)?,
+            Token::Keyword(Keyword::Break) | Token::Keyword(Keyword::Continue) => {
+                self.parse_loop_control(scope)?
+            }
             _ => {
                 let is_reassignment = self
-                    .find_ahead(vec![Token::Assignment], |t| {
-                        matches!(
-                            t,
-                            Token::Punctuation(Punctuation::SemiColon)
-                                | Token::Punctuation(Punctuation::OpenBrace)
-                        )
-                    })?
+                    .find_ahead(
+                        std::iter::once(Token::Assignment)
+                            .chain(ReassignmentOperator::iter().map(Token::ReassignmentOperator))
+                            .collect(),
+                        |t| {
+                            matches!(
+                                t,
+                                Token::Punctuation(Punctuation::SemiColon)
+                                    | Token::Punctuation(Punctuation::OpenBrace)
+                                    // `{ f() }` ohne `;`, das `=` danach gehört nicht dazu
+                                    | Token::Punctuation(Punctuation::CloseBrace)
+                            )
+                        },
+                    )?
                     .is_some();
 
                 if is_reassignment {
                     self.parse_variable_reassignment(scope)?
                 } else {
                     let expression = self.parse_expression(scope)?;
-                    Spanned {
-                        value: Statement::Expr(Spanned {
-                            value: expression.value.expression,
-                            span: expression.span,
-                        }),
-                        span: expression.span,
+                    let span = expression.span;
+
+                    // `{ x + 1 }`, der letzte ausdruck ohne `;` ist der wert des blocks,
+                    // in `if`, `while` und funktionen wird er wie mit `;` verworfen
+                    if scope.is_value
+                        && expression.value._type != DataType::None
+                        && self.peek()?.value == Token::Punctuation(Punctuation::CloseBrace)
+                    {
+                        Spanned {
+                            value: Statement::Return { value: expression },
+                            span,
+                        }
+                    } else {
+                        Spanned {
+                            value: Statement::Expr(Spanned {
+                                value: expression.value.expression,
+                                span,
+                            }),
+                            span,
+                        }
                     }
                 }
 
@@ -281,7 +478,11 @@ impl Parser {
               // })
         };
 
-        if let Token::Keyword(Keyword::If) | Token::Keyword(Keyword::While) = next.value {
+        if let Token::Keyword(Keyword::If)
+        | Token::Keyword(Keyword::While)
+        | Token::Keyword(Keyword::For)
+        | Token::Keyword(Keyword::Match) = next.value
+        {
             requires_semicolon = false;
         }
 
@@ -318,6 +519,13 @@ impl Parser {
             }
 
             Token::Identifier(data_type_name) => {
+                if self.hidden_types.contains(data_type_name) {
+                    return Err(Spanned {
+                        value: ParserError::ItemIsPrivate(data_type_name.clone()),
+                        span: next.span,
+                    });
+                }
+
                 if let Some(ref mut class) = self.program.custom_types.get(data_type_name).cloned()
                 {
                     if let DataType::Custom(custom_type) = &class.value {
@@ -351,7 +559,7 @@ impl Parser {
                             // panic!("hg");
 
                             *class = Spanned {
-                                value: DataType::Custom(subtype),
+                                value: DataType::Custom(Box::new(subtype)),
                                 span: class.span,
                             }
                         }
@@ -386,6 +594,39 @@ impl Parser {
                 })
             }
 
+            // `|int64, int64| -> bool`, `||` ist ein eigenes token
+            Token::Punctuation(Punctuation::Pipe) | Token::Operator(Operator::Or) => {
+                let mut params = vec![];
+                let mut span = next.span;
+
+                if next.value == Token::Punctuation(Punctuation::Pipe) {
+                    span = span.extend(&self.walk_separated_values(
+                        Token::Punctuation(Punctuation::Comma),
+                        Token::Punctuation(Punctuation::Pipe),
+                        |parser| {
+                            params.push(parser.parse_data_type(generics)?.value);
+                            Ok(())
+                        },
+                    )?);
+                }
+
+                let mut return_type = DataType::None;
+                if self.peek()?.value == Token::Punctuation(Punctuation::ThinArrow) {
+                    self.next_token()?;
+                    let _type = self.parse_data_type(generics)?;
+                    span = span.extend(&_type.span);
+                    return_type = _type.value;
+                }
+
+                Ok(Spanned {
+                    value: DataType::Function {
+                        params,
+                        return_type: Box::new(return_type),
+                    },
+                    span,
+                })
+            }
+
             Token::Punctuation(Punctuation::OpenBracket) => {
                 let value_type = self.parse_data_type(generics)?;
                 self.expect_next(&[Token::Punctuation(Punctuation::SemiColon)])?;
@@ -488,7 +729,7 @@ impl Parser {
         _trait: &Trait,
         params: &[DataType],
     ) -> bool {
-        self.get_type_info(_type).implements_trait(_trait, params)
+        self.program.implements_trait(_type, _trait, params)
     }
 
     pub(super) fn get_function(
@@ -506,7 +747,17 @@ impl Parser {
 
     pub(super) fn next_token(&mut self) -> Result<Spanned<Token>, Spanned<ParserError>> {
         match self.tokens.next() {
-            Some(token) => Ok(token),
+            Some(token) => {
+                self.after_semicolon = token.value == Token::Punctuation(Punctuation::SemiColon);
+                match token.value {
+                    Token::Punctuation(Punctuation::OpenBrace) => self.brace_depth += 1,
+                    Token::Punctuation(Punctuation::CloseBrace) => {
+                        self.brace_depth = self.brace_depth.saturating_sub(1)
+                    }
+                    _ => {}
+                }
+                Ok(token)
+            }
             None => Err(Spanned {
                 value: ParserError::UnexpectedEOF,
                 span: Span::default(),
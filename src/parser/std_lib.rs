@@ -0,0 +1,78 @@
+use std::path::{Component, Path, PathBuf};
+
+/// ordner unter dem die eingebettete std liegt, existiert nicht auf der festplatte
+pub const EMBEDDED_ROOT: &str = "<embedded>";
+
+/// `example/std`, mit `include_str!` in den compiler eingebaut,
+/// damit `import "std/io.mx"` ohne kopie der std (und im browser) funktioniert
+#[cfg(feature = "embedded-std")]
+const STD_FILES: &[(&str, &str)] = &[
+    ("std/io.mx", include_str!("../../example/std/io.mx")),
+    ("std/env.mx", include_str!("../../example/std/env.mx")),
+    ("std/fs.mx", include_str!("../../example/std/fs.mx")),
+    ("std/mem.mx", include_str!("../../example/std/mem.mx")),
+    ("std/rand.mx", include_str!("../../example/std/rand.mx")),
+    ("std/string.mx", include_str!("../../example/std/string.mx")),
+    ("std/time.mx", include_str!("../../example/std/time.mx")),
+    (
+        "std/collections/list.mx",
+        include_str!("../../example/std/collections/list.mx"),
+    ),
+    (
+        "std/net/common.mx",
+        include_str!("../../example/std/net/common.mx"),
+    ),
+    (
+        "std/net/posix/sys.mx",
+        include_str!("../../example/std/net/posix/sys.mx"),
+    ),
+    (
+        "std/net/posix/tcp.mx",
+        include_str!("../../example/std/net/posix/tcp.mx"),
+    ),
+    (
+        "std/net/posix/udp.mx",
+        include_str!("../../example/std/net/posix/udp.mx"),
+    ),
+    (
+        "std/net/win/udp.mx",
+        include_str!("../../example/std/net/win/udp.mx"),
+    ),
+    (
+        "std/net/win/winstructs.mx",
+        include_str!("../../example/std/net/win/winstructs.mx"),
+    ),
+];
+
+#[cfg(not(feature = "embedded-std"))]
+const STD_FILES: &[(&str, &str)] = &[];
+
+pub fn is_embedded(path: &Path) -> bool {
+    path.starts_with(EMBEDDED_ROOT)
+}
+
+/// der inhalt einer eingebetteten datei, `path` beginnt mit [`EMBEDDED_ROOT`]
+pub fn embedded_file(path: &Path) -> Option<&'static str> {
+    let path = normalize(path);
+    let path = path.strip_prefix(EMBEDDED_ROOT).ok()?;
+
+    STD_FILES
+        .iter()
+        .find(|(name, _)| Path::new(name) == path)
+        .map(|(_, code)| *code)
+}
+
+/// löst `.` und `..` auf ohne das dateisystem zu fragen (`canonicalize` geht hier nicht)
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
@@ -89,8 +89,10 @@ impl Parser {
         // panic!("type_info: {:?}", type_info);
         // sollte nicht panicen, siehe check oben
         let result_type = {
-            let result_type = type_info
+            let result_type = self
+                .program
                 .get_trait_return_type(
+                    &base.value._type,
                     &Trait::Index,
                     &[base.value._type.clone(), idx.value._type.clone()],
                 )
@@ -9,7 +9,7 @@ use crate::{
     parser::{
         ast::{
             Block, ClassLiteral, CommonGeneric, CustomDataType, DataType, DataTypeGetter,
-            DataTypeInfo, Expr, FunctionParam, InternalNameGetter, TypedExpr,
+            DataTypeInfo, Expr, FunctionParam, GenericBounds, InternalNameGetter, TypedExpr,
         },
         error::ParserError,
         parser_main::Parser,
@@ -19,6 +19,7 @@ use crate::{
 impl Parser {
     pub fn parse_class_def(&mut self) -> Result<Spanned<DataType>, Spanned<ParserError>> {
         self.next_token()?; // "class"
+        let attributes = std::mem::take(&mut self.item_attributes);
         let mut generics = vec![];
         let mut is_generic = false;
 
@@ -29,7 +30,7 @@ impl Parser {
         {
             if let Token::Operator(Operator::LessThan) = self.peek()?.value {
                 is_generic = true;
-                generics = self.parse_generics()?.value;
+                generics = self.parse_generics(None)?.value;
             }
 
             let fields = self.parse_class_fields(&generics)?;
@@ -43,7 +44,7 @@ impl Parser {
                 });
             }
 
-            let class = DataType::Custom(CustomDataType {
+            let class = DataType::Custom(Box::new(CustomDataType {
                 display_name: name.clone(),
                 name: name.clone(),
                 fields,
@@ -51,8 +52,10 @@ impl Parser {
                 subtypes: HashMap::new(),
                 generics,
                 subtype_of: None,
+                variants: None,
                 is_generic,
-            });
+                is_packed: attributes.packed,
+            }));
 
             self.program.custom_types.insert(
                 name,
@@ -159,11 +162,19 @@ impl Parser {
 
             let mut class = self
                 .get_type_from_name(&Spanned {
-                    value: class_name,
+                    value: class_name.clone(),
                     span,
                 })?
                 .clone();
 
+            // enums werden über ihre varianten erstellt
+            if matches!(&class.value, DataType::Custom(custom_type) if custom_type.is_enum()) {
+                return Err(Spanned {
+                    value: ParserError::ClassDoesNotExist(class_name),
+                    span,
+                });
+            }
+
             // panic!("{:?}", class.value._type());
 
             let mut fields: Vec<(Spanned<String>, Spanned<TypedExpr>)> = vec![];
@@ -240,7 +251,7 @@ impl Parser {
                     };
                     // panic!("{}", subtype);
                     class = Spanned {
-                        value: DataType::Custom(subtype),
+                        value: DataType::Custom(Box::new(subtype)),
                         span: class.span,
                     }
                 }
@@ -250,10 +261,10 @@ impl Parser {
 
             span = span.extend(&end);
 
-            if let DataType::Custom(CustomDataType {
+            if let Some(CustomDataType {
                 fields: defined_fields,
                 ..
-            }) = &class.value
+            }) = class.value.as_custom()
             {
                 for (param, arg) in defined_fields.value.iter().zip(fields.iter()) {
                     if param.name.value != arg.0.value {
@@ -321,11 +332,12 @@ impl Parser {
     ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
         self.expect_next(&[Token::Punctuation(Punctuation::Period)])?;
         let class = base.value._type.clone();
-        if let DataType::Custom(CustomDataType {
+        if let Some(CustomDataType {
             display_name: name,
             fields,
+            variants: None,
             ..
-        }) = &class
+        }) = class.as_custom()
         {
             if let Spanned {
                 value: Token::Identifier(field_name),
@@ -367,8 +379,10 @@ impl Parser {
         })
     }
 
+    /// `bounds` ist nur bei funktionen erlaubt, `<T: Show + Equal>`
     pub(in crate::parser) fn parse_generics(
         &mut self,
+        mut bounds: Option<&mut GenericBounds>,
     ) -> Result<Spanned<Vec<Spanned<DataType>>>, Spanned<ParserError>> {
         let mut span = self.next_token()?.span; // skip "<"
         let mut generics = vec![];
@@ -382,6 +396,42 @@ impl Parser {
                     span: generic_span,
                 } = parser.expect_next(&[Token::Identifier("generic name".to_string())])?
                 {
+                    if let Token::Punctuation(Punctuation::Colon) = parser.peek()?.value {
+                        let colon = parser.next_token()?;
+                        let bounds = match bounds.as_deref_mut() {
+                            Some(bounds) => bounds.entry(generic_name.clone()).or_default(),
+                            None => {
+                                return Err(Spanned {
+                                    value: ParserError::UnexpectedToken(colon.value),
+                                    span: colon.span,
+                                })
+                            }
+                        };
+
+                        loop {
+                            if let Spanned {
+                                value: Token::Identifier(trait_name),
+                                span: trait_span,
+                            } = parser
+                                .expect_next(&[Token::Identifier("trait name".to_string())])?
+                            {
+                                if !parser.program.traits.contains_key(&trait_name) {
+                                    return Err(Spanned {
+                                        value: ParserError::TraitDoesNotExist(trait_name),
+                                        span: trait_span,
+                                    });
+                                }
+
+                                bounds.push(trait_name);
+                            }
+
+                            match parser.peek()?.value {
+                                Token::Operator(Operator::Add) => parser.next_token()?,
+                                _ => break,
+                            };
+                        }
+                    }
+
                     let generic = Spanned {
                         value: DataType::Generic(generic_name),
                         span: generic_span,
@@ -0,0 +1,503 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    lexer::{
+        position::{Span, Spanned},
+        tokens::{Literal, Punctuation, Token},
+    },
+    parser::{
+        ast::{
+            Block, CallArg, Capture, Closure, CommonGeneric, DataType, Expr, ForIterable,
+            FunctionParam, Statement, TypedExpr,
+        },
+        error::ParserError,
+        parser_main::Parser,
+    },
+};
+
+impl Parser {
+    /// `|x: int64, y: int64| -> int64 { x + y }` oder `|| { ... }`
+    pub(in crate::parser) fn parse_closure(
+        &mut self,
+        scope: &mut Block,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let start = self.next_token()?;
+        let mut params: Vec<Spanned<FunctionParam>> = vec![];
+        let mut params_span = start.span;
+
+        // `||` ist ein eigenes token
+        if start.value == Token::Punctuation(Punctuation::Pipe) {
+            params_span = params_span.extend(&self.walk_separated_values(
+                Token::Punctuation(Punctuation::Comma),
+                Token::Punctuation(Punctuation::Pipe),
+                |parser| {
+                    if let Spanned {
+                        value: Token::Identifier(name),
+                        span,
+                    } = parser.expect_next(&[Token::Identifier("parameter name".to_string())])?
+                    {
+                        parser.expect_next(&[Token::Punctuation(Punctuation::Colon)])?;
+                        let _type = parser.parse_data_type(Some(&scope.generics))?;
+
+                        if params.iter().any(|p| p.value.name.value == name) {
+                            return Err(Spanned {
+                                value: ParserError::ParamNameAlreadyExists(name),
+                                span,
+                            });
+                        }
+
+                        params.push(Spanned {
+                            value: FunctionParam {
+                                name: Spanned { value: name, span },
+                                _type,
+                            },
+                            span,
+                        });
+                    }
+
+                    Ok(())
+                },
+            )?);
+        }
+
+        let return_type = match self.peek()?.value {
+            Token::Punctuation(Punctuation::ThinArrow) => {
+                self.next_token()?;
+                Some(self.parse_data_type(Some(&scope.generics))?)
+            }
+            _ => None,
+        };
+
+        // der body ist eine eigene funktion, sieht aber die variablen außerhalb
+        let mut variables = scope.variables.clone();
+        for param in params.iter() {
+            variables.insert(
+                param.value.name.value.clone(),
+                Spanned {
+                    value: param.value.clone().into(),
+                    span: param.span,
+                },
+            );
+        }
+
+        let mut closure_scope = Block {
+            variables,
+            generics: scope.generics.clone(),
+            generic_bounds: scope.generic_bounds.clone(),
+            return_type: DataType::None,
+            function_depth: 0,
+            in_loop: false,
+            ..Default::default()
+        };
+
+        let errors_before = self.errors.len();
+        let body = self.parse_value_block(&mut closure_scope)?;
+        let span = start.span.extend(&body.span);
+
+        let return_type = match return_type {
+            Some(return_type) => {
+                if self.errors.len() == errors_before && body.value.return_type != return_type.value
+                {
+                    return Err(Spanned {
+                        value: ParserError::WrongReturnType(
+                            return_type.value,
+                            body.value.return_type.clone(),
+                        ),
+                        span,
+                    });
+                }
+                return_type.value
+            }
+            None => body.value.return_type.clone(),
+        };
+
+        let mut used = BTreeSet::new();
+        collect_block_variables(&body.value, &mut used);
+
+        let captures = used
+            .iter()
+            .filter(|name| !params.iter().any(|p| p.value.name.value == **name))
+            .filter_map(|name| scope.variables.get(name))
+            .map(|variable| Capture {
+                variable: variable.value.clone(),
+                by_reference: variable.value.is_mutable,
+            })
+            .collect();
+
+        let _type = DataType::Function {
+            params: params.iter().map(|p| p.value._type.value.clone()).collect(),
+            return_type: Box::new(return_type.clone()),
+        };
+
+        Ok(Spanned {
+            value: TypedExpr {
+                expression: Expr::Closure(Closure {
+                    params: Spanned {
+                        value: params,
+                        span: params_span,
+                    },
+                    body,
+                    return_type,
+                    captures,
+                }),
+                _type,
+                raw: None,
+            },
+            span,
+        })
+    }
+
+    /// `f(1, 2)` mit `f: |int64, int64| -> int64`
+    pub(in crate::parser) fn parse_closure_call(
+        &mut self,
+        scope: &mut Block,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let closure = self.parse_variable(scope)?;
+
+        let (params, return_type) = match &closure.value._type {
+            DataType::Function {
+                params,
+                return_type,
+            } => (params.clone(), *return_type.clone()),
+            _ => unreachable!(),
+        };
+
+        let mut args_span = self
+            .expect_next(&[Token::Punctuation(Punctuation::OpenParen)])?
+            .span;
+        let mut args: Vec<Spanned<CallArg>> = vec![];
+
+        let end = self.walk_separated_values(
+            Token::Punctuation(Punctuation::Comma),
+            Token::Punctuation(Punctuation::CloseParen),
+            |parser| {
+                let value = parser.parse_expression(scope)?;
+                args.push(Spanned {
+                    value: CallArg(value.value),
+                    span: value.span,
+                });
+                Ok(())
+            },
+        )?;
+        args_span = args_span.extend(&end);
+
+        if params.len() != args.len() {
+            return Err(Spanned {
+                value: ParserError::WrongArguments(
+                    params.iter().map(|p| p.to_string()).collect(),
+                    args.iter().map(|a| a.value.0._type.to_string()).collect(),
+                ),
+                span: args_span,
+            });
+        }
+
+        for (param, arg) in params.iter().zip(args.iter()) {
+            if *param != arg.value.0._type {
+                return Err(Spanned {
+                    value: ParserError::WrongType(param.clone(), arg.value.0._type.clone()),
+                    span: arg.span,
+                });
+            }
+        }
+
+        let span = closure.span.extend(&args_span);
+
+        Ok(Spanned {
+            value: TypedExpr {
+                expression: Expr::ClosureCall {
+                    closure: Box::new(closure),
+                    args: Spanned {
+                        value: args,
+                        span: args_span,
+                    },
+                },
+                _type: return_type,
+                raw: None,
+            },
+            span,
+        })
+    }
+
+    /// `list.map(double)`, die funktion wird ohne aufruf als wert benutzt
+    pub(in crate::parser) fn parse_function_ref(
+        &mut self,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let name = match self.next_token()? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        let function_name = self.resolve_function(&name)?;
+        self.parse_function_ref_named(name, function_name)
+    }
+
+    /// `function_name` ist der interne name, `name` steht im code
+    pub(in crate::parser) fn parse_function_ref_named(
+        &mut self,
+        name: Spanned<String>,
+        function_name: String,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let function = self
+            .get_function(&Spanned {
+                value: function_name,
+                span: name.span,
+            })?
+            .value
+            .clone();
+
+        if function.is_generic() {
+            return Err(Spanned {
+                value: ParserError::GenericFunctionAsValue(name.value),
+                span: name.span,
+            });
+        }
+
+        let _type = DataType::Function {
+            params: function
+                .params
+                .value
+                .iter()
+                .map(|p| p.value._type.value.clone())
+                .collect(),
+            return_type: Box::new(function.return_type.value.clone()),
+        };
+
+        Ok(Spanned {
+            value: TypedExpr {
+                expression: Expr::FunctionRef(Box::new(function)),
+                _type,
+                raw: None,
+            },
+            span: name.span,
+        })
+    }
+}
+
+/// namen aller variablen, die im block gelesen oder verändert werden
+fn collect_block_variables(block: &Block, out: &mut BTreeSet<String>) {
+    walk_block(block, &mut |expr| match expr {
+        Expr::Variable(variable) => {
+            out.insert(variable.name.value.clone());
+        }
+        // verschachtelte closures fangen selbst, die variablen müssen aber auch hier vorhanden sein
+        Expr::Closure(closure) => {
+            for capture in closure.captures.iter() {
+                out.insert(capture.variable.name.value.clone());
+            }
+        }
+        _ => {}
+    });
+}
+
+/// deklarationen eines bodys, die eine closure per referenz fängt und die ihren block verlassen
+/// kann. die backends legen sie auf den heap, sonst zeigt die umgebung nach dem ende der funktion
+/// auf ihren alten stack. der name allein reicht nicht, `let x` kann mehrmals deklariert werden
+pub fn reference_captures(block: &Block) -> HashSet<Spanned<String>> {
+    let local = local_closures(block);
+    let mut out = HashSet::new();
+    walk_block(block, &mut |expr| match expr {
+        Expr::Closure(closure) if !local.contains(&closure.body.span) => {
+            for capture in closure.captures.iter().filter(|c| c.by_reference) {
+                out.insert(capture.variable.name.clone());
+            }
+        }
+        _ => {}
+    });
+    out
+}
+
+/// closures, die ihren block nicht verlassen: `(|x: int64| -> int64 { x })(1)` oder
+/// `let f = |..| ..;`, wenn `f` danach nur aufgerufen wird. ihre umgebung liegt auf dem stack,
+/// nach dem block ist sie wieder frei. schlüssel ist der span des bodys
+pub fn local_closures(block: &Block) -> HashSet<Span> {
+    let mut bound = vec![];
+    collect_closure_bindings(block, &mut bound);
+
+    // gleichnamige variablen zählen mit, dann bleibt die closure eben auf dem heap
+    let mut uses: HashMap<String, usize> = HashMap::new();
+    let mut calls: HashMap<String, usize> = HashMap::new();
+    let mut out = HashSet::new();
+
+    walk_block(block, &mut |expr| match expr {
+        Expr::Variable(variable) => *uses.entry(variable.name.value.clone()).or_default() += 1,
+        // eine andere closure behält eine kopie
+        Expr::Closure(closure) => {
+            for capture in closure.captures.iter() {
+                *uses.entry(capture.variable.name.value.clone()).or_default() += 1;
+            }
+        }
+        Expr::ClosureCall { closure, .. } => match &closure.value.expression {
+            Expr::Variable(variable) => *calls.entry(variable.name.value.clone()).or_default() += 1,
+            Expr::Closure(closure) if is_leaf_closure(closure) => {
+                out.insert(closure.body.span);
+            }
+            _ => {}
+        },
+        _ => {}
+    });
+
+    out.extend(
+        bound
+            .into_iter()
+            .filter(|(name, _)| uses.get(name) == calls.get(name))
+            .map(|(_, span)| span),
+    );
+    out
+}
+
+/// `let f = |..| ..;` in den statements des blocks und seiner unterblöcke
+fn collect_closure_bindings(block: &Block, out: &mut Vec<(String, Span)>) {
+    for statement in block.statements.iter() {
+        match &statement.value {
+            Statement::VariableDecl { name, value, .. } => {
+                if let Expr::Closure(closure) = &value.value.expression {
+                    if is_leaf_closure(closure) {
+                        out.push((name.value.clone(), closure.body.span));
+                    }
+                }
+            }
+            Statement::If {
+                true_branch,
+                else_if_branches,
+                false_branch,
+                ..
+            } => {
+                collect_closure_bindings(&true_branch.value, out);
+                for branch in else_if_branches.iter() {
+                    collect_closure_bindings(&branch.value.body.value, out);
+                }
+                if let Some(false_branch) = false_branch {
+                    collect_closure_bindings(&false_branch.value, out);
+                }
+            }
+            Statement::WhileLoop { body, .. } | Statement::ForLoop { body, .. } => {
+                collect_closure_bindings(&body.value, out)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// eine closure in der closure könnte die per referenz gefangenen variablen weiter tragen
+fn is_leaf_closure(closure: &Closure) -> bool {
+    let mut nested = false;
+    walk_block(&closure.body.value, &mut |expr| {
+        nested |= matches!(expr, Expr::Closure(_));
+    });
+    !nested
+}
+
+fn walk_block(block: &Block, visit: &mut dyn FnMut(&Expr)) {
+    for statement in block.statements.iter() {
+        walk_statement(&statement.value, visit);
+    }
+}
+
+fn walk_statement(statement: &Statement, visit: &mut dyn FnMut(&Expr)) {
+    match statement {
+        Statement::If {
+            condition,
+            true_branch,
+            else_if_branches,
+            false_branch,
+        } => {
+            walk_expr(&condition.value, visit);
+            walk_block(&true_branch.value, visit);
+            for branch in else_if_branches.iter() {
+                walk_expr(&branch.value.condition.value, visit);
+                walk_block(&branch.value.body.value, visit);
+            }
+            if let Some(false_branch) = false_branch {
+                walk_block(&false_branch.value, visit);
+            }
+        }
+        Statement::WhileLoop { condition, body } => {
+            walk_expr(&condition.value, visit);
+            walk_block(&body.value, visit);
+        }
+        Statement::ForLoop { iterable, body, .. } => {
+            match &iterable.value {
+                ForIterable::Range { start, end } => {
+                    walk_expr(&start.value, visit);
+                    walk_expr(&end.value, visit);
+                }
+                ForIterable::Iterable(iterable) => walk_expr(&iterable.value, visit),
+            }
+            walk_block(&body.value, visit);
+        }
+        Statement::VariableDecl { value, .. } | Statement::Return { value } => {
+            walk_expr(&value.value, visit)
+        }
+        Statement::Expr(expr) => walk_expression(&expr.value, visit),
+        Statement::VariableMutation {
+            variable,
+            new_value,
+        } => {
+            walk_expr(&variable.value, visit);
+            walk_expr(&new_value.value, visit);
+        }
+        Statement::Break | Statement::Continue => {}
+    }
+}
+
+fn walk_expr(expr: &TypedExpr, visit: &mut dyn FnMut(&Expr)) {
+    walk_expression(&expr.expression, visit)
+}
+
+fn walk_expression(expr: &Expr, visit: &mut dyn FnMut(&Expr)) {
+    visit(expr);
+    match expr {
+        Expr::Literal(Literal::ArrayLiteral(array)) => {
+            for value in array.values.value.iter() {
+                walk_expr(&value.value, visit);
+            }
+        }
+        Expr::Literal(Literal::Custom(class)) => {
+            for (_, value) in class.fields.value.iter() {
+                walk_expr(&value.value, visit);
+            }
+        }
+        Expr::Literal(_)
+        | Expr::ClassName(_)
+        | Expr::FunctionRef(_)
+        | Expr::Variable(_)
+        | Expr::Closure(_) => {}
+        Expr::Binary { lhs, rhs, .. } => {
+            walk_expr(&lhs.value, visit);
+            walk_expr(&rhs.value, visit);
+        }
+        Expr::Unary { expr, .. } => walk_expr(&expr.value, visit),
+        Expr::Cast { value, .. } | Expr::Reference { value } => walk_expr(&value.value, visit),
+        Expr::Call { args, .. } => {
+            for arg in args.value.iter() {
+                walk_expr(&arg.value.0, visit);
+            }
+        }
+        Expr::Block { body } => walk_block(&body.value, visit),
+        Expr::Index { base, idx } => {
+            walk_expr(&base.value, visit);
+            walk_expr(&idx.value, visit);
+        }
+        Expr::FieldAccess { base, .. } | Expr::Deref(base) => walk_expr(&base.value, visit),
+        Expr::EnumVariant { args, .. } => {
+            for arg in args.value.iter() {
+                walk_expr(&arg.value, visit);
+            }
+        }
+        Expr::Match { value, arms } => {
+            walk_expr(&value.value, visit);
+            for arm in arms.iter() {
+                walk_expr(&arm.value.body.value, visit);
+            }
+        }
+        Expr::ClosureCall { closure, args } => {
+            walk_expr(&closure.value, visit);
+            for arg in args.value.iter() {
+                walk_expr(&arg.value.0, visit);
+            }
+        }
+    }
+}
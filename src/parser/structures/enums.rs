@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    lexer::{
+        position::Spanned,
+        tokens::{Operator, Punctuation, Token},
+    },
+    parser::{
+        ast::{
+            Block, CommonGeneric, CustomDataType, DataType, DataTypeInfo, EnumVariant, Expr,
+            FunctionParam, InternalNameGetter, TypedExpr,
+        },
+        error::ParserError,
+        parser_main::Parser,
+    },
+};
+
+impl Parser {
+    /*
+        enum Shape {
+            Circle(float),
+            Rect(float, float),
+        }
+
+        wird zu:
+
+        class Shape {
+            tag: int64,
+            Circle.0: float,
+            Rect.0: float,
+            Rect.1: float,
+        }
+    */
+    pub fn parse_enum_def(&mut self) -> Result<Spanned<DataType>, Spanned<ParserError>> {
+        self.next_token()?; // "enum"
+        let mut generics = vec![];
+        let mut is_generic = false;
+
+        let (name, span) = match self.expect_next(&[Token::Identifier("enum name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => (name, span),
+            _ => unreachable!(),
+        };
+
+        if let Token::Operator(Operator::LessThan) = self.peek()?.value {
+            is_generic = true;
+            generics = self.parse_generics(None)?.value;
+        }
+
+        let mut fields = vec![FunctionParam {
+            name: Spanned {
+                value: "tag".to_string(),
+                span,
+            },
+            _type: Spanned {
+                value: DataType::get_integer_type(),
+                span,
+            },
+        }];
+        let mut variants: Vec<EnumVariant> = vec![];
+
+        let start = self
+            .expect_next(&[Token::Punctuation(Punctuation::OpenBrace)])?
+            .span;
+
+        let end = self.walk_separated_values(
+            Token::Punctuation(Punctuation::Comma),
+            Token::Punctuation(Punctuation::CloseBrace),
+            |parser| {
+                let variant_name =
+                    match parser.expect_next(&[Token::Identifier("variant name".to_string())])? {
+                        Spanned {
+                            value: Token::Identifier(name),
+                            span,
+                        } => Spanned { value: name, span },
+                        _ => unreachable!(),
+                    };
+
+                if variants.iter().any(|v| v.name.value == variant_name.value) {
+                    return Err(Spanned {
+                        value: ParserError::VariantAlreadyExists(variant_name.value),
+                        span: variant_name.span,
+                    });
+                }
+
+                let mut variant_fields = vec![];
+
+                if let Token::Punctuation(Punctuation::OpenParen) = parser.peek()?.value {
+                    parser.next_token()?;
+                    parser.walk_separated_values(
+                        Token::Punctuation(Punctuation::Comma),
+                        Token::Punctuation(Punctuation::CloseParen),
+                        |parser| {
+                            let data_type = parser.parse_data_type(Some(&generics))?;
+
+                            fields.push(FunctionParam {
+                                name: Spanned {
+                                    value: format!(
+                                        "{}.{}",
+                                        variant_name.value,
+                                        variant_fields.len()
+                                    ),
+                                    span: data_type.span,
+                                },
+                                _type: data_type,
+                            });
+                            variant_fields.push(fields.len() - 1);
+
+                            Ok(())
+                        },
+                    )?;
+                }
+
+                variants.push(EnumVariant {
+                    name: variant_name,
+                    fields: variant_fields,
+                });
+
+                Ok(())
+            },
+        )?;
+
+        let enum_span = span.extend(&start).extend(&end);
+
+        if let Some(enum_already_exists) = self.program.custom_types.get(&name).cloned() {
+            return Err(Spanned {
+                value: ParserError::ClassAlreadyExists(name),
+                span: enum_already_exists.span,
+            });
+        }
+
+        let enum_type = DataType::Custom(Box::new(CustomDataType {
+            display_name: name.clone(),
+            name: name.clone(),
+            fields: Spanned {
+                value: fields,
+                span: start.extend(&end),
+            },
+            subtypes: HashMap::new(),
+            generics,
+            subtype_of: None,
+            variants: Some(variants),
+            is_generic,
+            is_packed: false,
+        }));
+
+        self.program.custom_types.insert(
+            name,
+            Spanned {
+                value: enum_type.clone(),
+                span: enum_span,
+            },
+        );
+
+        self.program.data_types.insert(
+            enum_type.internal_name(),
+            DataTypeInfo {
+                parent_type: enum_type.clone(),
+                methods: vec![],
+                traits: HashSet::new(),
+            },
+        );
+
+        Ok(Spanned {
+            value: enum_type,
+            span: enum_span,
+        })
+    }
+
+    /// `Shape::Circle(1.0)`, `Option::None<int64>`
+    /// `Shape::` wurde bereits konsumiert
+    pub(in crate::parser) fn parse_enum_variant(
+        &mut self,
+        scope: &mut Block,
+        enum_type: &CustomDataType,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let variant = match self.expect_next(&[Token::Identifier("variant name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        let (variant_idx, variant_def) = match enum_type.get_variant(&variant.value) {
+            Some((idx, variant_def)) => (idx, variant_def.clone()),
+            None => {
+                return Err(Spanned {
+                    value: ParserError::VariantDoesNotExist(
+                        variant.value,
+                        enum_type.display_name.clone(),
+                    ),
+                    span: variant.span,
+                })
+            }
+        };
+
+        let mut span = variant.span;
+
+        let mut generic_annotations = vec![];
+        if let Token::Operator(Operator::LessThan) = self.peek()?.value {
+            generic_annotations = self.collect_generic_annotations(Some(&scope.generics))?;
+        }
+
+        let mut args: Vec<Spanned<TypedExpr>> = vec![];
+        let mut args_span = span;
+        if let Token::Punctuation(Punctuation::OpenParen) = self.peek()?.value {
+            args_span = self.next_token()?.span;
+            let end = self.walk_separated_values(
+                Token::Punctuation(Punctuation::Comma),
+                Token::Punctuation(Punctuation::CloseParen),
+                |parser| {
+                    args.push(parser.parse_expression(scope)?);
+                    Ok(())
+                },
+            )?;
+            args_span = args_span.extend(&end);
+            span = span.extend(&end);
+        }
+
+        let payload_types = enum_type.variant_types(&variant_def);
+
+        if payload_types.len() != args.len() {
+            return Err(Spanned {
+                value: ParserError::WrongArguments(
+                    payload_types.iter().map(|t| t.to_string()).collect(),
+                    args.iter().map(|a| a.value._type.to_string()).collect(),
+                ),
+                span: args_span,
+            });
+        }
+
+        let mut enum_type = enum_type.clone();
+
+        if enum_type.is_generic() {
+            let specific_types = if !generic_annotations.is_empty() {
+                generic_annotations.into_iter().map(|g| g.value).collect()
+            } else {
+                // generics aus dem payload ermitteln
+                enum_type
+                    .generics
+                    .iter()
+                    .filter_map(|generic| {
+                        payload_types
+                            .iter()
+                            .position(|t| *t == generic.value)
+                            .map(|idx| args[idx].value._type.clone())
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            if specific_types.len() != enum_type.generics.len() {
+                return Err(Spanned {
+                    value: ParserError::WrongGenericParamCount(
+                        enum_type.generics.len(),
+                        specific_types.len(),
+                    ),
+                    span,
+                });
+            }
+
+            enum_type = match enum_type.subtypes.get(&specific_types) {
+                Some(subtype) => subtype.clone(),
+                None => enum_type.subtype(&specific_types, &mut self.program, true),
+            };
+        }
+
+        for (expected, arg) in enum_type
+            .variant_types(&variant_def)
+            .iter()
+            .zip(args.iter())
+        {
+            if *expected != arg.value._type {
+                return Err(Spanned {
+                    value: ParserError::WrongType(expected.clone(), arg.value._type.clone()),
+                    span: arg.span,
+                });
+            }
+        }
+
+        Ok(Spanned {
+            value: TypedExpr {
+                expression: Expr::EnumVariant {
+                    variant,
+                    variant_idx,
+                    args: Spanned {
+                        value: args,
+                        span: args_span,
+                    },
+                },
+                _type: DataType::Custom(Box::new(enum_type)),
+                raw: None,
+            },
+            span,
+        })
+    }
+}
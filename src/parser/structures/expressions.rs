@@ -35,9 +35,18 @@ impl Parser {
         } else {
             expr = match next.value {
                 Token::Punctuation(Punctuation::Ampersand) => self.parse_reference(scope),
+                Token::Keyword(Keyword::Match) => self.parse_match(scope),
+                Token::Punctuation(Punctuation::Pipe) | Token::Operator(Operator::Or) => {
+                    self.parse_closure(scope)
+                }
                 Token::Operator(_) => self.parse_unary_expression(scope),
                 Token::Punctuation(Punctuation::OpenBrace) => {
-                    let block = self.parse_block(scope)?;
+                    // break/continue dürfen den block nicht verlassen, sonst hätte er keinen wert
+                    let in_loop = std::mem::replace(&mut scope.in_loop, false);
+                    let block = self.parse_value_block(scope);
+                    scope.in_loop = in_loop;
+
+                    let block = block?;
 
                     Ok(Spanned {
                         value: TypedExpr {
@@ -55,10 +64,28 @@ impl Parser {
                 }
                 Token::DataLiteral(_data_literal) => self.parse_literal(),
                 Token::Punctuation(Punctuation::Tilde) => self.parse_deref(scope),
+                // klassen ohne `pub` aus anderen dateien
+                Token::Identifier(_name)
+                    if self.hidden_types.contains(&_name)
+                        && !scope.variables.contains_key(&_name) =>
+                {
+                    Err(Spanned {
+                        value: ParserError::ItemIsPrivate(_name),
+                        span: next.span,
+                    })
+                }
                 Token::Identifier(_name) => {
                     let next = self.peek_nth(1)?;
 
                     match next.value {
+                        Token::Punctuation(Punctuation::OpenParen)
+                            if matches!(
+                                scope.variables.get(&_name).map(|v| &v.value._type),
+                                Some(DataType::Function { .. })
+                            ) =>
+                        {
+                            self.parse_closure_call(scope)
+                        }
                         Token::Punctuation(Punctuation::OpenParen) => {
                             self.parse_func_call(scope, None)
                         }
@@ -87,7 +114,8 @@ impl Parser {
                                 )?
                                 .is_some();
 
-                            if !contains_open_brace {
+                            // `{` kann auch zu einer closure im argument gehören
+                            if !contains_open_brace || self.program.functions.contains_key(&_name) {
                                 // panic!("yes");
                                 self.parse_func_call(scope, None)
                             } else {
@@ -99,6 +127,13 @@ impl Parser {
                         {
                             self.parse_class_literal(scope)
                         }
+                        // `io::println(...)`
+                        Token::Punctuation(Punctuation::Colon)
+                            if self.namespaces.contains_key(&_name)
+                                && !scope.variables.contains_key(&_name) =>
+                        {
+                            self.parse_namespace_access(scope)
+                        }
                         // statische methode
                         Token::Punctuation(Punctuation::Colon)
                             if self.program.custom_types.contains_key(&_name) =>
@@ -110,17 +145,25 @@ impl Parser {
                             let data_type =
                                 self.program.custom_types.get(&_name).unwrap().value.clone();
 
-                            self.parse_func_call(
-                                scope,
-                                Some(&Spanned {
-                                    value: TypedExpr {
-                                        expression: Expr::ClassName(_name),
-                                        _type: data_type,
-                                        raw: None,
-                                    },
-                                    span: Span::default(),
-                                }),
-                            )
+                            match (&data_type, self.peek()?.value) {
+                                // enum variante: Shape::Circle(1.0)
+                                (DataType::Custom(custom_type), Token::Identifier(variant))
+                                    if custom_type.get_variant(&variant).is_some() =>
+                                {
+                                    self.parse_enum_variant(scope, custom_type)
+                                }
+                                _ => self.parse_func_call(
+                                    scope,
+                                    Some(&Spanned {
+                                        value: TypedExpr {
+                                            expression: Expr::ClassName(_name),
+                                            _type: data_type.clone(),
+                                            raw: None,
+                                        },
+                                        span: Span::default(),
+                                    }),
+                                ),
+                            }
                         }
 
                         _ => {
@@ -135,6 +178,11 @@ impl Parser {
                                 })
                             {
                                 self.parse_data_type_literal(scope)
+                            } else if !scope.variables.contains_key(&_name)
+                                && !self.visible_globals.contains_key(&_name)
+                                && self.is_visible_function(&_name)
+                            {
+                                self.parse_function_ref()
                             } else {
                                 self.parse_variable(scope)
                             }
@@ -220,6 +268,48 @@ impl Parser {
 
         Ok(expr)
     }
+    /// `&` und `|` sind punctuation (referenzen, closures) und `>>` sind zwei `>`
+    fn peek_binary_operator(
+        &mut self,
+    ) -> Result<Option<Spanned<BinaryOperator>>, Spanned<ParserError>> {
+        let next = match self.peek() {
+            Ok(next) => next,
+            Err(_) => return Ok(None),
+        };
+
+        let mut span = next.span;
+        let operator = match next.value {
+            Token::Punctuation(Punctuation::Ampersand) => BinaryOperator::BitAnd,
+            Token::Punctuation(Punctuation::Pipe) => BinaryOperator::BitOr,
+            Token::Operator(Operator::GreaterThan) => match self.peek_nth(1) {
+                // nur direkt hintereinander, `a > >b` ist kein shift
+                Ok(Spanned {
+                    value: Token::Operator(Operator::GreaterThan),
+                    span: second,
+                }) if second.start.abs == next.span.end.abs => {
+                    span = span.extend(&second);
+                    BinaryOperator::ShiftRight
+                }
+                _ => BinaryOperator::GreaterThan,
+            },
+            Token::Operator(operator) => match operator.to_binary_op() {
+                Some(op) => op,
+                None => {
+                    return Err(Spanned {
+                        value: ParserError::InvalidOperator(operator),
+                        span,
+                    })
+                }
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Spanned {
+            value: operator,
+            span,
+        }))
+    }
+
     // https://en.wikipedia.org/wiki/Operator-precedence_parser
     fn parse_binary_expression(
         &mut self,
@@ -227,48 +317,31 @@ impl Parser {
         min_precedence: u8,
         scope: &mut Block,
     ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
-        while let Ok(Spanned {
-            value: Token::Operator(first_operator),
+        while let Some(Spanned {
+            value: first_operator,
             span,
-        }) = self.peek()
+        }) = self.peek_binary_operator()?
         {
-            // let mut result_type = lhs.value._type.clone();
-            let first_operator = match first_operator.to_binary_op() {
-                Some(op) => op,
-                None => {
-                    return Err(Spanned {
-                        value: ParserError::InvalidOperator(first_operator),
-                        span,
-                    })
-                }
-            };
-
             if first_operator.precedence() < min_precedence {
                 break;
             }
 
-            self.tokens.next();
+            self.next_token()?;
+            if first_operator == BinaryOperator::ShiftRight {
+                self.next_token()?;
+            }
+
             let mut rhs = self.parse_primary_expression(None, scope)?;
 
             let mut expr_span = lhs.span.extend(&rhs.span);
 
             let operation_trait = Trait::from_binary_operator(&first_operator);
 
-            while let Ok(Spanned {
-                value: Token::Operator(second_operator),
+            while let Some(Spanned {
+                value: second_operator,
                 ..
-            }) = self.peek()
+            }) = self.peek_binary_operator()?
             {
-                let second_operator = match second_operator.to_binary_op() {
-                    Some(op) => op,
-                    None => {
-                        return Err(Spanned {
-                            value: ParserError::InvalidOperator(second_operator),
-                            span,
-                        })
-                    }
-                };
-
                 if second_operator.precedence() <= first_operator.precedence() {
                     break;
                 }
@@ -282,25 +355,19 @@ impl Parser {
                 rhs = self.parse_binary_expression(rhs, next_precedence, scope)?;
             }
 
-            if !self.implements_trait(
-                &lhs.value._type,
+            let result_type = match self.operator_return_type(
                 &operation_trait,
                 &[lhs.value._type.clone(), rhs.value._type.clone()],
+                scope,
             ) {
-                return Err(Spanned {
-                    value: ParserError::WrongType(lhs.value._type, rhs.value._type), // TODO: anderer fehler? trait not impl?
-                    span: expr_span,
-                });
-            }
-
-            let type_info = self.get_type_info(&lhs.value._type);
-            // sollte nicht panicen, siehe check oben
-            let result_type = type_info
-                .get_trait_return_type(
-                    &operation_trait,
-                    &[lhs.value._type.clone(), rhs.value._type.clone()],
-                )
-                .unwrap();
+                Some(result_type) => result_type,
+                None => {
+                    return Err(Spanned {
+                        value: ParserError::WrongType(lhs.value._type, rhs.value._type), // TODO: anderer fehler? trait not impl?
+                        span: expr_span,
+                    });
+                }
+            };
 
             expr_span = lhs.span.extend(&rhs.span);
 
@@ -357,21 +424,43 @@ impl Parser {
                 // TODO: vielleicht zu codegen https://llvm.org/docs/LangRef.html#i-fneg
                 UnaryOperator::Minus => {
                     if expr_type.is_integer() || expr_type.is_float() {
+                        let literal = if expr_type.is_integer() {
+                            Literal::Integer(-1)
+                        } else {
+                            Literal::Float(-1.)
+                        };
+
+                        let mut minus_one = Spanned {
+                            value: TypedExpr {
+                                _type: literal._type(),
+                                expression: Expr::Literal(literal),
+                                raw: None,
+                            },
+                            span: Default::default(),
+                        };
+
+                        // literale sind immer int64/float, z.b. bei uint8 oder float64 casten
+                        if minus_one.value._type != expr_type {
+                            minus_one = Spanned {
+                                value: TypedExpr {
+                                    expression: Expr::Cast {
+                                        value: Box::new(minus_one),
+                                        to_type: Spanned {
+                                            value: expr_type.clone(),
+                                            span: Default::default(),
+                                        },
+                                    },
+                                    _type: expr_type.clone(),
+                                    raw: None,
+                                },
+                                span: Default::default(),
+                            };
+                        }
+
                         return Ok(Spanned {
                             value: TypedExpr {
                                 expression: Expr::Binary {
-                                    lhs: Box::new(Spanned {
-                                        value: TypedExpr {
-                                            expression: Expr::Literal(if expr_type.is_integer() {
-                                                Literal::Integer(-1)
-                                            } else {
-                                                Literal::Float(-1.)
-                                            }),
-                                            _type: expr_type.clone(),
-                                            raw: None,
-                                        },
-                                        span: Default::default(),
-                                    }),
+                                    lhs: Box::new(minus_one),
                                     rhs: Box::new(expr),
                                     op: Spanned {
                                         value: BinaryOperator::Multiply,
@@ -478,23 +567,25 @@ impl Parser {
     ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
         self.expect_next(&[Token::Punctuation(Punctuation::Tilde)])?;
 
-        let mut expr = self.parse_expression(scope)?;
-        if let DataType::Pointer(value_type) = expr.value._type {
-            expr.value._type = *value_type;
-        } else {
-            return Err(Spanned {
-                value: ParserError::CannotDerefType(expr.value._type),
-                span: expr.span,
-            });
-        }
+        // der operand behält seinen pointer typ, z.b. für `~(ptr as *int8)`
+        let expr = self.parse_expression(scope)?;
+        let value_type = match &expr.value._type {
+            DataType::Pointer(value_type) => *value_type.clone(),
+            _type => {
+                return Err(Spanned {
+                    value: ParserError::CannotDerefType(_type.clone()),
+                    span: expr.span,
+                })
+            }
+        };
 
         Ok(Spanned {
+            span: expr.span,
             value: TypedExpr {
-                expression: Expr::Deref(Box::new(expr.clone())),
-                _type: expr.value._type,
+                expression: Expr::Deref(Box::new(expr)),
+                _type: value_type,
                 raw: None,
             },
-            span: expr.span,
         })
     }
 }
@@ -0,0 +1,146 @@
+use crate::{
+    lexer::{
+        position::Spanned,
+        tokens::{Keyword, Punctuation, Token},
+    },
+    parser::{
+        ast::{Block, DataType, ForIterable, Statement, Trait, TypedExpr, Variable},
+        error::ParserError,
+        parser_main::Parser,
+    },
+};
+
+impl Parser {
+    pub(in crate::parser) fn parse_for(
+        &mut self,
+        scope: &mut Block,
+    ) -> Result<Spanned<Statement>, Spanned<ParserError>> {
+        scope.function_depth += 1;
+        let start = self.expect_next(&[Token::Keyword(Keyword::For)])?.span;
+
+        let name = match self.expect_next(&[Token::Identifier("variable name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        self.expect_next(&[Token::Keyword(Keyword::In)])?;
+
+        let first = self.parse_expression(scope)?;
+
+        let (iterable, value_type) = match self.peek()?.value {
+            Token::Punctuation(Punctuation::Range) => {
+                self.next_token()?;
+                let end = self.parse_expression(scope)?;
+
+                if !first.value._type.is_integer() {
+                    return Err(Spanned {
+                        value: ParserError::WrongType(
+                            DataType::get_integer_type(),
+                            first.value._type,
+                        ),
+                        span: first.span,
+                    });
+                }
+
+                if end.value._type != first.value._type {
+                    return Err(Spanned {
+                        value: ParserError::WrongType(first.value._type, end.value._type),
+                        span: end.span,
+                    });
+                }
+
+                let value_type = first.value._type.clone();
+                let span = first.span.extend(&end.span);
+
+                (
+                    Spanned {
+                        value: ForIterable::Range {
+                            start: Box::new(first),
+                            end: Box::new(end),
+                        },
+                        span,
+                    },
+                    value_type,
+                )
+            }
+            _ => {
+                let value_type = self.get_iterable_value_type(&first)?;
+
+                (
+                    Spanned {
+                        value: ForIterable::Iterable(Box::new(first.clone())),
+                        span: first.span,
+                    },
+                    value_type,
+                )
+            }
+        };
+
+        let variable = Spanned {
+            value: Variable {
+                name: name.clone(),
+                is_mutable: false,
+                _type: value_type,
+            },
+            span: name.span,
+        };
+
+        // die laufvariable ist nur innerhalb des loops sichtbar
+        let mut loop_scope = scope.clone();
+        loop_scope.in_loop = true;
+        loop_scope
+            .variables
+            .insert(name.value.clone(), variable.clone());
+
+        let body = self.parse_block(&mut loop_scope)?;
+        let span = start.extend(&body.span);
+
+        Ok(Spanned {
+            value: Statement::ForLoop {
+                variable,
+                iterable,
+                body: Box::new(body),
+            },
+            span,
+        })
+    }
+
+    /// Arrays können direkt iteriert werden, alle anderen typen
+    /// brauchen eine implementierung von `iter_len` und `iter_get`
+    fn get_iterable_value_type(
+        &mut self,
+        iterable: &Spanned<TypedExpr>,
+    ) -> Result<DataType, Spanned<ParserError>> {
+        let _type = iterable.value._type.clone();
+
+        if let DataType::Array { value_type, .. } = &_type {
+            return Ok(*value_type.clone());
+        }
+
+        if !self.implements_trait(&_type, &Trait::IterLen, std::slice::from_ref(&_type))
+            || !self.implements_trait(
+                &_type,
+                &Trait::IterGet,
+                &[_type.clone(), DataType::Integer64],
+            )
+        {
+            return Err(Spanned {
+                value: ParserError::NotIterable(_type),
+                span: iterable.span,
+            });
+        }
+
+        // sollte nicht panicen, siehe check oben
+        Ok(self
+            .program
+            .get_trait_return_type(
+                &_type,
+                &Trait::IterGet,
+                &[_type.clone(), DataType::Integer64],
+            )
+            .unwrap())
+    }
+}
@@ -5,11 +5,12 @@ use crate::{
     },
     parser::{
         ast::{
-            Block, CallArg, CommonGeneric, DataType, Expr, Function, FunctionParam,
-            InternalNameGetter, TypedExpr, Variable, CLASS_SELF_ARG_NAME, TRAIT_NAMES_MAP,
+            Block, CallArg, CommonGeneric, DataType, Expr, Function, FunctionParam, GenericBounds,
+            InternalNameGetter, TypedExpr, Variable, CLASS_SELF_ARG_NAME,
         },
         error::ParserError,
         parser_main::Parser,
+        structures::traits::ImplTarget,
         utils::handle_generics,
         // utils::,
     },
@@ -19,11 +20,26 @@ use std::collections::HashMap;
 
 use std::collections::BTreeMap;
 
+/// die parameter und die position von `...`, falls die funktion variadisch ist
+type FuncParams = (Spanned<Vec<Spanned<FunctionParam>>>, Option<Span>);
+
 impl Parser {
     pub fn parse_func_def(&mut self) -> Result<Spanned<Function>, Spanned<ParserError>> {
+        self.parse_func_def_in(None)
+    }
+
+    /// `impl_target` ist gesetzt wenn die funktion in einem `impl` block steht
+    pub(in crate::parser) fn parse_func_def_in(
+        &mut self,
+        impl_target: Option<&ImplTarget>,
+    ) -> Result<Spanned<Function>, Spanned<ParserError>> {
+        let export = std::mem::take(&mut self.item_attributes).export;
         let mut is_extern = false;
         let mut parent_class = None;
-        let mut generics = vec![];
+        let mut generics = impl_target
+            .map(|target| target.generics.clone())
+            .unwrap_or_default();
+        let mut generic_bounds = GenericBounds::new();
 
         let start = self.next_token()?; // def/extern
 
@@ -40,33 +56,68 @@ impl Parser {
             let span = start.span.extend(&span);
 
             if Token::Operator(Operator::LessThan) == self.peek()?.value {
-                generics = self.parse_generics()?.value;
+                generics.extend(self.parse_generics(Some(&mut generic_bounds))?.value);
             }
 
-            let mut params = self.parse_func_params(&generics)?;
+            let (mut params, variadic) = self.parse_func_params(&generics)?;
 
             let next = self.peek()?;
 
-            match next.value {
-                Token::Keyword(Keyword::For) => {
+            match (next.value, impl_target) {
+                (Token::Keyword(Keyword::For), None) => {
                     // method
                     self.next_token()?;
                     parent_class = Some(self.parse_data_type(Some(&generics))?);
-                    // self parameter
-                    if let Some(self_param) = params.value.get_mut(0) {
-                        if self_param.value.name.value == *CLASS_SELF_ARG_NAME {
-                            self_param.value._type.value = parent_class.clone().unwrap().value;
-                        }
-                    }
                 }
-                Token::Punctuation(Punctuation::ThinArrow)
-                | Token::Punctuation(Punctuation::OpenBrace) => {}
+                (Token::Punctuation(Punctuation::ThinArrow), _)
+                | (Token::Punctuation(Punctuation::OpenBrace), _) => {}
 
-                _ => unreachable!(),
+                (token, _) => {
+                    return Err(Spanned {
+                        value: ParserError::UnexpectedToken(token),
+                        span: next.span,
+                    })
+                }
+            }
+
+            if let Some(target) = impl_target {
+                parent_class = Some(target.data_type.clone());
+            }
+
+            // self parameter
+            if let (Some(class), Some(self_param)) = (&parent_class, params.value.get_mut(0)) {
+                if self_param.value.name.value == *CLASS_SELF_ARG_NAME {
+                    self_param.value._type.value = class.value.clone();
+                }
             }
 
             let return_type = self.parse_func_return_type(&generics)?.unwrap_or_default();
 
+            // `extern def` mit body ist eine normale funktion, die auch c aufrufen kann
+            let c_abi = export
+                || (is_extern
+                    && self.peek().is_ok_and(|next| {
+                        next.value == Token::Punctuation(Punctuation::OpenBrace)
+                    }));
+            if c_abi {
+                is_extern = false;
+            }
+
+            if let (Some(span), false) = (variadic, is_extern) {
+                return Err(Spanned {
+                    value: ParserError::VariadicNotExtern,
+                    span,
+                });
+            }
+
+            // c kennt keine generics und methoden, `main` hat schon ein symbol
+            if export && (!generics.is_empty() || parent_class.is_some() || name == "main") {
+                return Err(Spanned {
+                    value: ParserError::AttributeNotAllowed("export".to_string()),
+                    span,
+                });
+            }
+
             let mut variables = BTreeMap::new();
 
             // TODO: verschieben nach parse_func_params
@@ -97,15 +148,20 @@ impl Parser {
                     value: Block {
                         statements: vec![],
                         variables,
-                        closure_params: vec![],
                         generics,
+                        generic_bounds,
                         return_type: return_type.value.clone(),
                         function_depth: 0,
+                        in_loop: false,
+                        is_value: false,
                     },
                     span: Span::default(),
                 },
                 return_type,
                 is_extern,
+                is_variadic: variadic.is_some(),
+                c_abi,
+                export,
                 method_of: parent_class.clone().map(|c| c.value),
                 generic_subtypes: HashMap::new(),
                 is_builtin: false,
@@ -118,6 +174,12 @@ impl Parser {
 
             match parent_class.as_ref().map(|c| &c.value) {
                 Some(inner) => {
+                    // operator methoden wie `add` implementieren ihr trait auch ohne `impl` block
+                    let trait_decl = match impl_target {
+                        Some(target) => Some(target.trait_.clone()),
+                        None => self.program.builtin_trait_of_method(&function.name),
+                    };
+
                     let data_type_info = self.get_type_info_mut(inner);
 
                     if data_type_info.methods.contains(&function.name) {
@@ -129,92 +191,92 @@ impl Parser {
 
                     let mut function_name = function.name.clone();
 
-                    // Funktion hier noch nicht vollständig, ist aber egal wahrscheinlich?
-                    // für trait ist eigentlich nur signatur wichtig, vielleicht function.signature()
-                    if let Some(trait_) = TRAIT_NAMES_MAP.get(&function.name) {
-                        if function.is_static_method() {
-                            return Err(Spanned {
-                                value: ParserError::TraitIsStaticMethod,
-                                span,
-                            });
-                        }
-
-                        let param_types: Vec<DataType> = function
-                            .params
-                            .value
-                            .iter()
-                            .skip(1) // skip entfernen wegen generics?
-                            .map(|p| p.value._type.value.clone())
-                            .collect();
-
-                        if data_type_info
-                            .get_trait_override_function_name(trait_, &param_types)
-                            .is_some()
-                        {
-                            return Err(Spanned {
-                                value: ParserError::TraitAlreadyImplemented(
-                                    trait_.clone(),
-                                    inner.clone(),
-                                ),
-                                span,
-                            });
-                        }
+                    if let Some(trait_decl) = trait_decl {
+                        let trait_ = trait_decl.as_trait();
+
+                        let trait_method = match trait_decl.get_method(&function.name) {
+                            Some(trait_method) => trait_method,
+                            None => {
+                                return Err(Spanned {
+                                    value: ParserError::MethodNotInTrait(
+                                        function.name.clone(),
+                                        trait_decl.name.value.clone(),
+                                    ),
+                                    span,
+                                })
+                            }
+                        };
 
-                        if let Some(err) = self.check_trait_reqs(inner, trait_, &function) {
+                        // Funktion hier noch nicht vollständig, für das trait ist nur die signatur wichtig
+                        // operator methoden ohne `impl` block dürfen andere typen haben (`Foo + int64`)
+                        let checked = match impl_target {
+                            Some(_) => trait_method.check_impl(&trait_, inner, &function),
+                            None => trait_method.check_overload(&trait_, &function),
+                        };
+                        if let Err(err) = checked {
                             return Err(Spanned { value: err, span });
                         }
 
-                        // self wird sonst doppelt geborrowed
-                        let data_type_info = self.get_type_info_mut(inner);
-
-                        let params = trait_.param_len();
-
-                        // -1 weil jedes trait self als parameter hat
-                        if function.params.value.len() - 1 != params {
-                            return Err(Spanned {
-                                value: ParserError::TraitParamCountMismatch(
-                                    trait_.clone(),
-                                    params,
-                                    function.params.value.len() - 1,
-                                ),
-                                span,
-                            });
-                        }
-
-                        function_name = format!(
-                            "{}_{}",
-                            trait_,
-                            function
+                        if trait_decl.builtin().is_some() {
+                            let param_types: Vec<DataType> = function
                                 .params
                                 .value
                                 .iter()
+                                .skip(1) // skip entfernen wegen generics?
                                 .map(|p| p.value._type.value.clone())
-                                .join("_")
-                        );
+                                .collect();
+
+                            if data_type_info
+                                .get_trait_override_function_name(&trait_, &param_types)
+                                .is_some()
+                            {
+                                return Err(Spanned {
+                                    value: ParserError::TraitAlreadyImplemented(
+                                        trait_.clone(),
+                                        inner.clone(),
+                                    ),
+                                    span,
+                                });
+                            }
+
+                            function_name = format!(
+                                "{}_{}",
+                                trait_,
+                                function
+                                    .params
+                                    .value
+                                    .iter()
+                                    .map(|p| p.value._type.value.clone())
+                                    .join("_")
+                            );
+
+                            let name = format!("{}_{}", inner, &function_name);
+                            if !data_type_info.traits.insert((
+                                trait_.clone(),
+                                function
+                                    .params
+                                    .value
+                                    .iter()
+                                    // .skip(1)
+                                    .map(|p| p.value._type.value.clone())
+                                    .collect(),
+                                Some(name),
+                                function.return_type.value.clone(),
+                            )) {
+                                return Err(Spanned {
+                                    value: ParserError::MethodAlreadyExists(
+                                        function_name,
+                                        inner.to_string(),
+                                    ),
+                                    span,
+                                });
+                            }
 
-                        let name = format!("{}_{}", inner, &function_name);
-                        if !data_type_info.traits.insert((
-                            trait_.clone(),
-                            function
-                                .params
-                                .value
-                                .iter()
-                                // .skip(1)
-                                .map(|p| p.value._type.value.clone())
-                                .collect(),
-                            Some(name),
-                            function.return_type.value.clone(),
-                        )) {
-                            return Err(Spanned {
-                                value: ParserError::MethodAlreadyExists(
-                                    function_name,
-                                    inner.to_string(),
-                                ),
-                                span,
-                            });
+                            function.trait_of = Some(inner.clone());
+                        } else {
+                            // methoden von user traits sind normale methoden
+                            data_type_info.methods.push(function.name.clone());
                         }
-
-                        function.trait_of = Some(inner.clone());
                     } else {
                         data_type_info.methods.push(function.name.clone());
                     }
@@ -222,14 +284,59 @@ impl Parser {
                     function.name = format!("{}_{}", inner.internal_name(), &function_name);
                 }
                 None => {
+                    // funktionen aus importierten dateien bekommen einen präfix,
+                    // externe funktionen brauchen ihren namen für den linker
+                    if let (Some(prefix), false) = (&self.module_prefix, is_extern) {
+                        function.name = format!("{prefix}.{name}");
+                    }
+
+                    // der name ohne präfix gehört dem symbol mit c abi
+                    if export {
+                        function.name = format!("{}.export", function.name);
+                    }
+
+                    let symbol = match export {
+                        true => &name,
+                        false => &function.name,
+                    };
+                    if self.program.functions.values().any(|f| {
+                        (export && f.value.name == *symbol)
+                            || (f.value.export && f.value.display_name.value == *symbol)
+                    }) {
+                        return Err(Spanned {
+                            value: ParserError::FunctionAlreadyExists(name),
+                            span,
+                        });
+                    }
+
                     if let Some(function_already_exists) =
-                        self.program.functions.get(&function.name).cloned()
+                        self.program.functions.get(&function.name)
+                    {
+                        // die gleiche externe funktion darf in mehreren dateien stehen
+                        if !(is_extern
+                            && function_already_exists.value.is_extern
+                            && function_already_exists.value.same_signature(&function))
+                        {
+                            return Err(Spanned {
+                                value: ParserError::FunctionAlreadyExists(name),
+                                span: function_already_exists.span,
+                            });
+                        }
+                    }
+
+                    if self
+                        .visible_functions
+                        .get(&name)
+                        .is_some_and(|visible| *visible != function.name)
                     {
                         return Err(Spanned {
                             value: ParserError::FunctionAlreadyExists(name),
-                            span: function_already_exists.span,
+                            span,
                         });
                     }
+
+                    self.visible_functions
+                        .insert(name.clone(), function.name.clone());
                 }
             }
 
@@ -241,6 +348,8 @@ impl Parser {
                 },
             );
 
+            let errors_before = self.errors.len();
+
             let body = match is_extern {
                 true => Spanned {
                     ..Default::default()
@@ -252,8 +361,11 @@ impl Parser {
 
             let function_span = span.extend(&function.body.span);
 
-            // prüfe ob return mit return_type übereinstimmt
-            if !is_extern && function.body.value.return_type != function.return_type.value {
+            // prüfe ob return mit return_type übereinstimmt (nicht nach fehlern im body)
+            if !is_extern
+                && self.errors.len() == errors_before
+                && function.body.value.return_type != function.return_type.value
+            {
                 return Err(Spanned {
                     value: ParserError::WrongReturnType(
                         function.return_type.value,
@@ -280,11 +392,13 @@ impl Parser {
         unreachable!()
     }
 
-    fn parse_func_params(
+    /// gibt auch die position von `...` zurück, falls die funktion variadisch ist
+    pub(in crate::parser) fn parse_func_params(
         &mut self,
         generics: &Vec<Spanned<DataType>>,
-    ) -> Result<Spanned<Vec<Spanned<FunctionParam>>>, Spanned<ParserError>> {
+    ) -> Result<FuncParams, Spanned<ParserError>> {
         let mut params = vec![];
+        let mut variadic: Option<Span> = None;
         let mut span = self
             .expect_next(&[Token::Punctuation(Punctuation::OpenParen)])?
             .span;
@@ -293,6 +407,18 @@ impl Parser {
             Token::Punctuation(Punctuation::Comma),
             Token::Punctuation(Punctuation::CloseParen),
             |parser| {
+                if let Some(ellipsis_span) = variadic {
+                    return Err(Spanned {
+                        value: ParserError::VariadicNotLast,
+                        span: ellipsis_span,
+                    });
+                }
+
+                if parser.peek()?.value == Token::Punctuation(Punctuation::Ellipsis) {
+                    variadic = Some(parser.next_token()?.span);
+                    return Ok(());
+                }
+
                 let name =
                     parser.expect_next(&[Token::Identifier("parameter name".to_string())])?;
                 if let Spanned {
@@ -341,13 +467,16 @@ impl Parser {
 
         span = span.extend(&end_span);
 
-        Ok(Spanned {
-            value: params,
-            span,
-        })
+        Ok((
+            Spanned {
+                value: params,
+                span,
+            },
+            variadic,
+        ))
     }
 
-    fn parse_func_return_type(
+    pub(in crate::parser) fn parse_func_return_type(
         &mut self,
         generics: &Vec<Spanned<DataType>>,
     ) -> Result<Option<Spanned<DataType>>, Spanned<ParserError>> {
@@ -384,381 +513,461 @@ impl Parser {
         scope: &mut Block,
         caller: Option<&Spanned<TypedExpr>>,
     ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
-        if let Spanned {
-            value: Token::Identifier(mut function_name),
-            span,
-        } = self.expect_next(&[Token::Identifier("function name".to_string())])?
-        {
-            let mut generic_annotations = vec![];
-            let mut span = span;
+        let name = match self.expect_next(&[Token::Identifier("function name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
 
-            if Token::Operator(Operator::LessThan) == self.peek()?.value {
-                generic_annotations = self.collect_generic_annotations(Some(&scope.generics))?;
-            }
+        // freie funktionen können aus anderen dateien kommen
+        let function_name = match caller {
+            Some(_) => name.value.clone(),
+            None => self.resolve_function(&name)?,
+        };
 
-            let mut args_span = self
-                .expect_next(&[Token::Punctuation(Punctuation::OpenParen)])?
-                .span;
+        self.parse_func_call_named(scope, caller, name, function_name)
+    }
 
-            let mut function: Option<Result<Spanned<Function>, Spanned<ParserError>>> = None;
+    /// bei freien funktionen ist `function_name` schon der interne name
+    pub(in crate::parser) fn parse_func_call_named(
+        &mut self,
+        scope: &mut Block,
+        caller: Option<&Spanned<TypedExpr>>,
+        display_name: Spanned<String>,
+        mut function_name: String,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let span = display_name.span;
+        let mut generic_annotations = vec![];
+        let mut span = span;
 
-            let display_name = function_name.clone();
+        if Token::Operator(Operator::LessThan) == self.peek()?.value {
+            generic_annotations = self.collect_generic_annotations(Some(&scope.generics))?;
+        }
 
-            if let Some(caller) = &caller {
-                if let DataType::Custom(inner) = &caller.value._type {
-                    let class_name = inner.name.clone();
-                    let raw_function_name = function_name.clone();
-                    // zuerst nicht generische implementierung bekommen
-                    // foo(self) for Foo<int>
-                    function_name = format!("{}_{}", class_name, raw_function_name);
-                    let mut func_res = self
-                        .get_function(&Spanned {
-                            value: function_name.clone(),
-                            span,
-                        })
-                        .cloned();
-
-                    // wenn es keine implementierung gibt,
-                    // dann generische implementierung
-                    // foo(self) for Foo<T>
-                    if func_res.is_err() {
-                        if let Some(subtype_of) = &inner.subtype_of {
-                            function_name = format!("{}_{}", subtype_of, raw_function_name);
-
-                            func_res = self
-                                .get_function(&Spanned {
-                                    value: function_name.clone(),
-                                    span,
-                                })
-                                .cloned();
-                        }
-                    };
-                    function = Some(func_res);
-                } else {
-                    // eingebauter datentyp
-                    function_name =
-                        format!("{}_{}", caller.value._type.internal_name(), function_name);
-                    function = Some(
-                        self.get_function(&Spanned {
-                            value: function_name.clone(),
-                            span,
-                        })
-                        .cloned(),
-                    );
-                }
-            }
+        let mut args_span = self
+            .expect_next(&[Token::Punctuation(Punctuation::OpenParen)])?
+            .span;
 
-            let mut function = match function {
-                Some(Ok(function)) => function,
-                Some(Err(function_does_not_exist)) => {
-                    if let Some(caller) = caller {
-                        return Err(Spanned {
-                            value: ParserError::MethodDoesNotExist(
-                                display_name,
-                                caller.value._type.to_string(),
-                            ),
-                            span,
-                        });
-                    }
+        let mut function: Option<Result<Spanned<Function>, Spanned<ParserError>>> = None;
 
-                    return Err(function_does_not_exist);
-                }
-                // keine methode
-                None => self
+        let display_name = display_name.value;
+
+        // methode eines trait bounds, x.show() mit x: T
+        if let Some(caller) = caller {
+            if let DataType::Generic(_) = caller.value._type {
+                return self.parse_bound_method_call(
+                    scope,
+                    caller,
+                    Spanned {
+                        value: display_name,
+                        span,
+                    },
+                    args_span,
+                );
+            }
+        }
+
+        if let Some(caller) = &caller {
+            if let DataType::Custom(inner) = &caller.value._type {
+                let class_name = inner.name.clone();
+                let raw_function_name = function_name.clone();
+                // zuerst nicht generische implementierung bekommen
+                // foo(self) for Foo<int>
+                function_name = format!("{}_{}", class_name, raw_function_name);
+                let mut func_res = self
                     .get_function(&Spanned {
                         value: function_name.clone(),
                         span,
-                    })?
-                    .to_owned(),
-            };
-
-            let mut args = vec![];
-
-            if let Some(class) = caller {
-                if let Some(self_param) = function.value.params.value.get_mut(0) {
-                    // Generics
-                    if class.value._type == self_param.value._type.value
-                        || if let (DataType::Custom(self_arg), DataType::Custom(self_param)) =
-                            (&class.value._type, &self_param.value._type.value)
-                        {
-                            self_arg.subtype_of == Some(self_param.name.clone())
-                        } else {
-                            false
-                        }
-                    {
-                        if let Expr::ClassName(_) = class.value.expression {
-                            return Err(Spanned {
-                                value: ParserError::MethodIsNotStatic(
-                                    function_name,
-                                    class.value._type.to_string(),
-                                ),
+                    })
+                    .cloned();
+
+                // wenn es keine implementierung gibt,
+                // dann generische implementierung
+                // foo(self) for Foo<T>
+                if func_res.is_err() {
+                    if let Some(subtype_of) = &inner.subtype_of {
+                        function_name = format!("{}_{}", subtype_of, raw_function_name);
+
+                        func_res = self
+                            .get_function(&Spanned {
+                                value: function_name.clone(),
                                 span,
-                            });
-                        }
-
-                        args.push(Spanned {
-                            value: CallArg(class.value.clone()),
-                            span: class.span,
-                        });
+                            })
+                            .cloned();
                     }
-                } else if function.value.is_static_method()
-                    && !matches!(class.value.expression, Expr::ClassName(_))
-                {
+                };
+                function = Some(func_res);
+            } else {
+                // eingebauter datentyp
+                function_name = format!("{}_{}", caller.value._type.internal_name(), function_name);
+                function = Some(
+                    self.get_function(&Spanned {
+                        value: function_name.clone(),
+                        span,
+                    })
+                    .cloned(),
+                );
+            }
+        }
+
+        let mut function = match function {
+            Some(Ok(function)) => function,
+            Some(Err(function_does_not_exist)) => {
+                if let Some(caller) = caller {
                     return Err(Spanned {
-                        value: ParserError::MethodIsStatic(
-                            function_name,
-                            class.value._type.to_string(),
+                        value: ParserError::MethodDoesNotExist(
+                            display_name,
+                            caller.value._type.to_string(),
                         ),
                         span,
                     });
                 }
-            }
 
-            let end = self.walk_separated_values(
-                Token::Punctuation(Punctuation::Comma),
-                Token::Punctuation(Punctuation::CloseParen),
-                |parser| {
-                    let value = parser.parse_expression(scope)?;
-                    let arg = Spanned {
-                        value: CallArg(value.value),
-                        span: value.span,
-                    };
+                return Err(function_does_not_exist);
+            }
+            // keine methode
+            None => self
+                .get_function(&Spanned {
+                    value: function_name.clone(),
+                    span,
+                })?
+                .to_owned(),
+        };
 
-                    args_span = span.extend(&arg.span);
-                    args.push(arg);
+        let mut args = vec![];
 
-                    Ok(())
-                },
-            )?;
-
-            span = span.extend(&end);
-
-            if function.value.is_generic() {
-                //     generics_annotations = if !generics_annotations.is_empty() {
-                //         generics_annotations
-                //     } else {
-                //         let mut generics: Vec<DataType> = function
-                //             .value
-                //             .params
-                //             .value
-                //             .iter()
-                //             .map(|p| p.value._type.value.clone())
-                //             .collect();
-
-                //         if let Some(ref caller) = function.value.method_of {
-                //             generics.push(caller.clone());
-                //         }
-
-                //         let mut specifics: Vec<DataType> =
-                //             args.iter().map(|a| a.value.0._type.clone()).collect();
-
-                //         specifics.push(function.value.return_type.value.clone());
-
-                //         let w = inferred_generic_annotations(&specifics, &generics)
-                //             .into_iter()
-                //             .map(|a| Spanned {
-                //                 value: a,
-                //                 span: Span::default(),
-                //             })
-                //             .collect_vec();
-
-                //         // println!("w: {:?}", &w);
-                //         w
-                //     };
-
-                // if generics_annotations.len() != function.value.generic_param_count() {
-                //     return Err(Spanned {
-                //         value: ParserError::WrongGenericParamCount(
-                //             function.value.generic_param_count(),
-                //             generics_annotations.len(),
-                //         ),
-                //         span,
-                //     });
-                // }
-
-                // let mut specific_types: Vec<DataType> = generics_annotations
-                //     .iter()
-                //     .map(|g| g.value.clone())
-                //     .collect();
-
-                let mut extra_annotations = None;
-
-                let mut param_types = function
-                    .value
-                    .params
-                    .value
-                    .iter()
-                    .map(|p| p.value._type.value.clone())
-                    .collect::<Vec<DataType>>();
-
-                let return_type_generics = function.value.return_type.value.generics();
-                if !return_type_generics.is_empty() {
-                    param_types.push(function.value.return_type.value.clone());
-
-                    if generic_annotations.len() != return_type_generics.len() {
-                        // Return type sollte der einzige fall sein wo man annotations braucht
-                        // hoffentlich
+        if let Some(class) = caller {
+            if let Some(self_param) = function.value.params.value.get_mut(0) {
+                // Generics
+                if class.value._type == self_param.value._type.value
+                    || if let (DataType::Custom(self_arg), DataType::Custom(self_param)) =
+                        (&class.value._type, &self_param.value._type.value)
+                    {
+                        self_arg.subtype_of == Some(self_param.name.clone())
+                    } else {
+                        false
+                    }
+                {
+                    if let Expr::ClassName(_) = class.value.expression {
                         return Err(Spanned {
-                            value: ParserError::WrongGenericParamCount(
-                                return_type_generics.len(),
-                                generic_annotations.len(),
+                            value: ParserError::MethodIsNotStatic(
+                                function_name,
+                                class.value._type.to_string(),
                             ),
                             span,
                         });
                     }
 
-                    let mut annotation_map: HashMap<String, DataType> = HashMap::new();
+                    args.push(Spanned {
+                        value: CallArg(class.value.clone()),
+                        span: class.span,
+                    });
+                }
+            } else if function.value.is_static_method()
+                && !matches!(class.value.expression, Expr::ClassName(_))
+            {
+                return Err(Spanned {
+                    value: ParserError::MethodIsStatic(
+                        function_name,
+                        class.value._type.to_string(),
+                    ),
+                    span,
+                });
+            }
+        }
 
-                    for (annotated, return_type_generic_name) in
-                        generic_annotations.iter().zip(return_type_generics.iter())
-                    {
-                        annotation_map
-                            .insert(return_type_generic_name.clone(), annotated.value.clone());
-                    }
+        let end = self.walk_separated_values(
+            Token::Punctuation(Punctuation::Comma),
+            Token::Punctuation(Punctuation::CloseParen),
+            |parser| {
+                let value = parser.parse_expression(scope)?;
+                let arg = Spanned {
+                    value: CallArg(value.value),
+                    span: value.span,
+                };
 
-                    extra_annotations = Some(annotation_map);
-                }
+                args_span = span.extend(&arg.span);
+                args.push(arg);
 
-                // if !generic_annotations.is_empty() {
-                //     if generic_annotations.len() != function.value.generic_param_count() {
-                //         return Err(Spanned {
-                //             value: ParserError::WrongGenericParamCount(
-                //                 function.value.generic_param_count(),
-                //                 generic_annotations.len(),
-                //             ),
-                //             span,
-                //         });
-                //     }
-
-                //     for (param_type, annotation) in param_types.iter_mut().zip(generic_annotations.iter()) {
-                //         let to_specify = param_type._type_mut();
-                //         // specify_generics(type_settables, generics, self, true);
-                //     }
-                // }
-
-                let mut specific_types: Vec<DataType> =
-                    args.iter().map(|a| a.value.0._type.clone()).collect();
-                specific_types.push(function.value.return_type.value.clone());
-
-                let (specified_generics, mut map) =
-                    handle_generics(&specific_types, &param_types, extra_annotations.as_ref());
-
-                if let Some(extra_annotations) = extra_annotations {
-                    for (key, value) in extra_annotations {
-                        map.insert(key, value);
-                    }
-                }
+                Ok(())
+            },
+        )?;
 
-                // } else {
-                //     if generics_annotations.len() != function.value.generic_param_count() {
-                //         return Err(Spanned {
-                //             value: ParserError::WrongGenericParamCount(
-                //                 function.value.generic_param_count(),
-                //                 generics_annotations.len(),
-                //             ),
-                //             span,
-                //         });
-                //     }
+        span = span.extend(&end);
+
+        if function.value.is_generic() {
+            //     generics_annotations = if !generics_annotations.is_empty() {
+            //         generics_annotations
+            //     } else {
+            //         let mut generics: Vec<DataType> = function
+            //             .value
+            //             .params
+            //             .value
+            //             .iter()
+            //             .map(|p| p.value._type.value.clone())
+            //             .collect();
+
+            //         if let Some(ref caller) = function.value.method_of {
+            //             generics.push(caller.clone());
+            //         }
+
+            //         let mut specifics: Vec<DataType> =
+            //             args.iter().map(|a| a.value.0._type.clone()).collect();
+
+            //         specifics.push(function.value.return_type.value.clone());
+
+            //         let w = inferred_generic_annotations(&specifics, &generics)
+            //             .into_iter()
+            //             .map(|a| Spanned {
+            //                 value: a,
+            //                 span: Span::default(),
+            //             })
+            //             .collect_vec();
+
+            //         // println!("w: {:?}", &w);
+            //         w
+            //     };
+
+            // if generics_annotations.len() != function.value.generic_param_count() {
+            //     return Err(Spanned {
+            //         value: ParserError::WrongGenericParamCount(
+            //             function.value.generic_param_count(),
+            //             generics_annotations.len(),
+            //         ),
+            //         span,
+            //     });
+            // }
 
-                //     println!("GEN: {:?}", generics_annotations);
-                //     println!("PARAM: {:?}", param_types);
+            // let mut specific_types: Vec<DataType> = generics_annotations
+            //     .iter()
+            //     .map(|g| g.value.clone())
+            //     .collect();
 
-                //     handle_generics(&generics_annotations.iter().map(|g| g.value.clone()).collect::<Vec<DataType>>(), &param_types)
-                // };
+            let mut extra_annotations = None;
 
-                // println!("MAP: {:?}", map);
+            let mut param_types = function
+                .value
+                .params
+                .value
+                .iter()
+                .map(|p| p.value._type.value.clone())
+                .collect::<Vec<DataType>>();
 
-                // specific_types.push(function.value.return_type.value.clone());
+            let return_type_generics = function.value.return_type.value.generics();
+            if !return_type_generics.is_empty() {
+                param_types.push(function.value.return_type.value.clone());
 
-                // println!("FF: {:?}", specific_types);
+                if generic_annotations.len() != return_type_generics.len() {
+                    // Return type sollte der einzige fall sein wo man annotations braucht
+                    // hoffentlich
+                    return Err(Spanned {
+                        value: ParserError::WrongGenericParamCount(
+                            return_type_generics.len(),
+                            generic_annotations.len(),
+                        ),
+                        span,
+                    });
+                }
 
-                // println!("specific types: {:?}", specific_types);
+                let mut annotation_map: HashMap<String, DataType> = HashMap::new();
 
-                let subtype = match function
-                    .value
-                    .generic_subtypes
-                    .get(&specific_types)
-                    .cloned()
+                for (annotated, return_type_generic_name) in
+                    generic_annotations.iter().zip(return_type_generics.iter())
                 {
-                    Some(subtype) => subtype,
-                    None => {
-                        // println!("SDASDSADAS");
-                        // println!("QDQDQ: {:?}", specific_types);
-                        let caller_type = caller.as_ref().map(|c| &c.value._type);
-                        let subtype =
-                            function
-                                .value
-                                .subtype(&map, caller_type, &mut self.program, true);
+                    annotation_map
+                        .insert(return_type_generic_name.clone(), annotated.value.clone());
+                }
 
-                        let function_ref = self.program.functions.get_mut(&function_name).unwrap();
+                extra_annotations = Some(annotation_map);
+            }
 
-                        function_ref
-                            .value
-                            .generic_subtypes
-                            .insert(specified_generics, subtype.clone());
+            // if !generic_annotations.is_empty() {
+            //     if generic_annotations.len() != function.value.generic_param_count() {
+            //         return Err(Spanned {
+            //             value: ParserError::WrongGenericParamCount(
+            //                 function.value.generic_param_count(),
+            //                 generic_annotations.len(),
+            //             ),
+            //             span,
+            //         });
+            //     }
+
+            //     for (param_type, annotation) in param_types.iter_mut().zip(generic_annotations.iter()) {
+            //         let to_specify = param_type._type_mut();
+            //         // specify_generics(type_settables, generics, self, true);
+            //     }
+            // }
 
-                        subtype
-                    }
-                };
+            let mut specific_types: Vec<DataType> =
+                args.iter().map(|a| a.value.0._type.clone()).collect();
+            specific_types.push(function.value.return_type.value.clone());
+
+            let (specified_generics, mut map) =
+                handle_generics(&specific_types, &param_types, extra_annotations.as_ref());
 
-                function.value = subtype;
+            if let Some(extra_annotations) = extra_annotations {
+                for (key, value) in extra_annotations {
+                    map.insert(key, value);
+                }
             }
 
-            // TODO: https://stackoverflow.com/questions/38163675/iterate-two-vectors-with-different-lengths
-            for (param, arg) in function
-                .value
-                .params
-                .value
-                .iter()
-                .map(|p| p.value.clone())
-                .zip(args.iter())
-            {
-                if param._type.value != arg.value.0._type
-                // && !arg.value.0._type.can_be_converted_to(&param._type.value)
-                {
-                    return Err(Spanned {
-                        value: ParserError::WrongType(param._type.value, arg.value.0._type.clone()),
-                        span: arg.span,
-                    });
+            // bounds beim aufruf prüfen, nicht erst nach specify_generics
+            for (generic, bounds) in function.value.body.value.generic_bounds.iter() {
+                if let Some(specific) = map.get(generic) {
+                    for bound in bounds {
+                        if !self.satisfies_bound(specific, bound, scope) {
+                            return Err(Spanned {
+                                value: ParserError::TraitNotImplemented(
+                                    bound.clone(),
+                                    specific.clone(),
+                                ),
+                                span,
+                            });
+                        }
+                    }
                 }
             }
 
-            if function.value.params.value.len() != args.len() {
-                return Err(Spanned {
-                    value: ParserError::WrongArguments(
+            // } else {
+            //     if generics_annotations.len() != function.value.generic_param_count() {
+            //         return Err(Spanned {
+            //             value: ParserError::WrongGenericParamCount(
+            //                 function.value.generic_param_count(),
+            //                 generics_annotations.len(),
+            //             ),
+            //             span,
+            //         });
+            //     }
+
+            //     println!("GEN: {:?}", generics_annotations);
+            //     println!("PARAM: {:?}", param_types);
+
+            //     handle_generics(&generics_annotations.iter().map(|g| g.value.clone()).collect::<Vec<DataType>>(), &param_types)
+            // };
+
+            // println!("MAP: {:?}", map);
+
+            // specific_types.push(function.value.return_type.value.clone());
+
+            // println!("FF: {:?}", specific_types);
+
+            // println!("specific types: {:?}", specific_types);
+
+            let subtype = match function
+                .value
+                .generic_subtypes
+                .get(&specific_types)
+                .cloned()
+            {
+                Some(subtype) => subtype,
+                None => {
+                    // println!("SDASDSADAS");
+                    // println!("QDQDQ: {:?}", specific_types);
+                    let caller_type = caller.as_ref().map(|c| &c.value._type);
+                    let subtype =
                         function
                             .value
-                            .params
-                            .value
-                            .into_iter()
-                            .map(|p| p.value._type.value.to_string())
-                            .collect(),
-                        args.into_iter()
-                            .map(|a| a.value.0._type.to_string())
-                            .collect(),
-                    ),
-                    span: args_span,
+                            .subtype(&map, caller_type, &mut self.program, true);
+
+                    let function_ref = self.program.functions.get_mut(&function_name).unwrap();
+
+                    function_ref
+                        .value
+                        .generic_subtypes
+                        .insert(specified_generics, subtype.clone());
+
+                    subtype
+                }
+            };
+
+            function.value = subtype;
+        }
+
+        // TODO: https://stackoverflow.com/questions/38163675/iterate-two-vectors-with-different-lengths
+        for (param, arg) in function
+            .value
+            .params
+            .value
+            .iter()
+            .map(|p| p.value.clone())
+            .zip(args.iter())
+        {
+            if param._type.value != arg.value.0._type
+            // && !arg.value.0._type.can_be_converted_to(&param._type.value)
+            {
+                return Err(Spanned {
+                    value: ParserError::WrongType(param._type.value, arg.value.0._type.clone()),
+                    span: arg.span,
                 });
             }
+        }
+
+        let param_count = function.value.params.value.len();
+        let variadic_args = function.value.is_variadic && args.len() >= param_count;
+
+        if param_count != args.len() && !variadic_args {
+            return Err(Spanned {
+                value: ParserError::WrongArguments(
+                    function
+                        .value
+                        .params
+                        .value
+                        .into_iter()
+                        .map(|p| p.value._type.value.to_string())
+                        .collect(),
+                    args.into_iter()
+                        .map(|a| a.value.0._type.to_string())
+                        .collect(),
+                ),
+                span: args_span,
+            });
+        }
 
-            let out = Ok(Spanned {
-                value: TypedExpr {
-                    expression: Expr::Call {
-                        function: function.clone(),
-                        args: Spanned {
-                            value: args,
-                            span: args_span,
+        // argumente für `...` werden wie in c erweitert
+        for arg in args.iter_mut().skip(param_count) {
+            let promoted = match arg.value.0._type.variadic_promotion() {
+                Some(promoted) => promoted,
+                None => {
+                    return Err(Spanned {
+                        value: ParserError::InvalidVariadicArg(arg.value.0._type.clone()),
+                        span: arg.span,
+                    })
+                }
+            };
+
+            if promoted != arg.value.0._type {
+                arg.value.0 = TypedExpr {
+                    expression: Expr::Cast {
+                        value: Box::new(Spanned {
+                            value: arg.value.0.clone(),
+                            span: arg.span,
+                        }),
+                        to_type: Spanned {
+                            value: promoted.clone(),
+                            span: arg.span,
                         },
                     },
-                    _type: function.value.return_type.value,
+                    _type: promoted,
                     raw: None,
-                },
-                span,
-            });
-
-            return out;
+                };
+            }
         }
 
-        unreachable!()
+        Ok(Spanned {
+            value: TypedExpr {
+                expression: Expr::Call {
+                    function: function.clone(),
+                    args: Spanned {
+                        value: args,
+                        span: args_span,
+                    },
+                },
+                _type: function.value.return_type.value,
+                raw: None,
+            },
+            span,
+        })
     }
 }
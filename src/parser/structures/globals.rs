@@ -0,0 +1,285 @@
+use crate::{
+    lexer::{
+        position::Spanned,
+        tokens::{Keyword, Literal, Punctuation, Token},
+    },
+    parser::{
+        ast::{BinaryOperator, Block, DataType, Expr, Global, TypedExpr, Variable},
+        error::ParserError,
+        parser_main::Parser,
+    },
+};
+
+impl Parser {
+    /// `const NAME: int64 = 1 + 2;` oder `static mut NAME: int64 = 0;`
+    pub(in crate::parser) fn parse_global(
+        &mut self,
+    ) -> Result<Spanned<Global>, Spanned<ParserError>> {
+        let start = self.expect_next(&[
+            Token::Keyword(Keyword::Const),
+            Token::Keyword(Keyword::Static),
+        ])?;
+        let is_const = start.value == Token::Keyword(Keyword::Const);
+
+        let mut is_mutable = false;
+        if !is_const && self.peek()?.value == Token::Keyword(Keyword::Mut) {
+            self.next_token()?;
+            is_mutable = true;
+        }
+
+        let name = match self.expect_next(&[Token::Identifier("global name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        let internal_name = match &self.module_prefix {
+            Some(prefix) => format!("{prefix}.{}", name.value),
+            None => name.value.clone(),
+        };
+
+        if self.program.globals.contains_key(&internal_name)
+            || self.visible_globals.contains_key(&name.value)
+        {
+            return Err(Spanned {
+                value: ParserError::GlobalAlreadyExists(name.value),
+                span: name.span,
+            });
+        }
+
+        self.expect_next(&[Token::Punctuation(Punctuation::Colon)])?;
+        let _type = self.parse_data_type(None)?;
+        self.expect_next(&[Token::Assignment])?;
+
+        // globale werte sehen keine lokalen variablen
+        let value = self.parse_expression(&mut Block::default())?;
+        let end = self.expect_next(&[Token::Punctuation(Punctuation::SemiColon)])?;
+
+        if _type.value != value.value._type {
+            return Err(Spanned {
+                value: ParserError::WrongType(_type.value, value.value._type),
+                span: _type.span.extend(&value.span),
+            });
+        }
+
+        let literal = match self.evaluate_constant(&value.value) {
+            Some(literal) => literal,
+            None => {
+                return Err(Spanned {
+                    value: ParserError::NotConstant,
+                    span: value.span,
+                })
+            }
+        };
+
+        let global = Spanned {
+            value: Global {
+                name: name.clone(),
+                _type,
+                value: literal,
+                is_mutable,
+                is_const,
+            },
+            span: start.span.extend(&end.span),
+        };
+
+        self.visible_globals
+            .insert(name.value, internal_name.clone());
+        self.program.globals.insert(internal_name, global.clone());
+        Ok(global)
+    }
+
+    /// lokale variable oder, falls es keine gibt, globale
+    pub(in crate::parser) fn lookup_variable(
+        &self,
+        scope: &Block,
+        name: &Spanned<String>,
+    ) -> Result<Spanned<Variable>, Spanned<ParserError>> {
+        match scope.get_variable(name) {
+            Ok(variable) => Ok(variable),
+            Err(err) => {
+                // bereits aufgelöste namen (z.b. bei `X = ...`) sind schon intern
+                let internal_name = match self.visible_globals.get(&name.value) {
+                    Some(internal_name) => internal_name,
+                    None if self.program.globals.contains_key(&name.value) => &name.value,
+                    None => return Err(err),
+                };
+
+                Ok(Spanned {
+                    value: self.global_variable(internal_name),
+                    span: name.span,
+                })
+            }
+        }
+    }
+
+    /// die variable heißt wie der interne name, damit codegen sie findet
+    pub(in crate::parser) fn global_variable(&self, internal_name: &str) -> Variable {
+        let global = &self.program.globals[internal_name].value;
+        Variable {
+            name: Spanned {
+                value: internal_name.to_string(),
+                span: global.name.span,
+            },
+            is_mutable: global.is_mutable,
+            _type: global._type.value.clone(),
+        }
+    }
+
+    /// wertet den ausdruck zur compile zeit aus, `None` wenn das nicht geht
+    fn evaluate_constant(&self, expr: &TypedExpr) -> Option<Literal> {
+        match &expr.expression {
+            Expr::Literal(
+                literal @ (Literal::Integer(_) | Literal::Float(_) | Literal::Boolean(_)),
+            ) => Some(literal.clone()),
+            Expr::Variable(variable) => match self.program.globals.get(&variable.name.value) {
+                Some(global) if global.value.is_const => Some(global.value.value.clone()),
+                _ => None,
+            },
+            Expr::Cast { value, to_type } => {
+                let literal = self.evaluate_constant(&value.value)?;
+                cast_constant(literal, &value.value._type, &to_type.value)
+            }
+            Expr::Binary { lhs, op, rhs } => {
+                let left = self.evaluate_constant(&lhs.value)?;
+                let right = self.evaluate_constant(&rhs.value)?;
+                binary_constant(left, &op.value, right, &lhs.value._type)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn bit_width(_type: &DataType) -> Option<u32> {
+    match _type {
+        DataType::Integer8 | DataType::UnsignedInteger8 => Some(8),
+        DataType::Integer16 | DataType::UnsignedInteger16 => Some(16),
+        DataType::Integer32 | DataType::UnsignedInteger32 => Some(32),
+        DataType::Integer64 | DataType::UnsignedInteger64 => Some(64),
+        _ => None,
+    }
+}
+
+/// der wert wie ihn der typ sieht, bei unsigned also nie negativ
+fn integer_value(value: isize, _type: &DataType) -> Option<i128> {
+    let bits = bit_width(_type)?;
+    if _type.is_unsigned_integer() {
+        Some(value as i128 & ((1i128 << bits) - 1))
+    } else {
+        Some(value as i128)
+    }
+}
+
+/// schneidet auf die breite des typs ab (wie llvm), gespeichert wird im zweierkomplement
+fn wrap_integer(value: i128, _type: &DataType) -> Option<Literal> {
+    let shift = 128 - bit_width(_type)?;
+    Some(Literal::Integer(((value << shift) >> shift) as isize))
+}
+
+fn float_value(value: f64, _type: &DataType) -> Literal {
+    match _type {
+        DataType::Float => Literal::Float(value as f32 as f64),
+        _ => Literal::Float(value),
+    }
+}
+
+fn cast_constant(literal: Literal, from: &DataType, to: &DataType) -> Option<Literal> {
+    match literal {
+        Literal::Integer(value) => {
+            let value = integer_value(value, from)?;
+            if to.is_integer() {
+                wrap_integer(value, to)
+            } else if to.is_float() {
+                Some(float_value(value as f64, to))
+            } else if to.is_boolean() {
+                Some(Literal::Boolean(value != 0))
+            } else {
+                None
+            }
+        }
+        Literal::Float(value) => {
+            if to.is_integer() {
+                wrap_integer(value as i128, to)
+            } else if to.is_float() {
+                Some(float_value(value, to))
+            } else {
+                None
+            }
+        }
+        Literal::Boolean(value) => {
+            if to.is_integer() {
+                wrap_integer(value as i128, to)
+            } else if to.is_boolean() {
+                Some(Literal::Boolean(value))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn binary_constant(
+    left: Literal,
+    op: &BinaryOperator,
+    right: Literal,
+    _type: &DataType,
+) -> Option<Literal> {
+    match (left, right) {
+        (Literal::Integer(left), Literal::Integer(right)) => {
+            let left = integer_value(left, _type)?;
+            let right = integer_value(right, _type)?;
+            let bits = bit_width(_type)?;
+
+            let value = match op {
+                BinaryOperator::Add => left + right,
+                BinaryOperator::Subtract => left - right,
+                BinaryOperator::Multiply => left.wrapping_mul(right),
+                BinaryOperator::Divide if right != 0 => left / right,
+                BinaryOperator::Modulo if right != 0 => left % right,
+                BinaryOperator::BitAnd => left & right,
+                BinaryOperator::BitOr => left | right,
+                BinaryOperator::BitXor => left ^ right,
+                BinaryOperator::ShiftLeft if (0..bits as i128).contains(&right) => left << right,
+                // unsigned werte sind hier schon positiv, das ist also automatisch lshr
+                BinaryOperator::ShiftRight if (0..bits as i128).contains(&right) => left >> right,
+                BinaryOperator::Equal => return Some(Literal::Boolean(left == right)),
+                BinaryOperator::NotEqual => return Some(Literal::Boolean(left != right)),
+                BinaryOperator::LessThan => return Some(Literal::Boolean(left < right)),
+                BinaryOperator::LessThanOrEqual => return Some(Literal::Boolean(left <= right)),
+                BinaryOperator::GreaterThan => return Some(Literal::Boolean(left > right)),
+                BinaryOperator::GreaterThanOrEqual => return Some(Literal::Boolean(left >= right)),
+                _ => return None,
+            };
+
+            wrap_integer(value, _type)
+        }
+        (Literal::Float(left), Literal::Float(right)) => {
+            let value = match op {
+                BinaryOperator::Add => left + right,
+                BinaryOperator::Subtract => left - right,
+                BinaryOperator::Multiply => left * right,
+                BinaryOperator::Divide => left / right,
+                BinaryOperator::Equal => return Some(Literal::Boolean(left == right)),
+                BinaryOperator::NotEqual => return Some(Literal::Boolean(left != right)),
+                BinaryOperator::LessThan => return Some(Literal::Boolean(left < right)),
+                BinaryOperator::LessThanOrEqual => return Some(Literal::Boolean(left <= right)),
+                BinaryOperator::GreaterThan => return Some(Literal::Boolean(left > right)),
+                BinaryOperator::GreaterThanOrEqual => return Some(Literal::Boolean(left >= right)),
+                _ => return None,
+            };
+
+            Some(float_value(value, _type))
+        }
+        (Literal::Boolean(left), Literal::Boolean(right)) => match op {
+            BinaryOperator::And => Some(Literal::Boolean(left && right)),
+            BinaryOperator::Or => Some(Literal::Boolean(left || right)),
+            BinaryOperator::Equal => Some(Literal::Boolean(left == right)),
+            BinaryOperator::NotEqual => Some(Literal::Boolean(left != right)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
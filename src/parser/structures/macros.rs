@@ -173,7 +173,10 @@ impl Parser {
 }
 
 // TODO: reqs auch mit funktionsargumenten sonst möglicher panic :221
-fn check_macro_requirements(parser: &mut Parser, reqs: (&str, &[&str])) -> Result<(), ParserError> {
+pub(in crate::parser) fn check_macro_requirements(
+    parser: &mut Parser,
+    reqs: (&str, &[&str]),
+) -> Result<(), ParserError> {
     let (class, methods) = reqs;
 
     // TODO: builtins + methoden argumente
@@ -209,7 +212,7 @@ fn block_parse(code: &str, ctx: &mut Program) -> TypedExpr {
     parser.program = ctx.clone();
     let mut scope = Block::default();
     // hier vielleicht panic
-    let block = parser.parse_block(&mut scope).unwrap();
+    let block = parser.parse_value_block(&mut scope).unwrap();
     let _span = block.span;
     let _type = block.value.return_type.clone();
 
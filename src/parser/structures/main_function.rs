@@ -0,0 +1,103 @@
+use itertools::peek_nth;
+
+use crate::{
+    lexer::{lexer_main::lex, position::Spanned},
+    parser::{
+        ast::{DataType, Function, MAIN_ARGS_FUNCTION_NAME},
+        error::ParserError,
+        parser_main::Parser,
+        structures::macros::check_macro_requirements,
+    },
+};
+
+/// typ des parameters von `def main(args: List<String>)`
+const MAIN_ARGS_TYPE: &str = "List<String>";
+
+impl Parser {
+    /// `def main() -> int64` oder `def main(args: List<String>) -> int64`
+    ///
+    /// codegen erzeugt daraus ein `main(i32, i8**)` für c,
+    /// bei `args` wird die liste von einer generierten funktion gebaut
+    pub(in crate::parser) fn check_main(
+        &mut self,
+        main: Spanned<Function>,
+    ) -> Result<(), Spanned<ParserError>> {
+        let return_type = &main.value.return_type.value;
+        let valid_return = return_type.is_integer()
+            || *return_type == DataType::Boolean
+            || *return_type == DataType::None;
+
+        let params = &main.value.params;
+        let takes_args = match params.value.as_slice() {
+            [] => false,
+            [param] if param.value._type.value.to_string() == MAIN_ARGS_TYPE => true,
+            _ => {
+                return Err(Spanned {
+                    value: ParserError::InvalidMainSignature,
+                    span: params.span,
+                })
+            }
+        };
+
+        if !valid_return {
+            return Err(Spanned {
+                value: ParserError::InvalidMainSignature,
+                span: main.value.return_type.span,
+            });
+        }
+
+        if takes_args {
+            check_macro_requirements(self, ("List", &["new", "push"]))
+                .and_then(|_| check_macro_requirements(self, ("String", &["from_c_string"])))
+                .map_err(|err| Spanned {
+                    value: err,
+                    span: params.span,
+                })?;
+
+            self.parse_generated(&main_args_code())?;
+        }
+
+        Ok(())
+    }
+
+    /// parst vom compiler erzeugten code, als stünde er am ende der datei
+    fn parse_generated(&mut self, code: &str) -> Result<(), Spanned<ParserError>> {
+        let tokens = lex(code).expect("generated code should always lex");
+        let tokens = std::mem::replace(&mut self.tokens, peek_nth(tokens));
+
+        while self.peek().is_ok() {
+            self.parse_func_def()?;
+        }
+
+        self.tokens = tokens;
+        Ok(())
+    }
+}
+
+/// `std/env.mx`, der body von `args()` baut auch die liste für `main`
+const ENV_SOURCE: &str = include_str!("../../../example/std/env.mx");
+const ENV_ARGS_SIGNATURE: &str = "pub def args() -> List<String>";
+
+/// `mx_argc` und `mx_argv` werden von codegen zusammen mit `main(i32, i8**)` erzeugt
+fn main_args_code() -> String {
+    let start = ENV_SOURCE
+        .find(ENV_ARGS_SIGNATURE)
+        .expect("std/env.mx should define args()");
+    let end = start
+        + ENV_SOURCE[start..]
+            .find("\n}")
+            .expect("args() in std/env.mx should be closed")
+        + "\n}".len();
+    let args = ENV_SOURCE[start..end].replacen(
+        ENV_ARGS_SIGNATURE,
+        &format!("def {MAIN_ARGS_FUNCTION_NAME}() -> {MAIN_ARGS_TYPE}"),
+        1,
+    );
+
+    format!(
+        "extern def mx_argc() -> int32
+        extern def mx_argv() -> *int64
+
+        {args}"
+    )
+}
@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+
+use crate::{
+    lexer::{
+        position::Spanned,
+        tokens::{Keyword, Punctuation, Token},
+    },
+    parser::{
+        ast::{Block, CustomDataType, DataType, Expr, MatchArm, MatchPattern, TypedExpr, Variable},
+        error::ParserError,
+        parser_main::Parser,
+    },
+};
+
+impl Parser {
+    /*
+        match shape {
+            Shape::Circle(r) => r * r * 3.14,
+            Shape::Rect(w, h) => w * h,
+            _ => 0.0,
+        }
+    */
+    pub(in crate::parser) fn parse_match(
+        &mut self,
+        scope: &mut Block,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let start = self.expect_next(&[Token::Keyword(Keyword::Match)])?.span;
+        let value = self.parse_expression(scope)?;
+
+        let enum_type = match &value.value._type {
+            DataType::Custom(custom_type) if custom_type.is_enum() => custom_type.clone(),
+            other => {
+                return Err(Spanned {
+                    value: ParserError::NotAnEnum(other.clone()),
+                    span: value.span,
+                })
+            }
+        };
+
+        self.expect_next(&[Token::Punctuation(Punctuation::OpenBrace)])?;
+
+        let mut arms: Vec<Spanned<MatchArm>> = vec![];
+        let mut covered = HashSet::new();
+        let mut has_wildcard = false;
+        let mut result_type: Option<DataType> = None;
+
+        let end =
+            self.walk_to_terminator(Token::Punctuation(Punctuation::CloseBrace), |parser| {
+                let arm = parser.parse_match_arm(scope, &enum_type)?;
+
+                // nach `_` oder einem zweiten arm für die variante läuft der arm nie
+                let pattern = &arm.value.pattern;
+                let is_new = match &pattern.value {
+                    MatchPattern::Variant { variant_idx, .. } => covered.insert(*variant_idx),
+                    MatchPattern::Wildcard => true,
+                };
+                if has_wildcard || !is_new {
+                    let name = match &pattern.value {
+                        MatchPattern::Variant { variant, .. } => {
+                            format!("{}::{}", enum_type.base_name(), variant.value)
+                        }
+                        MatchPattern::Wildcard => "_".to_string(),
+                    };
+                    return Err(Spanned {
+                        value: ParserError::UnreachableMatchArm(name),
+                        span: pattern.span,
+                    });
+                }
+                if let MatchPattern::Wildcard = pattern.value {
+                    has_wildcard = true;
+                }
+
+                // alle arms müssen den gleichen typ haben
+                let arm_type = arm.value.body.value._type.clone();
+                let expected = result_type.get_or_insert_with(|| arm_type.clone());
+                if *expected != arm_type {
+                    return Err(Spanned {
+                        value: ParserError::WrongType(expected.clone(), arm_type),
+                        span: arm.value.body.span,
+                    });
+                }
+
+                arms.push(arm);
+
+                if let Token::Punctuation(Punctuation::Comma) = parser.peek()?.value {
+                    parser.next_token()?;
+                }
+
+                Ok(())
+            })?;
+
+        let span = start.extend(&end);
+
+        if !has_wildcard {
+            let missing = enum_type
+                .variants
+                .iter()
+                .flatten()
+                .enumerate()
+                .filter(|(idx, _)| !covered.contains(idx))
+                .map(|(_, v)| v.name.value.clone())
+                .collect::<Vec<_>>();
+
+            if !missing.is_empty() {
+                return Err(Spanned {
+                    value: ParserError::NonExhaustiveMatch(missing),
+                    span,
+                });
+            }
+        }
+
+        Ok(Spanned {
+            value: TypedExpr {
+                expression: Expr::Match {
+                    value: Box::new(value),
+                    arms,
+                },
+                _type: result_type.unwrap_or_default(),
+                raw: None,
+            },
+            span,
+        })
+    }
+
+    fn parse_match_arm(
+        &mut self,
+        scope: &mut Block,
+        enum_type: &CustomDataType,
+    ) -> Result<Spanned<MatchArm>, Spanned<ParserError>> {
+        let enum_name = match self.expect_next(&[Token::Identifier("pattern".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        // die gebundenen variablen sind nur innerhalb des arms sichtbar
+        let mut arm_scope = scope.clone();
+
+        let pattern = if enum_name.value == "_" {
+            Spanned {
+                value: MatchPattern::Wildcard,
+                span: enum_name.span,
+            }
+        } else {
+            if enum_name.value != enum_type.base_name() {
+                return Err(Spanned {
+                    value: ParserError::UnexpectedTokenExpected(
+                        Token::Identifier(enum_type.base_name().to_string()),
+                        Token::Identifier(enum_name.value),
+                    ),
+                    span: enum_name.span,
+                });
+            }
+
+            self.expect_next(&[Token::Punctuation(Punctuation::Colon)])?;
+            self.expect_next(&[Token::Punctuation(Punctuation::Colon)])?;
+
+            let variant =
+                match self.expect_next(&[Token::Identifier("variant name".to_string())])? {
+                    Spanned {
+                        value: Token::Identifier(name),
+                        span,
+                    } => Spanned { value: name, span },
+                    _ => unreachable!(),
+                };
+
+            let (variant_idx, variant_def) = match enum_type.get_variant(&variant.value) {
+                Some((idx, variant_def)) => (idx, variant_def.clone()),
+                None => {
+                    return Err(Spanned {
+                        value: ParserError::VariantDoesNotExist(
+                            variant.value,
+                            enum_type.display_name.clone(),
+                        ),
+                        span: variant.span,
+                    })
+                }
+            };
+
+            let mut span = enum_name.span.extend(&variant.span);
+            let mut bindings: Vec<Spanned<String>> = vec![];
+
+            if let Token::Punctuation(Punctuation::OpenParen) = self.peek()?.value {
+                self.next_token()?;
+                let end = self.walk_separated_values(
+                    Token::Punctuation(Punctuation::Comma),
+                    Token::Punctuation(Punctuation::CloseParen),
+                    |parser| {
+                        match parser.expect_next(&[Token::Identifier("binding".to_string())])? {
+                            Spanned {
+                                value: Token::Identifier(name),
+                                span,
+                            } => bindings.push(Spanned { value: name, span }),
+                            _ => unreachable!(),
+                        };
+                        Ok(())
+                    },
+                )?;
+                span = span.extend(&end);
+            }
+
+            let payload_types = enum_type.variant_types(&variant_def);
+
+            if payload_types.len() != bindings.len() {
+                return Err(Spanned {
+                    value: ParserError::WrongArguments(
+                        payload_types.iter().map(|t| t.to_string()).collect(),
+                        bindings.iter().map(|b| b.value.clone()).collect(),
+                    ),
+                    span,
+                });
+            }
+
+            for (binding, _type) in bindings.iter().zip(payload_types) {
+                if binding.value == "_" {
+                    continue;
+                }
+
+                arm_scope.variables.insert(
+                    binding.value.clone(),
+                    Spanned {
+                        value: Variable {
+                            name: binding.clone(),
+                            is_mutable: false,
+                            _type,
+                        },
+                        span: binding.span,
+                    },
+                );
+            }
+
+            Spanned {
+                value: MatchPattern::Variant {
+                    variant,
+                    variant_idx,
+                    bindings,
+                },
+                span,
+            }
+        };
+
+        self.expect_next(&[Token::Punctuation(Punctuation::FatArrow)])?;
+        let body = self.parse_expression(&mut arm_scope)?;
+        let span = pattern.span.extend(&body.span);
+
+        Ok(Spanned {
+            value: MatchArm { pattern, body },
+            span,
+        })
+    }
+}
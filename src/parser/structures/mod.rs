@@ -1,11 +1,18 @@
 pub mod arrays;
 pub mod classes;
+pub mod closures;
+pub mod enums;
 pub mod expressions;
+pub mod r#for;
 pub mod functions;
+pub mod globals;
 pub mod r#if;
 pub mod macros;
+pub mod main_function;
+pub mod r#match;
 pub mod references;
 pub mod r#return;
+pub mod traits;
 pub mod type_cast;
 pub mod variables;
 pub mod r#while;
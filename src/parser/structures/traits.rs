@@ -0,0 +1,492 @@
+use itertools::Itertools;
+
+use crate::{
+    lexer::{
+        position::{Span, Spanned},
+        tokens::{Keyword, Operator, Punctuation, Token},
+    },
+    parser::{
+        ast::{
+            Block, CallArg, DataType, DataTypeSetter, Expr, Function, FunctionParam, Trait,
+            TraitDecl, TraitMethod, TypedExpr, CLASS_SELF_ARG_NAME, TRAIT_SELF_TYPE_NAME,
+        },
+        error::ParserError,
+        parser_main::Parser,
+        utils::{handle_generics, specify_generics},
+    },
+};
+
+/// `impl Show for Foo { ... }`
+pub struct ImplTarget {
+    pub trait_: TraitDecl,
+    pub data_type: Spanned<DataType>,
+    /// `impl<T> Show for Foo<T>`
+    pub generics: Vec<Spanned<DataType>>,
+}
+
+impl Parser {
+    /*
+        trait Show {
+            def show(self) -> String;
+        }
+    */
+    pub fn parse_trait_def(&mut self) -> Result<Spanned<TraitDecl>, Spanned<ParserError>> {
+        let start = self.expect_next(&[Token::Keyword(Keyword::Trait)])?.span;
+
+        let name = match self.expect_next(&[Token::Identifier("trait name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        self.expect_next(&[Token::Punctuation(Punctuation::OpenBrace)])?;
+
+        let mut methods: Vec<Spanned<TraitMethod>> = vec![];
+
+        let end =
+            self.walk_to_terminator(Token::Punctuation(Punctuation::CloseBrace), |parser| {
+                let method = parser.parse_trait_method()?;
+
+                if methods
+                    .iter()
+                    .any(|m| m.value.name.value == method.value.name.value)
+                {
+                    return Err(Spanned {
+                        value: ParserError::MethodAlreadyExists(
+                            method.value.name.value,
+                            name.value.clone(),
+                        ),
+                        span: method.span,
+                    });
+                }
+
+                methods.push(method);
+                Ok(())
+            })?;
+
+        if self.program.traits.contains_key(&name.value) {
+            return Err(Spanned {
+                value: ParserError::TraitAlreadyExists(name.value),
+                span: name.span,
+            });
+        }
+
+        // das erste trait mit der methode eines operators bindet ihn, das ist immer das prelude
+        let operator = match methods.as_slice() {
+            [method] => Trait::from_method_name(&method.value.name.value)
+                .filter(|trait_| self.program.operator_trait(trait_).is_none()),
+            _ => None,
+        };
+
+        let trait_decl = Spanned {
+            value: TraitDecl {
+                name: name.clone(),
+                methods,
+                operator,
+            },
+            span: start.extend(&end),
+        };
+
+        self.program.traits.insert(name.value, trait_decl.clone());
+
+        Ok(trait_decl)
+    }
+
+    /// `def show(self) -> String;`
+    fn parse_trait_method(&mut self) -> Result<Spanned<TraitMethod>, Spanned<ParserError>> {
+        let start = self.expect_next(&[Token::Keyword(Keyword::Def)])?.span;
+
+        let name = match self.expect_next(&[Token::Identifier("method name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        let mut method_generics = vec![];
+        if let Token::Operator(Operator::LessThan) = self.peek()?.value {
+            method_generics = self.parse_generics(None)?.value;
+        }
+
+        let mut generics = vec![Spanned {
+            value: DataType::Generic(TRAIT_SELF_TYPE_NAME.to_string()),
+            span: name.span,
+        }];
+        generics.extend(method_generics.iter().cloned());
+
+        let (params, variadic) = self.parse_func_params(&generics)?;
+
+        if let Some(span) = variadic {
+            return Err(Spanned {
+                value: ParserError::VariadicNotExtern,
+                span,
+            });
+        }
+
+        match params.value.first() {
+            Some(self_param) if self_param.value.name.value == CLASS_SELF_ARG_NAME => {}
+            _ => {
+                return Err(Spanned {
+                    value: ParserError::TraitIsStaticMethod,
+                    span: name.span,
+                })
+            }
+        }
+
+        let return_type = match self.peek()?.value {
+            Token::Punctuation(Punctuation::SemiColon) => Spanned {
+                value: DataType::None,
+                span: params.span,
+            },
+            _ => self.parse_func_return_type(&generics)?.unwrap_or_default(),
+        };
+
+        let end = self
+            .expect_next(&[Token::Punctuation(Punctuation::SemiColon)])?
+            .span;
+
+        Ok(Spanned {
+            value: TraitMethod {
+                name,
+                generics: method_generics,
+                params: params
+                    .value
+                    .into_iter()
+                    .skip(1)
+                    .map(|p| p.value._type)
+                    .collect(),
+                return_type,
+            },
+            span: start.extend(&end),
+        })
+    }
+
+    /*
+        impl Show for Foo {
+            def show(self) -> String {
+                return "Foo";
+            }
+        }
+    */
+    pub fn parse_impl(&mut self) -> Result<(), Spanned<ParserError>> {
+        let start = self.expect_next(&[Token::Keyword(Keyword::Impl)])?.span;
+
+        let mut generics = vec![];
+        if let Token::Operator(Operator::LessThan) = self.peek()?.value {
+            generics = self.parse_generics(None)?.value;
+        }
+
+        let trait_name = match self.expect_next(&[Token::Identifier("trait name".to_string())])? {
+            Spanned {
+                value: Token::Identifier(name),
+                span,
+            } => Spanned { value: name, span },
+            _ => unreachable!(),
+        };
+
+        let trait_decl = match self.program.traits.get(&trait_name.value) {
+            Some(trait_decl) => trait_decl.value.clone(),
+            None => {
+                return Err(Spanned {
+                    value: ParserError::TraitDoesNotExist(trait_name.value),
+                    span: trait_name.span,
+                })
+            }
+        };
+
+        self.expect_next(&[Token::Keyword(Keyword::For)])?;
+        let data_type = self.parse_data_type(Some(&generics))?;
+
+        let trait_ = trait_decl.as_trait();
+
+        // operator traits werden pro methode geprüft, siehe parse_func_def
+        if let Trait::Custom(_) = trait_ {
+            if self.program.implements(&data_type.value, &trait_name.value) {
+                return Err(Spanned {
+                    value: ParserError::TraitAlreadyImplemented(trait_.clone(), data_type.value),
+                    span: data_type.span,
+                });
+            }
+        }
+
+        let target = ImplTarget {
+            trait_: trait_decl,
+            data_type,
+            generics,
+        };
+
+        self.expect_next(&[Token::Punctuation(Punctuation::OpenBrace)])?;
+
+        let mut implemented = vec![];
+
+        let end =
+            self.walk_to_terminator(Token::Punctuation(Punctuation::CloseBrace), |parser| {
+                let function = parser.parse_func_def_in(Some(&target))?;
+                implemented.push(function.value.display_name.value);
+                Ok(())
+            })?;
+
+        let missing = target
+            .trait_
+            .methods
+            .iter()
+            .map(|m| m.value.name.value.clone())
+            .filter(|name| !implemented.contains(name))
+            .collect_vec();
+
+        if !missing.is_empty() {
+            return Err(Spanned {
+                value: ParserError::MissingTraitMethods(trait_name.value, missing),
+                span: start.extend(&end),
+            });
+        }
+
+        if let Trait::Custom(_) = trait_ {
+            let data_type = target.data_type.value;
+            self.get_type_info_mut(&data_type).traits.insert((
+                trait_,
+                vec![data_type.clone()],
+                None,
+                DataType::None,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `T: Show` ist innerhalb der funktion erfüllt, wenn der bound in `scope` steht
+    pub(in crate::parser) fn satisfies_bound(
+        &self,
+        _type: &DataType,
+        trait_name: &str,
+        scope: &Block,
+    ) -> bool {
+        match _type {
+            DataType::Generic(name) => scope
+                .generic_bounds
+                .get(name)
+                .is_some_and(|bounds| bounds.iter().any(|b| b == trait_name)),
+            _ => self.program.implements(_type, trait_name),
+        }
+    }
+
+    /// rückgabetyp eines operators für `params` (mit `self`),
+    /// mit `T: Add` gilt für `T + T` die signatur aus dem prelude
+    pub(in crate::parser) fn operator_return_type(
+        &self,
+        trait_: &Trait,
+        params: &[DataType],
+        scope: &Block,
+    ) -> Option<DataType> {
+        let self_type = params.first()?;
+
+        if let DataType::Generic(_) = self_type {
+            let trait_decl = self.program.operator_trait(trait_)?;
+            let trait_method = &trait_decl.methods.first()?.value;
+
+            if !trait_method.generics.is_empty()
+                || !self.satisfies_bound(self_type, &trait_decl.name.value, scope)
+            {
+                return None;
+            }
+
+            let (expected, return_type) = trait_method.signature(self_type);
+            return (expected == params).then_some(return_type);
+        }
+
+        self.program
+            .get_trait_return_type(self_type, trait_, params)
+    }
+
+    /// `x.show()` mit `x: T` und `T: Show`
+    /// die methode wird erst bei specify_generics durch die implementierung ersetzt
+    pub(in crate::parser) fn parse_bound_method_call(
+        &mut self,
+        scope: &mut Block,
+        caller: &Spanned<TypedExpr>,
+        method: Spanned<String>,
+        mut args_span: Span,
+    ) -> Result<Spanned<TypedExpr>, Spanned<ParserError>> {
+        let self_type = caller.value._type.clone();
+        let generic_name = self_type.to_string();
+
+        let bound_method = scope
+            .generic_bounds
+            .get(&generic_name)
+            .into_iter()
+            .flatten()
+            .filter_map(|bound| self.program.traits.get(bound))
+            .find_map(|trait_decl| {
+                let trait_method = trait_decl.value.get_method(&method.value)?;
+                Some((trait_decl.value.builtin(), trait_method.clone()))
+            });
+
+        let (operator_trait, trait_method) = match bound_method {
+            Some(bound_method) => bound_method,
+            None => {
+                return Err(Spanned {
+                    value: ParserError::MethodDoesNotExist(method.value, generic_name),
+                    span: method.span,
+                })
+            }
+        };
+
+        let mut args = vec![Spanned {
+            value: CallArg(caller.value.clone()),
+            span: caller.span,
+        }];
+
+        let end = self.walk_separated_values(
+            Token::Punctuation(Punctuation::Comma),
+            Token::Punctuation(Punctuation::CloseParen),
+            |parser| {
+                let value = parser.parse_expression(scope)?;
+                args_span = args_span.extend(&value.span);
+                args.push(Spanned {
+                    value: CallArg(value.value),
+                    span: value.span,
+                });
+
+                Ok(())
+            },
+        )?;
+
+        let span = method.span.extend(&end);
+
+        let mut params = trait_method
+            .params
+            .iter()
+            .map(|p| p.value.clone())
+            .collect_vec();
+        let mut return_type = trait_method.return_type.value.clone();
+
+        // generics der methode aus den argumenten, Self ist der generic
+        let (_, mut generic_map) = handle_generics(
+            &args
+                .iter()
+                .skip(1)
+                .map(|a| a.value.0._type.clone())
+                .collect_vec(),
+            &params,
+            None,
+        );
+        generic_map.insert(TRAIT_SELF_TYPE_NAME.to_string(), self_type.clone());
+
+        let mut types = params.iter_mut().flat_map(|p| p._type_mut()).collect_vec();
+        types.append(&mut return_type._type_mut());
+        specify_generics(&mut types, &generic_map, &mut self.program, false);
+
+        if params.len() != args.len() - 1 {
+            return Err(Spanned {
+                value: ParserError::WrongArguments(
+                    params.iter().map(|p| p.to_string()).collect(),
+                    args.iter()
+                        .skip(1)
+                        .map(|a| a.value.0._type.to_string())
+                        .collect(),
+                ),
+                span: args_span,
+            });
+        }
+
+        for (param, arg) in params.iter().zip(args.iter().skip(1)) {
+            if *param != arg.value.0._type {
+                return Err(Spanned {
+                    value: ParserError::WrongType(param.clone(), arg.value.0._type.clone()),
+                    span: arg.span,
+                });
+            }
+        }
+
+        // `a.add(b)` ist `a + b`, für zahlen gibt es keine funktion `add`
+        if let Some(op) = operator_trait.and_then(|trait_| trait_.to_binary_operator()) {
+            let rhs = &args[1];
+
+            return Ok(Spanned {
+                value: TypedExpr {
+                    expression: Expr::Binary {
+                        lhs: Box::new(caller.clone()),
+                        op: Spanned {
+                            value: op,
+                            span: method.span,
+                        },
+                        rhs: Box::new(Spanned {
+                            value: rhs.value.0.clone(),
+                            span: rhs.span,
+                        }),
+                    },
+                    _type: return_type,
+                    raw: None,
+                },
+                span,
+            });
+        }
+
+        let self_param = FunctionParam {
+            name: Spanned {
+                value: CLASS_SELF_ARG_NAME.to_string(),
+                span: caller.span,
+            },
+            _type: Spanned {
+                value: self_type.clone(),
+                span: caller.span,
+            },
+        };
+
+        let function = Function {
+            display_name: method.clone(),
+            name: format!("{}_{}", generic_name, method.value),
+            params: Spanned {
+                value: std::iter::once(self_param)
+                    .chain(
+                        params
+                            .into_iter()
+                            .enumerate()
+                            .map(|(idx, _type)| FunctionParam {
+                                name: Spanned {
+                                    value: format!("arg{}", idx),
+                                    span: args_span,
+                                },
+                                _type: Spanned {
+                                    value: _type,
+                                    span: args_span,
+                                },
+                            }),
+                    )
+                    .map(|p| Spanned {
+                        value: p,
+                        span: args_span,
+                    })
+                    .collect(),
+                span: args_span,
+            },
+            return_type: Spanned {
+                value: return_type.clone(),
+                span: trait_method.return_type.span,
+            },
+            method_of: Some(self_type),
+            ..Default::default()
+        };
+
+        Ok(Spanned {
+            value: TypedExpr {
+                expression: Expr::Call {
+                    function: Spanned {
+                        value: function,
+                        span: method.span,
+                    },
+                    args: Spanned {
+                        value: args,
+                        span: args_span,
+                    },
+                },
+                _type: return_type,
+                raw: None,
+            },
+            span,
+        })
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     },
     parser::{
         ast::{Block, DataType, Expr, TypedExpr},
+        cfg::Target,
         error::ParserError,
         parser_main::Parser,
     },
@@ -22,8 +23,16 @@ impl Parser {
         // wahrscheinlich entfernen TODO
         let _base_type_info = self.get_type_info(&base.value._type);
 
+        // `extern def` funktionen als funktionspointer für c, z.b. als callback für `qsort`
+        let c_function_pointer = matches!(
+            (&base.value.expression, &cast_to.value),
+            (Expr::FunctionRef(function), DataType::Pointer(_))
+                if function.c_abi && self.target != Target::Wasm
+        );
+
         if DataType::Pointer(Box::new(base.value._type.clone())) == cast_to.value
             || base.value._type.can_be_converted_to(&cast_to.value)
+            || c_function_pointer
         // TODO: traits
         // || base_type_info.implements_trait(&Trait::Cast, &[base.value._type.clone(), cast_to.value.clone()])
         {
@@ -4,7 +4,9 @@ use crate::{
         tokens::{Keyword, Punctuation, Token},
     },
     parser::{
-        ast::{Block, DataType, Expr, Statement, TypedExpr, Variable},
+        ast::{
+            Block, DataType, Expr, Statement, Trait, TypedExpr, Variable, ASSIGNMENT_PLACE_NAME,
+        },
         error::ParserError,
         parser_main::Parser,
     },
@@ -108,16 +110,20 @@ impl Parser {
             span,
         } = self.expect_next(&[Token::Identifier("variable name".to_string())])?
         {
-            let variable = scope.get_variable(&Spanned {
-                value: variable_name.clone(),
-                span,
-            })?;
+            let variable = self.lookup_variable(
+                scope,
+                &Spanned {
+                    value: variable_name,
+                    span,
+                },
+            )?;
 
             return Ok(Spanned {
                 value: TypedExpr {
                     expression: Expr::Variable(Variable {
+                        // bei globalen der interne name
                         name: Spanned {
-                            value: variable_name,
+                            value: variable.value.name.value.clone(),
                             span,
                         },
                         _type: variable.value._type.clone(),
@@ -140,14 +146,22 @@ impl Parser {
         let to_mutate = self.parse_expression(scope)?;
         let base_var = Self::find_base_variable(&to_mutate)?;
 
-        let variable = scope.get_variable(&Spanned {
-            value: base_var.name.value,
-            span: to_mutate.span,
-        })?;
+        let variable = self.lookup_variable(
+            scope,
+            &Spanned {
+                value: base_var.name.value,
+                span: to_mutate.span,
+            },
+        )?;
 
         if !variable.value.is_mutable {
+            let name = match self.program.globals.get(&variable.value.name.value) {
+                Some(global) => global.value.name.value.clone(),
+                None => variable.value.name.value.clone(),
+            };
+
             return Err(Spanned {
-                value: ParserError::VariableNotMutable(variable.value.name.value.clone()),
+                value: ParserError::VariableNotMutable(name),
                 span: variable.span,
             });
         }
@@ -161,7 +175,60 @@ impl Parser {
             });
         }
 
-        let new_value = self.parse_expression(scope)?;
+        let mut new_value = self.parse_expression(scope)?;
+
+        // `a += b` wird über den trait zu `a = a + b`, das ziel wird dabei nur einmal ausgewertet
+        if let Token::ReassignmentOperator(reassignment_op) = &op.value {
+            let binary_op = reassignment_op.to_binary_op();
+            let operation_trait = Trait::from_binary_operator(&binary_op);
+            let trait_args = [to_mutate.value._type.clone(), new_value.value._type.clone()];
+
+            let result_type = match self.operator_return_type(&operation_trait, &trait_args, scope)
+            {
+                Some(result_type) => result_type,
+                None => {
+                    return Err(Spanned {
+                        value: ParserError::WrongType(
+                            to_mutate.value._type.clone(),
+                            new_value.value._type,
+                        ),
+                        span: op.span,
+                    })
+                }
+            };
+
+            let place = Spanned {
+                value: TypedExpr {
+                    expression: Expr::Variable(Variable {
+                        name: Spanned {
+                            value: ASSIGNMENT_PLACE_NAME.to_string(),
+                            span: to_mutate.span,
+                        },
+                        is_mutable: true,
+                        _type: to_mutate.value._type.clone(),
+                    }),
+                    _type: to_mutate.value._type.clone(),
+                    raw: None,
+                },
+                span: to_mutate.span,
+            };
+
+            new_value = Spanned {
+                value: TypedExpr {
+                    expression: Expr::Binary {
+                        lhs: Box::new(place),
+                        op: Spanned {
+                            value: binary_op,
+                            span: op.span,
+                        },
+                        rhs: Box::new(new_value.clone()),
+                    },
+                    _type: result_type,
+                    raw: None,
+                },
+                span: to_mutate.span.extend(&new_value.span),
+            };
+        }
 
         if new_value.value._type != to_mutate.value._type {
             return Err(Spanned {
@@ -26,7 +26,11 @@ impl Parser {
             });
         }
 
-        let body = self.parse_block(scope)?;
+        let in_loop = std::mem::replace(&mut scope.in_loop, true);
+        let body = self.parse_block(scope);
+        scope.in_loop = in_loop;
+
+        let body = body?;
         let span = start.extend(&body.span);
 
         Ok(Spanned {
@@ -37,4 +41,35 @@ impl Parser {
             span,
         })
     }
+
+    /// `break` oder `continue`
+    pub(in crate::parser) fn parse_loop_control(
+        &mut self,
+        scope: &mut Block,
+    ) -> Result<Spanned<Statement>, Spanned<ParserError>> {
+        let (keyword, span) = match self.next_token()? {
+            Spanned {
+                value: Token::Keyword(keyword @ (Keyword::Break | Keyword::Continue)),
+                span,
+            } => (keyword, span),
+            _ => unreachable!(),
+        };
+
+        if !scope.in_loop {
+            return Err(Spanned {
+                value: ParserError::OutsideOfLoop(keyword),
+                span,
+            });
+        }
+
+        let statement = match keyword {
+            Keyword::Break => Statement::Break,
+            _ => Statement::Continue,
+        };
+
+        Ok(Spanned {
+            value: statement,
+            span,
+        })
+    }
 }
@@ -8,7 +8,8 @@ use crate::{
 
 use super::{
     ast::{
-        Block, CommonGeneric, CustomDataType, DataType, DataTypeGetter, DataTypeSettable, Program,
+        Block, CommonGeneric, CustomDataType, DataType, DataTypeGetter, DataTypeSettable,
+        DataTypeSetter, Program,
     },
     error::ParserError,
 };
@@ -47,7 +48,7 @@ pub fn specify_generics(
                             .collect_vec();
 
                         let subtype = inner.subtype(&specific_types, parser, handle_traits);
-                        *inner = subtype;
+                        **inner = subtype;
                     }
                 }
                 DataType::Pointer(inner) => {
@@ -60,9 +61,57 @@ pub fn specify_generics(
                         handle_traits,
                     );
                 }
+                DataType::Function {
+                    params,
+                    return_type,
+                } => {
+                    let mut type_settables = params
+                        .iter_mut()
+                        .map(DataTypeSettable::DataType)
+                        .collect_vec();
+                    type_settables.push(DataTypeSettable::DataType(return_type));
+
+                    specify_generics(&mut type_settables, generics, parser, handle_traits);
+                }
                 _ => {}
             },
             DataTypeSettable::FunctionCall(function, args) => {
+                // methode eines trait bounds, wird durch die implementierung ersetzt
+                if let Some(DataType::Generic(generic_name)) = function.method_of.clone() {
+                    if !function.is_generic() {
+                        match generics.get(&generic_name) {
+                            Some(DataType::Generic(_)) => {
+                                let mut type_settables = function
+                                    .params
+                                    .value
+                                    .iter_mut()
+                                    .flat_map(|p| p.value._type.value._type_mut())
+                                    .collect_vec();
+                                type_settables.append(&mut function.return_type.value._type_mut());
+
+                                specify_generics(
+                                    &mut type_settables,
+                                    generics,
+                                    parser,
+                                    handle_traits,
+                                );
+
+                                function.method_of = generics.get(&generic_name).cloned();
+                            }
+                            Some(specific_type) => {
+                                if let Some(implementation) = parser.resolve_trait_method(
+                                    specific_type,
+                                    &function.display_name.value,
+                                ) {
+                                    **function = implementation;
+                                }
+                            }
+                            None => {}
+                        }
+                        continue;
+                    }
+                }
+
                 if function.is_generic() {
                     let mut type_settables = args
                         .iter_mut()
@@ -138,7 +187,8 @@ fn find_specific_types(generic: CustomDataType, specific: CustomDataType) -> Opt
                 }
             }
             (DataType::Custom(generic), DataType::Custom(specific)) => {
-                if let Some(types) = find_specific_types(generic.clone(), specific.clone()) {
+                if let Some(types) = find_specific_types((**generic).clone(), (**specific).clone())
+                {
                     out.extend(types);
                 }
             }
@@ -187,24 +237,37 @@ pub fn handle_generics(
                             .collect::<Vec<_>>(),
                         annotations,
                     );
-                    for (g, t) in inferred_types.1.iter() {
-                        if !generics_to_types.contains_key(g) {
-                            generics_to_types.insert(g.clone(), t.clone());
-                            generics_order.push(g.clone());
-                        }
-                    }
+                    merge_inferred(
+                        &mut generics_to_types,
+                        &mut generics_order,
+                        inferred_types.1,
+                    );
                 }
             }
             DataType::Pointer(inner_param) => {
                 if let DataType::Pointer(inner_arg) = arg {
                     let (_result, generics) =
                         handle_generics(&[*inner_arg.clone()], &[*inner_param.clone()], None);
-                    for (g, t) in generics.iter() {
-                        if !generics_to_types.contains_key(g) {
-                            generics_to_types.insert(g.clone(), t.clone());
-                            generics_order.push(g.clone());
-                        }
-                    }
+                    merge_inferred(&mut generics_to_types, &mut generics_order, generics);
+                }
+            }
+            // `f: |T| -> U` mit `|int64| -> bool`
+            DataType::Function {
+                params: param_params,
+                return_type: param_return_type,
+            } => {
+                if let DataType::Function {
+                    params: arg_params,
+                    return_type: arg_return_type,
+                } = arg
+                {
+                    let mut args = arg_params.clone();
+                    args.push(*arg_return_type.clone());
+                    let mut params = param_params.clone();
+                    params.push(*param_return_type.clone());
+
+                    let (_result, generics) = handle_generics(&args, &params, None);
+                    merge_inferred(&mut generics_to_types, &mut generics_order, generics);
                 }
             }
             _ => {}
@@ -220,6 +283,23 @@ pub fn handle_generics(
     )
 }
 
+/// übernimmt generics aus verschachtelten typen (klassen, pointer, funktionen).
+/// `T -> T` sagt nichts aus: in einem generischen body bleibt z.b. `List<U>` als `List<T>` stehen,
+/// `T` muss dann aus den weiteren argumenten kommen
+fn merge_inferred(
+    generics_to_types: &mut HashMap<String, DataType>,
+    generics_order: &mut Vec<String>,
+    inferred: HashMap<String, DataType>,
+) {
+    for (g, t) in inferred {
+        if t == DataType::Generic(g.clone()) || generics_to_types.contains_key(&g) {
+            continue;
+        }
+        generics_order.push(g.clone());
+        generics_to_types.insert(g, t);
+    }
+}
+
 /// um genestete generics in funktionsaufrufen richtig zu erstzen
 /// bzw subtyp bilden
 pub fn handle_nested_generic_functions(
@@ -373,7 +453,7 @@ mod tests {
     #[test]
     fn handle_generics_with_classes() {
         let args: Vec<DataType> = vec![
-            DataType::Custom(CustomDataType {
+            DataType::Custom(Box::new(CustomDataType {
                 display_name: "List<int64>".to_string(),
                 name: "List--int64".to_string(),
                 fields: Spanned {
@@ -414,12 +494,14 @@ mod tests {
                 subtypes: HashMap::new(),
                 generics: vec![],
                 subtype_of: Some("List".to_string()),
+                variants: None,
                 is_generic: false,
-            }),
+                is_packed: false,
+            })),
             DataType::Integer64,
         ];
 
-        let params: Vec<DataType> = vec![DataType::Custom(CustomDataType {
+        let params: Vec<DataType> = vec![DataType::Custom(Box::new(CustomDataType {
             display_name: "List<T>".to_string(),
             name: "List".to_string(),
             fields: Spanned {
@@ -463,8 +545,10 @@ mod tests {
                 span: Span::default(),
             }],
             subtype_of: None,
+            variants: None,
             is_generic: true,
-        })];
+            is_packed: false,
+        }))];
 
         let (result, generics) = handle_generics(&args, &params, None);
 
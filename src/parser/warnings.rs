@@ -0,0 +1,517 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    error::CompilerWarning,
+    lexer::{
+        position::{Span, Spanned},
+        tokens::Literal,
+    },
+    parser::ast::{
+        DataType, Expr, ForIterable, Function, MatchPattern, Program, Statement, TypedExpr,
+        ASSIGNMENT_PLACE_NAME, CLASS_SELF_ARG_NAME, MAIN_ARGS_FUNCTION_NAME,
+    },
+};
+
+use super::ast::Block;
+
+/// sucht nach warnungen im fertig geparsten programm
+pub fn check_warnings(program: &Program) -> Vec<Spanned<CompilerWarning>> {
+    let imported: HashSet<&String> = program
+        .imports
+        .iter()
+        .flat_map(|import| import.functions.iter())
+        .collect();
+
+    let local_functions = program
+        .functions
+        .iter()
+        .filter(|(name, f)| !f.value.is_builtin && !f.value.is_extern && !imported.contains(name))
+        .collect::<Vec<_>>();
+
+    let mut checker = WarningChecker::default();
+
+    for (_, function) in local_functions.iter() {
+        checker.check_function(&function.value, program);
+    }
+
+    // unbenutzte imports, benutzt ist alles was irgendwo in dieser datei vorkommt
+    for import in program.imports.iter() {
+        let used = import.functions.iter().any(|f| checker.calls.contains(f))
+            || import.types.iter().any(|t| checker.types.contains(t))
+            || import.globals.iter().any(|g| checker.globals.contains(g));
+
+        if !used {
+            checker.warn(
+                CompilerWarning::UnusedImport(import.name.value.clone()),
+                import.name.span,
+            );
+        }
+    }
+
+    // funktionen, die von main aus nicht erreichbar sind
+    if program.require_main && program.functions.contains_key("main") {
+        let reachable = reachable_functions(program);
+
+        for (name, function) in local_functions {
+            if reachable.contains(name.as_str()) || is_trait_method(&function.value, program) {
+                continue;
+            }
+
+            checker.warn(
+                CompilerWarning::UnusedFunction(function.value.display_name.value.clone()),
+                function.value.display_name.span,
+            );
+        }
+    }
+
+    checker
+        .warnings
+        .sort_by_key(|w| (w.span.start.abs, w.span.end.abs));
+    checker.warnings
+}
+
+/// operator traits und user traits werden nicht direkt aufgerufen
+fn is_trait_method(function: &Function, program: &Program) -> bool {
+    function.trait_of.is_some()
+        || (function.method_of.is_some()
+            && program
+                .traits
+                .values()
+                .any(|t| t.value.get_method(&function.display_name.value).is_some()))
+}
+
+/// `foo--int64` -> `foo`
+fn base_function_name(name: &str) -> &str {
+    name.split("--").next().unwrap_or(name)
+}
+
+fn reachable_functions(program: &Program) -> HashSet<String> {
+    let mut reachable = HashSet::new();
+    let mut queue = VecDeque::from(["main".to_string(), MAIN_ARGS_FUNCTION_NAME.to_string()]);
+    // `#[export]` funktionen werden von c aus aufgerufen
+    queue.extend(
+        program
+            .functions
+            .values()
+            .filter(|f| f.value.export)
+            .map(|f| f.value.name.clone()),
+    );
+
+    while let Some(name) = queue.pop_front() {
+        if !reachable.insert(name.clone()) {
+            continue;
+        }
+
+        let function = match program.functions.get(&name) {
+            Some(function) => &function.value,
+            None => continue,
+        };
+
+        // aufrufe in generischen funktionen stehen erst in den subtypes fest
+        let mut checker = WarningChecker::default();
+        checker.check_block(&function.body.value);
+        for subtype in function.generic_subtypes.values() {
+            checker.check_block(&subtype.body.value);
+        }
+
+        queue.extend(checker.calls);
+    }
+
+    reachable
+}
+
+struct LocalVariable {
+    name: Spanned<String>,
+    is_mutable: bool,
+    read: bool,
+    mutated: bool,
+}
+
+#[derive(Default)]
+struct WarningChecker {
+    warnings: Vec<Spanned<CompilerWarning>>,
+    scopes: Vec<Vec<LocalVariable>>,
+    /// basisnamen aller aufgerufenen funktionen
+    calls: HashSet<String>,
+    /// namen aller benutzten custom types
+    types: HashSet<String>,
+    /// namen, die keine lokalen variablen sind, also globale
+    globals: HashSet<String>,
+    /// im code von makros (`vec![...]`), der steht nicht in der datei
+    in_macro: bool,
+}
+
+impl WarningChecker {
+    fn warn(&mut self, warning: CompilerWarning, span: Span) {
+        self.warnings.push(Spanned {
+            value: warning,
+            span,
+        });
+    }
+
+    fn check_function(&mut self, function: &Function, program: &Program) {
+        for param in function.params.value.iter() {
+            self.use_type(&param.value._type.value);
+        }
+        self.use_type(&function.return_type.value);
+
+        if function.is_extern {
+            return;
+        }
+
+        self.scopes.push(vec![]);
+        // parameter von trait methoden müssen nicht benutzt werden
+        if !is_trait_method(function, program) {
+            for param in function.params.value.iter() {
+                if param.value.name.value != CLASS_SELF_ARG_NAME {
+                    self.declare(param.value.name.clone(), false);
+                }
+            }
+        }
+
+        self.check_block(&function.body.value);
+        self.pop_scope();
+    }
+
+    fn declare(&mut self, name: Spanned<String>, is_mutable: bool) {
+        self.scopes
+            .last_mut()
+            .expect("declare outside of a scope")
+            .push(LocalVariable {
+                name,
+                is_mutable,
+                read: false,
+                mutated: false,
+            });
+    }
+
+    fn find_variable(&mut self, name: &str) -> Option<&mut LocalVariable> {
+        if !self.scopes.iter().flatten().any(|v| v.name.value == name) {
+            self.globals.insert(name.to_string());
+            return None;
+        }
+
+        self.scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|v| v.name.value == name)
+    }
+
+    fn pop_scope(&mut self) {
+        let scope = self.scopes.pop().unwrap_or_default();
+
+        for variable in scope {
+            // `_x` ist absichtlich unbenutzt
+            if variable.name.value.starts_with('_') {
+                continue;
+            }
+
+            if !variable.read {
+                self.warn(
+                    CompilerWarning::UnusedVariable(variable.name.value),
+                    variable.name.span,
+                );
+            } else if variable.is_mutable && !variable.mutated {
+                self.warn(
+                    CompilerWarning::UnusedMut(variable.name.value),
+                    variable.name.span,
+                );
+            }
+        }
+    }
+
+    fn use_type(&mut self, _type: &DataType) {
+        match _type {
+            DataType::Custom(custom) => {
+                self.types.insert(custom.name.clone());
+                self.types.insert(custom.display_name.clone());
+                if let Some(subtype_of) = &custom.subtype_of {
+                    self.types.insert(subtype_of.clone());
+                }
+                for generic in custom.generics.iter() {
+                    self.use_type(&generic.value);
+                }
+            }
+            DataType::Pointer(inner) => self.use_type(inner),
+            DataType::Array { value_type, .. } => self.use_type(value_type),
+            DataType::Function {
+                params,
+                return_type,
+            } => {
+                for param in params.iter() {
+                    self.use_type(param);
+                }
+                self.use_type(return_type);
+            }
+            _ => {}
+        }
+    }
+
+    fn check_block(&mut self, block: &Block) {
+        self.scopes.push(vec![]);
+
+        let mut returned = false;
+        let mut unreachable: Option<Span> = None;
+
+        for statement in block.statements.iter() {
+            if returned {
+                unreachable = Some(match unreachable {
+                    Some(span) => span.extend(&statement.span),
+                    None => statement.span,
+                });
+            }
+
+            if let Statement::Return { .. } = statement.value {
+                returned = true;
+            }
+
+            self.check_statement(&statement.value);
+        }
+
+        if let Some(span) = unreachable {
+            self.warn(CompilerWarning::UnreachableCode, span);
+        }
+
+        self.pop_scope();
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::If {
+                condition,
+                true_branch,
+                else_if_branches,
+                false_branch,
+            } => {
+                self.check_expr(&condition.value);
+                self.check_block(&true_branch.value);
+                for branch in else_if_branches.iter() {
+                    self.check_expr(&branch.value.condition.value);
+                    self.check_block(&branch.value.body.value);
+                }
+                if let Some(false_branch) = false_branch {
+                    self.check_block(&false_branch.value);
+                }
+            }
+            Statement::WhileLoop { condition, body } => {
+                self.check_expr(&condition.value);
+                self.check_block(&body.value);
+            }
+            Statement::ForLoop {
+                variable,
+                iterable,
+                body,
+            } => {
+                match &iterable.value {
+                    ForIterable::Range { start, end } => {
+                        self.check_expr(&start.value);
+                        self.check_expr(&end.value);
+                    }
+                    ForIterable::Iterable(iterable) => self.check_expr(&iterable.value),
+                }
+
+                self.scopes.push(vec![]);
+                self.declare(variable.value.name.clone(), false);
+                self.check_block(&body.value);
+                self.pop_scope();
+            }
+            Statement::VariableDecl {
+                is_mutable,
+                name,
+                _type,
+                value,
+            } => {
+                self.check_expr(&value.value);
+                if let Some(_type) = _type {
+                    self.use_type(&_type.value);
+                }
+                self.declare(name.clone(), *is_mutable);
+            }
+            Statement::Return { value } => self.check_expr(&value.value),
+            Statement::Expr(expr) => {
+                self.check_expression(&expr.value);
+
+                // `extern` funktionen aus c geben oft nur einen status zurück (`printf`)
+                let discarded = match &expr.value {
+                    Expr::Call { function, .. }
+                        if !function.value.is_extern
+                            && function.value.return_type.value != DataType::None =>
+                    {
+                        Some(function.value.display_name.value.clone())
+                    }
+                    Expr::ClosureCall { closure, .. } => match &closure.value._type {
+                        DataType::Function { return_type, .. }
+                            if **return_type != DataType::None =>
+                        {
+                            Some(match &closure.value.expression {
+                                Expr::Variable(variable) => variable.name.value.clone(),
+                                _ => "closure".to_string(),
+                            })
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let (Some(name), false) = (discarded, self.in_macro) {
+                    self.warn(CompilerWarning::UnusedResult(name), expr.span);
+                }
+            }
+            Statement::VariableMutation {
+                variable,
+                new_value,
+            } => {
+                self.check_expr(&new_value.value);
+
+                // `x += 1` liest `x` über den platzhalter
+                let reads_target = matches!(
+                    &new_value.value.expression,
+                    Expr::Binary { lhs, .. } if matches!(
+                        &lhs.value.expression,
+                        Expr::Variable(place) if place.name.value == ASSIGNMENT_PLACE_NAME
+                    )
+                );
+
+                match reads_target {
+                    true => {
+                        self.check_expr(&variable.value);
+                        self.mark_mutated(&variable.value);
+                    }
+                    false => self.check_target(&variable.value),
+                }
+            }
+            Statement::Break | Statement::Continue => {}
+        }
+    }
+
+    /// `x = ...` liest `x` nicht, `x.y = ...` und `x[i] = ...` zählen als lesen
+    fn check_target(&mut self, target: &TypedExpr) {
+        if let Expr::Variable(variable) = &target.expression {
+            if let Some(variable) = self.find_variable(&variable.name.value) {
+                variable.mutated = true;
+            }
+            return;
+        }
+
+        self.check_expr(target);
+        self.mark_mutated(target);
+    }
+
+    fn mark_mutated(&mut self, expr: &TypedExpr) {
+        match &expr.expression {
+            Expr::Variable(variable) => {
+                if let Some(variable) = self.find_variable(&variable.name.value) {
+                    variable.mutated = true;
+                }
+            }
+            Expr::Index { base, .. } | Expr::FieldAccess { base, .. } | Expr::Deref(base) => {
+                self.mark_mutated(&base.value)
+            }
+            _ => {}
+        }
+    }
+
+    fn check_expr(&mut self, expr: &TypedExpr) {
+        self.use_type(&expr._type);
+
+        let is_macro = expr.raw.is_some() && matches!(expr.expression, Expr::Block { .. });
+        let in_macro = self.in_macro;
+        self.in_macro |= is_macro;
+        self.check_expression(&expr.expression);
+        self.in_macro = in_macro;
+    }
+
+    fn check_expression(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(literal) => match literal {
+                Literal::ArrayLiteral(array) => {
+                    for value in array.values.value.iter() {
+                        self.check_expr(&value.value);
+                    }
+                }
+                Literal::Custom(class) => {
+                    self.use_type(&class._type);
+                    for (_, value) in class.fields.value.iter() {
+                        self.check_expr(&value.value);
+                    }
+                }
+                _ => {}
+            },
+            Expr::Variable(variable) => {
+                if let Some(variable) = self.find_variable(&variable.name.value) {
+                    variable.read = true;
+                }
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                self.check_expr(&lhs.value);
+                self.check_expr(&rhs.value);
+            }
+            Expr::Unary { expr, .. } => self.check_expr(&expr.value),
+            Expr::Cast { value, to_type } => {
+                self.check_expr(&value.value);
+                self.use_type(&to_type.value);
+            }
+            // über den pointer kann die variable verändert werden
+            Expr::Reference { value } => {
+                self.check_expr(&value.value);
+                self.mark_mutated(&value.value);
+            }
+            Expr::Call { function, args } => {
+                self.calls
+                    .insert(base_function_name(&function.value.name).to_string());
+                if let Some(method_of) = &function.value.method_of {
+                    self.use_type(method_of);
+                }
+                for arg in args.value.iter() {
+                    self.check_expr(&arg.value.0);
+                }
+            }
+            Expr::Block { body } => self.check_block(&body.value),
+            Expr::Index { base, idx } => {
+                self.check_expr(&base.value);
+                self.check_expr(&idx.value);
+            }
+            Expr::FieldAccess { base, .. } => self.check_expr(&base.value),
+            Expr::Deref(value) => self.check_expr(&value.value),
+            Expr::ClassName(name) => {
+                self.types.insert(name.clone());
+            }
+            Expr::EnumVariant { args, .. } => {
+                for arg in args.value.iter() {
+                    self.check_expr(&arg.value);
+                }
+            }
+            Expr::Match { value, arms } => {
+                self.check_expr(&value.value);
+                for arm in arms.iter() {
+                    self.scopes.push(vec![]);
+                    if let MatchPattern::Variant { bindings, .. } = &arm.value.pattern.value {
+                        for binding in bindings.iter() {
+                            self.declare(binding.clone(), false);
+                        }
+                    }
+                    self.check_expr(&arm.value.body.value);
+                    self.pop_scope();
+                }
+            }
+            Expr::Closure(closure) => {
+                self.scopes.push(vec![]);
+                for param in closure.params.value.iter() {
+                    self.declare(param.value.name.clone(), false);
+                }
+                self.check_block(&closure.body.value);
+                self.pop_scope();
+            }
+            Expr::ClosureCall { closure, args } => {
+                self.check_expr(&closure.value);
+                for arg in args.value.iter() {
+                    self.check_expr(&arg.value.0);
+                }
+            }
+            Expr::FunctionRef(function) => {
+                self.calls
+                    .insert(base_function_name(&function.name).to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+#![cfg(test)]
+
+/// programme, die llvm, der interpreter und wasm gleich ausführen müssen, mit exit code.
+/// jedes backend läuft sie in seinem eigenen `backend_cases` test
+pub const BACKEND_CASES: &[(&str, i32)] = &[
+    (
+        "def main() -> int64 {
+            let a = 200 as uint8;
+            let b = 3 as uint8;
+            let mut r = ((a / b) + (a % b)) as int64;
+
+            let u = (0 - 1) as uint64;
+            if u > (10 as uint64) {
+                r = r + 1;
+            }
+
+            return r + ((u >> (60 as uint64)) as int64);
+        }",
+        84,
+    ),
+    (
+        "def main() -> int64 {
+            let a = 1.5 as float64;
+            let b = a * (4 as float64);
+            let c = b as float;
+
+            if b > a {
+                return (c as int64) + ((b / (4 as float64) * (2 as float64)) as int64);
+            }
+            return 0;
+        }",
+        9,
+    ),
+    (
+        "def main() -> int64 {
+            let mut x = 5;
+            let mut p = &x;
+            ~p = 7;
+            let arr = [1, 2, 3];
+            return x + arr[2];
+        }",
+        10,
+    ),
+    (
+        "import \"std/mem.mx\";
+
+        def main() -> int64 {
+            let mut p = malloc(16) as *int64;
+            ~p = 40;
+            let q = realloc(p as int64, 64) as *int64;
+            let value = (~q) + 2;
+            free(q as int64);
+            return value;
+        }",
+        42,
+    ),
+    (
+        "enum Shape {
+            Circle(int64),
+            Rect(int64, int64),
+            Empty,
+        }
+
+        def area(shape: Shape) -> int64 {
+            return match shape {
+                Shape::Circle(r) => r * r * 3,
+                Shape::Rect(w, h) => w * h,
+                _ => 0,
+            };
+        }
+
+        def main() -> int64 {
+            return area(Shape::Circle(2)) + area(Shape::Rect(3, 4)) + area(Shape::Empty);
+        }",
+        24,
+    ),
+    (
+        "def make_adder(n: int64) -> |int64| -> int64 {
+            return |x: int64| -> int64 { x + n };
+        }
+
+        def double(x: int64) -> int64 {
+            return x * 2;
+        }
+
+        def apply(f: |int64| -> int64, x: int64) -> int64 {
+            return f(x);
+        }
+
+        def main() -> int64 {
+            let mut count = 0;
+            let inc = || { count = count + 1; };
+            inc();
+            inc();
+
+            let add5 = make_adder(5);
+            return add5(10) + apply(double, count);
+        }",
+        19,
+    ),
+    (
+        "def make_counter() -> || -> int64 {
+            let mut count = 0;
+            return || -> int64 {
+                count = count + 1;
+                return count;
+            };
+        }
+
+        def main() -> int64 {
+            let next = make_counter();
+            let other = make_counter();
+            next();
+            next();
+            other();
+            return next() * 10 + other();
+        }",
+        32,
+    ),
+    (
+        "import \"std/mem.mx\";
+
+        def make_adder(n: int64) -> |int64| -> int64 {
+            return |x: int64| -> int64 { x + n };
+        }
+
+        def main() -> int64 {
+            let mut total = 0;
+            let mut i = 0;
+            while i < 1000 {
+                let step = |x: int64| -> int64 { x + i };
+                total = step(total) % 1000;
+
+                let add = make_adder(i * 2);
+                total = add(total) % 1000;
+                free_closure(add);
+                i += 1;
+            }
+            return total % 256;
+        }",
+        244,
+    ),
+    (
+        "import \"std/collections/list.mx\";
+
+        def main() -> int64 {
+            let list = List::new<int64>();
+            for i in 0..5 {
+                if i == 3 {
+                    continue;
+                }
+                list.push(i);
+            }
+
+            let mut sum = 0;
+            for value in list {
+                sum = sum + value;
+            }
+            return sum * 10 + list.len;
+        }",
+        74,
+    ),
+    (
+        "static mut COUNTER: int64 = 0;
+        const STEP: int64 = 3;
+
+        def bump() {
+            COUNTER += STEP;
+        }
+
+        def main() -> int64 {
+            let mut i = 0;
+            while true {
+                if i == 4 {
+                    break;
+                }
+                bump();
+                i = i + 1;
+            }
+            return COUNTER;
+        }",
+        12,
+    ),
+    (
+        "import \"std/io.mx\";
+
+        def main() -> int64 {
+            exit(3);
+            return 0;
+        }",
+        3,
+    ),
+    // das ziel von `+=` wird nur einmal berechnet, `bump()` läuft pro zuweisung einmal
+    (
+        "static mut CALLS: int64 = 0;
+
+        def bump() -> int64 {
+            CALLS += 1;
+            return CALLS;
+        }
+
+        def main() -> int64 {
+            let mut list = [1, 2, 3, 4];
+            list[bump()] += 5;
+            list[bump()] *= 10;
+            return list[1] + list[2] + CALLS * 100;
+        }",
+        237,
+    ),
+];
@@ -1020,7 +1020,602 @@ use rstest::rstest;
     }",
     true
 )]
+#[case(
+    "def main() -> int64 {
+        let a = 5;
+        for x in a {
+            return x;
+        }
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        for i in 0..1.0 {
+            return i;
+        }
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        for i in 0..10 {
+            i = 5;
+        }
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let index = 1;
+        let input: int64 = index;
+        return input;
+    }",
+    true
+)]
+#[case(
+    "def main() -> int64 {
+        break;
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        while true {
+            let a = {
+                continue;
+                return 1;
+            };
+        }
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        while true {
+            if true {
+                break;
+            } else {
+                continue;
+            }
+        }
+        return 0;
+    }",
+    true
+)]
+#[case(
+    "enum Shape {
+        Circle(float),
+        Rect(float, float),
+    }
+
+    def main() -> int64 {
+        let s = Shape::Circle(1.0);
+        let a = match s {
+            Shape::Circle(r) => r,
+        };
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "enum Shape {
+        Circle(float),
+        Rect(float, float),
+    }
+
+    def main() -> int64 {
+        let s = Shape::Circle(1.0);
+        let a = match s {
+            Shape::Circle(r) => r,
+            _ => 0.0,
+        };
+        return 0;
+    }",
+    true
+)]
+#[case(
+    "enum Shape {
+        Circle(float),
+    }
+
+    def main() -> int64 {
+        let s = Shape::Square(1.0);
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "enum Shape {
+        Circle(float),
+    }
+
+    def main() -> int64 {
+        let s = Shape::Circle(1);
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "enum Shape {
+        Circle(float),
+        Rect(float, float),
+    }
+
+    def main() -> int64 {
+        let s = Shape::Rect(1.0, 2.0);
+        let a = match s {
+            Shape::Circle(r) => 1,
+            Shape::Rect(w, h) => w,
+        };
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let a = 5;
+        let b = match a {
+            _ => 1,
+        };
+        return b;
+    }",
+    false
+)]
+#[case(
+    "trait Show {
+        def show(self) -> int64;
+    }
 
+    class Foo {
+        x: int64,
+    }
+
+    impl Show for Foo {
+        def show(self) -> int64 {
+            return self.x;
+        }
+    }
+
+    def print_all<T: Show>(a: T, b: T) -> int64 {
+        return a.show() + b.show();
+    }
+
+    def main() -> int64 {
+        return print_all(Foo { x: 1, }, Foo { x: 2, });
+    }",
+    true
+)]
+#[case(
+    "class Foo {
+        x: int64,
+    }
+
+    def show<T>(a: T) -> int64 {
+        return a.show();
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def show<T: Show>(a: T) -> int64 {
+        return 0;
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "trait Show {
+        def show(self) -> int64;
+    }
+
+    class Foo {
+        x: int64,
+    }
+
+    def print<T: Show>(a: T) -> int64 {
+        return a.show();
+    }
+
+    def main() -> int64 {
+        return print(Foo { x: 1, });
+    }",
+    false
+)]
+#[case(
+    "def sum<T>(a: T, b: T) -> T {
+        return a + b;
+    }
+
+    def main() -> int64 {
+        return sum<int64>(1, 2);
+    }",
+    false
+)]
+#[case(
+    "class Foo {
+        x: int64,
+    }
+
+    def add(self, other: int64) for Foo -> Foo {
+        return Foo { x: self.x + other, };
+    }
+
+    def sum<T: Add>(a: T, b: T) -> T {
+        return a + b;
+    }
+
+    def main() -> int64 {
+        let foo = Foo { x: 1, } + 2;
+        return sum<Foo>(foo, foo).x;
+    }",
+    false
+)]
+#[case(
+    "def sum<T: Add>(a: T, b: int64) -> T {
+        return a + b;
+    }
+
+    def main() -> int64 {
+        return sum<int64>(1, 2);
+    }",
+    false
+)]
+#[case(
+    "trait Show {
+        def show(self) -> int64;
+        def name(self) -> int64;
+    }
+
+    class Foo {
+        x: int64,
+    }
+
+    impl Show for Foo {
+        def show(self) -> int64 {
+            return self.x;
+        }
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "trait Show {
+        def show(self) -> int64;
+    }
+
+    class Foo {
+        x: int64,
+    }
+
+    impl Show for Foo {
+        def show(self) -> bool {
+            return true;
+        }
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "trait Show {
+        def show(self) -> int64;
+    }
+
+    class Foo {
+        x: int64,
+    }
+
+    impl Show for Foo {
+        def show(self) -> int64 {
+            return self.x;
+        }
+
+        def other(self) -> int64 {
+            return 0;
+        }
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "class Vec2 {
+        x: int64,
+        y: int64,
+    }
+
+    impl Add for Vec2 {
+        def add(self, other: Vec2) -> Vec2 {
+            return Vec2 {
+                x: self.x + other.x,
+                y: self.y + other.y,
+            };
+        }
+    }
+
+    def main() -> int64 {
+        let a = Vec2 { x: 1, y: 2, };
+        let b = a + a;
+        return b.y;
+    }",
+    true
+)]
+#[case(
+    "class Foo {
+        x: int64,
+    }
+
+    impl Index for Foo {
+        def idx(self, index: int64) -> int64 {
+            return self.x;
+        }
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let a = true ^ false;
+        let b = 1 << 3 >> 1;
+        return b;
+    }",
+    true
+)]
+#[case(
+    "def main() -> int64 {
+        let a = 1.0 & 2.0;
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let a = 1 << 2.0;
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let x = 1;
+        x += 2;
+        return x;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let mut b = true;
+        b *= false;
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let x = 5 as uint16;
+        let y = x as float64;
+        return y as int64;
+    }",
+    true
+)]
+#[case(
+    "def main() -> int64 {
+        let x = 5 as uint32;
+        let y = 5;
+        let z = x + y;
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let f = |x: int64| -> int64 { x };
+        return f(true);
+    }",
+    false
+)]
+#[case(
+    "def main() -> int64 {
+        let f = |x: int64| -> bool { x };
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def id<T>(x: T) -> T {
+        return x;
+    }
+
+    def main() -> int64 {
+        let f = id;
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "def five() -> int64 {
+        return 5;
+    }
+
+    const FIVE: int64 = five();
+
+    def main() -> int64 {
+        return FIVE;
+    }",
+    false
+)]
+#[case(
+    "const LIMIT: int64 = 10;
+
+    def main() -> int64 {
+        LIMIT = 5;
+        return LIMIT;
+    }",
+    false
+)]
+#[case(
+    "const LIMIT: int32 = 10;
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "static mut SEEN: bool = false;
+
+    def main() -> int64 {
+        SEEN = SEEN || 1 < 2;
+        return 0;
+    }",
+    true
+)]
+#[case(
+    "def main(x: int64) -> int64 {
+        return x;
+    }",
+    false
+)]
+#[case(
+    "def main() -> float {
+        return 1.0;
+    }",
+    false
+)]
+#[case(
+    "extern def printf(fmt: *int8, ...) -> int32
+
+    def main() -> int64 {
+        let fmt = 0 as *int8;
+        printf(fmt, 1 as int8, 2.5, fmt, true);
+        printf(fmt);
+        return 0;
+    }",
+    true
+)]
+#[case(
+    "def log(fmt: *int8, ...) {}
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "extern def printf(..., fmt: *int8) -> int32
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "extern def printf(fmt: *int8, ...) -> int32
+    class Foo {
+        x: int64,
+    }
+
+    def main() -> int64 {
+        printf(0 as *int8, Foo { x: 1, });
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "extern def printf(fmt: *int8, ...) -> int32
+
+    def main() -> int64 {
+        printf();
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "extern def callback(value: int64) -> int64 {
+        return value * 2;
+    }
+
+    def main() -> int64 {
+        let pointer = callback as *int8;
+        return callback(2);
+    }",
+    true
+)]
+#[case(
+    "def callback(value: int64) -> int64 {
+        return value * 2;
+    }
+
+    def main() -> int64 {
+        let pointer = callback as *int8;
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "extern def log(fmt: *int8, ...) -> int32 {
+        return 0 as int32;
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "#[export]
+    def add(a: int64, b: int64) -> int64 {
+        return a + b;
+    }
+
+    def main() -> int64 {
+        return add(1, 2);
+    }",
+    true
+)]
+#[case(
+    "extern def puts(s: *int8) -> int32
+
+    #[export]
+    def puts(s: *int8) -> int32 {
+        return 0 as int32;
+    }
+
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
+#[case(
+    "#[export]
+    def main() -> int64 {
+        return 0;
+    }",
+    false
+)]
 fn test_compile(#[case] code: &'static str, #[case] should_compile: bool) {
     let res = Compiler::compile(code, None);
     if should_compile {
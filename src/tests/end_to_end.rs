@@ -1,38 +1,30 @@
 #![cfg(test)]
-use crate::compiler::Compiler;
+use crate::{
+    compiler::{CompileOptions, Compiler},
+    tests::backends::BACKEND_CASES,
+    utils::{build, exit_code, run_ir, Emit},
+};
 use rstest::rstest;
-use serial_test::file_serial;
-use std::io::Write;
-
-// const TEMP_FILE: &str = "tmp.exe";
-#[cfg(target_os = "windows")]
-const TEMP_FILE: &str = "tmp.exe";
-#[cfg(not(target_os = "windows"))]
-const TEMP_FILE: &str = "tmp";
 
 #[rstest]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         return 1;
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         return -(10 + 5);
     }",
     Ok(-15)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         return - 5 * 10;
     }",
     Ok(-50)
 )]
-#[file_serial]
 #[case(
     "def my_function() -> int64 {
         return 2;
@@ -46,7 +38,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(4)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         return {
@@ -58,7 +49,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(6)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let b = 10;
@@ -69,7 +59,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "class Foo<T> {
         data: T,
@@ -86,7 +75,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(30)
 )]
-#[file_serial]
 #[case(
     "def fib(n: int64) -> int64 {
         if n == 0 {
@@ -103,13 +91,11 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(55)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         return 10 + true;
     }", Err(())
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let num = 17;
@@ -193,7 +179,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(5)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let a = 4;
@@ -208,7 +193,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let a = 4;
@@ -221,7 +205,6 @@ const TEMP_FILE: &str = "tmp";
 
     }", Err(())
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let a = 4;
@@ -234,7 +217,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(4)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let a = 4;
@@ -247,7 +229,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(4)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let boolean = true;
@@ -261,7 +242,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let foo = 100;
@@ -276,7 +256,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(200)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let list_1 = [1, 2, 3];
@@ -287,7 +266,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(5)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let mut list = [1, 2, 3];
@@ -297,7 +275,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(10)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -312,7 +289,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(10)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -329,7 +305,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(30)
 )]
-#[file_serial]
 #[case(
     "class Bar {
         data: int64,
@@ -352,7 +327,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(20)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -371,7 +345,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(10)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -394,7 +367,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(10)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -441,7 +413,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(10)
 )]
-#[file_serial]
 #[case(
     "def foo() {
         if true {
@@ -458,7 +429,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(0)
 )]
-#[file_serial]
 #[case(
     "extern def calloc(num: int64, size: int64) -> int64
 
@@ -479,7 +449,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(0)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let bool1 = true;
@@ -493,7 +462,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "class Foo<T> {
         inner: T,
@@ -517,7 +485,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(10)
 )]
-#[file_serial]
 #[case(
     "def is_three(self) for int64 -> bool {
         return self == 3;
@@ -540,7 +507,6 @@ const TEMP_FILE: &str = "tmp";
     Ok(1)
 )]
 // der test führt zu stackoverflow rekursion in trait vielleicht fixen
-// #[file_serial]
 // #[case(
 //     "def eq(self, other: int) for int -> bool {
 //         return self == other;
@@ -567,7 +533,6 @@ const TEMP_FILE: &str = "tmp";
 //     }",
 //     Ok(1)
 // )]
-#[file_serial]
 #[case(
     "def is_prime(self) for int64 -> bool {
         if self < 2 {
@@ -602,7 +567,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         if true {
@@ -615,7 +579,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         if true {
@@ -630,7 +593,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         if true {
@@ -646,7 +608,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(0)
 )]
-#[file_serial]
 #[case(
     "def add(self, other: int64) for int64 -> int64 {
         return 4;
@@ -660,7 +621,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(4)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -680,7 +640,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(30)
 )]
-#[file_serial]
 #[case(
     "def eq(self, other: int64) for int64 -> bool {
         return true;
@@ -716,7 +675,6 @@ const TEMP_FILE: &str = "tmp";
 
     Err(())
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -736,7 +694,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(10)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -756,7 +713,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(30)
 )]
-#[file_serial]
 #[case(
     "class Foo {
         data: int64,
@@ -785,7 +741,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(60)
 )]
-#[file_serial]
 #[case(
     "
         def main() -> int64 {
@@ -804,7 +759,6 @@ const TEMP_FILE: &str = "tmp";
     ",
     Ok(50)
 )]
-// #[file_serial]
 // #[case(
 //     "class Foo {
 //         data: int,
@@ -829,7 +783,6 @@ const TEMP_FILE: &str = "tmp";
 //     }",
 //     Ok(30)
 // )]
-// #[file_serial]
 // #[case(
 //     "class Foo {
 //         data: int,
@@ -851,7 +804,6 @@ const TEMP_FILE: &str = "tmp";
 //     }",
 //     Ok(10)
 // )]
-#[file_serial]
 #[case(
     "class Vector {
         x: int64,
@@ -883,7 +835,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(1)
 )]
-#[file_serial]
 #[case(
     "class SmallList {
         first: int64,
@@ -912,7 +863,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(60)
 )]
-#[file_serial]
 #[case(
     "class SmallList {
         first: int64,
@@ -944,7 +894,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(70)
 )]
-#[file_serial]
 #[case(
     "def idx(self, idx: int64) for int64 -> *int64 {
         return &(self * idx);
@@ -961,7 +910,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(80)
 )]
-#[file_serial]
 #[case(
     "def inner<T>(x: T) -> T {
         return x;
@@ -977,7 +925,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(42)
 )]
-#[file_serial]
 #[case(
     "class Foo<T> {
         inner: T;
@@ -998,7 +945,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(15)
 )]
-#[file_serial]
 #[case(
     "class Foo<T> {
         inner: T;
@@ -1015,7 +961,6 @@ const TEMP_FILE: &str = "tmp";
     ",
     Ok(0)
 )]
-#[file_serial]
 #[case(
     "class Foo<T> {
         inner: T;
@@ -1032,7 +977,6 @@ const TEMP_FILE: &str = "tmp";
     ",
     Err(())
 )]
-#[file_serial]
 #[case(
     "class Foo<T> {
         inner: T,
@@ -1056,14 +1000,12 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(42)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         return 4.0 as int64;
     }",
     Ok(4)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let a = 1.0;
@@ -1073,7 +1015,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(4)
 )]
-#[file_serial]
 #[case(
     "def main() -> int64 {
         let a = 120 as float;
@@ -1083,7 +1024,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(123)
 )]
-#[file_serial]
 #[case(
     "# comment
     def main() -> int64 {
@@ -1092,7 +1032,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(11)
 )]
-#[file_serial]
 #[case(
     "def foo() {}
     def main() -> int64 {
@@ -1101,7 +1040,6 @@ const TEMP_FILE: &str = "tmp";
     }",
     Err(())
 )]
-#[file_serial]
 #[case(
     "
     extern def calloc(num: int64, size: int64) -> int64
@@ -1140,46 +1078,765 @@ const TEMP_FILE: &str = "tmp";
     }",
     Ok(0)
 )]
+#[case(
+    "def main() -> int64 {
+        let mut sum = 0;
+        for i in 0..5 {
+            sum = sum + i;
+        }
+        return sum;
+    }",
+    Ok(10)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut sum = 0;
+        for x in [1, 2, 3] {
+            sum = sum + x;
+        }
+        return sum;
+    }",
+    Ok(6)
+)]
+#[case(
+    "def main() -> int64 {
+        let i = 7;
+        let mut sum = 0;
+        for i in 0..3 {
+            for j in i..3 {
+                sum = sum + j;
+            }
+        }
+        return sum + i;
+    }",
+    Ok(15)
+)]
+#[case(
+    "class Counter {
+        max: int64,
+    }
+
+    def iter_len(self) for Counter -> int64 {
+        return self.max;
+    }
+
+    def iter_get(self, index: int64) for Counter -> int64 {
+        return index * 2;
+    }
+
+    def main() -> int64 {
+        let counter = Counter { max: 4 };
+        let mut sum = 0;
+        for x in counter {
+            sum = sum + x;
+        }
+        return sum;
+    }",
+    Ok(12)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut i = 0;
+        while true {
+            if i == 5 {
+                break;
+            }
+            i = i + 1;
+        }
+        return i;
+    }",
+    Ok(5)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut sum = 0;
+        for i in 0..10 {
+            if i % 2 == 0 {
+                continue;
+            }
+            sum = sum + i;
+        }
+        return sum;
+    }",
+    Ok(25)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut count = 0;
+        for i in 0..3 {
+            let mut j = 0;
+            while true {
+                j = j + 1;
+                if j > i {
+                    break;
+                }
+                count = count + 1;
+            }
+            if i == 1 {
+                continue;
+            }
+            count = count + 10;
+        }
+        return count;
+    }",
+    Ok(23)
+)]
+#[case(
+    "enum Shape {
+        Square(int64),
+        Rect(int64, int64),
+        Empty,
+    }
+
+    def area(shape: Shape) -> int64 {
+        return match shape {
+            Shape::Square(s) => s * s,
+            Shape::Rect(w, h) => w * h,
+            Shape::Empty => 0,
+        };
+    }
+
+    def main() -> int64 {
+        let a = area(Shape::Rect(2, 3));
+        let b = area(Shape::Square(4));
+        let c = area(Shape::Empty);
+        return a + b + c;
+    }",
+    Ok(22)
+)]
+#[case(
+    "enum Option<T> {
+        Some(T),
+        None,
+    }
+
+    def main() -> int64 {
+        let a = Option::Some(5);
+        let b = Option::None<int64>;
+
+        let x = match a {
+            Option::Some(v) => v,
+            Option::None => 0,
+        };
+        let y = match b {
+            Option::Some(v) => v,
+            _ => 10,
+        };
+        return x + y;
+    }",
+    Ok(15)
+)]
+#[case(
+    "trait Area {
+        def area(self) -> int64;
+    }
+
+    class Square {
+        side: int64,
+    }
+
+    class Rect {
+        w: int64,
+        h: int64,
+    }
+
+    impl Area for Square {
+        def area(self) -> int64 {
+            return self.side * self.side;
+        }
+    }
+
+    impl Area for Rect {
+        def area(self) -> int64 {
+            return self.w * self.h;
+        }
+    }
+
+    def double_area<T: Area>(shape: T) -> int64 {
+        return shape.area() * 2;
+    }
+
+    def main() -> int64 {
+        let s = Square { side: 3, };
+        let r = Rect { w: 2, h: 5, };
+        return double_area(s) + double_area(r) + s.area();
+    }",
+    Ok(47)
+)]
+#[case(
+    "trait Grow {
+        def grow(self) -> Self;
+        def size(self) -> int64;
+    }
+
+    class Tree {
+        height: int64,
+    }
+
+    impl Grow for Tree {
+        def grow(self) -> Tree {
+            return Tree {
+                height: self.height + 1,
+            };
+        }
+
+        def size(self) -> int64 {
+            return self.height;
+        }
+    }
+
+    def grown_size<T: Grow>(x: T) -> int64 {
+        let a = x.grow();
+        let b = a.grow();
+        return b.size();
+    }
+
+    def main() -> int64 {
+        return grown_size(Tree { height: 5, });
+    }",
+    Ok(7)
+)]
+#[case(
+    "class Vec2 {
+        x: int64,
+        y: int64,
+    }
+
+    impl Add for Vec2 {
+        def add(self, other: Vec2) -> Vec2 {
+            return Vec2 { x: self.x + other.x, y: self.y + other.y, };
+        }
+    }
+
+    def sum<T: Add>(a: T, b: T) -> T {
+        return a + b;
+    }
+
+    def sum_calls<T: Add>(a: T, b: T) -> T {
+        return a.add(b);
+    }
+
+    def largest<T: GreaterThan>(a: T, b: T) -> T {
+        if a > b {
+            return a;
+        }
+        return b;
+    }
+
+    def main() -> int64 {
+        let v = sum<Vec2>(Vec2 { x: 1, y: 2, }, Vec2 { x: 3, y: 4, });
+        let w = sum_calls<Vec2>(v, v);
+        return sum<int64>(10, 20) + sum_calls<int64>(1, 2) + largest<int64>(7, 5) + w.y;
+    }",
+    Ok(52)
+)]
+#[case(
+    "def main() -> int64 {
+        return 6 & 3 | 1 << 4 ^ 1;
+    }",
+    Ok(19)
+)]
+#[case(
+    "def main() -> int64 {
+        let x = 0 - 64;
+        return (x >> 2) + (200 >> 3);
+    }",
+    Ok(9)
+)]
+#[case(
+    "def hash(values: [int64; 4]) -> int64 {
+        let mut h = 7;
+        for v in values {
+            h = (h << 5) ^ (h >> 2) ^ v;
+        }
+        return h & 127;
+    }
+
+    def main() -> int64 {
+        return hash([1, 2, 3, 4]);
+    }",
+    Ok(55)
+)]
+#[case(
+    "class Flags {
+        bits: int64,
+    }
+
+    impl BitOr for Flags {
+        def bitor(self, other: Flags) -> Flags {
+            return Flags { bits: self.bits | other.bits, };
+        }
+    }
+
+    def main() -> int64 {
+        let a = Flags { bits: 1, };
+        let b = Flags { bits: 4, };
+        let c = a | b;
+        return c.bits;
+    }",
+    Ok(5)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut x = 100;
+        x -= 10;
+        x /= 4;
+        x %= 5;
+        x |= 8;
+        x &= 14;
+        x ^= 3;
+        x >>= 1;
+        x *= 3;
+        return x;
+    }",
+    Ok(12)
+)]
+#[case(
+    "class Counter {
+        len: int64,
+    }
+
+    def grow(self) for Counter {
+        self.len *= 2;
+        self.len += 1;
+    }
+
+    def main() -> int64 {
+        let counter = Counter { len: 3, };
+        counter.grow();
+
+        let mut list = [1, 2, 3];
+        list[1] += 10;
+        list[2] <<= 2;
+
+        return counter.len + list[1] + list[2];
+    }",
+    Ok(31)
+)]
+#[case(
+    "class Vec2 {
+        x: int64,
+        y: int64,
+    }
 
+    impl Add for Vec2 {
+        def add(self, other: Vec2) -> Vec2 {
+            return Vec2 { x: self.x + other.x, y: self.y + other.y, };
+        }
+    }
+
+    def main() -> int64 {
+        let mut a = Vec2 { x: 1, y: 2, };
+        a += Vec2 { x: 3, y: 4, };
+        return a.x * 10 + a.y;
+    }",
+    Ok(46)
+)]
+#[case(
+    "def main() -> int64 {
+        let x = 255 as uint8;
+        let y = 255 as int8;
+        return (x as int64) + (y as int64);
+    }",
+    Ok(254)
+)]
+#[case(
+    "def main() -> int64 {
+        let add = |a: int64, b: int64| -> int64 { return a + b; };
+        return add(2, 3);
+    }",
+    Ok(5)
+)]
+#[case(
+    "def main() -> int64 {
+        let offset = 10;
+        let f = |x: int64| { x + offset };
+        return f(5);
+    }",
+    Ok(15)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut count = 0;
+        let inc = || { count = count + 1; };
+        inc();
+        inc();
+        inc();
+        return count;
+    }",
+    Ok(3)
+)]
+#[case(
+    "def double(x: int64) -> int64 {
+        return x * 2;
+    }
+
+    def apply(f: |int64| -> int64, x: int64) -> int64 {
+        return f(x);
+    }
+
+    def main() -> int64 {
+        let three = 3;
+        return apply(double, 5) + apply(|x: int64| -> int64 { x - three }, 7);
+    }",
+    Ok(14)
+)]
+#[case(
+    "def make_adder(n: int64) -> |int64| -> int64 {
+        return |x: int64| -> int64 { x + n };
+    }
+
+    def main() -> int64 {
+        let add5 = make_adder(5);
+        return add5(10);
+    }",
+    Ok(15)
+)]
+#[case(
+    "static mut CALLS: int64 = 0;
+
+    def tick() -> int64 {
+        CALLS += 1;
+        return CALLS;
+    }
+
+    def main() -> int64 {
+        let mut i = 0;
+        while i < 3 {
+            if i > 0 {
+                tick()
+            }
+            i += 1;
+        }
+        return CALLS + 40;
+    }",
+    Ok(42)
+)]
+#[case(
+    "import \"std/collections/list.mx\";
+
+    def double(x: int64) -> int64 {
+        return x * 2;
+    }
+
+    def main() -> int64 {
+        let list = List::new<int64>();
+        list.push<int64>(1);
+        list.push<int64>(2);
+        list.push<int64>(3);
+        let doubled = list.map<int64>(double);
+        let big = doubled.filter<int64>(|x: int64| -> bool { x > 2 });
+        return big.len * 10 + big[1];
+    }",
+    Ok(26)
+)]
+#[case(
+    "def apply_twice<T>(f: |T| -> T, x: T) -> T {
+        return f(f(x));
+    }
+
+    def main() -> int64 {
+        return apply_twice<int64>(|x: int64| -> int64 { x * 3 }, 2);
+    }",
+    Ok(18)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut x = 1;
+        let y = {
+            x = x + 10;
+            if x > 5 {
+                return x * 2;
+            }
+            return 0;
+        };
+        return x + y;
+    }",
+    Ok(33)
+)]
+#[case(
+    "def main() -> int64 {
+        let a = 2;
+        let b = {
+            let a = 5;
+            let c = { a * 3 };
+            c + a
+        };
+        return a + b;
+    }",
+    Ok(22)
+)]
+#[case(
+    "def main() -> int64 {
+        let mut sum = 0;
+        for i in 0..4 {
+            sum = sum + {
+                if i % 2 == 0 {
+                    return i;
+                }
+                return 10;
+            };
+        }
+        return sum;
+    }",
+    Ok(22)
+)]
+#[case(
+    "const BASE: int64 = 4;
+    const SIZE: int64 = BASE * 3 + (1 << 2);
+    const SMALL: uint8 = (250 as uint8) + (10 as uint8);
+
+    def main() -> int64 {
+        return SIZE + (SMALL as int64);
+    }",
+    Ok(20)
+)]
+#[case(
+    "static mut COUNTER: int64 = 0;
+
+    def bump(by: int64) {
+        COUNTER += by;
+    }
+
+    def main() -> int64 {
+        bump(3);
+        bump(4);
+        let add = |x: int64| -> int64 { COUNTER + x };
+        return add(1) * 2;
+    }",
+    Ok(16)
+)]
+#[case(
+    "static mut TOTAL: int64 = 1;
+
+    def shadow() -> int64 {
+        let TOTAL = 5;
+        return TOTAL;
+    }
+
+    def main() -> int64 {
+        TOTAL = TOTAL + shadow();
+        return TOTAL;
+    }",
+    Ok(6)
+)]
+#[case(
+    "import \"std/collections/list.mx\";
+
+    def main() -> int64 {
+        let list = List::new<int64>();
+        list.push(3);
+        list.push(4);
+        let last = list.pop<int64>();
+        return list.len + last;
+    }",
+    Ok(5)
+)]
+#[case(
+    "import \"std/string.mx\";
+    import \"std/collections/list.mx\";
+
+    def main(args: List<String>) -> int64 {
+        # das erste argument ist der pfad zum programm
+        return args.len;
+    }",
+    Ok(1)
+)]
+#[case(
+    "import \"std/io.mx\";
+    import \"std/string.mx\";
+
+    def main() -> int64 {
+        # printf gibt die anzahl der geschriebenen zeichen zurück
+        let written = printf(\"%d %.1f %s\\n\".to_c_string(), 7 as int16, 1.5, \"ok\".to_c_string());
+        return written as int64;
+    }",
+    Ok(9)
+)]
+#[case(
+    "# `ldiv_t` kommt in zwei registern zurück, `div_t` in einem
+    class LDiv {
+        quot: int64,
+        rem: int64,
+    }
+
+    class Div {
+        quot: int32,
+        rem: int32,
+    }
+
+    extern def ldiv(numer: int64, denom: int64) -> LDiv
+    extern def div(numer: int32, denom: int32) -> Div
+
+    def main() -> int64 {
+        let a = ldiv(47, 10);
+        let b = div(47 as int32, 10 as int32);
+        return a.quot + a.rem * 10 + (b.quot * b.rem) as int64;
+    }",
+    Ok(102)
+)]
+#[case(
+    "extern def qsort(base: *int64, count: int64, size: int64, compare: *int8) -> void
+
+    extern def compare(a: *int64, b: *int64) -> int32 {
+        if (~a) < (~b) {
+            return -1 as int32;
+        }
+        if (~a) > (~b) {
+            return 1 as int32;
+        }
+        return 0 as int32;
+    }
+
+    def main() -> int64 {
+        let values = [3, 1, 2];
+        qsort((&values) as *int64, 3, 8, compare as *int8);
+        return values[0] * 100 + values[1] * 10 + values[2];
+    }",
+    Ok(123)
+)]
+#[case(
+    "# über 16 bytes, im c abi als `byval` und `sret`
+    class Big {
+        a: int64,
+        b: int64,
+        c: int64,
+    }
+
+    extern def sum(big: Big, scale: float64) -> Big {
+        return Big { a: big.a + big.b + big.c, b: (scale * (2.0 as float64)) as int64, c: 0, };
+    }
+
+    def main() -> int64 {
+        let big = sum(Big { a: 1, b: 2, c: 3, }, 1.5 as float64);
+        return big.a + big.b;
+    }",
+    Ok(9)
+)]
+#[case(
+    "import \"std/env.mx\";
+    import \"std/string.mx\";
+
+    def main() -> int64 {
+        set_env(\"MX_ENV_TEST\", \"42\");
+        let value = get_env(\"MX_ENV_TEST\");
+        let arguments = args();
+        return value.parse_int() + arguments.len;
+    }",
+    Ok(43)
+)]
 fn end_to_end_test(#[case] source_code: &'static str, #[case] expected: Result<i32, ()>) {
     let result = compile_and_run(source_code);
 
     assert_eq!(result, expected);
 }
 
+#[test]
+fn backend_cases() {
+    for (source_code, expected) in BACKEND_CASES {
+        assert_eq!(compile_and_run(source_code), Ok(*expected), "{source_code}");
+    }
+}
+
 fn compile_and_run(source_code: &str) -> Result<i32, ()> {
-    let ir = match Compiler::compile(source_code, None) {
-        Ok(out) => out,
-        Err(_) => {
-            return Err(());
-        }
-    };
+    let ir = Compiler::compile(source_code, None).map_err(|_| ())?;
+    let status = run_ir(&ir, &[], &[]).map_err(|_| ())?;
 
-    let mut out = std::process::Command::new("clang")
-        .args(["-x", "ir", "-", "-o", TEMP_FILE])
-        .stdin(std::process::Stdio::piped())
-        .spawn()
-        .expect("failed to execute process");
+    Ok(exit_code(status))
+}
 
-    let mut stdin = out.stdin.as_ref().unwrap();
-    stdin.write_all(ir.as_bytes()).unwrap();
+// client und server im selben prozess, `connect` wartet nicht auf `accept`
+#[rstest]
+#[case(
+    "import \"std/string.mx\";
+    import \"std/net/common.mx\";
+    import \"std/net/posix/tcp.mx\";
 
-    let status = out.wait().unwrap();
+    def main() -> int64 {
+        let listener = TcpListener::bind(SockAddrV4::from_string(\"127.0.0.1:0\"));
+        let client = TcpStream::connect(listener.local_addr());
+        let server = listener.accept();
+
+        client.send_string(\"ping\");
+        let request = server.recv_string(16);
+        server.send_string(request + \" pong\");
+        let response = client.recv_string(16);
+
+        client.close();
+        server.close();
+        listener.close();
+        return response.len();
+    }",
+    Ok(9)
+)]
+#[case(
+    "import \"std/string.mx\";
+    from \"std/mem.mx\" import malloc;
+    import \"std/net/common.mx\";
+    import \"std/net/posix/udp.mx\";
 
-    if status.code().unwrap() != 0 {
-        return Err(());
-    }
+    def main() -> int64 {
+        let server = UdpSocket::bind(SockAddrV4::from_string(\"127.0.0.1:0\"));
+        let client = UdpSocket::bind(SockAddrV4::from_string(\"127.0.0.1:0\"));
+        client.connect(server.local_addr());
 
-    while !std::path::Path::new(&TEMP_FILE).exists() {
-        std::thread::sleep(std::time::Duration::from_millis(100));
-    }
+        let message = \"hello\";
+        client.send(message.to_c_string(), message.len());
 
-    let mut out = std::process::Command::new(TEMP_FILE)
-        .spawn()
-        .expect("failed to execute process");
+        let buf = malloc(16) as *int8;
+        let datagram = server.recv_from(buf, 16);
+        server.send_to(buf, datagram.len, datagram.source);
+        let len = client.recv(buf, 16);
 
-    let status = out.wait().unwrap();
-    let code = status.code().unwrap();
+        if datagram.source.port() != client.local_addr().port() {
+            return 1;
+        }
 
-    Ok(code)
+        client.close();
+        server.close();
+        return datagram.len + len;
+    }",
+    Ok(10)
+)]
+#[cfg(unix)]
+fn posix_net_test(#[case] source_code: &'static str, #[case] expected: Result<i32, ()>) {
+    let result = compile_and_run(source_code);
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+#[cfg(unix)]
+fn library_symbols() {
+    let code = "def private_helper() -> int64 {
+            return 42;
+        }
+
+        #[export]
+        def answer() -> int64 {
+            return private_helper();
+        }";
+    let options = CompileOptions {
+        library: true,
+        ..Default::default()
+    };
+    let ir = Compiler::compile_with_options(code, None, &options).unwrap();
+
+    let object = std::env::temp_dir().join(format!("mx-symbols-{}.o", std::process::id()));
+    build(&ir, &object, Emit::Object, &[]).unwrap();
+    let output = std::process::Command::new("nm").arg(&object).output();
+    let _ = std::fs::remove_file(&object);
+    let symbols = String::from_utf8(output.unwrap().stdout).unwrap();
+
+    // `T` ist global, `t` nur innerhalb der objekt datei sichtbar, macos setzt `_` davor
+    let kind = |name: &str| {
+        symbols.lines().find_map(|line| {
+            let mut parts = line.split_whitespace().rev();
+            let symbol = parts.next()?;
+            (symbol.trim_start_matches('_') == name).then(|| parts.next().unwrap().to_string())
+        })
+    };
+    assert_eq!(kind("answer").as_deref(), Some("T"));
+    assert_ne!(kind("private_helper").as_deref(), Some("T"));
+    assert_ne!(kind("answer.export").as_deref(), Some("T"));
 }
@@ -0,0 +1,185 @@
+#![cfg(test)]
+use crate::{
+    compiler::{CompileOptions, Compiler},
+    interpreter::{CapturedOutput, Interpreter, ReplError, RuntimeError, Session, Value},
+    tests::backends::BACKEND_CASES,
+};
+use rstest::rstest;
+
+#[rstest]
+#[case(
+    "def main() -> int64 {
+        return (-(10 + 5)) * 2 + 40;
+    }",
+    Ok(10)
+)]
+#[case(
+    "def fib(n: int64) -> int64 {
+        if n < 2 {
+            return n;
+        }
+        return fib(n - 1) + fib(n - 2);
+    }
+
+    def main() -> int64 {
+        return fib(15);
+    }",
+    Ok(610)
+)]
+#[case(
+    "class Vec2 {
+        x: int64,
+        y: int64,
+    }
+
+    impl Add for Vec2 {
+        def add(self, other: Vec2) -> Vec2 {
+            return Vec2 { x: self.x + other.x, y: self.y + other.y, };
+        }
+    }
+
+    def scale(self, by: int64) for Vec2 {
+        self.x = self.x * by;
+        self.y = self.y * by;
+    }
+
+    def main() -> int64 {
+        let mut a = Vec2 { x: 1, y: 2, };
+        a += Vec2 { x: 3, y: 4, };
+        a.scale(2);
+        return a.x * 100 + a.y;
+    }",
+    Ok(812)
+)]
+#[case(
+    "import \"std/string.mx\";
+    import \"std/collections/list.mx\";
+
+    def main(args: List<String>) -> int64 {
+        return args.len;
+    }",
+    Ok(1)
+)]
+#[case(
+    "import \"std/time.mx\";
+
+    def main() -> int64 {
+        let start = unix_millis();
+        sleep_ms(20);
+        if unix_millis() - start >= 20 {
+            return 1;
+        }
+        return 0;
+    }",
+    Ok(1)
+)]
+#[case(
+    "def main() -> int64 {
+        let zero = 0;
+        return 1 / zero;
+    }",
+    Err(RuntimeError::DivisionByZero)
+)]
+#[case(
+    "import \"std/mem.mx\";
+
+    def main() -> int64 {
+        return malloc(-1);
+    }",
+    Err(RuntimeError::OutOfMemory)
+)]
+#[case(
+    "import \"std/mem.mx\";
+
+    def main() -> int64 {
+        return calloc(4611686018427387904, 8);
+    }",
+    Err(RuntimeError::OutOfMemory)
+)]
+fn interpreter_test(#[case] source_code: &str, #[case] expected: Result<i32, RuntimeError>) {
+    let program = Compiler::parse_with_options(source_code, None, &CompileOptions::default())
+        .expect("program should compile");
+
+    assert_eq!(Interpreter::new(program).run_main(), expected);
+}
+
+#[test]
+fn backend_cases() {
+    for (source_code, expected) in BACKEND_CASES {
+        let program = Compiler::parse_with_options(source_code, None, &CompileOptions::default())
+            .expect("program should compile");
+
+        assert_eq!(
+            Interpreter::new(program).run_main(),
+            Ok(*expected),
+            "{source_code}"
+        );
+    }
+}
+
+#[test]
+fn interpreter_output_test() {
+    let source_code = "import \"std/io.mx\";
+    import \"std/string.mx\";
+
+    def main() {
+        let n = 42;
+        println(\"n = \" + n.to_string());
+        printf(\"%s %05.1f %ld %c%%\\n\".to_c_string(), \"x\".to_c_string(), 2.5, n, 65 as int8);
+    }";
+
+    let program = Compiler::parse_with_options(source_code, None, &CompileOptions::default())
+        .expect("program should compile");
+    let output = CapturedOutput::default();
+
+    let result = Interpreter::new(program)
+        .with_output(Box::new(output.clone()))
+        .run_main();
+
+    assert_eq!(result, Ok(0));
+    assert_eq!(output.contents(), "n = 42\nx 002.5 42 A%\n");
+}
+
+#[rstest]
+#[case(&["let x = 40;", "x + 2"], Value::Integer(42))]
+#[case(
+    &["def square(n: int64) -> int64 { return n * n; }", "square(7)"],
+    Value::Integer(49)
+)]
+#[case(
+    &["let mut n = 1;", "for i in 0..4 { n = n * 2; }", "n"],
+    Value::Integer(16)
+)]
+#[case(
+    &["class Point { x: int64, y: int64, }", "Point { x: 1, y: 2, }"],
+    Value::Class {
+        name: "Point".to_string(),
+        fields: vec![
+            ("x".to_string(), Value::Integer(1)),
+            ("y".to_string(), Value::Integer(2)),
+        ],
+    }
+)]
+fn repl_test(#[case] inputs: &[&str], #[case] expected: Value) {
+    let mut session = Session::new(vec![]).with_output(Box::new(CapturedOutput::default()));
+    let (last, inputs) = inputs.split_last().unwrap();
+
+    for input in inputs {
+        assert_eq!(session.eval(input).unwrap(), None);
+    }
+
+    assert_eq!(session.eval(last).unwrap(), Some(expected));
+}
+
+#[test]
+fn repl_runtime_error_test() {
+    let mut session = Session::new(vec![]).with_output(Box::new(CapturedOutput::default()));
+
+    session.eval("let zero = 0;").unwrap();
+    assert!(matches!(
+        session.eval("1 / zero"),
+        Err(ReplError::Runtime(RuntimeError::DivisionByZero))
+    ));
+    assert!(matches!(session.eval("zero +"), Err(ReplError::Compile(_))));
+    assert_eq!(session.eval("zero + 1").unwrap(), Some(Value::Integer(1)));
+}
@@ -2,7 +2,7 @@
 use crate::lexer::{
     lexer_main::{lex, lex_unspanned},
     position::{Position, Span, Spanned},
-    tokens::{Keyword, Literal, Operator, Punctuation, Token},
+    tokens::{Keyword, Literal, Operator, Punctuation, ReassignmentOperator, Token},
 };
 use pretty_assertions::assert_eq;
 
@@ -12,8 +12,7 @@ pub fn unspanned_basic() {
 
     let expected = vec![
         Token::Punctuation(Punctuation::SemiColon),
-        Token::Punctuation(Punctuation::Period),
-        Token::Punctuation(Punctuation::Period),
+        Token::Punctuation(Punctuation::Range),
         Token::Punctuation(Punctuation::CloseBrace),
         Token::Punctuation(Punctuation::CloseParen),
     ];
@@ -21,6 +20,113 @@ pub fn unspanned_basic() {
     assert_eq!(lex_unspanned(input).unwrap(), expected)
 }
 
+#[test]
+pub fn unspanned_for_range() {
+    let input = "for index in 0..int64";
+
+    let expected = vec![
+        Token::Keyword(Keyword::For),
+        Token::Identifier("index".to_string()),
+        Token::Keyword(Keyword::In),
+        Token::DataLiteral(Literal::Integer(0)),
+        Token::Punctuation(Punctuation::Range),
+        Token::Identifier("int64".to_string()),
+    ];
+
+    assert_eq!(lex_unspanned(input).unwrap(), expected)
+}
+
+#[test]
+pub fn unspanned_variadic() {
+    let input = "(fmt: *int8, ...)";
+
+    let expected = vec![
+        Token::Punctuation(Punctuation::OpenParen),
+        Token::Identifier("fmt".to_string()),
+        Token::Punctuation(Punctuation::Colon),
+        Token::Operator(Operator::Multiply),
+        Token::Identifier("int8".to_string()),
+        Token::Punctuation(Punctuation::Comma),
+        Token::Punctuation(Punctuation::Ellipsis),
+        Token::Punctuation(Punctuation::CloseParen),
+    ];
+
+    assert_eq!(lex_unspanned(input).unwrap(), expected)
+}
+
+#[test]
+pub fn unspanned_globals() {
+    let input = "const A: int64 = 1; static mut B";
+
+    let expected = vec![
+        Token::Keyword(Keyword::Const),
+        Token::Identifier("A".to_string()),
+        Token::Punctuation(Punctuation::Colon),
+        Token::Identifier("int64".to_string()),
+        Token::Assignment,
+        Token::DataLiteral(Literal::Integer(1)),
+        Token::Punctuation(Punctuation::SemiColon),
+        Token::Keyword(Keyword::Static),
+        Token::Keyword(Keyword::Mut),
+        Token::Identifier("B".to_string()),
+    ];
+
+    assert_eq!(lex_unspanned(input).unwrap(), expected)
+}
+
+#[test]
+pub fn unspanned_bitwise() {
+    let input = "a & b | c ^ d << 2 >> 1 && e";
+
+    // `>>` bleibt zwei tokens, wegen `List<List<T>>`
+    let expected = vec![
+        Token::Identifier("a".to_string()),
+        Token::Punctuation(Punctuation::Ampersand),
+        Token::Identifier("b".to_string()),
+        Token::Punctuation(Punctuation::Pipe),
+        Token::Identifier("c".to_string()),
+        Token::Operator(Operator::BitXor),
+        Token::Identifier("d".to_string()),
+        Token::Operator(Operator::ShiftLeft),
+        Token::DataLiteral(Literal::Integer(2)),
+        Token::Operator(Operator::GreaterThan),
+        Token::Operator(Operator::GreaterThan),
+        Token::DataLiteral(Literal::Integer(1)),
+        Token::Operator(Operator::And),
+        Token::Identifier("e".to_string()),
+    ];
+
+    assert_eq!(lex_unspanned(input).unwrap(), expected)
+}
+
+#[test]
+pub fn unspanned_compound_assignment() {
+    let input = "a *= 2; b <<= c >>= d |= e &= f ^= g %= h;";
+
+    let expected = vec![
+        Token::Identifier("a".to_string()),
+        Token::ReassignmentOperator(ReassignmentOperator::MultiplyEqual),
+        Token::DataLiteral(Literal::Integer(2)),
+        Token::Punctuation(Punctuation::SemiColon),
+        Token::Identifier("b".to_string()),
+        Token::ReassignmentOperator(ReassignmentOperator::ShiftLeftEqual),
+        Token::Identifier("c".to_string()),
+        Token::ReassignmentOperator(ReassignmentOperator::ShiftRightEqual),
+        Token::Identifier("d".to_string()),
+        Token::ReassignmentOperator(ReassignmentOperator::BitOrEqual),
+        Token::Identifier("e".to_string()),
+        Token::ReassignmentOperator(ReassignmentOperator::BitAndEqual),
+        Token::Identifier("f".to_string()),
+        Token::ReassignmentOperator(ReassignmentOperator::BitXorEqual),
+        Token::Identifier("g".to_string()),
+        Token::ReassignmentOperator(ReassignmentOperator::ModuloEqual),
+        Token::Identifier("h".to_string()),
+        Token::Punctuation(Punctuation::SemiColon),
+    ];
+
+    assert_eq!(lex_unspanned(input).unwrap(), expected)
+}
+
 #[test]
 pub fn unspanned_mixed() {
     let input = "if true false {return;}; .";
@@ -1,6 +1,12 @@
 #![cfg(test)]
 use crate::{
-    codegen::llvm_instructions::{FunctionCall, IRValue, IRVariable, MemoryOperation, ToIR},
+    codegen::{
+        header::c_header,
+        llvm_instructions::{FunctionCall, IRValue, IRVariable, MemoryOperation, ToIR},
+        structs::ParamAttribute,
+        structures::c_abi::{Abi, AbiArg},
+    },
+    compiler::{CompileOptions, Compiler},
     lexer::tokens::Literal,
     parser::ast::DataType,
 };
@@ -39,11 +45,236 @@ fn basic_instructions() {
             name: "%_foo".to_string(),
             _type: DataType::Integer64,
         })],
+        variadic_params: None,
+        arg_attributes: vec![],
     };
 
     let expected = "call i1 @bar(i64 %_foo)";
 
     assert_eq!(instruction.to_ir(), expected);
+
+    let instruction = FunctionCall {
+        name: "printf".to_string(),
+        return_type: DataType::Integer32,
+        args: vec![
+            IRValue::Variable(IRVariable {
+                name: "%_fmt".to_string(),
+                _type: DataType::Pointer(Box::new(DataType::Integer8)),
+            }),
+            IRValue::Variable(IRVariable {
+                name: "%_x".to_string(),
+                _type: DataType::Float64,
+            }),
+        ],
+        variadic_params: Some(vec![DataType::Pointer(Box::new(DataType::Integer8))]),
+        arg_attributes: vec![],
+    };
+
+    let expected = "call i32 (i8*, ...) @printf(i8* %_fmt,double %_x)";
+
+    assert_eq!(instruction.to_ir(), expected);
+}
+
+#[test]
+fn c_abi_attributes() {
+    let big = DataType::Array {
+        value_type: Box::new(DataType::Integer64),
+        len: 3,
+    };
+
+    let instruction = FunctionCall {
+        name: "take".to_string(),
+        return_type: DataType::None,
+        args: vec![IRValue::Variable(IRVariable {
+            name: "%_big".to_string(),
+            _type: DataType::Pointer(Box::new(big.clone())),
+        })],
+        variadic_params: None,
+        arg_attributes: vec![Some(ParamAttribute::ByVal(big))],
+    };
+
+    let expected = "call void @take([3 x i64]* byval([3 x i64]) align 8 %_big)";
+
+    assert_eq!(instruction.to_ir(), expected);
+}
+
+#[test]
+fn c_abi_classes() {
+    let program = Compiler::parse_with_options(
+        "class Pair {
+            a: int64,
+            b: float64,
+        }
+
+        class Small {
+            a: int32,
+            b: float,
+        }
+
+        class Floats {
+            x: float,
+            y: float,
+            z: float,
+        }
+
+        class Big {
+            a: int64,
+            b: int64,
+            c: int64,
+        }
+
+        def main() -> int64 {
+            return 0;
+        }",
+        None,
+        &CompileOptions::default(),
+    )
+    .unwrap();
+
+    let class = |name: &str| program.custom_types[name].value.clone();
+    let param = |abi: Abi, name: &str| {
+        abi.signature(&[class(name)], &DataType::None).params[0]
+            .1
+            .clone()
+    };
+    let returned = |abi: Abi, name: &str| abi.signature(&[], &class(name)).return_value;
+
+    // sysv: jeder 8 byte teil in einem eigenen register
+    assert_eq!(
+        param(Abi::SysV, "Pair"),
+        AbiArg::Coerce(vec![DataType::Integer64, DataType::Float64])
+    );
+    assert_eq!(
+        param(Abi::SysV, "Small"),
+        AbiArg::Coerce(vec![DataType::Integer64])
+    );
+    assert_eq!(
+        param(Abi::SysV, "Floats"),
+        AbiArg::Coerce(vec![DataType::Float64, DataType::Float])
+    );
+    assert_eq!(param(Abi::SysV, "Big"), AbiArg::Memory);
+    assert_eq!(returned(Abi::SysV, "Big"), AbiArg::Memory);
+
+    // ohne freie register auf dem stack
+    let signature = Abi::SysV.signature(
+        &[
+            DataType::Integer64,
+            DataType::Integer64,
+            DataType::Integer64,
+            DataType::Integer64,
+            DataType::Integer64,
+            DataType::Integer64,
+            class("Pair"),
+        ],
+        &DataType::None,
+    );
+    assert_eq!(signature.params[6].1, AbiArg::Memory);
+
+    assert_eq!(
+        param(Abi::Win64, "Small"),
+        AbiArg::Coerce(vec![DataType::Integer64])
+    );
+    assert_eq!(param(Abi::Win64, "Pair"), AbiArg::Indirect);
+    assert_eq!(returned(Abi::Win64, "Pair"), AbiArg::Memory);
+
+    assert_eq!(
+        param(Abi::AArch64, "Floats"),
+        AbiArg::Coerce(vec![DataType::Array {
+            value_type: Box::new(DataType::Float),
+            len: 3,
+        }])
+    );
+    assert_eq!(param(Abi::AArch64, "Big"), AbiArg::Indirect);
+    assert_eq!(returned(Abi::AArch64, "Big"), AbiArg::Memory);
+}
+
+#[test]
+fn export_header() {
+    let code = "class Point {
+            x: float64,
+            y: float64,
+        }
+
+        #[packed]
+        class Header {
+            tag: int8,
+            origin: Point,
+            name: [int8; 8],
+        }
+
+        #[export]
+        def scale(point: Point, by: float64) -> Point {
+            return Point { x: point.x * by, y: point.y * by, };
+        }
+
+        #[export]
+        def tag(header: *Header) -> bool {
+            return true;
+        }
+
+        def helper() -> int64 {
+            return 42;
+        }
+
+        #[export]
+        def answer() -> int64 {
+            return helper();
+        }";
+    let options = CompileOptions {
+        library: true,
+        ..Default::default()
+    };
+
+    let program = Compiler::parse_with_options(code, None, &options).unwrap();
+    let expected = "/* generated by the mx compiler */
+#ifndef GEOMETRY_H
+#define GEOMETRY_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+typedef struct Point Point;
+typedef struct Header Header;
+
+struct Point {
+    double x;
+    double y;
+};
+
+struct __attribute__((packed)) Header {
+    int8_t tag;
+    Point origin;
+    int8_t name[8];
+};
+
+Point scale(Point point, double by);
+bool tag(Header* header);
+int64_t answer(void);
+
+#endif /* GEOMETRY_H */
+";
+    assert_eq!(c_header(&program, "geometry.h"), expected);
+
+    // ohne `main`, die symbole ohne präfix haben das c abi
+    let ir = Compiler::compile_with_options(code, None, &options).unwrap();
+    assert!(ir.contains("define i64 @answer()"));
+    assert!(ir.contains("define internal i64 @answer.export()"));
+    assert!(ir.contains("define internal i64 @helper()"));
+    assert!(!ir.contains("@main("));
+}
+
+#[test]
+fn main_wrapper_symbols() {
+    let code = "def main() -> int64 {
+            return 0;
+        }";
+    let ir = Compiler::compile(code, None).unwrap();
+
+    // `mx_argc` und `mx_argv` bleiben in der objekt datei
+    assert!(ir.contains("define i32 @main(i32 %_argc,i8** %_argv)"));
+    assert!(ir.contains("define internal i32 @mx_argc()"));
+    assert!(ir.contains("define internal i64* @mx_argv()"));
+    assert!(ir.contains("@__mx_argc = internal global i32 0"));
 }
 
 #[test]
@@ -55,3 +286,32 @@ fn literals() {
     //     Literal::Float(1.15).to_ir()
     // );
 }
+
+#[test]
+fn module_prefix_is_relative() {
+    // das gleiche projekt an zwei orten ergibt das gleiche ir
+    let compile_in = |name: &str| {
+        let dir = std::env::temp_dir().join(format!("mx-prefix-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("util.mx"),
+            "pub def answer() -> int64 {
+                return 42;
+            }",
+        )
+        .unwrap();
+
+        let code = "import \"util.mx\";
+
+        def main() -> int64 {
+            return answer();
+        }";
+        let ir = Compiler::compile(code, Some(dir.clone()));
+        let _ = std::fs::remove_dir_all(&dir);
+        ir.unwrap()
+    };
+
+    let ir = compile_in("a");
+    assert!(ir.contains("@util.9a620564.answer"), "{ir}");
+    assert_eq!(ir, compile_in("b"));
+}
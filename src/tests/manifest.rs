@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+use std::path::PathBuf;
+
+use crate::manifest::Manifest;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn manifest_defaults() {
+    let manifest = Manifest::parse(
+        "[project]
+        name = \"hello\"",
+        PathBuf::from("project"),
+    )
+    .unwrap();
+
+    assert_eq!(manifest.entry(), PathBuf::from("project").join("main.mx"));
+    assert_eq!(
+        manifest.output(),
+        PathBuf::from("project").join(format!("hello{}", std::env::consts::EXE_SUFFIX))
+    );
+    assert!(manifest.library_paths().is_empty());
+    assert!(manifest.link_args().is_empty());
+}
+
+#[test]
+fn manifest_link_args() {
+    let root = PathBuf::from("project");
+    let manifest = Manifest::parse(
+        "[project]
+        name = \"server\"
+        entry = \"src/main.mx\"
+        output = \"bin/server\"
+        lib-paths = [\"vendor\"]
+
+        [link]
+        libs = [\"ws2_32\", \"m\"]
+        search-paths = [\"libs\"]
+        objects = [\"c/helper.o\"]",
+        root.clone(),
+    )
+    .unwrap();
+
+    assert_eq!(manifest.entry(), root.join("src/main.mx"));
+    assert_eq!(manifest.output(), root.join("bin/server"));
+    assert_eq!(manifest.library_paths(), vec![root.join("vendor")]);
+    assert_eq!(
+        manifest.link_args(),
+        vec![
+            root.join("c/helper.o").display().to_string(),
+            format!("-L{}", root.join("libs").display()),
+            "-lws2_32".to_string(),
+            "-lm".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn manifest_unknown_field() {
+    let result = Manifest::parse(
+        "[project]
+        name = \"hello\"
+        entyr = \"main.mx\"",
+        PathBuf::from("."),
+    );
+
+    assert!(result.is_err());
+}
@@ -1,8 +1,13 @@
 // #![allow(clippy::all)]
 pub mod lexer;
 pub mod llvm_instructions;
+pub mod manifest;
 pub mod parser;
 pub mod tokens;
 // pub mod test;
+pub mod backends;
 pub mod code;
 pub mod end_to_end;
+pub mod interpreter;
+pub mod warnings;
+pub mod wasm;
@@ -7,9 +7,11 @@ use crate::{
         tokens::Literal,
     },
     parser::{
-        ast::{BinaryOperator, DataType, Expr, Function, Statement, TypedExpr},
-        error::ParserError,
+        ast::{BinaryOperator, DataType, Expr, Function, Program, Statement, Trait, TypedExpr},
+        cfg::Target,
+        error::{ImportedError, ParserError},
         parser_main::Parser,
+        structures::closures::{local_closures, reference_captures},
         utils::same_variant,
     },
 };
@@ -480,10 +482,12 @@ fn function_decl_missing_var() {
 
     let mut parser = Parser::new(tokens, None);
 
+    // der fehler im body wird gesammelt, die funktion selbst wird trotzdem geparsed
     let func = parser.parse_func_def();
 
+    assert!(func.is_ok());
     assert!(same_variant(
-        &func.err().unwrap().value,
+        &parser.errors[0].value,
         &ParserError::VariableNotFound("".to_string())
     ))
 }
@@ -728,3 +732,610 @@ fn type_inference() {
 
     assert!(res.is_err());
 }
+
+#[test]
+fn collect_multiple_errors() {
+    let tokens = lex("def foo() -> int64 {
+        let x: int64 = true;
+        let y = z;
+        return 0;
+    }
+
+    def bar( {
+    }
+
+    def main() -> int64 {
+        return 0;
+    }
+    ")
+    .unwrap();
+
+    let mut parser = Parser::new(tokens, None).with_require_main(true);
+
+    let errors = parser.parse().unwrap_err();
+
+    assert_eq!(errors.len(), 3);
+    assert!(matches!(errors[0].value, ParserError::WrongType(..)));
+    assert!(matches!(errors[1].value, ParserError::VariableNotFound(..)));
+}
+
+#[test]
+fn recover_after_finished_statement() {
+    // der fehler kommt vor dem ersten token des statements, das `;` davor zählt nicht
+    for code in [
+        "def main() -> int64 {
+            let a = 1;
+            )
+            return a;
+        }",
+        "def main() -> int64 {
+            let a = 1;
+            def x() {}
+            return a;
+        }",
+    ] {
+        let tokens = lex(code).unwrap();
+        let errors = Parser::new(tokens, None)
+            .with_require_main(true)
+            .parse()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1, "code: \"{code}\"");
+    }
+}
+
+#[test]
+fn import_error_trace() {
+    let dir = std::env::temp_dir().join("mx_import_error_trace");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.mx"), "import \"b.mx\";").unwrap();
+    std::fs::write(
+        dir.join("b.mx"),
+        "def foo() -> int64 {
+            return x;
+        }",
+    )
+    .unwrap();
+
+    let tokens = lex("import \"a.mx\";
+
+    def main() -> int64 {
+        return 0;
+    }
+    ")
+    .unwrap();
+
+    let mut parser = Parser::new(tokens, None).with_relative_path(Some(dir.clone()));
+
+    let errors = parser.parse().unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    let import = match &errors[0].value {
+        ParserError::ImportFailed(import) => import,
+        other => panic!("expected import error, got: {other:?}"),
+    };
+
+    assert!(import.file.ends_with("b.mx"));
+    assert_eq!(import.trace.len(), 1);
+    assert!(import.trace[0].file.ends_with("a.mx"));
+    assert!(matches!(
+        import.errors[0].value,
+        ImportedError::Parser(ParserError::VariableNotFound(..))
+    ));
+}
+
+/// schreibt die module in ein eigenes verzeichnis und parst `code` darin
+fn parse_with_modules(
+    dir_name: &str,
+    modules: &[(&str, &str)],
+    code: &str,
+) -> Result<(), Vec<Spanned<ParserError>>> {
+    let dir = std::env::temp_dir().join(dir_name);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, content) in modules {
+        std::fs::write(dir.join(file), content).unwrap();
+    }
+
+    let tokens = lex(code).unwrap();
+    let mut parser = Parser::new(tokens, None).with_relative_path(Some(dir));
+    parser.parse().map(|_| ())
+}
+
+#[test]
+fn private_function_not_visible() {
+    let errors = parse_with_modules(
+        "mx_private_function",
+        &[(
+            "m.mx",
+            "def helper() -> int64 { return 1; }
+            pub def public() -> int64 { return helper(); }",
+        )],
+        "import \"m.mx\";
+        def main() -> int64 {
+            return helper();
+        }",
+    )
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].value,
+        ParserError::ItemIsPrivate("helper".to_string())
+    );
+}
+
+#[test]
+fn namespace_access() {
+    parse_with_modules(
+        "mx_namespace_access",
+        &[(
+            "m.mx",
+            "pub const ANSWER: int64 = 42;
+            pub def add(a: int64, b: int64) -> int64 { return a + b; }",
+        )],
+        "import \"m.mx\" as m;
+        def main() -> int64 {
+            let f = m::add;
+            return m::add(m::ANSWER, f(1, 2));
+        }",
+    )
+    .unwrap();
+}
+
+#[test]
+fn namespace_does_not_leak() {
+    let errors = parse_with_modules(
+        "mx_namespace_leak",
+        &[(
+            "m.mx",
+            "pub def add(a: int64, b: int64) -> int64 { return a + b; }",
+        )],
+        "import \"m.mx\" as m;
+        def main() -> int64 {
+            return add(1, 2);
+        }",
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        errors[0].value,
+        ParserError::FunctionDoesNotExist(..)
+    ));
+}
+
+#[test]
+fn selective_import_missing_item() {
+    let errors = parse_with_modules(
+        "mx_selective_import",
+        &[(
+            "m.mx",
+            "def add(a: int64, b: int64) -> int64 { return a + b; }",
+        )],
+        "from \"m.mx\" import add;
+        def main() -> int64 {
+            return 0;
+        }",
+    )
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0].value,
+        ParserError::NotExported(name, _) if name == "add"
+    ));
+}
+
+#[test]
+fn same_private_name_in_two_modules() {
+    parse_with_modules(
+        "mx_same_private_name",
+        &[
+            (
+                "a.mx",
+                "def helper() -> int64 { return 1; }
+                pub def a() -> int64 { return helper(); }",
+            ),
+            (
+                "b.mx",
+                "def helper() -> int64 { return 2; }
+                pub def b() -> int64 { return helper(); }",
+            ),
+        ],
+        "import \"a.mx\";
+        import \"b.mx\";
+        def helper() -> int64 { return 3; }
+        def main() -> int64 {
+            return a() + b() + helper();
+        }",
+    )
+    .unwrap();
+}
+
+#[test]
+fn import_from_library_path() {
+    let lib = std::env::temp_dir().join("mx_library_path_lib");
+    std::fs::create_dir_all(&lib).unwrap();
+    std::fs::write(
+        lib.join("mylib.mx"),
+        "pub def answer() -> int64 { return 42; }",
+    )
+    .unwrap();
+    let dir = std::env::temp_dir().join("mx_library_path_project");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let tokens = lex("import \"mylib.mx\";
+    def main() -> int64 {
+        return answer();
+    }")
+    .unwrap();
+
+    let mut parser = Parser::new(tokens.clone(), None).with_relative_path(Some(dir.clone()));
+    let errors = parser.parse().unwrap_err();
+    assert!(matches!(errors[0].value, ParserError::FileNotFound(..)));
+
+    let mut parser = Parser::new(tokens, None)
+        .with_relative_path(Some(dir))
+        .with_library_paths(vec![lib]);
+    parser.parse().unwrap();
+}
+
+#[test]
+#[cfg(feature = "embedded-std")]
+fn import_embedded_std() {
+    let dir = std::env::temp_dir().join("mx_embedded_std");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let tokens = lex("import \"std/io.mx\";
+    import \"std/fs.mx\";
+    def main() -> int64 {
+        println(\"hello\");
+        return 0;
+    }")
+    .unwrap();
+
+    let mut parser = Parser::new(tokens, None).with_relative_path(Some(dir));
+    parser.parse().unwrap();
+}
+
+fn parse_for_target(code: &str, target: Target) -> Result<Program, Vec<Spanned<ParserError>>> {
+    let tokens = lex(code).unwrap();
+    Parser::new(tokens, None).with_target(target).parse()
+}
+
+#[test]
+fn cfg_attributes() {
+    let code = "# kein attribut
+    #[cfg(target_os = \"windows\")]
+    extern def Sleep(ms: int64) -> void
+
+    #[cfg(not(target_os = \"windows\"))]
+    extern def nanosleep(req: *int64, rem: *int64) -> int32
+
+    #[cfg(target_os = \"macos\")]
+    from \"does_not_exist.mx\" import foo
+
+    #[cfg(target_os = \"windows\")]
+    pub def sleep_ms(ms: int64) {
+        Sleep(ms);
+    }
+
+    #[cfg(any(target_os = \"linux\", target_os = \"macos\"))]
+    #[cfg(target_family = \"unix\")]
+    pub def sleep_ms(ms: int64) {
+        let req = ms * 1000000;
+        nanosleep(&req, &req);
+    }";
+
+    let program = parse_for_target(code, Target::Linux).unwrap();
+    assert!(program.functions.contains_key("nanosleep"));
+    assert!(program.functions.contains_key("sleep_ms"));
+    assert!(!program.functions.contains_key("Sleep"));
+
+    // das zweite `sleep_ms` wird nicht geparst, sonst würde `nanosleep` fehlen
+    let program = parse_for_target(code, Target::Windows).unwrap();
+    assert!(program.functions.contains_key("Sleep"));
+    assert!(program.functions.contains_key("sleep_ms"));
+    assert!(!program.functions.contains_key("nanosleep"));
+
+    let program = parse_for_target(code, Target::Wasm).unwrap();
+    assert!(!program.functions.contains_key("sleep_ms"));
+
+    // die datei aus dem `from` import wird nur auf macos gesucht
+    let errors = parse_for_target(code, Target::MacOs).unwrap_err();
+    assert!(matches!(errors[0].value, ParserError::FileNotFound(..)));
+}
+
+#[test]
+fn cfg_skips_extern() {
+    // das übersprungene `extern` darf nicht beim folgenden `def` enden
+    let program = parse_for_target(
+        "#[cfg(target_os = \"windows\")]
+        extern def closesocket(socket: int64) -> int32
+        #[cfg(not(target_os = \"windows\"))]
+        extern def close(fd: int32) -> int32
+
+        #[cfg(target_os = \"windows\")]
+        extern def compare(a: *int64, b: *int64) -> int32 {
+            return 0;
+        }
+        #[cfg(target_os = \"linux\")]
+        def main() -> int64 {
+            return close(0 as int32) as int64;
+        }",
+        Target::Linux,
+    )
+    .unwrap();
+
+    assert!(program.functions.contains_key("close"));
+    assert!(program.functions.contains_key("main"));
+    assert!(!program.functions.contains_key("closesocket"));
+    assert!(!program.functions.contains_key("compare"));
+}
+
+#[test]
+fn cfg_errors() {
+    let errors = parse_for_target(
+        "#[cfg(target_os = \"plan9\")]
+        def foo() {}
+
+        #[inline]
+        def bar() {}",
+        Target::Linux,
+    )
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0].value, ParserError::UnknownCfgOption(..)));
+    assert!(matches!(errors[1].value, ParserError::UnknownAttribute(..)));
+}
+
+#[test]
+fn class_layouts() {
+    let program = parse_for_target(
+        "class Aligned {
+            a: int8,
+            b: int32,
+            c: int16,
+        }
+
+        #[packed]
+        pub class Packed {
+            a: int8,
+            b: int32,
+            c: int16,
+        }
+
+        class SockAddrIn {
+            sin_family: int16,
+            sin_port: int16,
+            sin_addr: [int8; 4],
+            sin_zero: [int8; 8],
+        }",
+        Target::Linux,
+    )
+    .unwrap();
+
+    let custom = |name: &str| match &program.custom_types[name].value {
+        DataType::Custom(custom) => custom.clone(),
+        _ => unreachable!(),
+    };
+
+    // wie in c: `b` liegt auf 4, am ende wird auf 12 aufgefüllt
+    let aligned = custom("Aligned");
+    assert_eq!(aligned.field_offset(1), 4);
+    assert_eq!(aligned.field_offset(2), 8);
+    assert_eq!(program.custom_types["Aligned"].value.size(), 12);
+
+    let packed = custom("Packed");
+    assert!(packed.is_packed);
+    assert_eq!(packed.field_offset(1), 1);
+    assert_eq!(packed.field_offset(2), 5);
+    assert_eq!(program.custom_types["Packed"].value.size(), 7);
+
+    assert_eq!(program.custom_types["SockAddrIn"].value.size(), 16);
+}
+
+#[test]
+fn packed_only_on_classes() {
+    let errors = parse_for_target(
+        "#[packed]
+        def foo() {}",
+        Target::Linux,
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        errors[0].value,
+        ParserError::AttributeNotAllowed(..)
+    ));
+}
+
+#[test]
+fn export_attribute() {
+    let program = parse_for_target(
+        "#[export]
+        def add(a: int64, b: int64) -> int64 {
+            return a + b;
+        }",
+        Target::Linux,
+    )
+    .unwrap();
+
+    // das symbol `add` gehört dem wrapper mit c abi
+    let add = &program.functions["add.export"].value;
+    assert!(add.export && add.c_abi);
+    assert_eq!(add.display_name.value, "add");
+
+    for code in [
+        "#[export]
+        class Foo {
+            a: int64,
+        }",
+        "#[export]
+        def id<T>(value: T) -> T {
+            return value;
+        }",
+        "#[export]
+        extern def abs(value: int32) -> int32",
+    ] {
+        let errors = parse_for_target(code, Target::Linux).unwrap_err();
+        assert!(
+            matches!(errors[0].value, ParserError::AttributeNotAllowed(..)),
+            "code: \"{code}\""
+        );
+    }
+}
+
+#[test]
+fn prelude_operator_traits() {
+    let program = Parser::new(vec![], None)
+        .with_require_main(false)
+        .parse()
+        .unwrap();
+
+    // jeder operator wird über ein trait im prelude aufgelöst
+    for op in [
+        BinaryOperator::Add,
+        BinaryOperator::Subtract,
+        BinaryOperator::Multiply,
+        BinaryOperator::Divide,
+        BinaryOperator::Modulo,
+        BinaryOperator::Equal,
+        BinaryOperator::NotEqual,
+        BinaryOperator::LessThan,
+        BinaryOperator::LessThanOrEqual,
+        BinaryOperator::GreaterThan,
+        BinaryOperator::GreaterThanOrEqual,
+        BinaryOperator::And,
+        BinaryOperator::Or,
+        BinaryOperator::BitAnd,
+        BinaryOperator::BitOr,
+        BinaryOperator::BitXor,
+        BinaryOperator::ShiftLeft,
+        BinaryOperator::ShiftRight,
+    ] {
+        let trait_ = Trait::from_binary_operator(&op);
+        assert!(
+            program.operator_trait(&trait_).is_some(),
+            "{trait_} is not declared in the prelude"
+        );
+    }
+
+    // die signaturen der eingebauten implementierungen kommen aus dem prelude
+    assert_eq!(
+        program.get_trait_return_type(
+            &DataType::Integer32,
+            &Trait::Add,
+            &[DataType::Integer32, DataType::Integer32]
+        ),
+        Some(DataType::Integer32)
+    );
+    assert_eq!(
+        program.get_trait_return_type(
+            &DataType::Float,
+            &Trait::LessThan,
+            &[DataType::Float, DataType::Float]
+        ),
+        Some(DataType::Boolean)
+    );
+}
+
+#[test]
+fn user_trait_does_not_bind_operator() {
+    let tokens = lex("trait Plus {
+        def add(self, other: Self) -> Self;
+    }")
+    .unwrap();
+
+    let program = Parser::new(tokens, None)
+        .with_require_main(false)
+        .parse()
+        .unwrap();
+
+    assert_eq!(program.traits["Plus"].value.builtin(), None);
+    assert_eq!(
+        program.operator_trait(&Trait::Add).unwrap().name.value,
+        "Add"
+    );
+}
+
+#[test]
+fn closure_storage() {
+    let code = "def keep(f: || -> int64) -> int64 {
+        return f();
+    }
+
+    def main() -> int64 {
+        let mut count = 1;
+        let kept = keep(|| -> int64 { count });
+        let mut count = 2;
+        let inc = || { count = count + 1; };
+        inc();
+        return kept + count;
+    }";
+    let program = Parser::new(lex(code).unwrap(), None).parse().unwrap();
+    let body = &program.functions["main"].value.body.value;
+
+    // nur die erste deklaration von `count` wird von einer closure gefangen, die den block verlässt
+    let boxed = reference_captures(body);
+    assert_eq!(boxed.len(), 1);
+    assert!(boxed.iter().all(|name| name.value == "count"));
+    assert!(boxed
+        .iter()
+        .all(|name| name.span.start.abs == code.find("count").unwrap()));
+
+    let Statement::VariableDecl { value, .. } = &body.statements[3].value else {
+        panic!("expected `let inc`")
+    };
+    let Expr::Closure(inc) = &value.value.expression else {
+        panic!("expected a closure")
+    };
+    assert_eq!(local_closures(body), [inc.body.span].into());
+}
+
+#[test]
+fn duplicate_match_arm() {
+    let tokens = lex("enum Shape {
+        Circle(int64),
+        Empty,
+    }
+
+    def area(shape: Shape) -> int64 {
+        return match shape {
+            Shape::Circle(r) => r,
+            Shape::Empty => 0,
+            Shape::Circle(_) => 1,
+        };
+    }")
+    .unwrap();
+
+    let errors = Parser::new(tokens, None).parse().unwrap_err();
+
+    assert_eq!(
+        errors[0].value,
+        ParserError::UnreachableMatchArm("Shape::Circle".to_string())
+    );
+}
+
+#[test]
+fn match_arm_after_wildcard() {
+    let tokens = lex("enum Shape {
+        Circle(int64),
+        Empty,
+    }
+
+    def area(shape: Shape) -> int64 {
+        return match shape {
+            _ => 0,
+            Shape::Empty => 1,
+        };
+    }")
+    .unwrap();
+
+    let errors = Parser::new(tokens, None).parse().unwrap_err();
+
+    assert_eq!(
+        errors[0].value,
+        ParserError::UnreachableMatchArm("Shape::Empty".to_string())
+    );
+}
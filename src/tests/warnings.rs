@@ -0,0 +1,154 @@
+#![cfg(test)]
+use crate::{
+    compiler::{CompileOptions, Compiler},
+    error::WarningConfig,
+    lexer::lexer_main::lex,
+    parser::{parser_main::Parser, warnings::check_warnings},
+};
+use rstest::rstest;
+
+#[rstest]
+#[case(
+    "def main() -> int64 {
+    let a = 5;
+    return a;
+}",
+    &[]
+)]
+#[case(
+    "def main() -> int64 {
+    let a = 5;
+    return 0;
+}",
+    &["W001"]
+)]
+#[case(
+    "def main() -> int64 {
+    let _a = 5;
+    return 0;
+}",
+    &[]
+)]
+#[case(
+    "def main() -> int64 {
+    let mut a = 5;
+    return a;
+}",
+    &["W002"]
+)]
+#[case(
+    "def main() -> int64 {
+    let mut a = 5;
+    a = 6;
+    return a;
+}",
+    &[]
+)]
+#[case(
+    "def main() -> int64 {
+    return 0;
+    let a = 5;
+}",
+    &["W003", "W001"]
+)]
+#[case(
+    "def foo() -> int64 {
+    return 1;
+}
+
+def main() -> int64 {
+    return 0;
+}",
+    &["W004"]
+)]
+#[case(
+    "def foo() -> int64 {
+    return 1;
+}
+
+def main() -> int64 {
+    return foo();
+}",
+    &[]
+)]
+#[case(
+    "def bar() -> int64 {
+    return 1;
+}
+
+#[export]
+def foo() -> int64 {
+    return bar();
+}
+
+def main() -> int64 {
+    return 0;
+}",
+    &[]
+)]
+#[case(
+    "def main() -> int64 {
+    let mut sum = 0;
+    for i in 0..3 {
+        sum = sum + i;
+    }
+    return sum;
+}",
+    &[]
+)]
+#[case(
+    "def foo() -> int64 {
+    return 1;
+}
+
+def main() -> int64 {
+    foo();
+    let _a = foo();
+    return 0;
+}",
+    &["W006"]
+)]
+fn test_warnings(#[case] code: &str, #[case] expected: &[&str]) {
+    let tokens = lex(code).unwrap();
+    let program = Parser::new(tokens, None)
+        .with_source_code(code)
+        .with_require_main(true)
+        .parse()
+        .unwrap();
+
+    let warnings = check_warnings(&program)
+        .into_iter()
+        .map(|w| w.value.code())
+        .collect::<Vec<_>>();
+
+    assert_eq!(warnings, expected, "code: \"{}\n\"", code);
+}
+
+#[test]
+fn deny_warnings() {
+    let code = "def main() -> int64 {
+        let a = 5;
+        return 0;
+    }";
+
+    let options = CompileOptions {
+        warnings: WarningConfig {
+            deny: vec!["warnings".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let errs = Compiler::compile_with_options(code, None, &options).unwrap_err();
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].value.code(), "W001");
+
+    // abgeschaltete warnungen werden auch nicht zu fehlern
+    let options = CompileOptions {
+        warnings: WarningConfig {
+            deny: vec!["W002".to_string()],
+            allow: vec!["W001".to_string()],
+        },
+        ..Default::default()
+    };
+    assert!(Compiler::compile_with_options(code, None, &options).is_ok());
+}
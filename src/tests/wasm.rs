@@ -0,0 +1,317 @@
+#![cfg(test)]
+use crate::{
+    compiler::{CompileOptions, Compiler},
+    interpreter::host::format_c,
+    parser::cfg::Target,
+    tests::backends::BACKEND_CASES,
+    wasm::wasm_main::{ENTRY_EXPORT, HEAP_BASE_EXPORT, IMPORT_MODULE},
+};
+use rstest::rstest;
+use wasmi::{core::Trap, Caller, Engine, Extern, Func, Linker, Memory, Module, Store, Value};
+
+/// die importe für die tests, wie in der web demo
+#[derive(Debug, Default)]
+struct Host {
+    output: Vec<u8>,
+    /// nächste freie adresse für `malloc`, 0 bis zum ersten aufruf
+    heap: usize,
+}
+
+fn memory(caller: &Caller<'_, Host>) -> Memory {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .unwrap()
+}
+
+/// die größe steht vor jedem block (für `realloc`), `free` gibt nichts frei
+fn malloc(caller: &mut Caller<'_, Host>, size: i64) -> i64 {
+    if caller.data().heap == 0 {
+        let heap_base = caller
+            .get_export(HEAP_BASE_EXPORT)
+            .and_then(Extern::into_global)
+            .unwrap();
+        let Value::I32(heap_base) = heap_base.get(&*caller) else {
+            unreachable!()
+        };
+        caller.data_mut().heap = heap_base as usize;
+    }
+
+    let address = caller.data().heap + 8;
+    let end = address + (size as usize).div_ceil(8) * 8;
+
+    let memory = memory(caller);
+    if end > memory.data(&*caller).len() {
+        return 0;
+    }
+
+    memory.data_mut(&mut *caller)[address - 8..address]
+        .copy_from_slice(&(size as u64).to_le_bytes());
+    caller.data_mut().heap = end;
+    address as i64
+}
+
+fn c_string(caller: &Caller<'_, Host>, address: i64) -> Vec<u8> {
+    let data = memory(caller).data(caller);
+    let start = address as usize;
+    let len = data[start..].iter().position(|b| *b == 0).unwrap();
+    data[start..start + len].to_vec()
+}
+
+fn linker(store: &mut Store<Host>) -> Linker<Host> {
+    let mut linker = <Linker<Host>>::new(store.engine());
+
+    let functions = [
+        (
+            "malloc",
+            Func::wrap(&mut *store, |mut caller: Caller<'_, Host>, size: i64| {
+                malloc(&mut caller, size)
+            }),
+        ),
+        (
+            "calloc",
+            Func::wrap(
+                &mut *store,
+                |mut caller: Caller<'_, Host>, num: i64, size: i64| {
+                    // der speicher hinter dem heap ist noch nie benutzt worden und damit 0
+                    malloc(&mut caller, num * size)
+                },
+            ),
+        ),
+        (
+            "realloc",
+            Func::wrap(
+                &mut *store,
+                |mut caller: Caller<'_, Host>, address: i64, size: i64| {
+                    let new_address = malloc(&mut caller, size);
+                    if address != 0 && new_address != 0 {
+                        let (address, new_address) = (address as usize, new_address as usize);
+                        let data = memory(&caller).data_mut(&mut caller);
+                        let old_size =
+                            u64::from_le_bytes(data[address - 8..address].try_into().unwrap());
+                        let len = (old_size as usize).min(size as usize);
+                        data.copy_within(address..address + len, new_address);
+                    }
+                    new_address
+                },
+            ),
+        ),
+        (
+            "free",
+            Func::wrap(&mut *store, |_: Caller<'_, Host>, _: i64| {}),
+        ),
+        (
+            "printf",
+            // die argumente für `...` liegen ab `args` in 8 byte schritten
+            Func::wrap(
+                &mut *store,
+                |mut caller: Caller<'_, Host>, format: i64, args: i64| {
+                    let mut next = args as usize;
+                    let data = memory(&caller).data(&caller);
+                    let text = format_c(
+                        &c_string(&caller, format),
+                        || {
+                            let bytes = data[next..next + 8].try_into().unwrap();
+                            next += 8;
+                            i64::from_le_bytes(bytes)
+                        },
+                        |address| Ok::<_, ()>(c_string(&caller, address as i64)),
+                    )
+                    .unwrap();
+
+                    caller.data_mut().output.extend_from_slice(&text);
+                    text.len() as i64
+                },
+            ),
+        ),
+        (
+            "exit",
+            Func::wrap(
+                &mut *store,
+                |_: Caller<'_, Host>, code: i64| -> Result<(), Trap> {
+                    Err(Trap::i32_exit(code as i32))
+                },
+            ),
+        ),
+        (
+            "mx_argc",
+            Func::wrap(&mut *store, |_: Caller<'_, Host>| 1i64),
+        ),
+        (
+            "mx_argv",
+            Func::wrap(&mut *store, |mut caller: Caller<'_, Host>| {
+                let argv = malloc(&mut caller, 8);
+                let name = malloc(&mut caller, 5);
+
+                let data = memory(&caller).data_mut(&mut caller);
+                data[name as usize..name as usize + 5].copy_from_slice(b"main\0");
+                data[argv as usize..argv as usize + 8].copy_from_slice(&name.to_le_bytes());
+                argv
+            }),
+        ),
+    ];
+
+    for (name, function) in functions {
+        linker.define(IMPORT_MODULE, name, function).unwrap();
+    }
+
+    linker
+}
+
+/// übersetzt `source_code` nach wasm und ruft `main` auf, gibt den exit code
+/// (bzw. den trap) und die ausgabe zurück
+fn run(source_code: &str) -> (Result<i32, Trap>, String) {
+    let options = CompileOptions {
+        target: Target::Wasm,
+        ..Default::default()
+    };
+    let wat = Compiler::compile_wasm_with_options(source_code, None, &options)
+        .expect("program should compile");
+    let wasm = wat::parse_str(&wat).unwrap_or_else(|err| panic!("{err}\n{wat}"));
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..]).unwrap_or_else(|err| panic!("{err}\n{wat}"));
+    let mut store = Store::new(&engine, Host::default());
+
+    let instance = linker(&mut store)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let main = instance
+        .get_typed_func::<(), i32>(&store, ENTRY_EXPORT)
+        .unwrap();
+
+    let result = match main.call(&mut store, ()) {
+        Err(trap) => match trap.i32_exit_status() {
+            Some(code) => Ok(code),
+            None => Err(trap),
+        },
+        result => result,
+    };
+
+    let output = String::from_utf8_lossy(&store.data().output).to_string();
+    (result, output)
+}
+
+// was das backend selbst abbilden muss: `i64` werte, den shadow stack, die funktionstabelle
+// für closures und die importe des hosts, der rest steht in `BACKEND_CASES`
+#[rstest]
+#[case(
+    "def main() -> int64 {
+        let big = 4294967296 * 3;
+        if big < 4294967296 {
+            return 0;
+        }
+        return (big / 4294967296) + (big % 7);
+    }",
+    8
+)]
+#[case(
+    "def sum_to(n: int64) -> int64 {
+        let values = [n, n * 2, n * 3];
+        if n == 0 {
+            return 0;
+        }
+        return values[0] + sum_to(n - 1);
+    }
+
+    def main() -> int64 {
+        let mut total = 0;
+        for i in 0..10000 {
+            total = total + sum_to(10);
+        }
+        return total;
+    }",
+    550000
+)]
+#[case(
+    "def main() -> int64 {
+        let a = 200 as uint8;
+        let b = 3 as uint8;
+        let mut r = ((a / b) + (a % b)) as int64;
+
+        let c = (a + a) as int64;
+        let u = (0 - 1) as uint64;
+        if u > (10 as uint64) {
+            r = r + 1;
+        }
+
+        return r + c + ((u >> (60 as uint64)) as int64);
+    }",
+    228
+)]
+#[case(
+    "class Vec2 {
+        x: int64,
+        y: int64,
+    }
+
+    impl Add for Vec2 {
+        def add(self, other: Vec2) -> Vec2 {
+            return Vec2 { x: self.x + other.x, y: self.y + other.y, };
+        }
+    }
+
+    def scale(self, by: int64) for Vec2 {
+        self.x = self.x * by;
+        self.y = self.y * by;
+    }
+
+    def main() -> int64 {
+        let mut a = Vec2 { x: 1, y: 2, };
+        let b = a;
+        a += Vec2 { x: 3, y: 4, };
+        a.scale(2);
+        return a.x * 100 + a.y + b.x * 1000;
+    }",
+    1812
+)]
+#[case(
+    "import \"std/string.mx\";
+    import \"std/collections/list.mx\";
+
+    def main(args: List<String>) -> int64 {
+        let name = args[0];
+        return args.len + name.len() * 10;
+    }",
+    41
+)]
+fn wasm_test(#[case] source_code: &str, #[case] expected: i32) {
+    let (result, _) = run(source_code);
+    assert_eq!(result.unwrap(), expected);
+}
+
+#[test]
+fn backend_cases() {
+    for (source_code, expected) in BACKEND_CASES {
+        let (result, _) = run(source_code);
+        assert_eq!(result.unwrap(), *expected, "{source_code}");
+    }
+}
+
+#[test]
+fn wasm_output_test() {
+    let (result, output) = run("import \"std/io.mx\";
+    import \"std/string.mx\";
+
+    def main() {
+        let n = 42;
+        println(\"n = \" + n.to_string());
+        printf(\"100%%\".to_c_string());
+        printf(\" %s %05.1f %ld %c\".to_c_string(), \"x\".to_c_string(), 2.5, n, 65 as int8);
+    }");
+
+    assert_eq!(result.unwrap(), 0);
+    assert_eq!(output, "n = 42\n100% x 002.5 42 A");
+}
+
+#[test]
+fn wasm_trap_test() {
+    let (result, _) = run("def main() -> int64 {
+        let zero = 0;
+        return 1 / zero;
+    }");
+
+    assert!(result.is_err());
+}
@@ -1,4 +1,13 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use derive_more::Display;
 use semver::Version;
+use strum_macros::EnumString;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone, PartialEq)]
@@ -9,6 +18,55 @@ pub enum ClangError {
     NotInstalled,
     #[error("clang error: {0}")]
     ClangErrorCode(i32),
+    #[error("could not run clang: {0}")]
+    Io(String),
+    #[error("could not create the archive: {0}")]
+    Archive(String),
+}
+
+/// was aus dem ir gebaut wird, `--emit` in der cli
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, EnumString, Default)]
+pub enum Emit {
+    #[default]
+    #[display(fmt = "exe")]
+    #[strum(serialize = "exe")]
+    Executable,
+    #[display(fmt = "obj")]
+    #[strum(serialize = "obj")]
+    Object,
+    #[display(fmt = "staticlib")]
+    #[strum(serialize = "staticlib")]
+    StaticLib,
+    #[display(fmt = "sharedlib")]
+    #[strum(serialize = "sharedlib")]
+    SharedLib,
+}
+
+impl Emit {
+    /// `out.exe`, `out.o`, `libout.a` oder `libout.so`
+    pub fn default_file_name(&self) -> String {
+        let windows = cfg!(windows);
+        match self {
+            Emit::Executable => "out.exe".to_string(),
+            Emit::Object if windows => "out.obj".to_string(),
+            Emit::Object => "out.o".to_string(),
+            Emit::StaticLib if windows => "out.lib".to_string(),
+            Emit::StaticLib => "libout.a".to_string(),
+            Emit::SharedLib => format!(
+                "{}out{}",
+                std::env::consts::DLL_PREFIX,
+                std::env::consts::DLL_SUFFIX
+            ),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RunError {
+    #[error("{0}")]
+    Clang(#[from] ClangError),
+    #[error("could not run the program: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 pub fn get_clang_version() -> Option<Version> {
@@ -22,3 +80,134 @@ pub fn get_clang_version() -> Option<Version> {
 
     Version::parse(version).ok()
 }
+
+/// übergibt das ir an clang, `link_args` sind z.b. `-l` und `-L` flags oder objekt dateien
+pub fn link(ir: &str, output_path: &Path, link_args: &[String]) -> Result<(), ClangError> {
+    clang(ir, output_path, &[], link_args)
+}
+
+/// baut das ir als programm, objekt datei oder bibliothek
+pub fn build(
+    ir: &str,
+    output_path: &Path,
+    emit: Emit,
+    link_args: &[String],
+) -> Result<(), ClangError> {
+    match emit {
+        Emit::Executable => link(ir, output_path, link_args),
+        Emit::Object => clang(ir, output_path, &["-c"], &[]),
+        Emit::SharedLib => clang(ir, output_path, &["-shared", "-fPIC"], link_args),
+        Emit::StaticLib => {
+            let dir = TempDir::new().map_err(|e| ClangError::Io(e.to_string()))?;
+            let object = dir.0.join("out.o");
+            clang(ir, &object, &["-c"], &[])?;
+
+            // `ar` hängt an ein bestehendes archiv an
+            if output_path.exists() {
+                std::fs::remove_file(output_path)
+                    .map_err(|e| ClangError::Archive(e.to_string()))?;
+            }
+
+            let status = std::process::Command::new("ar")
+                .arg("rcs")
+                .arg(output_path)
+                .arg(&object)
+                .status()
+                .map_err(|e| ClangError::Archive(e.to_string()))?;
+
+            match status.success() {
+                true => Ok(()),
+                false => Err(ClangError::Archive(format!(
+                    "ar exited with {}",
+                    status.code().unwrap_or(-1)
+                ))),
+            }
+        }
+    }
+}
+
+fn clang(
+    ir: &str,
+    output_path: &Path,
+    flags: &[&str],
+    link_args: &[String],
+) -> Result<(), ClangError> {
+    // `-x none`, damit die weiteren dateien nicht auch als ir gelesen werden
+    let mut clang = std::process::Command::new("clang")
+        .args(flags)
+        .args(["-x", "ir", "-", "-x", "none"])
+        .args(link_args)
+        .arg("-o")
+        .arg(output_path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ClangError::NotInstalled,
+            _ => ClangError::Io(e.to_string()),
+        })?;
+
+    let mut stdin = clang.stdin.take().unwrap();
+    stdin
+        .write_all(ir.as_bytes())
+        .map_err(|e| ClangError::Io(e.to_string()))?;
+    // clang liest bis stdin geschlossen wird
+    drop(stdin);
+
+    let status = clang.wait().map_err(|e| ClangError::Io(e.to_string()))?;
+
+    if !status.success() {
+        return Err(ClangError::ClangErrorCode(status.code().unwrap_or(-1)));
+    }
+
+    Ok(())
+}
+
+/// temporärer ordner, der beim droppen gelöscht wird
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> std::io::Result<Self> {
+        // mehrere tests laufen gleichzeitig im selben prozess
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "mx-run-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir)?;
+        Ok(TempDir(dir))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// baut das ir in einem temporären ordner und führt es mit `args` aus,
+/// stdin, stdout und stderr werden an das programm weitergegeben
+pub fn run_ir(ir: &str, link_args: &[String], args: &[String]) -> Result<ExitStatus, RunError> {
+    let dir = TempDir::new()?;
+    let executable = dir.0.join(format!("main{}", std::env::consts::EXE_SUFFIX));
+
+    link(ir, &executable, link_args)?;
+
+    Ok(std::process::Command::new(&executable)
+        .args(args)
+        .status()?)
+}
+
+/// exit code wie in einer shell, `128 + signal` wenn das programm abgestürzt ist
+pub fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    status.code().unwrap_or(1)
+}
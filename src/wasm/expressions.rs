@@ -0,0 +1,663 @@
+use crate::{
+    lexer::{position::Spanned, tokens::Literal},
+    parser::ast::{
+        BinaryOperator, Closure, CustomDataType, DataType, Expr, Function, MatchArm, MatchPattern,
+        Trait, TypedExpr,
+    },
+};
+
+use super::{
+    functions::FunctionBuilder,
+    types::{is_aggregate, is_int_like, load, normalize, normalize_const, value_type, ValType},
+    wasm_main::{closure_signature, WasmGenerator},
+};
+
+impl WasmGenerator {
+    /// legt den wert von `expr` auf den operanden stack
+    pub(in crate::wasm) fn emit(&mut self, f: &mut FunctionBuilder, expr: &TypedExpr) {
+        self.emit_expr(f, &expr.expression, &expr._type);
+    }
+
+    /// `_type` ist der typ des ausdrucks, gibt zurück was auf dem operanden stack liegt
+    pub(in crate::wasm) fn emit_expr(
+        &mut self,
+        f: &mut FunctionBuilder,
+        expr: &Expr,
+        _type: &DataType,
+    ) -> Option<ValType> {
+        match expr {
+            Expr::Variable(_) | Expr::Index { .. } | Expr::FieldAccess { .. } | Expr::Deref(_) => {
+                self.emit_place_expr(f, expr);
+                match value_type(_type) {
+                    Some(val_type) => {
+                        f.emit_all(load(_type));
+                        Some(val_type)
+                    }
+                    None => {
+                        f.emit("drop");
+                        None
+                    }
+                }
+            }
+            Expr::Literal(literal) => self.emit_literal(f, literal, _type),
+            Expr::Binary { lhs, op, rhs } => self.emit_binary(f, &lhs.value, &op.value, &rhs.value),
+            Expr::Cast { value, to_type } => {
+                self.emit(f, &value.value);
+                emit_cast(f, &value.value._type, &to_type.value);
+                value_type(&to_type.value)
+            }
+            Expr::Reference { value } => {
+                self.emit_place(f, &value.value);
+                Some(ValType::I64)
+            }
+            Expr::Call { function, args } => {
+                let args = args
+                    .value
+                    .iter()
+                    .map(|arg| &arg.value.0)
+                    .collect::<Vec<_>>();
+
+                // `size_of(int64)`
+                if function.value.is_builtin {
+                    return match args.first().map(|arg| &arg.expression) {
+                        Some(Expr::Literal(Literal::DataType { value_type })) => {
+                            f.emit(format!("i64.const {}", value_type.size()));
+                            Some(ValType::I64)
+                        }
+                        _ => {
+                            f.emit("unreachable");
+                            value_type(_type)
+                        }
+                    };
+                }
+
+                self.call_with(f, &function.value, &args)
+            }
+            Expr::Block { body } => {
+                let stack_pointer = f.save_stack_pointer();
+                let result = self.emit_returning_block(f, &body.value, _type);
+                f.restore_stack_pointer(&stack_pointer);
+
+                let result = result?;
+                f.emit(format!("local.get {result}"));
+                f.materialize(_type);
+                value_type(_type)
+            }
+            Expr::EnumVariant {
+                variant_idx, args, ..
+            } => {
+                let DataType::Custom(custom) = _type else {
+                    unreachable!()
+                };
+                self.emit_enum_variant(f, custom, *variant_idx, &args.value);
+                Some(ValType::I64)
+            }
+            Expr::Match { value, arms } => self.emit_match(f, &value.value, arms, _type),
+            Expr::Closure(closure) => {
+                self.emit_closure(f, closure);
+                Some(ValType::I64)
+            }
+            Expr::ClosureCall { closure, args } => {
+                let DataType::Function {
+                    params,
+                    return_type,
+                } = &closure.value._type
+                else {
+                    unreachable!()
+                };
+
+                self.emit(f, &closure.value);
+                let value = f.set_local(ValType::I64);
+                let pointer = pointer_type();
+
+                // `{ index, umgebung }`
+                f.offset(&value, pointer.size());
+                f.emit_all(load(&pointer));
+                for arg in args.value.iter() {
+                    self.emit(f, &arg.value.0);
+                }
+                f.emit(format!("local.get {value}"));
+                f.emit_all(load(&pointer));
+                f.emit("i32.wrap_i64");
+
+                f.emit(format!(
+                    "call_indirect{}",
+                    closure_signature(params, return_type)
+                ));
+                f.materialize(return_type);
+                value_type(return_type)
+            }
+            Expr::FunctionRef(function) => {
+                let idx = self.function_ref_index(function);
+                self.emit_function_value(f, idx, None);
+                Some(ValType::I64)
+            }
+            // wird vom parser (noch) nicht erzeugt
+            Expr::Unary { .. } => {
+                f.emit("unreachable");
+                value_type(_type)
+            }
+            Expr::ClassName(_) => unreachable!(),
+        }
+    }
+
+    /// die adresse eines ausdrucks, werte ohne adresse werden auf den stack gelegt
+    pub(in crate::wasm) fn emit_place(&mut self, f: &mut FunctionBuilder, expr: &TypedExpr) {
+        match &expr.expression {
+            Expr::Variable(_) | Expr::Index { .. } | Expr::FieldAccess { .. } | Expr::Deref(_) => {
+                self.emit_place_expr(f, &expr.expression)
+            }
+            // aggregate sind schon eine adresse
+            _ if is_aggregate(&expr._type) => self.emit(f, expr),
+            _ => {
+                let address = self.emit_spilled(f, expr);
+                f.emit(format!("local.get {address}"));
+            }
+        }
+    }
+
+    fn emit_place_expr(&mut self, f: &mut FunctionBuilder, expr: &Expr) {
+        match expr {
+            Expr::Variable(variable) => {
+                let name = &variable.name.value;
+                match (f.lookup(name).cloned(), self.global_address(name)) {
+                    (Some(local), _) => f.emit(format!("local.get {local}")),
+                    (None, Some(address)) => f.emit(format!("i64.const {address}")),
+                    (None, None) => f.emit("unreachable"),
+                }
+            }
+            Expr::Index { base, idx } => {
+                let (base, idx) = (&base.value, &idx.value);
+
+                // `Index` gibt einen pointer auf das element zurück
+                if let Some(index) =
+                    self.trait_function(&Trait::Index, &[base._type.clone(), idx._type.clone()])
+                {
+                    self.call_with(f, &index, &[base, idx]);
+                    return;
+                }
+
+                let DataType::Array { value_type, .. } = &base._type else {
+                    f.emit("unreachable");
+                    return;
+                };
+
+                self.emit_place(f, base);
+                self.emit(f, idx);
+                f.emit(format!("i64.const {}", value_type.size()));
+                f.emit("i64.mul");
+                f.emit("i64.add");
+            }
+            Expr::FieldAccess {
+                base, field_idx, ..
+            } => {
+                let base = &base.value;
+                self.emit_place(f, base);
+
+                match &base._type {
+                    DataType::Custom(custom) => {
+                        let offset = custom.field_offset(*field_idx);
+                        if offset != 0 {
+                            f.emit(format!("i64.const {offset}"));
+                            f.emit("i64.add");
+                        }
+                    }
+                    _ => f.emit("unreachable"),
+                }
+            }
+            Expr::Deref(base) => self.emit(f, &base.value),
+            _ => unreachable!(),
+        }
+    }
+
+    /// wie `call_with` im interpreter, `self` wird als pointer übergeben
+    fn call_with(
+        &mut self,
+        f: &mut FunctionBuilder,
+        function: &Function,
+        args: &[&TypedExpr],
+    ) -> Option<ValType> {
+        let has_self_arg = function.is_method() && !function.is_static_method();
+        let (args, variadic_args) = args.split_at(function.params.value.len());
+
+        for (idx, arg) in args.iter().enumerate() {
+            match idx == 0 && has_self_arg {
+                true => self.emit_place(f, arg),
+                false => self.emit(f, arg),
+            }
+        }
+
+        // wie `va_list` bei clang für wasm liegen die argumente für `...` im speicher,
+        // hier alle in 8 byte, integer und pointer als `i64`
+        if function.is_variadic {
+            f.alloca(variadic_args.len() * 8);
+            let address = f.set_local(ValType::I64);
+
+            for (idx, arg) in variadic_args.iter().enumerate() {
+                // `float` ist schon zu `float64` erweitert
+                let slot_type = match arg._type.is_float() {
+                    true => DataType::Float64,
+                    false => DataType::Integer64,
+                };
+
+                self.emit(f, arg);
+                let value = f.set_local(value_type(&arg._type).unwrap());
+                f.store_field(&address, idx * 8, &value, &slot_type);
+            }
+
+            f.emit(format!("local.get {address}"));
+        }
+
+        let id = self.function_id(function);
+        f.emit(format!("call {id}"));
+
+        let return_type = &function.return_type.value;
+        f.materialize(return_type);
+        value_type(return_type)
+    }
+
+    fn emit_literal(
+        &mut self,
+        f: &mut FunctionBuilder,
+        literal: &Literal,
+        _type: &DataType,
+    ) -> Option<ValType> {
+        match literal {
+            Literal::ArrayLiteral(array) => {
+                let element_type = &array.value_type;
+                let values = &array.values.value;
+                f.alloca(element_type.size() * values.len());
+                let address = f.set_local(ValType::I64);
+
+                for (idx, value) in values.iter().enumerate() {
+                    self.store_value(f, &address, idx * element_type.size(), &value.value);
+                }
+
+                f.emit(format!("local.get {address}"));
+                Some(ValType::I64)
+            }
+            // die felder stehen schon in der richtigen reihenfolge
+            Literal::Custom(class) => {
+                let DataType::Custom(custom) = _type else {
+                    unreachable!()
+                };
+
+                f.alloca(_type.size());
+                let address = f.set_local(ValType::I64);
+
+                for (idx, (_, value)) in class.fields.value.iter().enumerate() {
+                    self.store_value(f, &address, custom.field_offset(idx), &value.value);
+                }
+
+                f.emit(format!("local.get {address}"));
+                Some(ValType::I64)
+            }
+            Literal::Integer(value) => {
+                let val_type = value_type(_type)?;
+                match val_type {
+                    ValType::F32 | ValType::F64 => {
+                        f.emit(format!("{val_type}.const {:?}", *value as f64))
+                    }
+                    _ => f.emit(format!(
+                        "i64.const {}",
+                        normalize_const(*value as i128, _type)
+                    )),
+                }
+                Some(val_type)
+            }
+            Literal::Float(value) => {
+                let val_type = value_type(_type)?;
+                match val_type {
+                    ValType::F32 | ValType::F64 => f.emit(format!("{val_type}.const {value:?}")),
+                    _ => f.emit(format!("i64.const {}", *value as i64)),
+                }
+                Some(val_type)
+            }
+            Literal::Boolean(value) => {
+                let val_type = value_type(_type)?;
+                f.emit(format!("i64.const {}", *value as i64));
+                Some(val_type)
+            }
+            Literal::Void | Literal::DataType { .. } => None,
+        }
+    }
+
+    /// wertet `value` aus und speichert es an `address + offset`
+    fn store_value(
+        &mut self,
+        f: &mut FunctionBuilder,
+        address: &str,
+        offset: usize,
+        value: &TypedExpr,
+    ) {
+        let Some(val_type) = value_type(&value._type) else {
+            self.emit(f, value);
+            return;
+        };
+
+        self.emit(f, value);
+        let value_local = f.set_local(val_type);
+        f.store_field(address, offset, &value_local, &value._type);
+    }
+
+    /// wie `parse_binary_expr`, überschriebene operatoren zuerst.
+    /// das ergebnis hat den typ der linken seite
+    fn emit_binary(
+        &mut self,
+        f: &mut FunctionBuilder,
+        lhs: &TypedExpr,
+        op: &BinaryOperator,
+        rhs: &TypedExpr,
+    ) -> Option<ValType> {
+        let trait_ = Trait::from_binary_operator(op);
+        if let Some(function) =
+            self.trait_function(&trait_, &[lhs._type.clone(), rhs._type.clone()])
+        {
+            return self.call_with(f, &function, &[lhs, rhs]);
+        }
+
+        let _type = &lhs._type;
+        self.emit(f, lhs);
+        self.emit(f, rhs);
+
+        if _type.is_float() {
+            let val_type = value_type(_type).unwrap();
+            let (op, is_comparison) = match op {
+                BinaryOperator::Add => ("add", false),
+                BinaryOperator::Subtract => ("sub", false),
+                BinaryOperator::Multiply => ("mul", false),
+                BinaryOperator::Divide => ("div", false),
+                // `lhs - trunc(lhs / rhs) * rhs`, wie `frem`
+                BinaryOperator::Modulo => {
+                    let right = f.set_local(val_type);
+                    let left = f.set_local(val_type);
+                    f.emit(format!("local.get {left}"));
+                    f.emit(format!("local.get {left}"));
+                    f.emit(format!("local.get {right}"));
+                    f.emit(format!("{val_type}.div"));
+                    f.emit(format!("{val_type}.trunc"));
+                    f.emit(format!("local.get {right}"));
+                    f.emit(format!("{val_type}.mul"));
+                    f.emit(format!("{val_type}.sub"));
+                    return Some(val_type);
+                }
+                BinaryOperator::Equal => ("eq", true),
+                BinaryOperator::NotEqual => ("ne", true),
+                BinaryOperator::LessThan => ("lt", true),
+                BinaryOperator::LessThanOrEqual => ("le", true),
+                BinaryOperator::GreaterThan => ("gt", true),
+                BinaryOperator::GreaterThanOrEqual => ("ge", true),
+                _ => {
+                    f.emit("unreachable");
+                    return Some(val_type);
+                }
+            };
+
+            f.emit(format!("{val_type}.{op}"));
+            if is_comparison {
+                f.emit("i64.extend_i32_u");
+                return Some(ValType::I64);
+            }
+            return Some(val_type);
+        }
+
+        // unsigned typen, bools und pointer sind nullerweitert, signed typen vorzeichenerweitert
+        let sign = match _type.is_signed_integer() {
+            true => "s",
+            false => "u",
+        };
+
+        let (op, normalize_result) = match op {
+            BinaryOperator::Add => ("i64.add".to_string(), true),
+            BinaryOperator::Subtract => ("i64.sub".to_string(), true),
+            BinaryOperator::Multiply => ("i64.mul".to_string(), true),
+            BinaryOperator::Divide => (format!("i64.div_{sign}"), true),
+            BinaryOperator::Modulo => (format!("i64.rem_{sign}"), true),
+            BinaryOperator::And | BinaryOperator::BitAnd => ("i64.and".to_string(), false),
+            BinaryOperator::Or | BinaryOperator::BitOr => ("i64.or".to_string(), false),
+            BinaryOperator::BitXor => ("i64.xor".to_string(), false),
+            BinaryOperator::ShiftLeft => ("i64.shl".to_string(), true),
+            BinaryOperator::ShiftRight => (format!("i64.shr_{sign}"), false),
+            comparison => {
+                let op = match comparison {
+                    BinaryOperator::Equal => "eq".to_string(),
+                    BinaryOperator::NotEqual => "ne".to_string(),
+                    BinaryOperator::LessThan => format!("lt_{sign}"),
+                    BinaryOperator::LessThanOrEqual => format!("le_{sign}"),
+                    BinaryOperator::GreaterThan => format!("gt_{sign}"),
+                    _ => format!("ge_{sign}"),
+                };
+                f.emit(format!("i64.{op}"));
+                f.emit("i64.extend_i32_u");
+                return Some(ValType::I64);
+            }
+        };
+
+        f.emit(op);
+        if normalize_result {
+            f.emit_all(normalize(_type));
+        }
+        Some(ValType::I64)
+    }
+
+    fn emit_enum_variant(
+        &mut self,
+        f: &mut FunctionBuilder,
+        custom: &CustomDataType,
+        variant_idx: usize,
+        args: &[Spanned<TypedExpr>],
+    ) {
+        f.alloca(DataType::Custom(Box::new(custom.clone())).size());
+        let address = f.set_local(ValType::I64);
+
+        let tag_type = &custom.fields.value[0]._type.value;
+        f.emit(format!("i64.const {variant_idx}"));
+        let tag = f.set_local(ValType::I64);
+        f.store_at(&address, &tag, tag_type);
+
+        let variant = &custom.variants.as_ref().unwrap()[variant_idx];
+        for (field_idx, arg) in variant.fields.iter().zip(args) {
+            self.store_value(f, &address, custom.field_offset(*field_idx), &arg.value);
+        }
+
+        f.emit(format!("local.get {address}"));
+    }
+
+    /// wie `parse_match`, der wert wird nur einmal ausgewertet
+    fn emit_match(
+        &mut self,
+        f: &mut FunctionBuilder,
+        value: &TypedExpr,
+        arms: &[Spanned<MatchArm>],
+        _type: &DataType,
+    ) -> Option<ValType> {
+        let DataType::Custom(custom) = &value._type else {
+            unreachable!()
+        };
+
+        self.emit(f, value);
+        let value = f.set_local(ValType::I64);
+        let tag_type = &custom.fields.value[0]._type.value;
+
+        let result = value_type(_type).map(|val_type| f.local(val_type));
+        let end = f.label("match");
+        f.emit(format!("block {end}"));
+
+        for arm in arms {
+            let (fields, bindings): (&[usize], &[Spanned<String>]) = match &arm.value.pattern.value
+            {
+                MatchPattern::Variant {
+                    variant_idx,
+                    bindings,
+                    ..
+                } => {
+                    f.emit(format!("local.get {value}"));
+                    f.emit_all(load(tag_type));
+                    f.emit(format!("i64.const {variant_idx}"));
+                    f.emit("i64.eq");
+                    f.emit("if");
+                    let fields = &custom.variants.as_ref().unwrap()[*variant_idx].fields;
+                    (fields, bindings)
+                }
+                MatchPattern::Wildcard => (&[], &[]),
+            };
+
+            f.push_scope();
+            let stack_pointer = f.save_stack_pointer();
+
+            for (field_idx, binding) in fields.iter().zip(bindings) {
+                if binding.value == "_" {
+                    continue;
+                }
+
+                let field_type = &custom.fields.value[*field_idx]._type.value;
+                f.offset(&value, custom.field_offset(*field_idx));
+                f.emit_all(load(field_type));
+                let field = f.set_local(value_type(field_type).unwrap());
+                let address = f.spill(&field, field_type);
+                f.declare(&binding.value, address);
+            }
+
+            let body = &arm.value.body.value;
+            let pushed = self.emit_expr(f, &body.expression, &body._type);
+            match (&result, pushed) {
+                (Some(result), _) => f.emit(format!("local.set {result}")),
+                (None, Some(_)) => f.emit("drop"),
+                (None, None) => {}
+            }
+
+            f.restore_stack_pointer(&stack_pointer);
+            f.pop_scope();
+            f.emit(format!("br {end}"));
+
+            if matches!(arm.value.pattern.value, MatchPattern::Variant { .. }) {
+                f.emit("end");
+            }
+        }
+
+        f.emit("end");
+
+        let result = result?;
+        f.emit(format!("local.get {result}"));
+        f.materialize(_type);
+        value_type(_type)
+    }
+
+    /// wie `closure_env_*` in codegen, `mut` variablen als pointer.
+    /// die umgebung kommt auf den heap, weil die closure die funktion überleben kann,
+    /// außer sie wird nur aufgerufen
+    fn emit_closure(&mut self, f: &mut FunctionBuilder, closure: &Closure) {
+        let idx = self.closure_index(closure);
+        let pointer = pointer_type();
+
+        let size = closure
+            .captures
+            .iter()
+            .map(|capture| match capture.by_reference {
+                true => pointer.size(),
+                false => capture.variable._type.size(),
+            })
+            .sum::<usize>();
+
+        if size == 0 {
+            self.emit_function_value(f, idx, None);
+            return;
+        }
+
+        match f.local_closures.contains(&closure.body.span) {
+            true => f.alloca(size),
+            false => {
+                let malloc = self.malloc_id();
+                f.emit(format!("i64.const {size}"));
+                f.emit(format!("call {malloc}"));
+            }
+        }
+        let env = f.set_local(ValType::I64);
+
+        let mut offset = 0;
+        for capture in closure.captures.iter() {
+            let variable = Expr::Variable(capture.variable.clone());
+            self.emit_place_expr(f, &variable);
+
+            let _type = match capture.by_reference {
+                true => pointer.clone(),
+                false => {
+                    f.emit_all(load(&capture.variable._type));
+                    capture.variable._type.clone()
+                }
+            };
+
+            let value = f.set_local(value_type(&_type).unwrap());
+            f.store_field(&env, offset, &value, &_type);
+            offset += _type.size();
+        }
+
+        self.emit_function_value(f, idx, Some(&env));
+    }
+
+    /// `{ index, umgebung }` auf dem stack, die adresse bleibt auf dem operanden stack
+    fn emit_function_value(&mut self, f: &mut FunctionBuilder, idx: usize, env: Option<&str>) {
+        let pointer = pointer_type();
+        f.alloca(pointer.size() * 2);
+        let address = f.set_local(ValType::I64);
+
+        f.emit(format!("i64.const {idx}"));
+        let idx = f.set_local(ValType::I64);
+        f.store_at(&address, &idx, &pointer);
+
+        let env = match env {
+            Some(env) => env.to_string(),
+            None => {
+                f.emit("i64.const 0");
+                f.set_local(ValType::I64)
+            }
+        };
+        f.store_field(&address, pointer.size(), &env, &pointer);
+
+        f.emit(format!("local.get {address}"));
+    }
+}
+
+fn pointer_type() -> DataType {
+    DataType::Pointer(Box::default())
+}
+
+/// wie `parse_type_cast`
+fn emit_cast(f: &mut FunctionBuilder, from: &DataType, to: &DataType) {
+    if from == to {
+        return;
+    }
+
+    if is_int_like(from) && is_int_like(to) {
+        f.emit_all(normalize(to));
+        return;
+    }
+
+    let (Some(from_type), Some(to_type)) = (value_type(from), value_type(to)) else {
+        return;
+    };
+
+    if is_int_like(from) && to.is_float() {
+        let sign = match from.is_signed_integer() {
+            true => "s",
+            false => "u",
+        };
+        f.emit(format!("{to_type}.convert_i64_{sign}"));
+        return;
+    }
+
+    // erst signed, wie `as i128` im interpreter, danach abschneiden
+    if from.is_float() && is_int_like(to) {
+        f.emit(format!("i64.trunc_sat_{from_type}_s"));
+        f.emit_all(normalize(to));
+        return;
+    }
+
+    match (from_type, to_type) {
+        (ValType::F32, ValType::F64) => f.emit("f64.promote_f32"),
+        (ValType::F64, ValType::F32) => f.emit("f32.demote_f64"),
+        // klassen, arrays, ... bleiben die gleiche adresse
+        _ if is_aggregate(from) || is_aggregate(to) || from_type == to_type => {}
+        _ => f.emit("unreachable"),
+    }
+}
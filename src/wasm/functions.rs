@@ -0,0 +1,383 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    lexer::position::{Span, Spanned},
+    parser::{
+        ast::{Block, Closure, DataType, Function, FunctionParam, CLASS_SELF_ARG_NAME},
+        structures::closures::{local_closures, reference_captures},
+    },
+};
+
+use super::{
+    types::{is_aggregate, load, store, value_type, ValType},
+    wasm_main::{WasmGenerator, ALLOCA, STACK_POINTER},
+};
+
+/// wohin ein `return` springt, der body einer funktion oder ein block ausdruck
+#[derive(Debug, Clone)]
+pub(in crate::wasm) struct ReturnTarget {
+    pub label: String,
+    /// lokale variable für den wert, `None` bei `void`
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::wasm) struct LoopLabels {
+    pub break_label: String,
+    pub continue_label: String,
+}
+
+/// eine wasm funktion im aufbau
+///
+/// variablen liegen im linearen speicher (shadow stack), die lokale variable enthält nur
+/// ihre adresse. damit funktionieren `&x` und `self` wie im kompilierten programm
+#[derive(Debug)]
+pub(in crate::wasm) struct FunctionBuilder {
+    id: String,
+    params: Vec<ValType>,
+    result: Option<ValType>,
+    locals: Vec<ValType>,
+    code: Vec<String>,
+    /// innerster block zuletzt, name -> lokale variable mit der adresse
+    scopes: Vec<HashMap<String, String>>,
+    pub return_targets: Vec<ReturnTarget>,
+    pub loops: Vec<LoopLabels>,
+    /// von closures per referenz gefangen, liegen auf dem heap statt auf dem shadow stack
+    pub boxed_variables: HashSet<Spanned<String>>,
+    /// verlassen ihren block nicht, die umgebung liegt auf dem shadow stack
+    pub local_closures: HashSet<Span>,
+    label_counter: usize,
+}
+
+impl FunctionBuilder {
+    pub fn new(id: String, params: Vec<ValType>, result: Option<ValType>) -> Self {
+        FunctionBuilder {
+            id,
+            params,
+            result,
+            locals: vec![],
+            code: vec![],
+            scopes: vec![],
+            return_targets: vec![],
+            loops: vec![],
+            boxed_variables: HashSet::new(),
+            local_closures: HashSet::new(),
+            label_counter: 0,
+        }
+    }
+
+    pub fn param(idx: usize) -> String {
+        format!("$p{idx}")
+    }
+
+    /// eine neue lokale variable
+    pub fn local(&mut self, val_type: ValType) -> String {
+        self.locals.push(val_type);
+        format!("$l{}", self.locals.len() - 1)
+    }
+
+    pub fn label(&mut self, name: &str) -> String {
+        self.label_counter += 1;
+        format!("${name}{}", self.label_counter)
+    }
+
+    pub fn emit(&mut self, instruction: impl Into<String>) {
+        self.code.push(instruction.into());
+    }
+
+    pub fn emit_all(&mut self, instructions: Vec<String>) {
+        self.code.extend(instructions);
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn declare(&mut self, name: &str, address: String) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), address);
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<&String> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// merkt sich den stack pointer, z.b. am anfang eines blocks
+    pub fn save_stack_pointer(&mut self) -> String {
+        let local = self.local(ValType::I32);
+        self.emit(format!("global.get {STACK_POINTER}"));
+        self.emit(format!("local.set {local}"));
+        local
+    }
+
+    pub fn restore_stack_pointer(&mut self, local: &str) {
+        self.emit(format!("local.get {local}"));
+        self.emit(format!("global.set {STACK_POINTER}"));
+    }
+
+    /// legt `size` bytes auf den stack, die adresse (`i64`) bleibt auf dem operanden stack
+    pub fn alloca(&mut self, size: usize) {
+        self.emit(format!("i32.const {size}"));
+        self.emit(format!("call {ALLOCA}"));
+    }
+
+    /// legt den wert aus `value` auf den stack, gibt die lokale variable mit der adresse zurück
+    pub fn spill(&mut self, value: &str, _type: &DataType) -> String {
+        let address = self.local(ValType::I64);
+        self.alloca(_type.size());
+        self.emit(format!("local.set {address}"));
+        self.store_at(&address, value, _type);
+        address
+    }
+
+    /// speichert `value` an die adresse in `address`
+    pub fn store_at(&mut self, address: &str, value: &str, _type: &DataType) {
+        self.store_field(address, 0, value, _type);
+    }
+
+    /// speichert `value` an `address + offset`, z.b. für felder
+    pub fn store_field(&mut self, address: &str, offset: usize, value: &str, _type: &DataType) {
+        self.offset(address, offset);
+        self.emit("i32.wrap_i64");
+        self.emit(format!("local.get {value}"));
+        self.emit_all(store(_type));
+    }
+
+    /// `address + offset` auf den operanden stack
+    pub fn offset(&mut self, address: &str, offset: usize) {
+        self.emit(format!("local.get {address}"));
+        if offset != 0 {
+            self.emit(format!("i64.const {offset}"));
+            self.emit("i64.add");
+        }
+    }
+
+    /// nimmt den wert vom operanden stack in eine neue lokale variable
+    pub fn set_local(&mut self, val_type: ValType) -> String {
+        let local = self.local(val_type);
+        self.emit(format!("local.set {local}"));
+        local
+    }
+
+    /// rückgabewerte liegen in einem schon freigegebenen bereich, aggregate werden daher
+    /// sofort in einen neuen bereich kopiert (`memory.copy` darf sich überlappen)
+    pub fn materialize(&mut self, _type: &DataType) {
+        if !is_aggregate(_type) {
+            return;
+        }
+
+        let source = self.set_local(ValType::I64);
+        let address = self.spill(&source, _type);
+        self.emit(format!("local.get {address}"));
+    }
+
+    pub fn to_wat(&self, export: Option<&str>) -> String {
+        let mut out = format!("  (func {}", self.id);
+        if let Some(export) = export {
+            out += &format!(" (export \"{export}\")");
+        }
+
+        for (idx, param) in self.params.iter().enumerate() {
+            out += &format!(" (param {} {param})", FunctionBuilder::param(idx));
+        }
+
+        if let Some(result) = self.result {
+            out += &format!(" (result {result})");
+        }
+        out += "\n";
+
+        for (idx, local) in self.locals.iter().enumerate() {
+            out += &format!("    (local $l{idx} {local})\n");
+        }
+
+        // verschachtelte blöcke einrücken, damit die ausgabe lesbar bleibt
+        let mut depth = 2;
+        for instruction in self.code.iter() {
+            if instruction == "end" || instruction == "else" {
+                depth -= 1;
+            }
+
+            out += &format!("{}{instruction}\n", "  ".repeat(depth));
+
+            let opens_block = ["block", "loop", "if"]
+                .iter()
+                .any(|op| instruction == op || instruction.starts_with(&format!("{op} ")));
+            if opens_block || instruction == "else" {
+                depth += 1;
+            }
+        }
+
+        out + "  )\n"
+    }
+}
+
+impl WasmGenerator {
+    pub(in crate::wasm) fn generate_function(&mut self, id: String, function: &Function) {
+        let params = function
+            .params
+            .value
+            .iter()
+            .enumerate()
+            .map(|(idx, param)| match is_self_param(idx, &param.value) {
+                true => ValType::I64,
+                false => value_type(&param.value._type.value).unwrap(),
+            })
+            .collect();
+        let return_type = &function.return_type.value;
+
+        let mut f = FunctionBuilder::new(id, params, value_type(return_type));
+        f.boxed_variables = reference_captures(&function.body.value);
+        f.local_closures = local_closures(&function.body.value);
+        let stack_pointer = f.save_stack_pointer();
+        f.push_scope();
+        bind_params(&mut f, &function.params.value, 0);
+
+        let result = self.emit_returning_block(&mut f, &function.body.value, return_type);
+        f.restore_stack_pointer(&stack_pointer);
+        if let Some(result) = result {
+            f.emit(format!("local.get {result}"));
+        }
+
+        self.output.push(f.to_wat(None));
+    }
+
+    /// wie `unpack_closure_env`, die umgebung ist der erste parameter.
+    /// `mut` variablen liegen als pointer in der umgebung
+    pub(in crate::wasm) fn generate_closure(&mut self, id: String, closure: &Closure) {
+        let params = std::iter::once(ValType::I64)
+            .chain(
+                closure
+                    .params
+                    .value
+                    .iter()
+                    .map(|param| value_type(&param.value._type.value).unwrap()),
+            )
+            .collect();
+
+        let mut f = FunctionBuilder::new(id, params, value_type(&closure.return_type));
+        f.boxed_variables = reference_captures(&closure.body.value);
+        f.local_closures = local_closures(&closure.body.value);
+        let stack_pointer = f.save_stack_pointer();
+        f.push_scope();
+
+        let env = FunctionBuilder::param(0);
+        let mut offset = 0;
+        for capture in closure.captures.iter() {
+            f.offset(&env, offset);
+            match capture.by_reference {
+                true => {
+                    let pointer = DataType::Pointer(Box::new(capture.variable._type.clone()));
+                    f.emit_all(load(&pointer));
+                    offset += pointer.size();
+                }
+                false => offset += capture.variable._type.size(),
+            }
+
+            let address = f.set_local(ValType::I64);
+            f.declare(&capture.variable.name.value, address);
+        }
+
+        bind_params(&mut f, &closure.params.value, 1);
+
+        let result = self.emit_returning_block(&mut f, &closure.body.value, &closure.return_type);
+        f.restore_stack_pointer(&stack_pointer);
+        if let Some(result) = result {
+            f.emit(format!("local.get {result}"));
+        }
+
+        self.output.push(f.to_wat(None));
+    }
+
+    /// `apply(double, 2)`, funktionen als wert haben die signatur einer closure
+    /// (umgebung zuerst), die umgebung wird ignoriert
+    pub(in crate::wasm) fn generate_function_ref(
+        &mut self,
+        id: String,
+        target: &str,
+        function: &Function,
+    ) {
+        let param_types = function
+            .params
+            .value
+            .iter()
+            .map(|param| param.value._type.value.clone())
+            .collect::<Vec<_>>();
+        let params = std::iter::once(ValType::I64)
+            .chain(param_types.iter().map(|_type| value_type(_type).unwrap()))
+            .collect();
+        let return_type = &function.return_type.value;
+
+        let mut f = FunctionBuilder::new(id, params, value_type(return_type));
+        let stack_pointer = f.save_stack_pointer();
+
+        let has_self_arg = function.is_method() && !function.is_static_method();
+        for (idx, _type) in param_types.iter().enumerate() {
+            let param = FunctionBuilder::param(idx + 1);
+            match idx == 0 && has_self_arg && !is_aggregate(_type) {
+                // `self` wird als pointer übergeben
+                true => {
+                    let address = f.spill(&param, _type);
+                    f.emit(format!("local.get {address}"));
+                }
+                false => f.emit(format!("local.get {param}")),
+            }
+        }
+
+        f.emit(format!("call {target}"));
+        if let Some(val_type) = value_type(return_type) {
+            let result = f.set_local(val_type);
+            f.restore_stack_pointer(&stack_pointer);
+            f.emit(format!("local.get {result}"));
+        } else {
+            f.restore_stack_pointer(&stack_pointer);
+        }
+
+        self.output.push(f.to_wat(None));
+    }
+
+    /// ein block mit eigenem `return` ziel, gibt die lokale variable mit dem ergebnis zurück
+    pub(in crate::wasm) fn emit_returning_block(
+        &mut self,
+        f: &mut FunctionBuilder,
+        block: &Block,
+        _type: &DataType,
+    ) -> Option<String> {
+        let result = value_type(_type).map(|val_type| f.local(val_type));
+        let label = f.label("return");
+
+        f.return_targets.push(ReturnTarget {
+            label: label.clone(),
+            result: result.clone(),
+        });
+
+        f.emit(format!("block {label}"));
+        self.emit_block(f, block);
+        f.emit("end");
+
+        f.return_targets.pop();
+        result
+    }
+}
+
+fn is_self_param(idx: usize, param: &FunctionParam) -> bool {
+    idx == 0 && param.name.value == CLASS_SELF_ARG_NAME
+}
+
+/// `self` zeigt auf das objekt des aufrufers, alle anderen parameter werden kopiert
+fn bind_params(f: &mut FunctionBuilder, params: &[Spanned<FunctionParam>], first: usize) {
+    for (idx, param) in params.iter().enumerate() {
+        let local = FunctionBuilder::param(first + idx);
+        let address = match is_self_param(idx, &param.value) && first == 0 {
+            true => local,
+            false => f.spill(&local, &param.value._type.value),
+        };
+
+        f.declare(&param.value.name.value, address);
+    }
+}
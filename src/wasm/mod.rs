@@ -0,0 +1,7 @@
+mod expressions;
+mod functions;
+mod statements;
+pub mod types;
+pub mod wasm_main;
+
+pub use wasm_main::WasmGenerator;
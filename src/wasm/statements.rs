@@ -0,0 +1,335 @@
+use crate::{
+    lexer::position::Spanned,
+    parser::ast::{
+        Block, DataType, ForIterable, Statement, Trait, TypedExpr, Variable, ASSIGNMENT_PLACE_NAME,
+    },
+};
+
+use super::{
+    functions::{FunctionBuilder, LoopLabels},
+    types::{load, normalize, store, value_type, ValType},
+    wasm_main::WasmGenerator,
+};
+
+impl WasmGenerator {
+    /// eigener block für die variablen, beim verlassen wird der stack zurückgesetzt
+    pub(in crate::wasm) fn emit_block(&mut self, f: &mut FunctionBuilder, block: &Block) {
+        f.push_scope();
+        let stack_pointer = f.save_stack_pointer();
+        self.emit_statements(f, &block.statements);
+        f.restore_stack_pointer(&stack_pointer);
+        f.pop_scope();
+    }
+
+    fn emit_statements(&mut self, f: &mut FunctionBuilder, statements: &[Spanned<Statement>]) {
+        for statement in statements {
+            self.emit_statement(f, &statement.value);
+        }
+    }
+
+    fn emit_statement(&mut self, f: &mut FunctionBuilder, statement: &Statement) {
+        match statement {
+            Statement::If {
+                condition,
+                true_branch,
+                else_if_branches,
+                false_branch,
+            } => {
+                self.emit_condition(f, &condition.value);
+                f.emit("if");
+                self.emit_block(f, &true_branch.value);
+
+                for branch in else_if_branches {
+                    f.emit("else");
+                    self.emit_condition(f, &branch.value.condition.value);
+                    f.emit("if");
+                    self.emit_block(f, &branch.value.body.value);
+                }
+
+                if let Some(false_branch) = false_branch {
+                    f.emit("else");
+                    self.emit_block(f, &false_branch.value);
+                }
+
+                for _ in 0..=else_if_branches.len() {
+                    f.emit("end");
+                }
+            }
+            // der stack wird am anfang jeder runde zurückgesetzt, auch nach `continue`
+            Statement::WhileLoop { condition, body } => {
+                let stack_pointer = f.save_stack_pointer();
+                let labels = self.enter_loop(f);
+
+                f.restore_stack_pointer(&stack_pointer);
+                self.emit_condition(f, &condition.value);
+                f.emit("i32.eqz");
+                f.emit(format!("br_if {}", labels.break_label));
+
+                self.emit_block(f, &body.value);
+                self.exit_loop(f, &labels);
+                f.restore_stack_pointer(&stack_pointer);
+            }
+            Statement::ForLoop {
+                variable,
+                iterable,
+                body,
+            } => {
+                f.push_scope();
+                let stack_pointer = f.save_stack_pointer();
+                self.emit_for(f, &variable.value, &iterable.value, &body.value);
+                f.restore_stack_pointer(&stack_pointer);
+                f.pop_scope();
+            }
+            Statement::VariableDecl { name, value, .. } => {
+                let address = match f.boxed_variables.contains(name) {
+                    true => self.emit_boxed(f, &value.value),
+                    false => self.emit_spilled(f, &value.value),
+                };
+                f.declare(&name.value, address);
+            }
+            Statement::Return { value } => {
+                let target = f.return_targets.last().unwrap().clone();
+                let pushed = self.emit_expr(f, &value.value.expression, &value.value._type);
+
+                match (&target.result, pushed) {
+                    (Some(result), _) => f.emit(format!("local.set {result}")),
+                    (None, Some(_)) => f.emit("drop"),
+                    (None, None) => {}
+                }
+
+                f.emit(format!("br {}", target.label));
+            }
+            // der typ des ausdrucks ist hier nicht bekannt, der wert wird aber auch nicht gebraucht
+            Statement::Expr(expr) => {
+                if self.emit_expr(f, &expr.value, &DataType::None).is_some() {
+                    f.emit("drop");
+                }
+            }
+            Statement::VariableMutation {
+                variable,
+                new_value,
+            } => {
+                let _type = &variable.value._type;
+                self.emit_place(f, &variable.value);
+                let address = f.set_local(ValType::I64);
+                // `a[i] += 1` liest das ziel über den platzhalter, ohne `i` noch einmal zu berechnen
+                f.declare(ASSIGNMENT_PLACE_NAME, address.clone());
+
+                if let Some(val_type) = value_type(_type) {
+                    self.emit(f, &new_value.value);
+                    let value = f.set_local(val_type);
+                    f.store_at(&address, &value, _type);
+                }
+            }
+            Statement::Break => {
+                let label = f.loops.last().unwrap().break_label.clone();
+                f.emit(format!("br {label}"));
+            }
+            Statement::Continue => {
+                let label = f.loops.last().unwrap().continue_label.clone();
+                f.emit(format!("br {label}"));
+            }
+        }
+    }
+
+    /// die bedingung als `i32` für `if` und `br_if`
+    pub(in crate::wasm) fn emit_condition(
+        &mut self,
+        f: &mut FunctionBuilder,
+        condition: &TypedExpr,
+    ) {
+        self.emit(f, condition);
+        f.emit("i32.wrap_i64");
+    }
+
+    /// der wert von `expr` auf dem stack, gibt die lokale variable mit der adresse zurück
+    pub(in crate::wasm) fn emit_spilled(
+        &mut self,
+        f: &mut FunctionBuilder,
+        expr: &TypedExpr,
+    ) -> String {
+        match value_type(&expr._type) {
+            Some(val_type) => {
+                self.emit(f, expr);
+                let value = f.set_local(val_type);
+                f.spill(&value, &expr._type)
+            }
+            None => {
+                self.emit(f, expr);
+                f.alloca(0);
+                f.set_local(ValType::I64)
+            }
+        }
+    }
+
+    /// wie `emit_spilled`, aber auf dem heap, weil eine closure die variable behalten kann
+    fn emit_boxed(&mut self, f: &mut FunctionBuilder, expr: &TypedExpr) -> String {
+        let Some(val_type) = value_type(&expr._type) else {
+            return self.emit_spilled(f, expr);
+        };
+
+        self.emit(f, expr);
+        let value = f.set_local(val_type);
+
+        let malloc = self.malloc_id();
+        f.emit(format!("i64.const {}", expr._type.size()));
+        f.emit(format!("call {malloc}"));
+        let address = f.set_local(ValType::I64);
+        f.store_at(&address, &value, &expr._type);
+        address
+    }
+
+    /// `block $break (loop $continue ...)`, `continue` springt an den anfang der schleife
+    fn enter_loop(&mut self, f: &mut FunctionBuilder) -> LoopLabels {
+        let labels = LoopLabels {
+            break_label: f.label("break"),
+            continue_label: f.label("continue"),
+        };
+
+        f.emit(format!("block {}", labels.break_label));
+        f.emit(format!("loop {}", labels.continue_label));
+        f.loops.push(labels.clone());
+        labels
+    }
+
+    fn exit_loop(&mut self, f: &mut FunctionBuilder, labels: &LoopLabels) {
+        f.loops.pop();
+        f.emit(format!("br {}", labels.continue_label));
+        f.emit("end");
+        f.emit("end");
+    }
+
+    /// wie `exec_for` im interpreter, der zähler läuft von `start` bis `end`,
+    /// bei `Iterable` wird das element über den index geholt
+    fn emit_for(
+        &mut self,
+        f: &mut FunctionBuilder,
+        variable: &Variable,
+        iterable: &ForIterable,
+        body: &Block,
+    ) {
+        match iterable {
+            ForIterable::Range { start, end } => {
+                let counter_type = start.value._type.clone();
+                let counter = self.emit_spilled(f, &start.value);
+                f.declare(&variable.name.value, counter.clone());
+
+                self.emit(f, &end.value);
+                let end = f.set_local(ValType::I64);
+
+                let stack_pointer = f.save_stack_pointer();
+                let labels = self.enter_loop(f);
+                f.restore_stack_pointer(&stack_pointer);
+
+                f.emit(format!("local.get {counter}"));
+                f.emit_all(load(&counter_type));
+                f.emit(format!("local.get {end}"));
+                f.emit(match counter_type.is_signed_integer() {
+                    true => "i64.ge_s",
+                    false => "i64.ge_u",
+                });
+                f.emit(format!("br_if {}", labels.break_label));
+
+                // `continue` springt nur ans ende des bodys, damit der zähler weiter läuft
+                let next = f.label("next");
+                f.emit(format!("block {next}"));
+                f.loops.last_mut().unwrap().continue_label = next.clone();
+                self.emit_block(f, body);
+                f.emit("end");
+
+                f.emit(format!("local.get {counter}"));
+                f.emit("i32.wrap_i64");
+                f.emit(format!("local.get {counter}"));
+                f.emit_all(load(&counter_type));
+                f.emit("i64.const 1");
+                f.emit("i64.add");
+                f.emit_all(normalize(&counter_type));
+                f.emit_all(store(&counter_type));
+
+                self.exit_loop(f, &labels);
+                f.restore_stack_pointer(&stack_pointer);
+            }
+            ForIterable::Iterable(iterable) => {
+                let iterable_type = iterable.value._type.clone();
+                let address = self.emit_spilled(f, &iterable.value);
+                let index_type = DataType::get_integer_type();
+
+                let (element_type, iter_get) = match &iterable_type {
+                    DataType::Array { value_type, len } => {
+                        f.emit(format!("i64.const {len}"));
+                        (*value_type.clone(), None)
+                    }
+                    _ => {
+                        let iter_len = self
+                            .trait_function(&Trait::IterLen, std::slice::from_ref(&iterable_type));
+                        let iter_get = self.trait_function(
+                            &Trait::IterGet,
+                            &[iterable_type.clone(), index_type.clone()],
+                        );
+
+                        let (Some(iter_len), Some(iter_get)) = (iter_len, iter_get) else {
+                            f.emit("unreachable");
+                            return;
+                        };
+
+                        f.emit(format!("local.get {address}"));
+                        let iter_len = self.function_id(&iter_len);
+                        f.emit(format!("call {iter_len}"));
+                        (iter_get.return_type.value.clone(), Some(iter_get))
+                    }
+                };
+                let len = f.set_local(ValType::I64);
+                let idx = f.local(ValType::I64);
+
+                let stack_pointer = f.save_stack_pointer();
+                let labels = self.enter_loop(f);
+                f.restore_stack_pointer(&stack_pointer);
+
+                f.emit(format!("local.get {idx}"));
+                f.emit(format!("local.get {len}"));
+                f.emit("i64.ge_s");
+                f.emit(format!("br_if {}", labels.break_label));
+
+                match iter_get {
+                    Some(iter_get) => {
+                        f.emit(format!("local.get {address}"));
+                        f.emit(format!("local.get {idx}"));
+                        let iter_get = self.function_id(&iter_get);
+                        f.emit(format!("call {iter_get}"));
+                    }
+                    None => {
+                        f.emit(format!("local.get {address}"));
+                        f.emit(format!("local.get {idx}"));
+                        f.emit(format!("i64.const {}", element_type.size()));
+                        f.emit("i64.mul");
+                        f.emit("i64.add");
+                        f.emit_all(load(&element_type));
+                    }
+                }
+
+                // aggregate von `iter_get` werden durch `spill` sofort kopiert
+                f.push_scope();
+                if let Some(val_type) = value_type(&element_type) {
+                    let element = f.set_local(val_type);
+                    let element = f.spill(&element, &element_type);
+                    f.declare(&variable.name.value, element);
+                }
+
+                let next = f.label("next");
+                f.emit(format!("block {next}"));
+                f.loops.last_mut().unwrap().continue_label = next.clone();
+                self.emit_block(f, body);
+                f.emit("end");
+                f.pop_scope();
+
+                f.emit(format!("local.get {idx}"));
+                f.emit("i64.const 1");
+                f.emit("i64.add");
+                f.emit(format!("local.set {idx}"));
+
+                self.exit_loop(f, &labels);
+                f.restore_stack_pointer(&stack_pointer);
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt::Display;
+
+use crate::parser::ast::DataType;
+
+/// die wasm typen auf dem operanden stack
+///
+/// alle integer, bools und pointer sind `i64`, kleinere typen werden nach jeder
+/// operation wieder vorzeichen- bzw. nullerweitert. klassen, arrays und funktionswerte
+/// sind die adresse (`i64`) ihrer bytes im linearen speicher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValType {
+    /// nur für adressen im speicher, z.b. den gespeicherten stack pointer
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl Display for ValType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValType::I32 => write!(f, "i32"),
+            ValType::I64 => write!(f, "i64"),
+            ValType::F32 => write!(f, "f32"),
+            ValType::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+/// `None` für `void`
+pub fn value_type(_type: &DataType) -> Option<ValType> {
+    match _type {
+        DataType::None => None,
+        DataType::Float => Some(ValType::F32),
+        DataType::Float64 => Some(ValType::F64),
+        _ => Some(ValType::I64),
+    }
+}
+
+/// werte die im speicher bleiben und nur über ihre adresse weitergegeben werden
+pub fn is_aggregate(_type: &DataType) -> bool {
+    matches!(
+        _type,
+        DataType::Custom(_) | DataType::Array { .. } | DataType::Function { .. }
+    )
+}
+
+/// `i64` adresse auf dem stack -> wert, bei aggregaten bleibt die adresse stehen
+pub fn load(_type: &DataType) -> Vec<String> {
+    if is_aggregate(_type) || _type == &DataType::None {
+        return vec![];
+    }
+
+    let op = match _type {
+        DataType::Integer8 => "i64.load8_s",
+        DataType::UnsignedInteger8 | DataType::Boolean => "i64.load8_u",
+        DataType::Integer16 => "i64.load16_s",
+        DataType::UnsignedInteger16 => "i64.load16_u",
+        DataType::Integer32 => "i64.load32_s",
+        DataType::UnsignedInteger32 => "i64.load32_u",
+        DataType::Float => "f32.load",
+        DataType::Float64 => "f64.load",
+        // pointer sind so groß wie auf dem host (`DataType::size`)
+        _type if _type.size() == 4 => "i64.load32_u",
+        _ => "i64.load",
+    };
+
+    vec!["i32.wrap_i64".to_string(), op.to_string()]
+}
+
+/// `i32` adresse und wert auf dem stack, aggregate werden kopiert (adresse als `i64`)
+pub fn store(_type: &DataType) -> Vec<String> {
+    if is_aggregate(_type) {
+        return vec![
+            "i32.wrap_i64".to_string(),
+            format!("i32.const {}", _type.size()),
+            "memory.copy".to_string(),
+        ];
+    }
+
+    let op = match _type {
+        DataType::None => return vec!["drop".to_string()],
+        DataType::Float => "f32.store",
+        DataType::Float64 => "f64.store",
+        _type => match _type.size() {
+            1 => "i64.store8",
+            2 => "i64.store16",
+            4 => "i64.store32",
+            _ => "i64.store",
+        },
+    };
+
+    vec![op.to_string()]
+}
+
+/// bringt einen `i64` wieder in den wertebereich von `_type`, wie das abschneiden in llvm
+pub fn normalize(_type: &DataType) -> Vec<String> {
+    let op = match _type {
+        DataType::Integer8 => "i64.extend8_s",
+        DataType::Integer16 => "i64.extend16_s",
+        DataType::Integer32 => "i64.extend32_s",
+        DataType::Boolean => return vec!["i64.const 1".to_string(), "i64.and".to_string()],
+        _type if is_int_like(_type) && _type.size() < 8 => {
+            let mask = (1u64 << (_type.size() * 8)) - 1;
+            return vec![format!("i64.const {mask}"), "i64.and".to_string()];
+        }
+        _ => return vec![],
+    };
+
+    vec![op.to_string()]
+}
+
+/// wie `normalize`, aber schon beim übersetzen
+pub fn normalize_const(value: i128, _type: &DataType) -> i64 {
+    let bits = _type.size().min(8) as u32 * 8;
+    if _type.is_boolean() {
+        return (value & 1) as i64;
+    }
+
+    if bits == 0 || bits == 64 || !is_int_like(_type) {
+        return value as i64;
+    }
+
+    let shift = 128 - bits;
+    match _type.is_signed_integer() {
+        true => ((value << shift) >> shift) as i64,
+        false => (value & ((1 << bits) - 1)) as i64,
+    }
+}
+
+pub fn is_int_like(_type: &DataType) -> bool {
+    _type.is_integer() || _type.is_boolean() || matches!(_type, DataType::Pointer(_))
+}
+
+/// bezeichner im wasm text format dürfen keine leerzeichen, klammern, ... enthalten
+pub fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z'
+            | 'A'..='Z'
+            | '0'..='9'
+            | '_'
+            | '.'
+            | '-'
+            | '<'
+            | '>'
+            | '*'
+            | '+'
+            | '!'
+            | '#'
+            | '%'
+            | '&'
+            | '/'
+            | ':'
+            | '='
+            | '?'
+            | '@'
+            | '^'
+            | '|'
+            | '~' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// die bytes als string im text format, z.b. für `data` segmente
+pub fn escape_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("\\{b:02x}")).collect()
+}
@@ -0,0 +1,383 @@
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use ordermap::OrderMap;
+
+use crate::{
+    interpreter::interpreter_main::literal_bytes,
+    parser::ast::{
+        Closure, CommonGeneric, DataType, Function, Program, Trait, MAIN_ARGS_FUNCTION_NAME,
+    },
+};
+
+use super::{
+    functions::FunctionBuilder,
+    types::{escape_bytes, sanitize, value_type, ValType},
+};
+
+pub const STACK_POINTER: &str = "$__stack_pointer";
+pub const ALLOCA: &str = "$__alloca";
+const MALLOC_IMPORT: &str = "$__malloc";
+/// modul aus dem die `extern` funktionen importiert werden
+pub const IMPORT_MODULE: &str = "env";
+/// der export den der host aufruft, gibt den exit code zurück
+pub const ENTRY_EXPORT: &str = "main";
+/// ab hier darf der host (`malloc`) den speicher benutzen
+pub const HEAP_BASE_EXPORT: &str = "__heap_base";
+
+/// adresse 0 bleibt frei, damit `null` nie auf einen wert zeigt
+const NULL_GUARD_SIZE: usize = 8;
+const STACK_SIZE: usize = 1024 * 1024;
+const PAGE_SIZE: usize = 64 * 1024;
+
+/// eine funktion die noch übersetzt werden muss
+enum Pending {
+    Function(String, Rc<Function>),
+    Closure(String, Box<Closure>),
+    FunctionRef(String, String, Rc<Function>),
+}
+
+/// übersetzt ein `Program` in das wasm text format, als zweites backend neben llvm
+///
+/// das layout im speicher ist das von `DataType::size` (wie beim interpreter), variablen
+/// liegen auf einem eigenen stack im linearen speicher. `extern` funktionen werden aus
+/// `env` importiert und müssen vom host bereitgestellt werden (`printf`, `malloc`, `free`, ...),
+/// alle integer und pointer sind dabei `i64`
+pub struct WasmGenerator {
+    pub program: Program,
+    /// alle funktionen inklusive generischer subtypen, nach internem namen
+    functions: HashMap<String, Rc<Function>>,
+    /// interner name -> wasm bezeichner, nur was auch benutzt wird
+    function_ids: HashMap<String, String>,
+    used_ids: HashSet<String>,
+    pending: Vec<Pending>,
+    imports: OrderMap<String, String>,
+    /// funktionen für `call_indirect`, index 0 ist `null`
+    table: Vec<String>,
+    function_refs: HashMap<String, usize>,
+    globals: HashMap<String, usize>,
+    data: Vec<(usize, Vec<u8>)>,
+    pub(in crate::wasm) output: Vec<String>,
+}
+
+impl WasmGenerator {
+    pub fn new(program: Program) -> Self {
+        let mut generator = WasmGenerator {
+            program,
+            functions: HashMap::new(),
+            function_ids: HashMap::new(),
+            used_ids: HashSet::new(),
+            pending: vec![],
+            imports: OrderMap::new(),
+            table: vec![],
+            function_refs: HashMap::new(),
+            globals: HashMap::new(),
+            data: vec![],
+            output: vec![],
+        };
+
+        generator.index_functions();
+        generator
+    }
+
+    /// das modul im text format, mit `wat::parse_str` wird daraus die binäre form
+    pub fn parse(&mut self) -> String {
+        let globals_end = self.layout_globals();
+        let stack_base = align(globals_end, 16);
+        let heap_base = stack_base + STACK_SIZE;
+
+        self.generate_entry();
+
+        while let Some(pending) = self.pending.pop() {
+            match pending {
+                Pending::Function(id, function) => self.generate_function(id, &function),
+                Pending::Closure(id, closure) => self.generate_closure(id, &closure),
+                Pending::FunctionRef(id, target, function) => {
+                    self.generate_function_ref(id, &target, &function)
+                }
+            }
+        }
+
+        let mut out = "(module\n".to_string();
+
+        for import in self.imports.values() {
+            out += &format!("  {import}\n");
+        }
+
+        // eine seite mehr, damit der heap nicht sofort wachsen muss
+        let pages = heap_base / PAGE_SIZE + 1;
+        out += &format!("  (memory (export \"memory\") {pages})\n");
+        out += &format!("  (global {STACK_POINTER} (mut i32) (i32.const {stack_base}))\n");
+        out += &format!("  (global (export \"{HEAP_BASE_EXPORT}\") i32 (i32.const {heap_base}))\n");
+
+        out += &format!("  (table {} funcref)\n", self.table.len() + 1);
+        if !self.table.is_empty() {
+            out += &format!("  (elem (i32.const 1) func {})\n", self.table.join(" "));
+        }
+
+        for (address, bytes) in self.data.iter() {
+            out += &format!(
+                "  (data (i32.const {address}) \"{}\")\n",
+                escape_bytes(bytes)
+            );
+        }
+
+        out += &alloca_function(stack_base + STACK_SIZE);
+        for function in self.output.iter() {
+            out += function;
+        }
+
+        out + ")\n"
+    }
+
+    /// wie beim interpreter, aber ohne funktionen die nie aufgerufen werden.
+    /// sonst müsste der host jede `extern` funktion aus der standard bibliothek anbieten
+    fn index_functions(&mut self) {
+        fn add(functions: &mut HashMap<String, Rc<Function>>, function: &Function) {
+            for subtype in function.generic_subtypes.values() {
+                add(functions, subtype);
+            }
+
+            if !function.is_generic() {
+                functions
+                    .entry(function.name.clone())
+                    .or_insert_with(|| Rc::new(function.clone()));
+            }
+        }
+
+        for function in self.program.functions.values() {
+            add(&mut self.functions, &function.value);
+        }
+    }
+
+    /// globale variablen liegen hinter der null adresse und werden mit `data` initialisiert
+    fn layout_globals(&mut self) -> usize {
+        let mut address = NULL_GUARD_SIZE;
+
+        for (name, global) in self.program.globals.iter() {
+            let bytes = literal_bytes(&global.value.value, &global.value._type.value);
+            self.globals.insert(name.clone(), address);
+
+            let size = global.value._type.value.size();
+            self.data.push((address, bytes));
+            address = align(address + size, 8);
+        }
+
+        address
+    }
+
+    pub(in crate::wasm) fn global_address(&self, name: &str) -> Option<usize> {
+        self.globals.get(name).copied()
+    }
+
+    /// ein eindeutiger bezeichner aus `name`
+    fn unique_id(&mut self, name: &str) -> String {
+        let base = format!("${}", sanitize(name));
+        let mut id = base.clone();
+        let mut counter = 0;
+
+        while self.used_ids.contains(&id) {
+            counter += 1;
+            id = format!("{base}.{counter}");
+        }
+
+        self.used_ids.insert(id.clone());
+        id
+    }
+
+    /// der bezeichner für einen aufruf, die funktion wird beim ersten mal übersetzt
+    /// (bzw. importiert)
+    pub(in crate::wasm) fn function_id(&mut self, function: &Function) -> String {
+        if let Some(id) = self.function_ids.get(&function.name) {
+            return id.clone();
+        }
+
+        // bei rekursion ist die funktion im aufruf noch ohne body
+        let function = self
+            .functions
+            .get(&function.name)
+            .cloned()
+            .unwrap_or_else(|| Rc::new(function.clone()));
+
+        let id = self.unique_id(&function.name);
+        self.function_ids.insert(function.name.clone(), id.clone());
+
+        match function.is_extern {
+            true => {
+                let import = format!(
+                    "(import \"{IMPORT_MODULE}\" \"{}\" (func {id}{}))",
+                    function.name,
+                    signature(&function)
+                );
+                self.imports.insert(function.name.clone(), import);
+            }
+            false => self.pending.push(Pending::Function(id.clone(), function)),
+        }
+
+        id
+    }
+
+    /// überschriebener operator (oder `Index`, `IterLen`, ...) wie `get_trait_function`
+    pub(in crate::wasm) fn trait_function(
+        &self,
+        trait_: &Trait,
+        params: &[DataType],
+    ) -> Option<Function> {
+        let type_info = self.program.get_type_info(&params[0]);
+        self.program.get_trait_function(&type_info, trait_, params)
+    }
+
+    /// die umgebung von closures liegt auf dem heap, auch ohne `import "std/mem.mx"`
+    pub(in crate::wasm) fn malloc_id(&mut self) -> String {
+        if let Some(malloc) = self.functions.get("malloc").cloned() {
+            if malloc.is_extern {
+                return self.function_id(&malloc);
+            }
+        }
+
+        if !self.imports.contains_key(MALLOC_IMPORT) {
+            let import = format!(
+                "(import \"{IMPORT_MODULE}\" \"malloc\" (func {MALLOC_IMPORT} (param i64) (result i64)))"
+            );
+            self.imports.insert(MALLOC_IMPORT.to_string(), import);
+        }
+
+        MALLOC_IMPORT.to_string()
+    }
+
+    /// index in der tabelle für `call_indirect`
+    pub(in crate::wasm) fn closure_index(&mut self, closure: &Closure) -> usize {
+        let id = self.unique_id("__closure");
+        self.pending
+            .push(Pending::Closure(id.clone(), Box::new(closure.clone())));
+        self.table.push(id);
+        self.table.len()
+    }
+
+    pub(in crate::wasm) fn function_ref_index(&mut self, function: &Function) -> usize {
+        if let Some(idx) = self.function_refs.get(&function.name) {
+            return *idx;
+        }
+
+        let target = self.function_id(function);
+        let function = self
+            .functions
+            .get(&function.name)
+            .cloned()
+            .unwrap_or_else(|| Rc::new(function.clone()));
+
+        let id = self.unique_id(&format!("__ref.{}", function.name));
+        self.pending
+            .push(Pending::FunctionRef(id.clone(), target, function.clone()));
+        self.table.push(id);
+        self.function_refs
+            .insert(function.name.clone(), self.table.len());
+        self.table.len()
+    }
+
+    /// `main` für den host, wie der wrapper in codegen.
+    /// `def main(args: List<String>)` bekommt die argumente von `mx_argc` und `mx_argv`
+    fn generate_entry(&mut self) {
+        let Some(main) = self.functions.get("main").cloned() else {
+            return;
+        };
+
+        let id = self.unique_id("__start");
+        let mut f = FunctionBuilder::new(id, vec![], Some(ValType::I32));
+
+        if !main.params.value.is_empty() {
+            if let Some(build_args) = self.functions.get(MAIN_ARGS_FUNCTION_NAME).cloned() {
+                let build_args_id = self.function_id(&build_args);
+                f.emit(format!("call {build_args_id}"));
+                f.materialize(&build_args.return_type.value);
+            }
+        }
+
+        let main_id = self.function_id(&main);
+        f.emit(format!("call {main_id}"));
+
+        match value_type(&main.return_type.value) {
+            Some(ValType::I64) => f.emit("i32.wrap_i64"),
+            Some(_) => {
+                f.emit("drop");
+                f.emit("i32.const 0");
+            }
+            None => f.emit("i32.const 0"),
+        }
+
+        self.output.push(f.to_wat(Some(ENTRY_EXPORT)));
+    }
+}
+
+/// `(param ...) (result ...)`, `self` ist immer ein pointer
+pub(in crate::wasm) fn signature(function: &Function) -> String {
+    let has_self_arg = function.is_method() && !function.is_static_method();
+    let mut out = String::new();
+
+    for (idx, param) in function.params.value.iter().enumerate() {
+        let val_type = match idx == 0 && has_self_arg {
+            true => ValType::I64,
+            false => value_type(&param.value._type.value).unwrap(),
+        };
+        out += &format!(" (param {val_type})");
+    }
+
+    // adresse der argumente für `...`
+    if function.is_variadic {
+        out += " (param i64)";
+    }
+
+    if let Some(result) = value_type(&function.return_type.value) {
+        out += &format!(" (result {result})");
+    }
+
+    out
+}
+
+/// `(param ...) (result ...)` für `call_indirect`, die umgebung kommt zuerst
+pub(in crate::wasm) fn closure_signature(params: &[DataType], return_type: &DataType) -> String {
+    let mut out = " (param i64)".to_string();
+
+    for param in params {
+        out += &format!(" (param {})", value_type(param).unwrap());
+    }
+
+    if let Some(result) = value_type(return_type) {
+        out += &format!(" (result {result})");
+    }
+
+    out
+}
+
+fn align(address: usize, alignment: usize) -> usize {
+    address.div_ceil(alignment) * alignment
+}
+
+/// reserviert `size` bytes auf dem stack (aufgerundet auf 8), bei einem überlauf wird abgebrochen
+fn alloca_function(stack_end: usize) -> String {
+    format!(
+        "  (func {ALLOCA} (param $size i32) (result i64)
+    (local $address i32)
+    global.get {STACK_POINTER}
+    local.set $address
+    local.get $address
+    local.get $size
+    i32.const 7
+    i32.add
+    i32.const -8
+    i32.and
+    i32.add
+    global.set {STACK_POINTER}
+    global.get {STACK_POINTER}
+    i32.const {stack_end}
+    i32.gt_u
+    if
+      unreachable
+    end
+    local.get $address
+    i64.extend_i32_u
+  )\n"
+    )
+}